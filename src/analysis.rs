@@ -0,0 +1,406 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Identifies font files from their raw bytes, the way
+//! `IDWriteFontFile::Analyze` does, without going through DirectWrite.
+
+use std::{error, fmt};
+
+use crate::tables::{self, Stream};
+use crate::{FontFaceType, Tag};
+
+/// The container format of a font file; mirrors `DWRITE_FONT_FILE_TYPE`,
+/// plus the web font formats DirectWrite can't load directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontFileFormat {
+    Unknown,
+    /// An sfnt with PostScript (`CFF ` or `CFF2`) outlines.
+    Cff,
+    /// An sfnt with TrueType (`glyf`) or bitmap-only glyphs.
+    TrueType,
+    TrueTypeCollection,
+    /// A Type 1 font program, as `.pfb` or `.pfa`, or its `.pfm` metrics.
+    Type1,
+    /// A Windows `.fon` or `.fnt` file.
+    Fon,
+    Woff,
+    Woff2,
+}
+
+/// Why DirectWrite won't load a font file from a memory buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsupportedFontReason {
+    /// The data doesn't start with the signature of any known font format.
+    UnknownFormat,
+    /// The data ends inside a header or table directory.
+    Truncated,
+    /// A TrueType collection that contains no faces.
+    EmptyCollection,
+    /// A table record of the given face points outside the file.
+    TableOutOfBounds { face_index: u32, tag: Tag },
+    /// WOFF and WOFF2 files have to be decompressed to an sfnt first.
+    Compressed,
+    /// Type 1 fonts can only be loaded from files on disk.
+    Type1,
+    /// Windows `.fon` fonts predate DirectWrite and aren't supported by it.
+    Fon,
+}
+
+impl fmt::Display for UnsupportedFontReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => write!(f, "Unknown font format"),
+            Self::Truncated => write!(f, "Truncated font data"),
+            Self::EmptyCollection => write!(f, "Font collection has no faces"),
+            Self::TableOutOfBounds { face_index, tag } => {
+                write!(f, "Table '{}' of face {} is out of bounds", tag, face_index)
+            }
+            Self::Compressed => write!(f, "Compressed web fonts must be decompressed first"),
+            Self::Type1 => write!(f, "Type 1 fonts can't be loaded from memory"),
+            Self::Fon => write!(f, "Windows .fon fonts are not supported"),
+        }
+    }
+}
+
+impl error::Error for UnsupportedFontReason {}
+
+/// The result of [`FontFileAnalysis::analyze`], matching the outputs of
+/// `IDWriteFontFile::Analyze`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FontFileAnalysis {
+    pub format: FontFileFormat,
+    /// The kind of faces in the file. For WOFF and WOFF2 this describes the
+    /// compressed font.
+    pub face_type: FontFaceType,
+    pub face_count: u32,
+    /// `None` if DirectWrite can load the file from a memory buffer.
+    pub unsupported: Option<UnsupportedFontReason>,
+}
+
+impl FontFileAnalysis {
+    pub fn analyze(data: &[u8]) -> FontFileAnalysis {
+        let result = match data.get(0..4) {
+            Some([0, 1, 0, 0]) | Some(b"true") | Some(b"OTTO") => analyze_sfnt(data),
+            Some(b"ttcf") => analyze_collection(data),
+            Some(b"wOFF") => analyze_woff(data),
+            Some(b"wOF2") => analyze_woff2(data),
+            Some([0x80, 0x01, ..]) | Some(b"%!PS") | Some(b"%!Fo") => analyze_type1(data),
+            Some([b'M', b'Z', ..]) => analyze_fon(data),
+            Some(_) => analyze_fnt(data),
+            None => Err(UnsupportedFontReason::Truncated),
+        };
+        result.unwrap_or_else(FontFileAnalysis::unknown)
+    }
+
+    /// Returns `true` if DirectWrite can load the file from a memory buffer.
+    pub fn is_supported(&self) -> bool {
+        self.unsupported.is_none()
+    }
+
+    fn unknown(reason: UnsupportedFontReason) -> FontFileAnalysis {
+        FontFileAnalysis {
+            format: FontFileFormat::Unknown,
+            face_type: FontFaceType::Unknown,
+            face_count: 0,
+            unsupported: Some(reason),
+        }
+    }
+
+    fn supported(format: FontFileFormat, face_type: FontFaceType, face_count: u32) -> Self {
+        FontFileAnalysis {
+            format,
+            face_type,
+            face_count,
+            unsupported: None,
+        }
+    }
+
+    fn unsupported(
+        format: FontFileFormat,
+        face_type: FontFaceType,
+        face_count: u32,
+        reason: UnsupportedFontReason,
+    ) -> Self {
+        FontFileAnalysis {
+            format,
+            face_type,
+            face_count,
+            unsupported: Some(reason),
+        }
+    }
+}
+
+type AnalysisResult = Result<FontFileAnalysis, UnsupportedFontReason>;
+
+fn truncated<T>(value: Option<T>) -> Result<T, UnsupportedFontReason> {
+    value.ok_or(UnsupportedFontReason::Truncated)
+}
+
+// Checks the table directory of one face, returning whether it has
+// PostScript outlines.
+/// Checks a face's table directory, and returns the face type its glyph
+/// tables make it: PostScript outlines win over TrueType ones, and faces
+/// with only embedded bitmaps are `Bitmap` faces, as DirectWrite reports.
+fn check_table_directory(
+    data: &[u8],
+    offset: usize,
+    face_index: u32,
+) -> Result<FontFaceType, UnsupportedFontReason> {
+    let mut s = truncated(Stream::new_at(data, offset))?;
+    let version = truncated(s.read_tag())?;
+    if !matches!(&version.0, [0, 1, 0, 0] | b"true" | b"OTTO") {
+        return Err(UnsupportedFontReason::UnknownFormat);
+    }
+    let num_tables = truncated(s.read_u16())?;
+    truncated(s.skip(6))?;
+    let (mut cff, mut glyf, mut bitmaps) = (false, false, false);
+    for _ in 0..num_tables {
+        let tag = truncated(s.read_tag())?;
+        truncated(s.skip(4))?;
+        let offset = truncated(s.read_u32())? as usize;
+        let length = truncated(s.read_u32())? as usize;
+        let in_bounds = offset
+            .checked_add(length)
+            .is_some_and(|end| end <= data.len());
+        if !in_bounds {
+            return Err(UnsupportedFontReason::TableOutOfBounds { face_index, tag });
+        }
+        cff |= tag == Tag::CFF || tag == Tag::CFF2;
+        glyf |= tag == Tag::GLYF;
+        bitmaps |= tag == Tag::EBDT || tag == Tag::CBDT || tag == Tag::SBIX;
+    }
+    Ok(if cff {
+        FontFaceType::Cff
+    } else if bitmaps && !glyf {
+        FontFaceType::Bitmap
+    } else {
+        FontFaceType::TrueType
+    })
+}
+
+fn analyze_sfnt(data: &[u8]) -> AnalysisResult {
+    let face_type = check_table_directory(data, 0, 0)?;
+    let format = match face_type {
+        FontFaceType::Cff => FontFileFormat::Cff,
+        _ => FontFileFormat::TrueType,
+    };
+    Ok(FontFileAnalysis::supported(format, face_type, 1))
+}
+
+fn analyze_collection(data: &[u8]) -> AnalysisResult {
+    let face_count = truncated(tables::read_u32(data, 8))?;
+    if face_count == 0 {
+        return Err(UnsupportedFontReason::EmptyCollection);
+    }
+    for face_index in 0..face_count {
+        let offset = truncated(tables::read_u32(data, 12 + 4 * face_index as usize))?;
+        check_table_directory(data, offset as usize, face_index)?;
+    }
+    Ok(FontFileAnalysis::supported(
+        FontFileFormat::TrueTypeCollection,
+        FontFaceType::TrueTypeCollection,
+        face_count,
+    ))
+}
+
+fn flavor_face_type(flavor: Tag) -> Result<FontFaceType, UnsupportedFontReason> {
+    match &flavor.0 {
+        [0, 1, 0, 0] | b"true" => Ok(FontFaceType::TrueType),
+        b"OTTO" => Ok(FontFaceType::Cff),
+        b"ttcf" => Ok(FontFaceType::TrueTypeCollection),
+        _ => Err(UnsupportedFontReason::UnknownFormat),
+    }
+}
+
+fn analyze_woff(data: &[u8]) -> AnalysisResult {
+    let mut s = Stream::new(data);
+    truncated(s.skip(4))?;
+    let flavor = truncated(s.read_tag())?;
+    let length = truncated(s.read_u32())?;
+    if length as usize != data.len() {
+        return Err(UnsupportedFontReason::Truncated);
+    }
+    // WOFF 1.0 has no collections.
+    let face_type = match flavor_face_type(flavor)? {
+        FontFaceType::TrueTypeCollection => return Err(UnsupportedFontReason::UnknownFormat),
+        face_type => face_type,
+    };
+    Ok(FontFileAnalysis::unsupported(
+        FontFileFormat::Woff,
+        face_type,
+        1,
+        UnsupportedFontReason::Compressed,
+    ))
+}
+
+fn read_uint_base128(s: &mut Stream) -> Option<u32> {
+    let mut value: u32 = 0;
+    for i in 0..5 {
+        let byte = s.read_u8()?;
+        // Leading zeros aren't allowed.
+        if i == 0 && byte == 0x80 {
+            return None;
+        }
+        if value & 0xfe00_0000 != 0 {
+            return None;
+        }
+        value = (value << 7) | u32::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn read_255_u16(s: &mut Stream) -> Option<u16> {
+    match s.read_u8()? {
+        253 => s.read_u16(),
+        254 => s.read_u8().map(|v| u16::from(v) + 253 * 2),
+        255 => s.read_u8().map(|v| u16::from(v) + 253),
+        code => Some(u16::from(code)),
+    }
+}
+
+fn analyze_woff2(data: &[u8]) -> AnalysisResult {
+    let mut s = Stream::new(data);
+    truncated(s.skip(4))?;
+    let flavor = truncated(s.read_tag())?;
+    let length = truncated(s.read_u32())?;
+    let num_tables = truncated(s.read_u16())?;
+    if length as usize != data.len() {
+        return Err(UnsupportedFontReason::Truncated);
+    }
+    let face_type = flavor_face_type(flavor)?;
+
+    let mut face_count = 1;
+    if face_type == FontFaceType::TrueTypeCollection {
+        // The collection directory follows the table directory, whose
+        // entries vary in length.
+        truncated(s.skip(48 - 14))?;
+        for _ in 0..num_tables {
+            let flags = truncated(s.read_u8())?;
+            if flags & 0x3f == 0x3f {
+                truncated(s.skip(4))?;
+            }
+            truncated(read_uint_base128(&mut s))?;
+            let transform = flags >> 6;
+            // `glyf` and `loca` are transformed unless the version is 3;
+            // every other table is transformed unless it is 0.
+            let transformed = match flags & 0x3f {
+                10 | 11 => transform != 3,
+                _ => transform != 0,
+            };
+            if transformed {
+                truncated(read_uint_base128(&mut s))?;
+            }
+        }
+        truncated(s.skip(4))?;
+        face_count = u32::from(truncated(read_255_u16(&mut s))?);
+        if face_count == 0 {
+            return Err(UnsupportedFontReason::EmptyCollection);
+        }
+    }
+    Ok(FontFileAnalysis::unsupported(
+        FontFileFormat::Woff2,
+        face_type,
+        face_count,
+        UnsupportedFontReason::Compressed,
+    ))
+}
+
+fn analyze_type1(data: &[u8]) -> AnalysisResult {
+    let is_type1 = data.starts_with(&[0x80, 0x01])
+        || data.starts_with(b"%!PS-AdobeFont")
+        || data.starts_with(b"%!FontType1");
+    if !is_type1 {
+        return Err(UnsupportedFontReason::UnknownFormat);
+    }
+    Ok(FontFileAnalysis::unsupported(
+        FontFileFormat::Type1,
+        FontFaceType::Type1,
+        1,
+        UnsupportedFontReason::Type1,
+    ))
+}
+
+const RT_FONT: u16 = 0x8008;
+
+// `FONTINFO.dfType`; the low bit is set for vector fonts.
+fn fnt_face_type(data: &[u8], offset: usize) -> Result<FontFaceType, UnsupportedFontReason> {
+    let font_type = truncated(tables::read_u16(data, offset + 0x42))?.swap_bytes();
+    if font_type & 1 != 0 {
+        Ok(FontFaceType::Vector)
+    } else {
+        Ok(FontFaceType::Bitmap)
+    }
+}
+
+// A 16-bit executable whose `RT_FONT` resources are `.fnt` fonts.
+fn analyze_fon(data: &[u8]) -> AnalysisResult {
+    let read_le_u16 = |offset: usize| tables::read_u16(data, offset).map(u16::swap_bytes);
+    let ne = truncated(tables::read_u32(data, 0x3c))?.swap_bytes() as usize;
+    if truncated(data.get(ne..ne + 2))? != b"NE" {
+        return Err(UnsupportedFontReason::UnknownFormat);
+    }
+    let mut offset = ne + truncated(read_le_u16(ne + 0x24))? as usize;
+    let align_shift = truncated(read_le_u16(offset))?;
+    if align_shift > 16 {
+        return Err(UnsupportedFontReason::UnknownFormat);
+    }
+    offset += 2;
+
+    let mut face_type = FontFaceType::Unknown;
+    let mut face_count = 0;
+    loop {
+        let type_id = truncated(read_le_u16(offset))?;
+        if type_id == 0 {
+            break;
+        }
+        let count = truncated(read_le_u16(offset + 2))? as usize;
+        offset += 8;
+        if type_id == RT_FONT {
+            for i in 0..count {
+                let resource = truncated(read_le_u16(offset + 12 * i))? as usize;
+                if face_count == 0 {
+                    face_type = fnt_face_type(data, resource << align_shift)?;
+                }
+                face_count += 1;
+            }
+        }
+        offset += 12 * count;
+    }
+    if face_count == 0 {
+        return Err(UnsupportedFontReason::UnknownFormat);
+    }
+    Ok(FontFileAnalysis::unsupported(
+        FontFileFormat::Fon,
+        face_type,
+        face_count,
+        UnsupportedFontReason::Fon,
+    ))
+}
+
+// A bare `.fnt`, or a Type 1 `.pfm`, which shares its header layout.
+fn analyze_fnt(data: &[u8]) -> AnalysisResult {
+    let version = truncated(tables::read_u16(data, 0))?.swap_bytes();
+    let size = truncated(tables::read_u32(data, 2))?.swap_bytes();
+    if size as usize != data.len() {
+        return Err(UnsupportedFontReason::UnknownFormat);
+    }
+    match version {
+        0x0100 => Ok(FontFileAnalysis::unsupported(
+            FontFileFormat::Type1,
+            FontFaceType::Type1,
+            1,
+            UnsupportedFontReason::Type1,
+        )),
+        0x0200 | 0x0300 => Ok(FontFileAnalysis::unsupported(
+            FontFileFormat::Fon,
+            fnt_face_type(data, 0)?,
+            1,
+            UnsupportedFontReason::Fon,
+        )),
+        _ => Err(UnsupportedFontReason::UnknownFormat),
+    }
+}
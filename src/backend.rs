@@ -0,0 +1,135 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The operations every font backend provides.
+//!
+//! On Windows the DirectWrite wrappers (`FontCollection`, `FontFace`, ...)
+//! implement these traits. The pure-Rust [`memory`](crate::memory) backend
+//! implements them on every platform, and off Windows the public font types
+//! are handles over whichever backend they were created from.
+//!
+//! Errors are reported as `HRESULT`s, the way the DirectWrite wrappers
+//! already do, so callers can treat every backend the same.
+
+use std::sync::Arc;
+
+use crate::{
+    FontFaceType, FontMetrics0, FontSimulations, FontStretch, FontStyle, FontWeight, GlyphOffset,
+    GlyphRunOutlineError, OutlineBuilder, DWRITE_FONT_AXIS_VALUE, DWRITE_FONT_SIMULATIONS, HRESULT,
+};
+
+/// Creates the objects that don't come from another object, the way
+/// `IDWriteFactory` does for DirectWrite.
+pub trait FactoryBackend {
+    /// Wraps an in-memory font file, failing if it isn't a font this backend
+    /// can read.
+    fn create_font_file_from_buffer(
+        &self,
+        data: Arc<dyn AsRef<[u8]> + Sync + Send>,
+    ) -> Result<Box<dyn FontFileBackend>, HRESULT>;
+}
+
+pub trait FontCollectionBackend {
+    fn font_family_count(&self) -> u32;
+
+    fn font_family(&self, index: u32) -> Result<Box<dyn FontFamilyBackend>, HRESULT>;
+
+    /// Find a family with the given name, ignoring case. Returns `None` if no
+    /// family with that name is found.
+    fn font_family_by_name(
+        &self,
+        family_name: &str,
+    ) -> Result<Option<Box<dyn FontFamilyBackend>>, HRESULT>;
+}
+
+pub trait FontFamilyBackend {
+    fn family_name(&self) -> Result<String, HRESULT>;
+
+    fn font_count(&self) -> u32;
+
+    fn font(&self, index: u32) -> Result<Box<dyn FontBackend>, HRESULT>;
+
+    fn first_matching_font(
+        &self,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Result<Box<dyn FontBackend>, HRESULT>;
+}
+
+pub trait FontBackend {
+    fn family_name(&self) -> String;
+
+    fn face_name(&self) -> String;
+
+    fn weight(&self) -> FontWeight;
+
+    fn stretch(&self) -> FontStretch;
+
+    fn style(&self) -> FontStyle;
+
+    fn simulations(&self) -> FontSimulations;
+
+    fn is_monospace(&self) -> Option<bool>;
+
+    fn create_font_face(&self) -> Box<dyn FontFaceBackend>;
+}
+
+pub trait FontFaceBackend {
+    fn files(&self) -> Result<Vec<Box<dyn FontFileBackend>>, HRESULT>;
+
+    fn face_type(&self) -> FontFaceType;
+
+    fn index(&self) -> u32;
+
+    fn simulations(&self) -> FontSimulations;
+
+    fn glyph_count(&self) -> u16;
+
+    fn metrics(&self) -> FontMetrics0;
+
+    fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT>;
+
+    /// Returns the contents of the OpenType table with the given tag, which
+    /// is byte-reversed just like `FontFace::font_table`'s.
+    fn font_table(&self, opentype_table_tag: u32) -> Result<Option<Vec<u8>>, HRESULT>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn glyph_run_outline(
+        &self,
+        em_size: f32,
+        glyph_indices: &[u16],
+        glyph_advances: Option<&[f32]>,
+        glyph_offsets: Option<&[GlyphOffset]>,
+        is_sideways: bool,
+        is_right_to_left: bool,
+        outline_builder: Box<dyn OutlineBuilder>,
+    ) -> Result<(), GlyphRunOutlineError>;
+
+    /// Returns the value of every variation axis of the face, or an empty
+    /// `Vec` if it isn't variable or the backend can't vary it.
+    fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT> {
+        Ok(vec![])
+    }
+
+    /// Creates a face of the same font with other axis values, or returns
+    /// `None` if the backend can't.
+    fn create_font_face_with_variations(
+        &self,
+        _simulations: DWRITE_FONT_SIMULATIONS,
+        _axis_values: &[DWRITE_FONT_AXIS_VALUE],
+    ) -> Option<Box<dyn FontFaceBackend>> {
+        None
+    }
+}
+
+pub trait FontFileBackend {
+    fn font_file_bytes(&self) -> Result<Vec<u8>, HRESULT>;
+
+    fn create_face(
+        &self,
+        face_index: u32,
+        simulations: DWRITE_FONT_SIMULATIONS,
+    ) -> Result<Box<dyn FontFaceBackend>, HRESULT>;
+}
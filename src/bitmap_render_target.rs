@@ -2,18 +2,19 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::c_void;
+use crate::sys::shared::windef::{HDC, RECT};
+use crate::sys::um::dcommon::DWRITE_MEASURING_MODE;
+use crate::sys::um::dwrite::IDWriteBitmapRenderTarget;
+use crate::sys::um::dwrite::{DWRITE_GLYPH_OFFSET, DWRITE_GLYPH_RUN};
+use crate::sys::um::wingdi::{GetCurrentObject, GetObjectW, BITMAP, OBJ_BITMAP, RGB};
 use std::cell::UnsafeCell;
 use std::mem::{size_of, zeroed};
 use std::slice;
-use winapi::ctypes::c_void;
-use winapi::shared::windef::{HDC, RECT};
-use winapi::um::dcommon::DWRITE_MEASURING_MODE;
-use winapi::um::dwrite::IDWriteBitmapRenderTarget;
-use winapi::um::dwrite::{DWRITE_GLYPH_OFFSET, DWRITE_GLYPH_RUN};
-use winapi::um::wingdi::{GetCurrentObject, GetObjectW, BITMAP, OBJ_BITMAP, RGB};
-use wio::com::ComPtr;
 
-use super::{FontFace, RenderingParams};
+use crate::font_face::FontFace;
+use crate::rendering_params::RenderingParams;
 
 pub struct BitmapRenderTarget {
     native: UnsafeCell<ComPtr<IDWriteBitmapRenderTarget>>,
@@ -41,6 +42,7 @@ impl BitmapRenderTarget {
         unsafe { (*self.native.get()).GetMemoryDC() }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_glyph_run(
         &self,
         baseline_origin_x: f32,
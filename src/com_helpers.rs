@@ -10,20 +10,19 @@ macro_rules! implement_iunknown {
                     riid: REFIID,
                     ppv_object: *mut *mut c_void,
                 ) -> HRESULT {
-                    use $crate::winapi::Interface;
-                    let this = if $crate::winapi::shared::guiddef::IsEqualGUID(
-                        &*riid,
-                        &$interface::uuidof(),
-                    ) {
-                        mem::transmute(unknown_this)
-                    } else if $crate::winapi::shared::guiddef::IsEqualGUID(
-                        &*riid,
-                        &IUnknown::uuidof(),
-                    ) {
-                        mem::transmute(unknown_this)
-                    } else {
-                        return $crate::winapi::shared::winerror::E_NOINTERFACE;
-                    };
+                    use $crate::sys::Interface;
+                    let this =
+                        if $crate::sys::shared::guiddef::IsEqualGUID(&*riid, &$interface::uuidof())
+                        {
+                            unknown_this as *mut $crate::sys::ctypes::c_void
+                        } else if $crate::sys::shared::guiddef::IsEqualGUID(
+                            &*riid,
+                            &IUnknown::uuidof(),
+                        ) {
+                            unknown_this as *mut $crate::sys::ctypes::c_void
+                        } else {
+                            return $crate::sys::shared::winerror::E_NOINTERFACE;
+                        };
 
                     (*unknown_this).AddRef();
                     *ppv_object = this;
@@ -59,22 +58,21 @@ macro_rules! implement_iunknown {
                 unsafe extern "system" fn QueryInterface(
                     unknown_this: *mut IUnknown,
                     riid: REFIID,
-                    ppvObject: *mut *mut $crate::winapi::ctypes::c_void,
+                    ppvObject: *mut *mut $crate::sys::ctypes::c_void,
                 ) -> HRESULT {
-                    use $crate::winapi::Interface;
-                    let this = if $crate::winapi::shared::guiddef::IsEqualGUID(
-                        &*riid,
-                        &$interface::uuidof(),
-                    ) {
-                        mem::transmute(unknown_this)
-                    } else if $crate::winapi::shared::guiddef::IsEqualGUID(
-                        &*riid,
-                        &IUnknown::uuidof(),
-                    ) {
-                        mem::transmute(unknown_this)
-                    } else {
-                        return $crate::winapi::shared::winerror::E_NOINTERFACE;
-                    };
+                    use $crate::sys::Interface;
+                    let this =
+                        if $crate::sys::shared::guiddef::IsEqualGUID(&*riid, &$interface::uuidof())
+                        {
+                            unknown_this as *mut $crate::sys::ctypes::c_void
+                        } else if $crate::sys::shared::guiddef::IsEqualGUID(
+                            &*riid,
+                            &IUnknown::uuidof(),
+                        ) {
+                            unknown_this as *mut $crate::sys::ctypes::c_void
+                        } else {
+                            return $crate::sys::shared::winerror::E_NOINTERFACE;
+                        };
 
                     (*unknown_this).AddRef();
                     *ppvObject = this;
@@ -0,0 +1,194 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// The DirectWrite wrappers as `backend` implementations. Everything here
+// just forwards to the inherent methods.
+
+use crate::sys::shared::winerror::DWRITE_E_FILEFORMAT;
+use std::sync::Arc;
+
+use crate::backend::{
+    FactoryBackend, FontBackend, FontCollectionBackend, FontFaceBackend, FontFamilyBackend,
+    FontFileBackend,
+};
+use crate::{
+    Factory, Font, FontCollection, FontFace, FontFaceType, FontFamily, FontFile, FontMetrics0,
+    FontSimulations, FontStretch, FontStyle, FontWeight, GlyphOffset, GlyphRunOutlineError,
+    OutlineBuilder, DWRITE_FONT_AXIS_VALUE, DWRITE_FONT_SIMULATIONS, HRESULT,
+};
+
+impl FactoryBackend for Factory {
+    fn create_font_file_from_buffer(
+        &self,
+        data: Arc<dyn AsRef<[u8]> + Sync + Send>,
+    ) -> Result<Box<dyn FontFileBackend>, HRESULT> {
+        FontFile::new_from_buffer_with_factory(self, data)
+            .map(|file| Box::new(file) as Box<dyn FontFileBackend>)
+            .ok_or(DWRITE_E_FILEFORMAT)
+    }
+}
+
+impl FontCollectionBackend for FontCollection {
+    fn font_family_count(&self) -> u32 {
+        self.get_font_family_count()
+    }
+
+    fn font_family(&self, index: u32) -> Result<Box<dyn FontFamilyBackend>, HRESULT> {
+        FontCollection::font_family(self, index)
+            .map(|family| Box::new(family) as Box<dyn FontFamilyBackend>)
+    }
+
+    fn font_family_by_name(
+        &self,
+        family_name: &str,
+    ) -> Result<Option<Box<dyn FontFamilyBackend>>, HRESULT> {
+        Ok(FontCollection::font_family_by_name(self, family_name)?
+            .map(|family| Box::new(family) as Box<dyn FontFamilyBackend>))
+    }
+}
+
+impl FontFamilyBackend for FontFamily {
+    fn family_name(&self) -> Result<String, HRESULT> {
+        FontFamily::family_name(self)
+    }
+
+    fn font_count(&self) -> u32 {
+        self.get_font_count()
+    }
+
+    fn font(&self, index: u32) -> Result<Box<dyn FontBackend>, HRESULT> {
+        FontFamily::font(self, index).map(|font| Box::new(font) as Box<dyn FontBackend>)
+    }
+
+    fn first_matching_font(
+        &self,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Result<Box<dyn FontBackend>, HRESULT> {
+        FontFamily::first_matching_font(self, weight, stretch, style)
+            .map(|font| Box::new(font) as Box<dyn FontBackend>)
+    }
+}
+
+impl FontBackend for Font {
+    fn family_name(&self) -> String {
+        Font::family_name(self)
+    }
+
+    fn face_name(&self) -> String {
+        Font::face_name(self)
+    }
+
+    fn weight(&self) -> FontWeight {
+        Font::weight(self)
+    }
+
+    fn stretch(&self) -> FontStretch {
+        Font::stretch(self)
+    }
+
+    fn style(&self) -> FontStyle {
+        Font::style(self)
+    }
+
+    fn simulations(&self) -> FontSimulations {
+        Font::simulations(self)
+    }
+
+    fn is_monospace(&self) -> Option<bool> {
+        Font::is_monospace(self)
+    }
+
+    fn create_font_face(&self) -> Box<dyn FontFaceBackend> {
+        Box::new(Font::create_font_face(self))
+    }
+}
+
+impl FontFaceBackend for FontFace {
+    fn files(&self) -> Result<Vec<Box<dyn FontFileBackend>>, HRESULT> {
+        Ok(FontFace::files(self)?
+            .into_iter()
+            .map(|file| Box::new(file) as Box<dyn FontFileBackend>)
+            .collect())
+    }
+
+    fn face_type(&self) -> FontFaceType {
+        self.get_type()
+    }
+
+    fn index(&self) -> u32 {
+        self.get_index()
+    }
+
+    fn simulations(&self) -> FontSimulations {
+        FontFace::simulations(self)
+    }
+
+    fn glyph_count(&self) -> u16 {
+        self.get_glyph_count()
+    }
+
+    fn metrics(&self) -> FontMetrics0 {
+        FontFace::metrics(self).metrics0()
+    }
+
+    fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT> {
+        FontFace::glyph_indices(self, code_points)
+    }
+
+    fn font_table(&self, opentype_table_tag: u32) -> Result<Option<Vec<u8>>, HRESULT> {
+        FontFace::font_table(self, opentype_table_tag)
+    }
+
+    fn glyph_run_outline(
+        &self,
+        em_size: f32,
+        glyph_indices: &[u16],
+        glyph_advances: Option<&[f32]>,
+        glyph_offsets: Option<&[GlyphOffset]>,
+        is_sideways: bool,
+        is_right_to_left: bool,
+        outline_builder: Box<dyn OutlineBuilder>,
+    ) -> Result<(), GlyphRunOutlineError> {
+        FontFace::glyph_run_outline(
+            self,
+            em_size,
+            glyph_indices,
+            glyph_advances,
+            glyph_offsets,
+            is_sideways,
+            is_right_to_left,
+            outline_builder,
+        )
+    }
+
+    fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT> {
+        FontFace::variations(self)
+    }
+
+    fn create_font_face_with_variations(
+        &self,
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axis_values: &[DWRITE_FONT_AXIS_VALUE],
+    ) -> Option<Box<dyn FontFaceBackend>> {
+        FontFace::create_font_face_with_variations(self, simulations, axis_values)
+            .map(|face| Box::new(face) as Box<dyn FontFaceBackend>)
+    }
+}
+
+impl FontFileBackend for FontFile {
+    fn font_file_bytes(&self) -> Result<Vec<u8>, HRESULT> {
+        FontFile::font_file_bytes(self)
+    }
+
+    fn create_face(
+        &self,
+        face_index: u32,
+        simulations: DWRITE_FONT_SIMULATIONS,
+    ) -> Result<Box<dyn FontFaceBackend>, HRESULT> {
+        FontFile::create_face(self, face_index, simulations)
+            .map(|face| Box::new(face) as Box<dyn FontFaceBackend>)
+    }
+}
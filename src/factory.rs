@@ -0,0 +1,144 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use crate::sys::com::ComPtr;
+use crate::sys::shared::guiddef::REFIID;
+use crate::sys::shared::winerror::{HRESULT_FROM_WIN32, S_OK};
+use crate::sys::um::dwrite::{IDWriteFactory, DWRITE_FACTORY_TYPE};
+use crate::sys::um::errhandlingapi::GetLastError;
+use crate::sys::um::libloaderapi::{GetProcAddress, LoadLibraryW};
+use crate::sys::um::unknwnbase::IUnknown;
+use crate::sys::um::winnt::{HRESULT, LPCSTR};
+use crate::sys::Interface;
+use std::ffi::CString;
+use std::mem;
+use std::os::raw::c_void;
+use std::ptr;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use super::FactoryType;
+use crate::helpers::ToWide;
+
+/// Owns an `IDWriteFactory`, the root object every other DirectWrite object
+/// is created from.
+///
+/// Most of the API uses the process-wide shared factory implicitly; the
+/// `_with_factory` constructors take one of these instead.
+pub struct Factory {
+    native: ComPtr<IDWriteFactory>,
+    factory_type: FactoryType,
+    /// Whether the loader of in-memory font files is registered with the
+    /// factory, shared with clones so that it's only registered once.
+    pub(crate) font_file_loader_registered: Arc<AtomicBool>,
+}
+
+impl Factory {
+    /// Creates a new factory by loading `dwrite.dll`.
+    ///
+    /// DirectWrite shares the internal state of a `Shared` factory, such as
+    /// its font cache, with every other shared factory in the process.
+    pub fn new(factory_type: FactoryType) -> Result<Factory, HRESULT> {
+        unsafe {
+            let native = create_factory(factory_type)?;
+            Ok(Factory::take(ComPtr::from_raw(native), factory_type))
+        }
+    }
+
+    /// Returns the process-wide factory used by the constructors that don't
+    /// take a [`Factory`].
+    pub fn shared() -> Result<Factory, HRESULT> {
+        let native = (*SHARED_FACTORY_RAW_PTR)? as *mut IDWriteFactory;
+        unsafe {
+            // The static keeps its own reference, so add one for this handle.
+            (*native).AddRef();
+            Ok(Factory::take(ComPtr::from_raw(native), FactoryType::Shared))
+        }
+    }
+
+    /// Wraps an existing factory, which may also be an `IDWriteFactory`
+    /// implemented in Rust.
+    pub fn take(native: ComPtr<IDWriteFactory>, factory_type: FactoryType) -> Factory {
+        Factory {
+            native,
+            factory_type,
+            font_file_loader_registered: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn factory_type(&self) -> FactoryType {
+        self.factory_type
+    }
+
+    /// Returns the wrapped `IDWriteFactory` without adding a reference.
+    ///
+    /// # Safety
+    ///
+    /// The pointer is only valid while `self` is alive, and the caller must
+    /// not `Release` it; `AddRef` it first to keep it for longer.
+    pub unsafe fn as_ptr(&self) -> *mut IDWriteFactory {
+        self.native.as_raw()
+    }
+
+    pub(crate) fn as_com_ptr(&self) -> ComPtr<IDWriteFactory> {
+        self.native.clone()
+    }
+}
+
+impl Clone for Factory {
+    fn clone(&self) -> Factory {
+        Factory {
+            native: self.as_com_ptr(),
+            factory_type: self.factory_type,
+            font_file_loader_registered: self.font_file_loader_registered.clone(),
+        }
+    }
+}
+
+lazy_static! {
+    static ref SHARED_FACTORY_RAW_PTR: Result<usize, HRESULT> =
+        unsafe { create_factory(FactoryType::Shared).map(|factory| factory as usize) };
+}
+
+pub(crate) fn shared_factory_ptr() -> *mut IDWriteFactory {
+    match *SHARED_FACTORY_RAW_PTR {
+        Ok(factory) => factory as *mut IDWriteFactory,
+        Err(hr) => panic!("failed to create the DirectWrite factory: {:#x}", hr),
+    }
+}
+
+fn last_error() -> HRESULT {
+    unsafe { HRESULT_FROM_WIN32(GetLastError()) }
+}
+
+unsafe fn create_factory(factory_type: FactoryType) -> Result<*mut IDWriteFactory, HRESULT> {
+    type DWriteCreateFactoryType =
+        extern "system" fn(DWRITE_FACTORY_TYPE, REFIID, *mut *mut IUnknown) -> HRESULT;
+
+    let dwrite_dll = LoadLibraryW("dwrite.dll".to_wide_null().as_ptr());
+    if dwrite_dll.is_null() {
+        return Err(last_error());
+    }
+    let create_factory_name = CString::new("DWriteCreateFactory").unwrap();
+    let dwrite_create_factory_ptr =
+        GetProcAddress(dwrite_dll, create_factory_name.as_ptr() as LPCSTR);
+    if dwrite_create_factory_ptr.is_null() {
+        return Err(last_error());
+    }
+
+    let dwrite_create_factory = mem::transmute::<*const c_void, DWriteCreateFactoryType>(
+        dwrite_create_factory_ptr as *const _,
+    );
+
+    let mut factory: *mut IDWriteFactory = ptr::null_mut();
+    let hr = dwrite_create_factory(
+        factory_type.t(),
+        &IDWriteFactory::uuidof(),
+        &mut factory as *mut *mut IDWriteFactory as *mut *mut IUnknown,
+    );
+    if hr != S_OK {
+        return Err(hr);
+    }
+    Ok(factory)
+}
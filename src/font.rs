@@ -2,42 +2,44 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::shared::minwindef::{FALSE, TRUE};
+use crate::sys::shared::winerror::S_OK;
+use crate::sys::um::dwrite::IDWriteFont;
+use crate::sys::um::dwrite::IDWriteFontFace;
+use crate::sys::um::dwrite::IDWriteFontFamily;
+use crate::sys::um::dwrite::IDWriteLocalizedStrings;
+use crate::sys::um::dwrite::DWRITE_FONT_METRICS;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_COPYRIGHT_NOTICE;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_DESCRIPTION;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_DESIGNER;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_DESIGNER_URL;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_DESIGN_SCRIPT_LANGUAGE_TAG;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_FONT_VENDOR_URL;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_FULL_NAME;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_ID;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_LICENSE_DESCRIPTION;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_LICENSE_INFO_URL;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_MANUFACTURER;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_POSTSCRIPT_CID_NAME;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_POSTSCRIPT_NAME;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_PREFERRED_FAMILY_NAMES;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_PREFERRED_SUBFAMILY_NAMES;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_SAMPLE_TEXT;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_SUPPORTED_SCRIPT_LANGUAGE_TAG;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_TRADEMARK;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_VERSION_STRINGS;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_WIN32_FAMILY_NAMES;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_WIN32_SUBFAMILY_NAMES;
+use crate::sys::um::dwrite::DWRITE_INFORMATIONAL_STRING_WWS_FAMILY_NAME;
+use crate::sys::um::dwrite_1::{IDWriteFont1, DWRITE_FONT_METRICS1};
 use std::cell::UnsafeCell;
 use std::mem;
 use std::ptr;
-use winapi::shared::minwindef::{FALSE, TRUE};
-use winapi::shared::winerror::S_OK;
-use winapi::um::dwrite::IDWriteFont;
-use winapi::um::dwrite::IDWriteFontFace;
-use winapi::um::dwrite::IDWriteFontFamily;
-use winapi::um::dwrite::IDWriteLocalizedStrings;
-use winapi::um::dwrite::DWRITE_FONT_METRICS;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_COPYRIGHT_NOTICE;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_DESCRIPTION;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_DESIGNER;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_DESIGNER_URL;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_DESIGN_SCRIPT_LANGUAGE_TAG;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_FONT_VENDOR_URL;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_FULL_NAME;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_ID;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_LICENSE_DESCRIPTION;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_LICENSE_INFO_URL;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_MANUFACTURER;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_POSTSCRIPT_CID_NAME;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_POSTSCRIPT_NAME;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_PREFERRED_FAMILY_NAMES;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_PREFERRED_SUBFAMILY_NAMES;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_SAMPLE_TEXT;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_SUPPORTED_SCRIPT_LANGUAGE_TAG;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_TRADEMARK;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_VERSION_STRINGS;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_WIN32_FAMILY_NAMES;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_WIN32_SUBFAMILY_NAMES;
-use winapi::um::dwrite::DWRITE_INFORMATIONAL_STRING_WWS_FAMILY_NAME;
-use winapi::um::dwrite_1::{IDWriteFont1, DWRITE_FONT_METRICS1};
-use wio::com::ComPtr;
 
 use super::*;
+use crate::font_face::FontFace;
+use crate::font_family::FontFamily;
 use helpers::*;
 
 pub struct Font {
@@ -61,15 +63,17 @@ impl Font {
             stretch: self.stretch(),
             style: self.style(),
             weight: self.weight(),
+            oblique_angle: None,
+            simulations: Some(self.simulations()),
         }
     }
 
     pub fn stretch(&self) -> FontStretch {
-        unsafe { mem::transmute::<u32, FontStretch>((*self.native.get()).GetStretch()) }
+        unsafe { FontStretch::from_u32((*self.native.get()).GetStretch()) }
     }
 
     pub fn style(&self) -> FontStyle {
-        unsafe { mem::transmute::<u32, FontStyle>((*self.native.get()).GetStyle()) }
+        unsafe { FontStyle::from_u32((*self.native.get()).GetStyle()) }
     }
 
     pub fn weight(&self) -> FontWeight {
@@ -84,7 +88,7 @@ impl Font {
     }
 
     pub fn simulations(&self) -> FontSimulations {
-        unsafe { mem::transmute::<u32, FontSimulations>((*self.native.get()).GetSimulations()) }
+        unsafe { FontSimulations::from_bits_truncate((*self.native.get()).GetSimulations()) }
     }
 
     pub fn family_name(&self) -> String {
@@ -93,7 +97,9 @@ impl Font {
             let hr = (*self.native.get()).GetFontFamily(&mut family);
             assert!(hr == 0);
 
-            FontFamily::take(ComPtr::from_raw(family)).name()
+            FontFamily::take(ComPtr::from_raw(family))
+                .family_name()
+                .unwrap()
         }
     }
 
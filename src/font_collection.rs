@@ -2,18 +2,21 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::shared::minwindef::{BOOL, FALSE, TRUE};
+use crate::sys::shared::winerror::S_OK;
+use crate::sys::um::dwrite::IDWriteFontCollectionLoader;
+use crate::sys::um::dwrite::{IDWriteFont, IDWriteFontCollection, IDWriteFontFamily};
+use crate::sys::um::winnt::HRESULT;
 use std::cell::UnsafeCell;
 use std::mem;
 use std::ptr;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use winapi::shared::minwindef::{BOOL, FALSE, TRUE};
-use winapi::shared::winerror::S_OK;
-use winapi::um::dwrite::IDWriteFontCollectionLoader;
-use winapi::um::dwrite::{IDWriteFont, IDWriteFontCollection, IDWriteFontFamily};
-use winapi::um::winnt::HRESULT;
-use wio::com::ComPtr;
-
-use super::{DWriteFactory, Font, FontDescriptor, FontFace, FontFamily};
+
+use super::{DWriteFactory, FontDescriptor};
+use crate::font::Font;
+use crate::font_face::FontFace;
+use crate::font_family::FontFamily;
 use crate::helpers::*;
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
@@ -132,6 +135,8 @@ impl FontCollection {
     }
 
     /// Find a font matching the given font descriptor in this [`FontCollection`].
+    /// Only exact matches are returned; use [`match_fonts`](crate::match_fonts)
+    /// to rank a family's fonts the way CSS does.
     pub fn font_from_descriptor(&self, desc: &FontDescriptor) -> Result<Option<Font>, HRESULT> {
         if let Some(family) = self.font_family_by_name(&desc.family_name)? {
             let font = family.first_matching_font(desc.weight, desc.stretch, desc.style)?;
@@ -147,6 +152,39 @@ impl FontCollection {
         Ok(None)
     }
 
+    /// Find a font matching the given font descriptor and create a
+    /// [`FontFace`] for it, with the descriptor's simulations if it has any,
+    /// and its oblique angle applied to the face's `slnt` axis if it has one.
+    pub fn font_face_from_descriptor(
+        &self,
+        desc: &FontDescriptor,
+    ) -> Result<Option<FontFace>, HRESULT> {
+        let font = match self.font_from_descriptor(desc)? {
+            Some(font) => font,
+            None => return Ok(None),
+        };
+        let face = font.create_font_face();
+        let mut axis_values = face.variations()?;
+        let varied = desc.apply_to_axis_values(&mut axis_values);
+        let simulations = desc.simulations.unwrap_or_else(|| face.simulations());
+        if !varied && simulations == face.simulations() {
+            return Ok(Some(face));
+        }
+        if let Some(face) = face.create_font_face_with_variations(simulations.bits(), &axis_values)
+        {
+            return Ok(Some(face));
+        }
+        // Faces without a font resource can still be created with other
+        // simulations from their file.
+        let files = face.files()?;
+        match files.first() {
+            Some(file) if !varied => Ok(Some(
+                file.create_face(face.get_index(), simulations.bits())?,
+            )),
+            _ => Ok(Some(face)),
+        }
+    }
+
     #[deprecated(note = "Use `font_from_face` instead.")]
     pub fn get_font_from_face(&self, face: &FontFace) -> Option<Font> {
         self.font_from_face(face).ok()
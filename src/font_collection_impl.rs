@@ -4,24 +4,24 @@
 
 // A temporary custom font collection that exists solely for the face-to-font mapping to work.
 
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::c_void;
+use crate::sys::shared::guiddef::REFIID;
+use crate::sys::shared::minwindef::{BOOL, FALSE, TRUE, ULONG};
+use crate::sys::shared::winerror::{E_INVALIDARG, S_OK};
+use crate::sys::um::dwrite::IDWriteFactory;
+use crate::sys::um::dwrite::IDWriteFontCollectionLoader;
+use crate::sys::um::dwrite::IDWriteFontCollectionLoaderVtbl;
+use crate::sys::um::dwrite::IDWriteFontFile;
+use crate::sys::um::dwrite::IDWriteFontFileEnumerator;
+use crate::sys::um::dwrite::IDWriteFontFileEnumeratorVtbl;
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::winnt::HRESULT;
 use std::mem;
 use std::sync::atomic::AtomicUsize;
-use winapi::ctypes::c_void;
-use winapi::shared::guiddef::REFIID;
-use winapi::shared::minwindef::{BOOL, FALSE, TRUE, ULONG};
-use winapi::shared::winerror::{E_INVALIDARG, S_OK};
-use winapi::um::dwrite::IDWriteFactory;
-use winapi::um::dwrite::IDWriteFontCollectionLoader;
-use winapi::um::dwrite::IDWriteFontCollectionLoaderVtbl;
-use winapi::um::dwrite::IDWriteFontFile;
-use winapi::um::dwrite::IDWriteFontFileEnumerator;
-use winapi::um::dwrite::IDWriteFontFileEnumeratorVtbl;
-use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
-use winapi::um::winnt::HRESULT;
-use wio::com::ComPtr;
 
 use crate::com_helpers::Com;
-use crate::FontFile;
+use crate::font_file::FontFile;
 
 static FONT_COLLECTION_LOADER_VTBL: IDWriteFontCollectionLoaderVtbl =
     IDWriteFontCollectionLoaderVtbl {
@@ -54,6 +54,7 @@ impl Com<IUnknown> for CustomFontCollectionLoaderImpl {
 }
 
 impl CustomFontCollectionLoaderImpl {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(font_files: &[FontFile]) -> ComPtr<IDWriteFontCollectionLoader> {
         unsafe {
             ComPtr::from_raw(
@@ -2,37 +2,44 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use std::cell::UnsafeCell;
-use std::mem::{self, zeroed};
-use std::slice;
-use std::{error, fmt, ptr};
-use winapi::ctypes::c_void;
-use winapi::shared::minwindef::{BOOL, FALSE, TRUE};
-use winapi::shared::winerror::S_OK;
-use winapi::um::dcommon::DWRITE_MEASURING_MODE;
-use winapi::um::dwrite::IDWriteRenderingParams;
-use winapi::um::dwrite::DWRITE_FONT_FACE_TYPE_TRUETYPE;
-use winapi::um::dwrite::{IDWriteFontFace, IDWriteFontFile};
-use winapi::um::dwrite::{DWRITE_FONT_FACE_TYPE_BITMAP, DWRITE_FONT_FACE_TYPE_CFF};
-use winapi::um::dwrite::{DWRITE_FONT_FACE_TYPE_RAW_CFF, DWRITE_FONT_FACE_TYPE_TYPE1};
-use winapi::um::dwrite::{DWRITE_FONT_FACE_TYPE_TRUETYPE_COLLECTION, DWRITE_FONT_FACE_TYPE_VECTOR};
-use winapi::um::dwrite::{DWRITE_FONT_SIMULATIONS, DWRITE_GLYPH_METRICS};
-use winapi::um::dwrite::{DWRITE_GLYPH_OFFSET, DWRITE_MATRIX, DWRITE_RENDERING_MODE};
-use winapi::um::dwrite::{DWRITE_RENDERING_MODE_DEFAULT, DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC};
-use winapi::um::dwrite_1::IDWriteFontFace1;
-use winapi::um::dwrite_3::{
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::c_void;
+use crate::sys::shared::minwindef::{BOOL, FALSE, TRUE};
+use crate::sys::shared::winerror::S_OK;
+use crate::sys::um::dcommon::DWRITE_MEASURING_MODE;
+use crate::sys::um::dwrite::IDWriteRenderingParams;
+use crate::sys::um::dwrite::DWRITE_FONT_FACE_TYPE_TRUETYPE;
+use crate::sys::um::dwrite::{IDWriteFontFace, IDWriteFontFile};
+use crate::sys::um::dwrite::{DWRITE_FONT_FACE_TYPE_BITMAP, DWRITE_FONT_FACE_TYPE_CFF};
+use crate::sys::um::dwrite::{DWRITE_FONT_FACE_TYPE_RAW_CFF, DWRITE_FONT_FACE_TYPE_TYPE1};
+use crate::sys::um::dwrite::{
+    DWRITE_FONT_FACE_TYPE_TRUETYPE_COLLECTION, DWRITE_FONT_FACE_TYPE_VECTOR,
+};
+use crate::sys::um::dwrite::{DWRITE_FONT_SIMULATIONS, DWRITE_GLYPH_METRICS};
+use crate::sys::um::dwrite::{DWRITE_GLYPH_OFFSET, DWRITE_MATRIX, DWRITE_RENDERING_MODE};
+use crate::sys::um::dwrite::{
+    DWRITE_RENDERING_MODE_DEFAULT, DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC,
+};
+use crate::sys::um::dwrite_1::IDWriteFontFace1;
+use crate::sys::um::dwrite_3::{
     IDWriteFontFace5, IDWriteFontResource, DWRITE_FONT_AXIS_ATTRIBUTES_VARIABLE,
     DWRITE_FONT_AXIS_VALUE,
 };
-use winapi::um::winnt::HRESULT;
-use winapi::Interface;
-use wio::com::ComPtr;
+use crate::sys::um::winnt::HRESULT;
+use crate::sys::Interface;
+use std::cell::UnsafeCell;
+use std::mem::{self, zeroed};
+use std::ptr;
+use std::slice;
 
-use super::{DWriteFactory, DefaultDWriteRenderParams, FontFile, FontMetrics};
+use super::{DWriteFactory, DefaultDWriteRenderParams};
 use crate::com_helpers::Com;
+use crate::font::FontMetrics;
+use crate::font_face_tables::FontFaceTables;
+use crate::font_file::FontFile;
 use crate::geometry_sink_impl::GeometrySinkImpl;
 use crate::outline_builder::OutlineBuilder;
-use crate::FontSimulations;
+use crate::{FontFaceType, FontSimulations, GlyphRunOutlineError, Tag};
 
 pub struct FontFace {
     native: UnsafeCell<ComPtr<IDWriteFontFace>>,
@@ -240,7 +247,7 @@ impl FontFace {
     /// Returns the contents of the OpenType table with the given tag.
     ///
     /// NB: The bytes of the tag are reversed! You probably want to use the `u32::swap_bytes()`
-    /// method on the tag value before calling this method.
+    /// method on the tag value before calling this method, or `font_table_by_tag` instead.
     pub fn font_table(&self, opentype_table_tag: u32) -> Result<Option<Vec<u8>>, HRESULT> {
         let mut table_data_ptr: *const u8 = ptr::null_mut();
         let mut table_size: u32 = 0;
@@ -270,6 +277,11 @@ impl FontFace {
         }
     }
 
+    /// Returns the contents of the OpenType table with the given tag.
+    pub fn font_table_by_tag(&self, tag: Tag) -> Result<Option<Vec<u8>>, HRESULT> {
+        self.font_table(tag.to_dwrite())
+    }
+
     pub fn get_recommended_rendering_mode(
         &self,
         em_size: f32,
@@ -310,6 +322,7 @@ impl FontFace {
     }
 
     #[deprecated(note = "Use `glyph_run_outline` instead.")]
+    #[allow(clippy::too_many_arguments)]
     pub fn get_glyph_run_outline(
         &self,
         em_size: f32,
@@ -332,6 +345,7 @@ impl FontFace {
         .unwrap()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn glyph_run_outline(
         &self,
         em_size: f32,
@@ -524,6 +538,29 @@ impl FontFace {
             .collect())
     }
 
+    /// Returns the value of the variation axis with the given tag, or `None` if
+    /// the font doesn't vary along it.
+    pub fn variation_by_tag(&self, tag: Tag) -> Result<Option<f32>, HRESULT> {
+        Ok(self
+            .variations()?
+            .iter()
+            .find(|axis_value| axis_value.axisTag == tag.to_dwrite())
+            .map(|axis_value| axis_value.value))
+    }
+
+    /// Like `create_font_face_with_variations`, but takes `(tag, value)` pairs.
+    pub fn create_font_face_with_variations_by_tag(
+        &self,
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axis_values: &[(Tag, f32)],
+    ) -> Option<FontFace> {
+        let axis_values: Vec<_> = axis_values
+            .iter()
+            .map(|&(tag, value)| tag.axis_value(value))
+            .collect();
+        self.create_font_face_with_variations(simulations, &axis_values)
+    }
+
     pub fn create_font_face_with_variations(
         &self,
         simulations: DWRITE_FONT_SIMULATIONS,
@@ -553,11 +590,25 @@ impl FontFace {
     }
 
     pub fn simulations(&self) -> FontSimulations {
-        unsafe {
-            std::mem::transmute::<DWRITE_FONT_SIMULATIONS, FontSimulations>(
-                (*self.native.get()).GetSimulations(),
-            )
-        }
+        unsafe { FontSimulations::from_bits_truncate((*self.native.get()).GetSimulations()) }
+    }
+}
+
+impl FontFaceTables for FontFace {
+    fn font_table_by_tag(&self, tag: Tag) -> Result<Option<Vec<u8>>, HRESULT> {
+        FontFace::font_table_by_tag(self, tag)
+    }
+
+    fn get_glyph_count(&self) -> u16 {
+        FontFace::get_glyph_count(self)
+    }
+
+    fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT> {
+        FontFace::glyph_indices(self, code_points)
+    }
+
+    fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT> {
+        FontFace::variations(self)
     }
 }
 
@@ -572,32 +623,3 @@ impl Clone for FontFace {
         }
     }
 }
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum FontFaceType {
-    Unknown,
-    Cff,
-    RawCff,
-    TrueType,
-    TrueTypeCollection,
-    Type1,
-    Vector,
-    Bitmap,
-}
-
-#[derive(Debug)]
-pub enum GlyphRunOutlineError {
-    InvalidInput,
-    Win32Error(HRESULT),
-}
-
-impl fmt::Display for GlyphRunOutlineError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::InvalidInput => write!(f, "Invalid input"),
-            Self::Win32Error(code) => write!(f, "{:#x}", code),
-        }
-    }
-}
-
-impl error::Error for GlyphRunOutlineError {}
@@ -0,0 +1,253 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// The `FontFace` accessors that are computed from the face's OpenType
+// tables rather than asked of the backend. Both `FontFace` types implement
+// the trait by forwarding the four required methods to their own, so the
+// parsing is written once and behaves the same on every platform.
+
+use crate::font_variations::{self, NamedInstance, VariationAxis};
+use crate::glyph_names::GlyphNames;
+use crate::rendering_mode::recommended_rendering_mode;
+use crate::tables::avar::AvarTable;
+use crate::tables::bitmap::BitmapTables;
+use crate::tables::cmap::CmapTable;
+use crate::tables::colr::{ColorGlyphs, ColrTable};
+use crate::tables::cpal::CpalTable;
+use crate::tables::fvar::FvarTable;
+use crate::tables::gasp::GaspTable;
+use crate::tables::kern::{self, KerningPair};
+use crate::tables::layout::LayoutTable;
+use crate::tables::name::NameTable;
+use crate::tables::os2::Os2Table;
+use crate::tables::stat::StatTable;
+use crate::tables::svg::SvgTable;
+use crate::{RenderingMode, Tag, DWRITE_FONT_AXIS_VALUE, DWRITE_MEASURING_MODE, HRESULT};
+
+/// Parsed views of a font face's OpenType tables. Only the first four
+/// methods need implementing; `FontFace` does so on every platform.
+pub trait FontFaceTables {
+    /// Returns the contents of the OpenType table with the given tag.
+    fn font_table_by_tag(&self, tag: Tag) -> Result<Option<Vec<u8>>, HRESULT>;
+
+    fn get_glyph_count(&self) -> u16;
+
+    fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT>;
+
+    /// Returns the face's variation axis values, or an empty `Vec` if it
+    /// isn't variable.
+    fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT>;
+
+    /// Parses every record of the `name` table, or returns `None` if the
+    /// face has no `name` table or it's malformed.
+    fn name_table(&self) -> Result<Option<NameTable>, HRESULT> {
+        Ok(self
+            .font_table_by_tag(Tag::NAME)?
+            .and_then(|data| NameTable::parse(&data)))
+    }
+
+    /// Parses the `OS/2` table, or returns `None` if the face has no `OS/2`
+    /// table or it's shorter than its version requires.
+    fn os2_table(&self) -> Result<Option<Os2Table>, HRESULT> {
+        Ok(self
+            .font_table_by_tag(Tag::OS2)?
+            .and_then(|data| Os2Table::parse(&data)))
+    }
+
+    /// Parses every subtable of the `cmap` table, or returns `None` if the
+    /// face has no `cmap` table or it's malformed.
+    fn cmap_table(&self) -> Result<Option<CmapTable>, HRESULT> {
+        Ok(self
+            .font_table_by_tag(Tag::CMAP)?
+            .and_then(|data| CmapTable::parse(&data)))
+    }
+
+    /// Lists every kerning pair in the face, sorted by glyph pair, from the
+    /// `GPOS` `kern` feature or else the `kern` table.
+    fn kerning_pairs(&self) -> Result<Vec<KerningPair>, HRESULT> {
+        let gpos = self.font_table_by_tag(Tag::GPOS)?;
+        let kern = self.font_table_by_tag(Tag::KERN)?;
+        Ok(kern::kerning_pairs(
+            gpos.as_deref(),
+            kern.as_deref(),
+            self.get_glyph_count(),
+        ))
+    }
+
+    /// Describes the face's variation axes, from the `fvar`, `STAT` and
+    /// `name` tables, or returns an empty `Vec` if the face isn't variable.
+    fn variation_axes(&self) -> Result<Vec<VariationAxis>, HRESULT> {
+        let fvar = match fvar_table(self)? {
+            Some(fvar) => fvar,
+            None => return Ok(vec![]),
+        };
+        let stat = self
+            .font_table_by_tag(Tag::STAT)?
+            .and_then(|data| StatTable::parse(&data));
+        let names = self.name_table()?;
+        Ok(font_variations::variation_axes(
+            &fvar,
+            stat.as_ref(),
+            names.as_ref(),
+        ))
+    }
+
+    /// Lists the face's named instances, from the `fvar` and `name` tables.
+    fn named_instances(&self) -> Result<Vec<NamedInstance>, HRESULT> {
+        let fvar = match fvar_table(self)? {
+            Some(fvar) => fvar,
+            None => return Ok(vec![]),
+        };
+        let names = self.name_table()?;
+        Ok(font_variations::named_instances(&fvar, names.as_ref()))
+    }
+
+    /// Parses the scripts, language systems and features of the `GSUB`
+    /// table, or returns `None` if the face has none or it's malformed.
+    fn gsub_table(&self) -> Result<Option<LayoutTable>, HRESULT> {
+        Ok(self
+            .font_table_by_tag(Tag::GSUB)?
+            .and_then(|data| LayoutTable::parse(&data)))
+    }
+
+    /// Parses the scripts, language systems and features of the `GPOS`
+    /// table, or returns `None` if the face has none or it's malformed.
+    fn gpos_table(&self) -> Result<Option<LayoutTable>, HRESULT> {
+        Ok(self
+            .font_table_by_tag(Tag::GPOS)?
+            .and_then(|data| LayoutTable::parse(&data)))
+    }
+
+    /// Lists every feature tag in `GSUB` and `GPOS`, sorted and without
+    /// duplicates.
+    fn feature_tags(&self) -> Result<Vec<Tag>, HRESULT> {
+        let mut tags = vec![];
+        for table in self.gsub_table()?.iter().chain(&self.gpos_table()?) {
+            tags.extend(table.feature_tags());
+        }
+        tags.sort();
+        tags.dedup();
+        Ok(tags)
+    }
+
+    /// Parses the `CPAL` table, or returns `None` if the face has no `CPAL`
+    /// table or it's malformed.
+    fn cpal_table(&self) -> Result<Option<CpalTable>, HRESULT> {
+        Ok(self
+            .font_table_by_tag(Tag::CPAL)?
+            .and_then(|data| CpalTable::parse(&data)))
+    }
+
+    /// Parses the `COLR` table and normalizes the face's axis values, for
+    /// resolving its color glyphs' layers or paint graphs, or returns `None`
+    /// if the face has no `COLR` table or it's malformed. The glyphs of the
+    /// trees' [`Paint::Glyph`] leaves can be outlined with
+    /// `glyph_run_outline`. Keep the result for the face's lifetime rather
+    /// than calling this per glyph.
+    ///
+    /// [`Paint::Glyph`]: crate::tables::colr::Paint::Glyph
+    fn color_glyphs(&self) -> Result<Option<ColorGlyphs>, HRESULT> {
+        let table = match self
+            .font_table_by_tag(Tag::COLR)?
+            .and_then(|data| ColrTable::parse(&data))
+        {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+        Ok(Some(ColorGlyphs::new(table, normalized_coordinates(self)?)))
+    }
+
+    /// Parses the face's embedded bitmaps, from `sbix`, `CBLC` and `EBLC`,
+    /// for looking up its strikes and glyph bitmaps. Every call copies the
+    /// tables out of the face, so keep the result for the face's lifetime.
+    fn bitmap_tables(&self) -> Result<BitmapTables, HRESULT> {
+        let sbix = self.font_table_by_tag(Tag::SBIX)?;
+        let cblc = self.font_table_by_tag(Tag::CBLC)?;
+        let cbdt = self.font_table_by_tag(Tag::CBDT)?;
+        let eblc = self.font_table_by_tag(Tag::EBLC)?;
+        let ebdt = self.font_table_by_tag(Tag::EBDT)?;
+        Ok(BitmapTables::parse(
+            sbix.as_deref(),
+            cblc.as_deref(),
+            cbdt.as_deref(),
+            eblc.as_deref(),
+            ebdt.as_deref(),
+            self.get_glyph_count(),
+        ))
+    }
+
+    /// Parses the `SVG ` table, for looking up the documents that draw the
+    /// face's glyphs, or returns `None` if the face has none or it's
+    /// malformed. Keep the result for the face's lifetime rather than
+    /// calling this per glyph.
+    fn svg_table(&self) -> Result<Option<SvgTable>, HRESULT> {
+        Ok(self
+            .font_table_by_tag(Tag::SVG)?
+            .and_then(|data| SvgTable::parse(&data)))
+    }
+
+    /// Reads the face's glyph names, from `post` or else the CFF charset,
+    /// for naming its glyphs and finding glyphs by name. Keep the result for
+    /// the face's lifetime rather than calling this per glyph.
+    fn glyph_names(&self) -> Result<GlyphNames, HRESULT> {
+        let post = self.font_table_by_tag(Tag::POST)?;
+        let head = self.font_table_by_tag(Tag::HEAD)?;
+        let cff = self.font_table_by_tag(Tag::CFF)?;
+        Ok(GlyphNames::parse(
+            post.as_deref(),
+            head.as_deref(),
+            cff.as_deref(),
+            self.cmap_table()?,
+        ))
+    }
+
+    /// Recommends how to render text of `em_size` DIPs at `pixels_per_dip`
+    /// from the face's `gasp` table, without asking DirectWrite, so that
+    /// every platform makes the same choice. See
+    /// [`recommended_rendering_mode`](crate::recommended_rendering_mode).
+    fn recommended_rendering_mode(
+        &self,
+        em_size: f32,
+        pixels_per_dip: f32,
+        measuring_mode: DWRITE_MEASURING_MODE,
+        outline_threshold: Option<f32>,
+    ) -> Result<RenderingMode, HRESULT> {
+        let ranges = self
+            .font_table_by_tag(Tag::GASP)?
+            .and_then(|data| GaspTable::parse(&data))
+            .map(|gasp| gasp.ranges)
+            .unwrap_or_default();
+        Ok(recommended_rendering_mode(
+            &ranges,
+            em_size,
+            pixels_per_dip,
+            measuring_mode,
+            outline_threshold,
+        ))
+    }
+}
+
+fn fvar_table<F: FontFaceTables + ?Sized>(face: &F) -> Result<Option<FvarTable>, HRESULT> {
+    Ok(face
+        .font_table_by_tag(Tag::FVAR)?
+        .and_then(|data| FvarTable::parse(&data)))
+}
+
+/// Returns the face's normalized variation coordinates, in `fvar` axis
+/// order, or an empty `Vec` if it isn't variable.
+fn normalized_coordinates<F: FontFaceTables + ?Sized>(face: &F) -> Result<Vec<f32>, HRESULT> {
+    let fvar = match fvar_table(face)? {
+        Some(fvar) => fvar,
+        None => return Ok(vec![]),
+    };
+    let values: Vec<_> = face
+        .variations()?
+        .iter()
+        .map(|axis_value| (Tag::from_dwrite(axis_value.axisTag), axis_value.value))
+        .collect();
+    let avar = face
+        .font_table_by_tag(Tag::AVAR)?
+        .and_then(|data| AvarTable::parse(&data));
+    Ok(fvar.normalize(&values, avar.as_ref()))
+}
@@ -2,12 +2,16 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::um::dwrite_2::{IDWriteFactory2, IDWriteFontFallback};
 use std::cell::UnsafeCell;
 use std::ptr::null_mut;
-use winapi::um::dwrite_2::{IDWriteFactory2, IDWriteFontFallback};
-use wio::com::ComPtr;
 
 use super::*;
+use crate::factory::Factory;
+use crate::font::Font;
+use crate::font_collection::FontCollection;
+use crate::text_analysis_source::TextAnalysisSource;
 
 pub struct FontFallback {
     native: UnsafeCell<ComPtr<IDWriteFontFallback>>,
@@ -23,17 +27,29 @@ pub struct FallbackResult {
 }
 
 impl FontFallback {
+    /// Returns the system fallback, or `None` if DirectWrite predates
+    /// `IDWriteFactory2` or fails to provide one.
     pub fn get_system_fallback() -> Option<FontFallback> {
-        unsafe {
-            let factory = ComPtr::from_raw(DWriteFactory());
-            let factory2: Option<ComPtr<IDWriteFactory2>> = factory.cast().ok();
-            std::mem::forget(factory);
-            let factory2 = factory2?;
-            let mut native = null_mut();
-            let hr = factory2.GetSystemFontFallback(&mut native);
-            assert_eq!(hr, 0);
-            Some(Self::take(ComPtr::from_raw(native)))
+        unsafe { Self::get_system_fallback_in(DWriteFactory()) }
+    }
+
+    /// Like [`get_system_fallback`](FontFallback::get_system_fallback), but
+    /// with the fallback of `factory`.
+    pub fn get_system_fallback_with_factory(factory: &Factory) -> Option<FontFallback> {
+        unsafe { Self::get_system_fallback_in(factory.as_ptr()) }
+    }
+
+    unsafe fn get_system_fallback_in(native_factory: *mut IDWriteFactory) -> Option<FontFallback> {
+        let factory = ComPtr::from_raw(native_factory);
+        let factory2: Option<ComPtr<IDWriteFactory2>> = factory.cast().ok();
+        std::mem::forget(factory);
+        let factory2 = factory2?;
+        let mut native = null_mut();
+        let hr = factory2.GetSystemFontFallback(&mut native);
+        if hr != S_OK {
+            return None;
         }
+        Some(Self::take(ComPtr::from_raw(native)))
     }
 
     pub fn take(native: ComPtr<IDWriteFontFallback>) -> FontFallback {
@@ -48,6 +64,7 @@ impl FontFallback {
     }
 
     // TODO: map_characters (main function)
+    #[allow(clippy::too_many_arguments)]
     pub fn map_characters(
         &self,
         text_analysis_source: &TextAnalysisSource,
@@ -59,6 +76,7 @@ impl FontFallback {
         base_style: FontStyle,
         base_stretch: FontStretch,
     ) -> FallbackResult {
+        let mut base_family = base_family.map(|s| s.to_wide_null());
         unsafe {
             let mut font = null_mut();
             let mut mapped_length = 0;
@@ -68,9 +86,7 @@ impl FontFallback {
                 text_position,
                 text_length,
                 base_font.as_ptr(),
-                base_family
-                    .map(|s| s.to_wide_null().as_mut_ptr())
-                    .unwrap_or(null_mut()),
+                base_family.as_mut().map_or(null_mut(), |s| s.as_mut_ptr()),
                 base_weight.t(),
                 base_style.t(),
                 base_stretch.t(),
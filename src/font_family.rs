@@ -2,13 +2,15 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::um::dwrite::IDWriteLocalizedStrings;
+use crate::sys::um::dwrite::{IDWriteFont, IDWriteFontCollection, IDWriteFontFamily};
 use std::cell::UnsafeCell;
 use std::ptr;
-use winapi::um::dwrite::IDWriteLocalizedStrings;
-use winapi::um::dwrite::{IDWriteFont, IDWriteFontCollection, IDWriteFontFamily};
-use wio::com::ComPtr;
 
 use super::*;
+use crate::font::Font;
+use crate::font_collection::FontCollection;
 use helpers::*;
 
 pub struct FontFamily {
@@ -2,24 +2,26 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::c_void;
+use crate::sys::ffi::{OsStrExt, OsStringExt};
+use crate::sys::shared::winerror::S_OK;
+use crate::sys::um::dwrite::{IDWriteFontFace, IDWriteFontFile, IDWriteFontFileStream};
+use crate::sys::um::dwrite::{IDWriteFontFileLoader, IDWriteLocalFontFileLoader};
+use crate::sys::um::dwrite::{DWRITE_FONT_FACE_TYPE, DWRITE_FONT_FILE_TYPE_UNKNOWN};
+use crate::sys::um::dwrite::{DWRITE_FONT_FACE_TYPE_UNKNOWN, DWRITE_FONT_SIMULATIONS};
+use crate::sys::um::winnt::HRESULT;
 use std::cell::UnsafeCell;
 use std::ffi::OsString;
-use std::os::windows::ffi::{OsStrExt, OsStringExt};
 use std::path::Path;
 use std::path::PathBuf;
 use std::ptr;
 use std::slice;
 use std::sync::Arc;
-use winapi::ctypes::c_void;
-use winapi::shared::winerror::S_OK;
-use winapi::um::dwrite::{IDWriteFontFace, IDWriteFontFile, IDWriteFontFileStream};
-use winapi::um::dwrite::{IDWriteFontFileLoader, IDWriteLocalFontFileLoader};
-use winapi::um::dwrite::{DWRITE_FONT_FACE_TYPE, DWRITE_FONT_FILE_TYPE_UNKNOWN};
-use winapi::um::dwrite::{DWRITE_FONT_FACE_TYPE_UNKNOWN, DWRITE_FONT_SIMULATIONS};
-use winapi::um::winnt::HRESULT;
-use wio::com::ComPtr;
 
 use super::DWriteFactory;
+use crate::analysis::FontFileAnalysis;
+use crate::factory::Factory;
 use crate::font_face::FontFace;
 use crate::font_file_loader_impl::DataFontHelper;
 
@@ -28,6 +30,8 @@ pub struct FontFile {
     stream: UnsafeCell<Option<ComPtr<IDWriteFontFileStream>>>,
     data_key: usize,
     face_type: DWRITE_FONT_FACE_TYPE,
+    // The factory this file was created with, if it isn't the shared one.
+    factory: Option<Factory>,
 }
 
 impl FontFile {
@@ -54,6 +58,7 @@ impl FontFile {
                 stream: UnsafeCell::new(None),
                 data_key: 0,
                 face_type: DWRITE_FONT_FACE_TYPE_UNKNOWN,
+                factory: None,
             };
 
             if ff.analyze() == 0 {
@@ -70,13 +75,35 @@ impl FontFile {
     }
 
     pub fn new_from_buffer(data: Arc<dyn AsRef<[u8]> + Sync + Send>) -> Option<FontFile> {
-        let (font_file, font_file_stream, key) = DataFontHelper::register_font_buffer(data);
+        Self::new_from_buffer_in(None, data)
+    }
+
+    /// Like [`FontFile::new_from_buffer`], but registers the buffer with
+    /// `factory` and creates faces from it.
+    pub fn new_from_buffer_with_factory(
+        factory: &Factory,
+        data: Arc<dyn AsRef<[u8]> + Sync + Send>,
+    ) -> Option<FontFile> {
+        Self::new_from_buffer_in(Some(factory.clone()), data)
+    }
+
+    fn new_from_buffer_in(
+        factory: Option<Factory>,
+        data: Arc<dyn AsRef<[u8]> + Sync + Send>,
+    ) -> Option<FontFile> {
+        // Don't register buffers that DirectWrite is bound to reject.
+        if !FontFileAnalysis::analyze((*data).as_ref()).is_supported() {
+            return None;
+        }
+        let (font_file, font_file_stream, key) =
+            DataFontHelper::register_font_buffer(factory.as_ref(), data).ok()?;
 
         let mut ff = FontFile {
             native: UnsafeCell::new(font_file),
             stream: UnsafeCell::new(Some(font_file_stream)),
             data_key: key,
             face_type: DWRITE_FONT_FACE_TYPE_UNKNOWN,
+            factory,
         };
 
         if ff.analyze() == 0 {
@@ -92,13 +119,21 @@ impl FontFile {
     }
 
     pub fn analyze_buffer(buffer: Arc<dyn AsRef<[u8]> + Sync + Send>) -> u32 {
-        let (font_file, font_file_stream, key) = DataFontHelper::register_font_buffer(buffer);
+        if !FontFileAnalysis::analyze((*buffer).as_ref()).is_supported() {
+            return 0;
+        }
+        let (font_file, font_file_stream, key) =
+            match DataFontHelper::register_font_buffer(None, buffer) {
+                Ok(registered) => registered,
+                Err(_) => return 0,
+            };
 
         let mut ff = FontFile {
             native: UnsafeCell::new(font_file),
             stream: UnsafeCell::new(Some(font_file_stream)),
             data_key: key,
             face_type: DWRITE_FONT_FACE_TYPE_UNKNOWN,
+            factory: None,
         };
 
         ff.analyze()
@@ -131,6 +166,7 @@ impl FontFile {
             stream: UnsafeCell::new(None),
             data_key: 0,
             face_type: DWRITE_FONT_FACE_TYPE_UNKNOWN,
+            factory: None,
         };
         ff.analyze();
         ff
@@ -261,7 +297,11 @@ impl FontFile {
         unsafe {
             let mut face: *mut IDWriteFontFace = ptr::null_mut();
             let ptr = self.as_com_ptr();
-            let hr = (*DWriteFactory()).CreateFontFace(
+            let factory = match self.factory {
+                Some(ref factory) => factory.as_ptr(),
+                None => DWriteFactory(),
+            };
+            let hr = (*factory).CreateFontFace(
                 self.face_type,
                 1,
                 &ptr.as_raw(),
@@ -286,6 +326,7 @@ impl Clone for FontFile {
                 stream: UnsafeCell::new((*self.stream.get()).clone()),
                 data_key: self.data_key,
                 face_type: self.face_type,
+                factory: self.factory.clone(),
             }
         }
     }
@@ -1,24 +1,27 @@
 #![allow(non_snake_case, non_upper_case_globals)]
 
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::c_void;
+use crate::sys::shared::basetsd::{UINT32, UINT64};
+use crate::sys::shared::guiddef::REFIID;
+use crate::sys::shared::minwindef::ULONG;
+use crate::sys::shared::winerror::{
+    DWRITE_E_ALREADYREGISTERED, E_FAIL, E_INVALIDARG, E_NOTIMPL, S_OK,
+};
+use crate::sys::um::dwrite::{IDWriteFactory, IDWriteFontFile};
+use crate::sys::um::dwrite::{IDWriteFontFileLoader, IDWriteFontFileLoaderVtbl};
+use crate::sys::um::dwrite::{IDWriteFontFileStream, IDWriteFontFileStreamVtbl};
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::winnt::HRESULT;
 use std::collections::HashMap;
 use std::marker::Send;
 use std::sync::atomic::AtomicUsize;
 use std::sync::{atomic, Arc, Mutex};
 use std::{mem, ptr};
-use winapi::ctypes::c_void;
-use winapi::shared::basetsd::{UINT32, UINT64};
-use winapi::shared::guiddef::REFIID;
-use winapi::shared::minwindef::ULONG;
-use winapi::shared::winerror::{E_FAIL, E_INVALIDARG, E_NOTIMPL, S_OK};
-use winapi::um::dwrite::IDWriteFontFile;
-use winapi::um::dwrite::{IDWriteFontFileLoader, IDWriteFontFileLoaderVtbl};
-use winapi::um::dwrite::{IDWriteFontFileStream, IDWriteFontFileStreamVtbl};
-use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
-use winapi::um::winnt::HRESULT;
-use wio::com::ComPtr;
 
 use super::DWriteFactory;
 use crate::com_helpers::*;
+use crate::factory::Factory;
 
 struct FontFileLoader;
 
@@ -169,7 +172,7 @@ struct FontFileStreamPtr(*mut IDWriteFontFileStream);
 
 unsafe impl Send for FontFileStreamPtr {}
 
-static mut FONT_FILE_KEY: atomic::AtomicUsize = AtomicUsize::new(0);
+static FONT_FILE_KEY: atomic::AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Clone)]
 struct FontFileLoaderWrapper(ComPtr<IDWriteFontFileLoader>);
@@ -184,23 +187,47 @@ lazy_static! {
         unsafe {
             let ffl_native = FontFileLoader::new();
             let ffl = ComPtr::<IDWriteFontFileLoader>::from_raw(ffl_native.into_interface());
-            let hr = (*DWriteFactory()).RegisterFontFileLoader(ffl.as_raw());
-            assert!(hr == 0);
             Mutex::new(FontFileLoaderWrapper(ffl))
         }
     };
+    static ref SHARED_FACTORY_LOADER: Result<(), HRESULT> =
+        unsafe { DataFontHelper::register_loader(DWriteFactory()) };
 }
 
 pub(crate) struct DataFontHelper;
 
 impl DataFontHelper {
+    /// Registers `font_data` with `factory`, or the shared factory if it's
+    /// `None`, registering the loader with the factory first if need be.
     pub(crate) fn register_font_buffer(
+        factory: Option<&Factory>,
         font_data: Arc<dyn AsRef<[u8]> + Sync + Send>,
-    ) -> (
-        ComPtr<IDWriteFontFile>,
-        ComPtr<IDWriteFontFileStream>,
-        usize,
-    ) {
+    ) -> Result<
+        (
+            ComPtr<IDWriteFontFile>,
+            ComPtr<IDWriteFontFileStream>,
+            usize,
+        ),
+        HRESULT,
+    > {
+        let native_factory = match factory {
+            Some(factory) => {
+                if !factory
+                    .font_file_loader_registered
+                    .load(atomic::Ordering::Acquire)
+                {
+                    unsafe { Self::register_loader(factory.as_ptr())? };
+                    factory
+                        .font_file_loader_registered
+                        .store(true, atomic::Ordering::Release);
+                }
+                unsafe { factory.as_ptr() }
+            }
+            None => {
+                (*SHARED_FACTORY_LOADER)?;
+                DWriteFactory()
+            }
+        };
         unsafe {
             let key = FONT_FILE_KEY.fetch_add(1, atomic::Ordering::Relaxed);
             let font_file_stream_native = FontFileStream::new(key, font_data);
@@ -215,18 +242,31 @@ impl DataFontHelper {
             let mut font_file: *mut IDWriteFontFile = ptr::null_mut();
             {
                 let loader = FONT_FILE_LOADER.lock().unwrap();
-                let hr = (*DWriteFactory()).CreateCustomFontFileReference(
-                    mem::transmute(&key),
+                let hr = (*native_factory).CreateCustomFontFileReference(
+                    &key as *const usize as *const c_void,
                     mem::size_of::<usize>() as UINT32,
                     loader.0.as_raw(),
                     &mut font_file,
                 );
-                assert!(hr == S_OK);
+                if hr != S_OK {
+                    return Err(hr);
+                }
             }
             let font_file = ComPtr::from_raw(font_file);
 
-            (font_file, font_file_stream, key)
+            Ok((font_file, font_file_stream, key))
+        }
+    }
+
+    /// Registers the loader with `factory`, which may already have it if
+    /// another wrapper of the same factory registered it.
+    unsafe fn register_loader(factory: *mut IDWriteFactory) -> Result<(), HRESULT> {
+        let loader = FONT_FILE_LOADER.lock().unwrap();
+        let hr = (*factory).RegisterFontFileLoader(loader.0.as_raw());
+        if hr != S_OK && hr != DWRITE_E_ALREADYREGISTERED {
+            return Err(hr);
         }
+        Ok(())
     }
 
     fn unregister_font_data(key: usize) {
@@ -0,0 +1,174 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The font matching algorithm from CSS Fonts Level 4 (§5.2), which picks a
+//! face from a family by stretch, then style, then weight.
+//!
+//! DirectWrite's `GetFirstMatchingFont` weighs these differently, so callers
+//! that need to agree with browsers can rank a family's faces here instead.
+
+use std::cmp::Ordering;
+
+use crate::{FontDescriptor, FontStretch, FontStyle, FontWeight};
+
+// Each property's search visits candidates in a few passes, say heavier
+// weights and then lighter ones. A distance is the pass number times this
+// plus the difference from the desired value, which is always smaller.
+const PASS: f32 = 10000.;
+
+/// How far a candidate is from the desired font for each property, in the
+/// order the algorithm considers them. Smaller is better, and distances
+/// compare lexicographically.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct MatchDistance {
+    pub stretch: f32,
+    pub style: f32,
+    pub weight: f32,
+}
+
+/// A candidate ranked by [`match_fonts`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FontMatch {
+    /// The candidate's index in the slice passed to `match_fonts`.
+    pub index: usize,
+    pub distance: MatchDistance,
+}
+
+impl MatchDistance {
+    /// Returns how far `candidate` is from `desired`. Family names aren't
+    /// compared; the candidates are assumed to be faces of one family.
+    pub fn between(desired: &FontDescriptor, candidate: &FontDescriptor) -> MatchDistance {
+        MatchDistance {
+            stretch: stretch_distance(desired.stretch, candidate.stretch),
+            style: style_distance(desired, candidate),
+            weight: weight_distance(desired.weight, candidate.weight),
+        }
+    }
+}
+
+/// Ranks `candidates` against `desired`, best match first. The first entry
+/// is the face CSS would select; candidates at the same distance keep their
+/// relative order.
+pub fn match_fonts(desired: &FontDescriptor, candidates: &[FontDescriptor]) -> Vec<FontMatch> {
+    let mut matches: Vec<_> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| FontMatch {
+            index,
+            distance: MatchDistance::between(desired, candidate),
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        a.distance
+            .partial_cmp(&b.distance)
+            .unwrap_or(Ordering::Equal)
+    });
+    matches
+}
+
+fn stretch_percentage(stretch: FontStretch) -> f32 {
+    stretch.to_percentage().unwrap_or(100.)
+}
+
+// Narrower requests look at narrower faces first, wider requests at wider
+// ones.
+fn stretch_distance(desired: FontStretch, candidate: FontStretch) -> f32 {
+    let desired = stretch_percentage(desired);
+    let candidate = stretch_percentage(candidate);
+    let preferred = if desired <= 100. {
+        candidate <= desired
+    } else {
+        candidate >= desired
+    };
+    let pass = if preferred { 0. } else { PASS };
+    pass + (candidate - desired).abs()
+}
+
+// Light requests look at lighter faces first and bold requests at heavier
+// ones. Requests from 400 to 500 first try heavier faces up to 500, then
+// lighter faces, then faces heavier than 500.
+fn weight_distance(desired: FontWeight, candidate: FontWeight) -> f32 {
+    let desired = desired.to_u32() as f32;
+    let candidate = candidate.to_u32() as f32;
+    let pass = if (400. ..=500.).contains(&desired) {
+        if candidate >= desired && candidate <= 500. {
+            0.
+        } else if candidate < desired {
+            1.
+        } else {
+            2.
+        }
+    } else if desired < 400. {
+        if candidate <= desired {
+            0.
+        } else {
+            1.
+        }
+    } else if candidate >= desired {
+        0.
+    } else {
+        1.
+    };
+    pass * PASS + (candidate - desired).abs()
+}
+
+fn oblique_angle(descriptor: &FontDescriptor) -> f32 {
+    descriptor
+        .oblique_angle
+        .filter(|angle| !angle.is_nan())
+        .unwrap_or(FontDescriptor::DEFAULT_OBLIQUE_ANGLE)
+}
+
+// Angles at least as slanted as the request come first, then less slanted
+// ones. Requests under 11° first stay under 11°, then try steeper angles
+// last, mirroring the weight search around 400–500.
+fn oblique_angle_distance(desired: f32, candidate: f32) -> f32 {
+    // Negative angles lean the other way; search them as mirror images.
+    let (desired, candidate) = if desired < 0. {
+        (-desired, -candidate)
+    } else {
+        (desired, candidate)
+    };
+    let pass = if desired < 11. {
+        if candidate >= desired && candidate < 11. {
+            0.
+        } else if candidate < desired {
+            1.
+        } else {
+            2.
+        }
+    } else if candidate >= desired {
+        0.
+    } else {
+        1.
+    };
+    pass * (PASS / 10.) + (candidate - desired).abs()
+}
+
+// Italic requests try italic, then oblique, then normal faces; oblique
+// requests try oblique, then italic, then normal faces; and normal requests
+// try normal, then oblique, then italic faces. Oblique faces are searched
+// by angle, starting from the requested one, 14° for italic or 0° for
+// normal.
+fn style_distance(desired: &FontDescriptor, candidate: &FontDescriptor) -> f32 {
+    use FontStyle::*;
+
+    let (passes, desired_angle) = match desired.style {
+        Italic => (
+            [Italic, Oblique, Normal],
+            FontDescriptor::DEFAULT_OBLIQUE_ANGLE,
+        ),
+        Oblique => ([Oblique, Italic, Normal], oblique_angle(desired)),
+        Normal => ([Normal, Oblique, Italic], 0.),
+    };
+    let pass = passes
+        .iter()
+        .position(|&style| style == candidate.style)
+        .unwrap_or(passes.len()) as f32;
+    let angle_distance = match candidate.style {
+        Oblique => oblique_angle_distance(desired_angle, oblique_angle(candidate)),
+        _ => 0.,
+    };
+    pass * PASS + angle_distance
+}
@@ -0,0 +1,610 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Font descriptions written as strings: the CSS `font` shorthand, such as
+//! `italic bold condensed 12px "Segoe UI", sans-serif`, and fontconfig
+//! patterns, such as `DejaVu Sans-12:weight=200:slant=italic`.
+
+use std::{error, fmt};
+
+use crate::{FontDescriptor, FontPropertyError, FontStretch, FontStyle, FontWeight, Tag};
+
+/// A font size, in the units it was written in.
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FontSize {
+    Pixels(f32),
+    Points(f32),
+}
+
+/// A [`FontDescriptor`] along with the parts of a font description that
+/// don't belong in one.
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontSpec {
+    /// The font to look for, named after the first family listed.
+    pub descriptor: FontDescriptor,
+    /// The families listed after the first, to try in order if it's missing.
+    pub fallback_families: Vec<String>,
+    pub size: Option<FontSize>,
+    /// Variation axis values to apply, fontconfig's `fontvariations`.
+    pub variations: Vec<(Tag, f32)>,
+}
+
+/// The error returned when a font description can't be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseFontSpecError {
+    /// A quoted family name is missing its closing quote.
+    UnterminatedString,
+    /// No family was given.
+    MissingFamily,
+    /// The CSS shorthand has no font size.
+    MissingSize,
+    /// A word or value that doesn't fit where it appears.
+    Unexpected(String),
+    /// A property was given twice, such as two weights.
+    Duplicate(&'static str),
+    /// A property value that can't be represented, such as `small-caps` or a
+    /// size in `em`s.
+    Unsupported(String),
+    InvalidProperty(FontPropertyError),
+}
+
+impl fmt::Display for ParseFontSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnterminatedString => write!(f, "Unterminated string"),
+            Self::MissingFamily => write!(f, "No font family given"),
+            Self::MissingSize => write!(f, "No font size given"),
+            Self::Unexpected(s) => write!(f, "Unexpected {:?}", s),
+            Self::Duplicate(property) => write!(f, "Font {} given twice", property),
+            Self::Unsupported(s) => write!(f, "Unsupported value {:?}", s),
+            Self::InvalidProperty(error) => error.fmt(f),
+        }
+    }
+}
+
+impl error::Error for ParseFontSpecError {}
+
+impl From<FontPropertyError> for ParseFontSpecError {
+    fn from(error: FontPropertyError) -> ParseFontSpecError {
+        ParseFontSpecError::InvalidProperty(error)
+    }
+}
+
+// The CSS generic families, which are written unquoted.
+const GENERIC_FAMILIES: [&str; 6] = [
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+];
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    String(String),
+    Comma,
+    Slash,
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, ParseFontSpecError> {
+    let mut tokens = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ',' | '/' => {
+                chars.next();
+                tokens.push(if c == ',' { Token::Comma } else { Token::Slash });
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => string.push(escaped),
+                            None => return Err(ParseFontSpecError::UnterminatedString),
+                        },
+                        Some(end) if end == c => break,
+                        Some(other) => string.push(other),
+                        None => return Err(ParseFontSpecError::UnterminatedString),
+                    }
+                }
+                tokens.push(Token::String(string));
+            }
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || ",/\"'".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn unexpected(token: &Token) -> ParseFontSpecError {
+    ParseFontSpecError::Unexpected(match token {
+        Token::Word(text) | Token::String(text) => text.clone(),
+        Token::Comma => ",".to_owned(),
+        Token::Slash => "/".to_owned(),
+    })
+}
+
+// Splits `12.5px` into `(12.5, "px")`.
+fn split_number(word: &str) -> Option<(f32, &str)> {
+    let end = word
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or(word.len());
+    let number = word[..end].parse().ok()?;
+    Some((number, &word[end..]))
+}
+
+fn set<T>(
+    slot: &mut Option<T>,
+    value: T,
+    property: &'static str,
+) -> Result<(), ParseFontSpecError> {
+    if slot.is_some() {
+        return Err(ParseFontSpecError::Duplicate(property));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+// fontconfig's weight scale against the OpenType one, which CSS shares.
+const FC_WEIGHTS: [(f32, f32); 12] = [
+    (0., 100.),
+    (40., 200.),
+    (50., 300.),
+    (55., 350.),
+    (75., 380.),
+    (80., 400.),
+    (100., 500.),
+    (180., 600.),
+    (200., 700.),
+    (205., 800.),
+    (210., 900.),
+    (215., 1000.),
+];
+
+// Maps `x` through the piecewise linear function with the given points,
+// clamping at the ends.
+fn interpolate(points: &[(f32, f32)], x: f32) -> f32 {
+    if x <= points[0].0 {
+        return points[0].1;
+    }
+    for pair in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        if x <= x1 {
+            return y0 + (x - x0) * (y1 - y0) / (x1 - x0);
+        }
+    }
+    points[points.len() - 1].1
+}
+
+fn weight_from_fontconfig(weight: f32) -> FontWeight {
+    FontWeight::from_axis_value(interpolate(&FC_WEIGHTS, weight))
+}
+
+fn weight_to_fontconfig(weight: FontWeight) -> f32 {
+    let points: Vec<_> = FC_WEIGHTS.iter().map(|&(fc, ot)| (ot, fc)).collect();
+    interpolate(&points, weight.to_u32() as f32)
+}
+
+// fontconfig's named constants, as (name, property, value).
+const FC_CONSTANTS: [(&str, &str, f32); 32] = [
+    ("thin", "weight", 0.),
+    ("extralight", "weight", 40.),
+    ("ultralight", "weight", 40.),
+    ("light", "weight", 50.),
+    ("demilight", "weight", 55.),
+    ("semilight", "weight", 55.),
+    ("book", "weight", 75.),
+    ("regular", "weight", 80.),
+    ("normal", "weight", 80.),
+    ("medium", "weight", 100.),
+    ("demibold", "weight", 180.),
+    ("semibold", "weight", 180.),
+    ("bold", "weight", 200.),
+    ("extrabold", "weight", 205.),
+    ("ultrabold", "weight", 205.),
+    ("black", "weight", 210.),
+    ("heavy", "weight", 210.),
+    ("extrablack", "weight", 215.),
+    ("ultrablack", "weight", 215.),
+    ("roman", "slant", 0.),
+    ("italic", "slant", 100.),
+    ("oblique", "slant", 110.),
+    ("ultracondensed", "width", 50.),
+    ("extracondensed", "width", 63.),
+    ("condensed", "width", 75.),
+    ("semicondensed", "width", 87.),
+    ("semiexpanded", "width", 113.),
+    ("expanded", "width", 125.),
+    ("extraexpanded", "width", 150.),
+    ("ultraexpanded", "width", 200.),
+    ("proportional", "spacing", 0.),
+    ("mono", "spacing", 100.),
+];
+
+fn fontconfig_constant(name: &str) -> Option<(&'static str, f32)> {
+    FC_CONSTANTS
+        .iter()
+        .find(|&&(constant, _, _)| name.eq_ignore_ascii_case(constant))
+        .map(|&(_, property, value)| (property, value))
+}
+
+// Splits `s` at unescaped occurrences of `separator`, leaving escapes in
+// place.
+fn split_unescaped(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == separator {
+            parts.push(&s[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+fn escape_fontconfig(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        if "\\-:,=".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn quote_css(family: &str) -> String {
+    if GENERIC_FAMILIES.contains(&family) {
+        return family.to_owned();
+    }
+    let mut quoted = String::from("\"");
+    for c in family.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn number(value: &str) -> Result<f32, ParseFontSpecError> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| ParseFontSpecError::Unexpected(value.to_owned()))
+}
+
+impl FontSpec {
+    /// Wraps a descriptor with no size, fallbacks or variations.
+    pub fn new(descriptor: FontDescriptor) -> FontSpec {
+        FontSpec {
+            descriptor,
+            fallback_families: vec![],
+            size: None,
+            variations: vec![],
+        }
+    }
+
+    fn from_families(mut families: Vec<String>) -> Result<FontSpec, ParseFontSpecError> {
+        if families.is_empty() || families.iter().any(|family| family.is_empty()) {
+            return Err(ParseFontSpecError::MissingFamily);
+        }
+        let family_name = families.remove(0);
+        let mut spec = FontSpec::new(FontDescriptor {
+            family_name,
+            weight: FontWeight::Regular,
+            stretch: FontStretch::Normal,
+            style: FontStyle::Normal,
+            oblique_angle: None,
+            simulations: None,
+        });
+        spec.fallback_families = families;
+        Ok(spec)
+    }
+
+    /// Parses the CSS `font` shorthand: optional style, weight and stretch,
+    /// then a size in `px` or `pt` and a list of families. `medium`, the
+    /// initial size, leaves the size unset.
+    ///
+    /// Any line height after the size is accepted and dropped, as it isn't a
+    /// property of the font. `small-caps`, relative weights and system fonts
+    /// such as `caption` aren't supported.
+    pub fn from_css(s: &str) -> Result<FontSpec, ParseFontSpecError> {
+        let tokens = tokenize(s)?;
+        let mut tokens = tokens.iter().peekable();
+        let mut style = None;
+        let mut oblique_angle = None;
+        let mut weight = None;
+        let mut stretch = None;
+        let mut size = None;
+        let mut normals = 0;
+        loop {
+            let word = match tokens.next() {
+                Some(Token::Word(word)) => word,
+                Some(Token::String(_)) | Some(Token::Comma) | None => {
+                    return Err(ParseFontSpecError::MissingSize)
+                }
+                Some(token) => return Err(unexpected(token)),
+            };
+            let keyword = word.to_ascii_lowercase();
+            if keyword == "medium" {
+                break;
+            }
+            if let Some((value, unit)) = split_number(&keyword) {
+                match unit {
+                    _ if value < 0. => return Err(ParseFontSpecError::Unexpected(word.clone())),
+                    "" => set(&mut weight, word.parse()?, "weight")?,
+                    "px" => {
+                        size = Some(FontSize::Pixels(value));
+                        break;
+                    }
+                    "pt" => {
+                        size = Some(FontSize::Points(value));
+                        break;
+                    }
+                    _ => return Err(ParseFontSpecError::Unsupported(word.clone())),
+                }
+                continue;
+            }
+            match &*keyword {
+                "normal" => normals += 1,
+                "italic" => set(&mut style, FontStyle::Italic, "style")?,
+                "oblique" => {
+                    set(&mut style, FontStyle::Oblique, "style")?;
+                    if let Some(Token::Word(next)) = tokens.peek() {
+                        if let Some((angle, unit)) = split_number(next) {
+                            if unit.eq_ignore_ascii_case("deg") {
+                                oblique_angle = Some(angle);
+                                tokens.next();
+                            }
+                        }
+                    }
+                }
+                "bold" => set(&mut weight, FontWeight::Bold, "weight")?,
+                "bolder" | "lighter" | "small-caps" => {
+                    return Err(ParseFontSpecError::Unsupported(word.clone()))
+                }
+                _ => match keyword.parse::<FontStretch>() {
+                    Ok(value) if value != FontStretch::Undefined => {
+                        set(&mut stretch, value, "stretch")?
+                    }
+                    _ => return Err(ParseFontSpecError::Unexpected(word.clone())),
+                },
+            }
+        }
+        let given = [style.is_some(), weight.is_some(), stretch.is_some()];
+        if normals + given.iter().filter(|&&given| given).count() > 4 {
+            return Err(ParseFontSpecError::Duplicate("normal"));
+        }
+
+        if tokens.peek() == Some(&&Token::Slash) {
+            tokens.next();
+            match tokens.next() {
+                Some(Token::Word(_)) => {}
+                Some(token) => return Err(unexpected(token)),
+                None => return Err(ParseFontSpecError::MissingFamily),
+            }
+        }
+
+        // Each family is either one quoted string or a run of plain words.
+        let mut families = vec![];
+        let mut family: Option<(String, bool)> = None;
+        for token in tokens {
+            family = match (token, family.take()) {
+                (Token::Comma, family) => {
+                    families.push(family.map(|(name, _)| name).unwrap_or_default());
+                    None
+                }
+                (Token::String(string), None) => Some((string.clone(), true)),
+                (Token::Word(word), None) => Some((word.clone(), false)),
+                (Token::Word(word), Some((name, false))) => {
+                    Some((format!("{} {}", name, word), false))
+                }
+                (token, _) => return Err(unexpected(token)),
+            };
+        }
+        families.push(family.map(|(name, _)| name).unwrap_or_default());
+
+        let mut spec = FontSpec::from_families(families)?;
+        spec.size = size;
+        spec.descriptor.style = style.unwrap_or(FontStyle::Normal);
+        spec.descriptor.oblique_angle = oblique_angle;
+        spec.descriptor.weight = weight.unwrap_or(FontWeight::Regular);
+        spec.descriptor.stretch = stretch.unwrap_or(FontStretch::Normal);
+        Ok(spec)
+    }
+
+    /// Formats the CSS `font` shorthand, leaving out properties at their
+    /// initial values. Variations can't be expressed in the shorthand and
+    /// are left out.
+    pub fn to_css(&self) -> String {
+        let descriptor = &self.descriptor;
+        let mut parts = vec![];
+        match (descriptor.style, descriptor.oblique_angle) {
+            (FontStyle::Normal, _) => {}
+            (FontStyle::Oblique, Some(angle)) => parts.push(format!("oblique {}deg", angle)),
+            (style, _) => parts.push(style.to_string()),
+        }
+        if descriptor.weight == FontWeight::Bold {
+            parts.push("bold".to_owned());
+        } else if descriptor.weight != FontWeight::Regular {
+            parts.push(descriptor.weight.to_string());
+        }
+        if descriptor.stretch != FontStretch::Normal {
+            parts.extend(descriptor.stretch.css_keyword().map(str::to_owned));
+        }
+        parts.push(match self.size {
+            Some(FontSize::Pixels(size)) => format!("{}px", size),
+            Some(FontSize::Points(size)) => format!("{}pt", size),
+            None => "medium".to_owned(),
+        });
+        let families: Vec<_> = Some(&descriptor.family_name)
+            .into_iter()
+            .chain(&self.fallback_families)
+            .map(|family| quote_css(family))
+            .collect();
+        parts.push(families.join(", "));
+        parts.join(" ")
+    }
+
+    /// Parses a fontconfig pattern: a comma-separated list of families, an
+    /// optional `-` and point size, then `:name=value` properties. `weight`,
+    /// `slant`, `width`, `size`, `pixelsize` and `fontvariations` are
+    /// understood, as are bare constants such as `:bold`; other properties
+    /// are ignored.
+    pub fn from_fontconfig(s: &str) -> Result<FontSpec, ParseFontSpecError> {
+        let mut parts = split_unescaped(s, ':').into_iter();
+        let names = split_unescaped(parts.next().unwrap_or(""), '-');
+        let families = split_unescaped(names[0], ',')
+            .into_iter()
+            .map(|family| unescape(family).trim().to_owned())
+            .collect();
+        let mut spec = FontSpec::from_families(families)?;
+        match names.len() {
+            1 => {}
+            2 => spec.size = Some(FontSize::Points(number(&unescape(names[1]))?)),
+            _ => return Err(ParseFontSpecError::Unexpected(names[2].to_owned())),
+        }
+
+        for property in parts {
+            let (name, value) = match property.find('=') {
+                Some(i) => (&property[..i], &property[i + 1..]),
+                None => match fontconfig_constant(property) {
+                    Some((name, _)) => (name, property),
+                    None => continue,
+                },
+            };
+            let name = name.trim().to_ascii_lowercase();
+            if name == "fontvariations" {
+                spec.variations = parse_variations(value)?;
+                continue;
+            }
+            if !["weight", "slant", "width", "size", "pixelsize"].contains(&&*name) {
+                continue;
+            }
+            // Only the first of a list of values is used.
+            let value = unescape(split_unescaped(value, ',')[0]);
+            let value = value.trim();
+            let value = match fontconfig_constant(value) {
+                Some((property, value)) if property == name => value,
+                Some(_) => return Err(ParseFontSpecError::Unexpected(value.to_owned())),
+                None => number(value)?,
+            };
+            match &*name {
+                "weight" => spec.descriptor.weight = weight_from_fontconfig(value),
+                "slant" => {
+                    spec.descriptor.style = if value >= 110. {
+                        FontStyle::Oblique
+                    } else if value >= 100. {
+                        FontStyle::Italic
+                    } else {
+                        FontStyle::Normal
+                    }
+                }
+                "width" => spec.descriptor.stretch = FontStretch::from_percentage(value)?,
+                "size" => spec.size = Some(FontSize::Points(value)),
+                _ => spec.size = Some(FontSize::Pixels(value)),
+            }
+        }
+        Ok(spec)
+    }
+
+    /// Formats a fontconfig pattern, leaving out properties at their initial
+    /// values. fontconfig has no oblique angles, so they're left out.
+    pub fn to_fontconfig(&self) -> String {
+        let descriptor = &self.descriptor;
+        let families: Vec<_> = Some(&descriptor.family_name)
+            .into_iter()
+            .chain(&self.fallback_families)
+            .map(|family| escape_fontconfig(family))
+            .collect();
+        let mut pattern = families.join(",");
+        if let Some(FontSize::Points(size)) = self.size {
+            pattern += &format!("-{}", size);
+        }
+        if descriptor.weight != FontWeight::Regular {
+            pattern += &format!(":weight={}", weight_to_fontconfig(descriptor.weight));
+        }
+        match descriptor.style {
+            FontStyle::Normal => {}
+            FontStyle::Italic => pattern += ":slant=100",
+            FontStyle::Oblique => pattern += ":slant=110",
+        }
+        if descriptor.stretch != FontStretch::Normal {
+            if let Some(percentage) = descriptor.stretch.to_percentage() {
+                pattern += &format!(":width={}", percentage);
+            }
+        }
+        if let Some(FontSize::Pixels(size)) = self.size {
+            pattern += &format!(":pixelsize={}", size);
+        }
+        if !self.variations.is_empty() {
+            let variations: Vec<_> = self
+                .variations
+                .iter()
+                .map(|(tag, value)| format!("{}={}", tag, value))
+                .collect();
+            pattern += &format!(":fontvariations={}", variations.join(","));
+        }
+        pattern
+    }
+}
+
+// Parses fontconfig's `fontvariations`, such as `wght=300,wdth=80`.
+fn parse_variations(value: &str) -> Result<Vec<(Tag, f32)>, ParseFontSpecError> {
+    split_unescaped(value, ',')
+        .into_iter()
+        .filter(|variation| !variation.trim().is_empty())
+        .map(|variation| {
+            let variation = unescape(variation);
+            let (tag, value) = match variation.find('=') {
+                Some(i) => (&variation[..i], &variation[i + 1..]),
+                None => return Err(ParseFontSpecError::Unexpected(variation.clone())),
+            };
+            let tag = tag
+                .trim()
+                .parse()
+                .map_err(|_| ParseFontSpecError::Unexpected(tag.to_owned()))?;
+            Ok((tag, number(value.trim())?))
+        })
+        .collect()
+}
@@ -0,0 +1,169 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Descriptions of a variable font's axes and named instances, put together
+//! from the `fvar`, `STAT` and `name` tables.
+//!
+//! DirectWrite reports the current value of each axis but not its range,
+//! its name or the instances the designer named.
+
+use std::fmt;
+
+use crate::tables::fvar::FvarTable;
+use crate::tables::name::NameTable;
+use crate::tables::stat::StatTable;
+use crate::{Tag, DWRITE_FONT_AXIS_VALUE};
+
+/// A name `STAT` gives to a value or range of values on an axis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AxisValueName {
+    pub name: Option<String>,
+    pub value: f32,
+    /// The inclusive range the name covers, if it covers more than `value`.
+    pub range: Option<(f32, f32)>,
+    /// The value this one is the style-linked counterpart of, such as Bold
+    /// for Regular.
+    pub linked_value: Option<f32>,
+    /// The name can be left out of face names, as "Regular" usually is.
+    pub elidable: bool,
+}
+
+/// A variation axis, in user coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariationAxis {
+    pub tag: Tag,
+    /// The axis name, such as "Weight", in US English if available.
+    pub name: Option<String>,
+    pub min_value: f32,
+    pub default_value: f32,
+    pub max_value: f32,
+    /// Whether the font asks for the axis to be left out of user interfaces.
+    pub hidden: bool,
+    /// Where this axis's value names go in a face name, from `STAT`.
+    pub ordering: Option<u16>,
+    /// The named values on this axis, from `STAT`.
+    pub value_names: Vec<AxisValueName>,
+}
+
+impl VariationAxis {
+    /// Clamps `value` to the axis range.
+    pub fn clamp(&self, value: f32) -> f32 {
+        value.max(self.min_value).min(self.max_value)
+    }
+
+    /// The `DWRITE_FONT_AXIS_VALUE` for `value` on this axis, clamped to the
+    /// axis range.
+    pub fn axis_value(&self, value: f32) -> DWRITE_FONT_AXIS_VALUE {
+        self.tag.axis_value(self.clamp(value))
+    }
+}
+
+/// A named instance of a variable font, such as "Semibold Condensed".
+///
+/// Its [`axis_values`](NamedInstance::axis_values), which it's also
+/// `AsRef` to, are what `create_font_face_with_variations` takes.
+#[derive(Clone)]
+pub struct NamedInstance {
+    /// The instance's subfamily name, in US English if available.
+    pub name: Option<String>,
+    pub postscript_name: Option<String>,
+    axis_values: Vec<DWRITE_FONT_AXIS_VALUE>,
+}
+
+impl NamedInstance {
+    /// The instance's value on every axis, in axis order.
+    pub fn axis_values(&self) -> &[DWRITE_FONT_AXIS_VALUE] {
+        &self.axis_values
+    }
+
+    /// Returns the instance's value on the axis with the given tag.
+    pub fn value(&self, tag: Tag) -> Option<f32> {
+        self.axis_values
+            .iter()
+            .find(|axis_value| axis_value.axisTag == tag.to_dwrite())
+            .map(|axis_value| axis_value.value)
+    }
+}
+
+impl AsRef<[DWRITE_FONT_AXIS_VALUE]> for NamedInstance {
+    fn as_ref(&self) -> &[DWRITE_FONT_AXIS_VALUE] {
+        &self.axis_values
+    }
+}
+
+impl fmt::Debug for NamedInstance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let values: Vec<_> = self
+            .axis_values
+            .iter()
+            .map(|axis_value| (Tag::from_dwrite(axis_value.axisTag), axis_value.value))
+            .collect();
+        f.debug_struct("NamedInstance")
+            .field("name", &self.name)
+            .field("postscript_name", &self.postscript_name)
+            .field("axis_values", &values)
+            .finish()
+    }
+}
+
+fn find_name(names: Option<&NameTable>, name_id: u16) -> Option<String> {
+    names?.find(name_id).map(str::to_owned)
+}
+
+pub(crate) fn variation_axes(
+    fvar: &FvarTable,
+    stat: Option<&StatTable>,
+    names: Option<&NameTable>,
+) -> Vec<VariationAxis> {
+    fvar.axes
+        .iter()
+        .map(|axis| {
+            let stat_axis = stat.and_then(|stat| {
+                let index = stat.axes.iter().position(|a| a.tag == axis.tag)?;
+                Some((stat, index as u16))
+            });
+            let value_names = match stat_axis {
+                Some((stat, index)) => stat
+                    .values_for_axis(index)
+                    .map(|value| AxisValueName {
+                        name: find_name(names, value.name_id),
+                        value: value.values[0].1,
+                        range: value.range,
+                        linked_value: value.linked_value,
+                        elidable: value.elidable,
+                    })
+                    .collect(),
+                None => vec![],
+            };
+            VariationAxis {
+                tag: axis.tag,
+                name: find_name(names, axis.name_id),
+                min_value: axis.min_value,
+                default_value: axis.default_value,
+                max_value: axis.max_value,
+                hidden: axis.hidden,
+                ordering: stat_axis.map(|(stat, index)| stat.axes[index as usize].ordering),
+                value_names,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn named_instances(fvar: &FvarTable, names: Option<&NameTable>) -> Vec<NamedInstance> {
+    fvar.instances
+        .iter()
+        .map(|instance| NamedInstance {
+            name: find_name(names, instance.subfamily_name_id),
+            postscript_name: instance
+                .postscript_name_id
+                .and_then(|name_id| find_name(names, name_id)),
+            axis_values: fvar
+                .axes
+                .iter()
+                .zip(&instance.coordinates)
+                .map(|(axis, &value)| axis.tag.axis_value(value))
+                .collect(),
+        })
+        .collect()
+}
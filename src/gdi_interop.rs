@@ -2,13 +2,17 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::shared::winerror::S_OK;
+use crate::sys::um::dwrite::IDWriteBitmapRenderTarget;
+use crate::sys::um::dwrite::IDWriteGdiInterop;
+use crate::sys::um::winnt::HRESULT;
 use std::cell::UnsafeCell;
 use std::ptr;
-use winapi::um::dwrite::IDWriteBitmapRenderTarget;
-use winapi::um::dwrite::IDWriteGdiInterop;
-use wio::com::ComPtr;
 
-use super::{BitmapRenderTarget, DWriteFactory};
+use super::DWriteFactory;
+use crate::bitmap_render_target::BitmapRenderTarget;
+use crate::factory::Factory;
 
 pub struct GdiInterop {
     native: UnsafeCell<ComPtr<IDWriteGdiInterop>>,
@@ -24,6 +28,17 @@ impl GdiInterop {
         }
     }
 
+    pub fn create_with_factory(factory: &Factory) -> Result<GdiInterop, HRESULT> {
+        unsafe {
+            let mut native: *mut IDWriteGdiInterop = ptr::null_mut();
+            let hr = (*factory.as_ptr()).GetGdiInterop(&mut native);
+            if hr != S_OK {
+                return Err(hr);
+            }
+            Ok(GdiInterop::take(ComPtr::from_raw(native)))
+        }
+    }
+
     pub fn take(native: ComPtr<IDWriteGdiInterop>) -> GdiInterop {
         GdiInterop {
             native: UnsafeCell::new(native),
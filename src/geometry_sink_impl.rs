@@ -1,16 +1,17 @@
 #![allow(non_snake_case, non_upper_case_globals)]
 
-use std::mem;
+use crate::sys::shared::guiddef::REFIID;
+use crate::sys::shared::minwindef::{UINT, ULONG};
+use crate::sys::shared::winerror::S_OK;
+use crate::sys::um::d2d1::{ID2D1SimplifiedGeometrySink, ID2D1SimplifiedGeometrySinkVtbl};
+use crate::sys::um::d2d1::{D2D1_BEZIER_SEGMENT, D2D1_FIGURE_BEGIN, D2D1_FIGURE_END};
+use crate::sys::um::d2d1::{
+    D2D1_FIGURE_END_CLOSED, D2D1_FILL_MODE, D2D1_PATH_SEGMENT, D2D1_POINT_2F,
+};
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::winnt::HRESULT;
 use std::slice;
 use std::sync::atomic::AtomicUsize;
-use winapi::shared::guiddef::REFIID;
-use winapi::shared::minwindef::{UINT, ULONG};
-use winapi::shared::winerror::S_OK;
-use winapi::um::d2d1::{ID2D1SimplifiedGeometrySink, ID2D1SimplifiedGeometrySinkVtbl};
-use winapi::um::d2d1::{D2D1_BEZIER_SEGMENT, D2D1_FIGURE_BEGIN, D2D1_FIGURE_END};
-use winapi::um::d2d1::{D2D1_FIGURE_END_CLOSED, D2D1_FILL_MODE, D2D1_PATH_SEGMENT, D2D1_POINT_2F};
-use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
-use winapi::um::winnt::HRESULT;
 
 use crate::com_helpers::Com;
 use crate::outline_builder::OutlineBuilder;
@@ -64,9 +65,7 @@ unsafe extern "system" fn GeometrySinkImpl_BeginFigure(
     _: D2D1_FIGURE_BEGIN,
 ) {
     let this = GeometrySinkImpl::from_interface(this);
-    this
-        .outline_builder
-        .move_to(start_point.x, start_point.y)
+    this.outline_builder.move_to(start_point.x, start_point.y)
 }
 
 unsafe extern "system" fn GeometrySinkImpl_EndFigure(
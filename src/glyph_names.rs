@@ -0,0 +1,157 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Glyph names, from the `post` table or the CFF charset, and the code
+//! points the Adobe Glyph List specification derives from names.
+//!
+//! DirectWrite doesn't expose glyph names at all, so both platforms read
+//! them from the font's own tables.
+
+use std::collections::HashMap;
+
+use crate::tables::cff::CffTable;
+use crate::tables::cmap::CmapTable;
+use crate::tables::post::PostTable;
+
+lazy_static! {
+    /// The Adobe Glyph List, from glyph names to their code points.
+    static ref ADOBE_GLYPH_LIST: HashMap<&'static str, Vec<u32>> = include_str!("glyphlist.txt")
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, code_points) = line.split_once(';')?;
+            let code_points = code_points
+                .split(' ')
+                .map(|code_point| u32::from_str_radix(code_point, 16).ok())
+                .collect::<Option<_>>()?;
+            Some((name, code_points))
+        })
+        .collect();
+}
+
+/// Derives the code points a glyph name stands for, following the Adobe
+/// Glyph List specification: anything after the first period is ignored,
+/// underscores separate the components of ligatures, and each component is
+/// an Adobe Glyph List name, `uni` followed by groups of four hexadecimal
+/// digits or `u` followed by four to six. Returns an empty `Vec` if nothing
+/// can be derived.
+///
+/// ```
+/// use dwrote::code_points_for_glyph_name;
+///
+/// assert_eq!(code_points_for_glyph_name("Aacute.sc"), ['Á' as u32]);
+/// assert_eq!(code_points_for_glyph_name("f_f_i"), ['f' as u32, 'f' as u32, 'i' as u32]);
+/// assert_eq!(code_points_for_glyph_name("uni20AC0308"), [0x20ac, 0x308]);
+/// assert_eq!(code_points_for_glyph_name("u1F600"), [0x1f600]);
+/// ```
+pub fn code_points_for_glyph_name(name: &str) -> Vec<u32> {
+    let name = name.split('.').next().unwrap_or_default();
+    name.split('_').flat_map(component_code_points).collect()
+}
+
+fn component_code_points(component: &str) -> Vec<u32> {
+    if let Some(code_points) = ADOBE_GLYPH_LIST.get(component) {
+        return code_points.clone();
+    }
+    let is_scalar_value =
+        |code_point: u32| code_point < 0xd800 || (0xe000..=0x10ffff).contains(&code_point);
+    let hex = |digits: &str| {
+        if !digits
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'A'..=b'F').contains(&b))
+        {
+            return None;
+        }
+        u32::from_str_radix(digits, 16)
+            .ok()
+            .filter(|&code_point| is_scalar_value(code_point))
+    };
+    if let Some(digits) = component.strip_prefix("uni") {
+        if !digits.is_empty() && digits.len() % 4 == 0 {
+            let code_points = (0..digits.len())
+                .step_by(4)
+                .map(|i| hex(&digits[i..i + 4]))
+                .collect::<Option<Vec<_>>>();
+            if let Some(code_points) = code_points {
+                return code_points;
+            }
+        }
+    }
+    if let Some(digits) = component.strip_prefix('u') {
+        if (4..=6).contains(&digits.len()) {
+            if let Some(code_point) = hex(digits) {
+                return vec![code_point];
+            }
+        }
+    }
+    vec![]
+}
+
+/// A face's glyph names, from `post` formats 1, 2 and 2.5 or else the
+/// `CFF ` charset, indexed both ways so that a face's glyphs can be named
+/// one by one. Built by `FontFaceTables::glyph_names`.
+#[derive(Clone, Debug)]
+pub struct GlyphNames {
+    names: Vec<Option<String>>,
+    glyphs: HashMap<String, u16>,
+    cmap: Option<CmapTable>,
+}
+
+impl GlyphNames {
+    pub(crate) fn parse(
+        post: Option<&[u8]>,
+        head: Option<&[u8]>,
+        cff: Option<&[u8]>,
+        cmap: Option<CmapTable>,
+    ) -> GlyphNames {
+        let names: Vec<_> = match post.and_then(PostTable::parse) {
+            Some(post) if !post.glyph_names.is_empty() => {
+                post.glyph_names.into_iter().map(Some).collect()
+            }
+            _ => match head
+                .zip(cff)
+                .and_then(|(head, cff)| CffTable::parse(head, cff))
+            {
+                Some(cff) => (0..cff.glyph_count())
+                    .map(|glyph_id| cff.glyph_name(glyph_id))
+                    .collect(),
+                None => vec![],
+            },
+        };
+        let mut glyphs = HashMap::new();
+        for (glyph_id, name) in names.iter().enumerate() {
+            if let Some(name) = name {
+                // The first glyph with a name wins.
+                glyphs.entry(name.clone()).or_insert(glyph_id as u16);
+            }
+        }
+        GlyphNames {
+            names,
+            glyphs,
+            cmap,
+        }
+    }
+
+    /// Returns a glyph's name, or `None` if the face doesn't name it.
+    pub fn glyph_name(&self, glyph_id: u16) -> Option<&str> {
+        self.names.get(glyph_id as usize)?.as_deref()
+    }
+
+    /// Finds a glyph by name. Names the face doesn't list, such as `uni0416`
+    /// in a face without names, are mapped to a code point with the Adobe
+    /// Glyph List rules and looked up in `cmap`.
+    pub fn glyph_by_name(&self, name: &str) -> Option<u16> {
+        if let Some(&glyph_id) = self.glyphs.get(name) {
+            return Some(glyph_id);
+        }
+        match *code_points_for_glyph_name(name) {
+            [code_point] => self
+                .cmap
+                .as_ref()?
+                .glyph_index(code_point)
+                .filter(|&glyph_id| glyph_id != 0),
+            _ => None,
+        }
+    }
+}
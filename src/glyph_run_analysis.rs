@@ -2,17 +2,17 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::shared::windef::RECT;
+use crate::sys::um::dcommon::DWRITE_MEASURING_MODE;
+use crate::sys::um::dwrite::DWRITE_TEXTURE_CLEARTYPE_3x1;
+use crate::sys::um::dwrite::IDWriteGlyphRunAnalysis;
+use crate::sys::um::dwrite::{DWRITE_TEXTURE_ALIASED_1x1, DWRITE_GLYPH_RUN, DWRITE_TEXTURE_TYPE};
+use crate::sys::um::dwrite::{DWRITE_MATRIX, DWRITE_RENDERING_MODE};
+use crate::sys::um::winnt::HRESULT;
 use std::cell::UnsafeCell;
 use std::mem;
 use std::ptr;
-use winapi::shared::windef::RECT;
-use winapi::um::dcommon::DWRITE_MEASURING_MODE;
-use winapi::um::dwrite::DWRITE_TEXTURE_CLEARTYPE_3x1;
-use winapi::um::dwrite::IDWriteGlyphRunAnalysis;
-use winapi::um::dwrite::{DWRITE_TEXTURE_ALIASED_1x1, DWRITE_GLYPH_RUN, DWRITE_TEXTURE_TYPE};
-use winapi::um::dwrite::{DWRITE_MATRIX, DWRITE_RENDERING_MODE};
-use winapi::um::winnt::HRESULT;
-use wio::com::ComPtr;
 
 use super::DWriteFactory;
 
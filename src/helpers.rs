@@ -2,14 +2,14 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::wchar_t;
+use crate::sys::ffi::OsStrExt;
+use crate::sys::shared::minwindef::{BOOL, FALSE};
+use crate::sys::shared::winerror::S_OK;
+use crate::sys::um::dwrite::IDWriteLocalizedStrings;
+use crate::sys::um::winnls::GetUserDefaultLocaleName;
 use std::ffi::OsStr;
-use std::os::windows::ffi::OsStrExt;
-use winapi::ctypes::wchar_t;
-use winapi::shared::minwindef::{BOOL, FALSE};
-use winapi::shared::winerror::S_OK;
-use winapi::um::dwrite::IDWriteLocalizedStrings;
-use winapi::um::winnls::GetUserDefaultLocaleName;
-use wio::com::ComPtr;
 
 lazy_static! {
     static ref SYSTEM_LOCALE: Vec<wchar_t> = {
@@ -3,6 +3,9 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 #![allow(non_upper_case_globals)]
+// Off Windows, the DirectWrite wrappers are only built for the tests that
+// drive them with fake objects, which leave most of them unused.
+#![cfg_attr(all(test, not(windows)), allow(dead_code))]
 
 #[cfg(feature = "serde_serialization")]
 extern crate serde;
@@ -13,49 +16,78 @@ extern crate serde_derive;
 #[macro_use]
 extern crate lazy_static;
 extern crate libc;
+#[cfg(windows)]
 extern crate winapi;
 
 include!("types.rs");
 
-use std::ffi::CString;
+#[cfg(any(windows, test))]
 use std::ptr;
-use winapi::shared::guiddef::REFIID;
-use winapi::shared::winerror::S_OK;
-use winapi::um::dwrite::IDWriteFactory;
-use winapi::um::dwrite::IDWriteRenderingParams;
-use winapi::um::dwrite::DWRITE_FACTORY_TYPE;
-use winapi::um::dwrite::DWRITE_FACTORY_TYPE_SHARED;
-use winapi::um::unknwnbase::IUnknown;
-use winapi::um::winnt::LPCSTR;
-use winapi::Interface;
-
+#[cfg(any(windows, test))]
+use sys::shared::winerror::S_OK;
+#[cfg(any(windows, test))]
+use sys::um::dwrite::IDWriteFactory;
+#[cfg(any(windows, test))]
+use sys::um::dwrite::IDWriteRenderingParams;
+
+#[cfg(windows)]
 pub use winapi::um::winnt::HRESULT;
 
+// winapi and wio, or stand-ins for them that let the tests build the
+// DirectWrite wrappers everywhere.
+#[cfg(any(windows, test))]
+mod sys;
+
+#[cfg(any(windows, test))]
 mod helpers;
+#[cfg(any(windows, test))]
 use helpers::ToWide;
-use std::os::raw::c_void;
 
 #[cfg(test)]
 mod test;
+#[cfg(test)]
+mod test_font;
+
+// Off Windows, winapi has no definitions at all, so the handful of plain
+// DirectWrite structs and constants that are part of our API are mirrored
+// here instead. Windows test builds compile them too, to check them
+// against winapi.
+#[cfg(any(not(windows), test))]
+#[cfg_attr(windows, allow(dead_code))]
+mod portable_types;
+#[cfg(not(windows))]
+pub use portable_types::*;
 
 // We still use the DWrite structs for things like metrics; re-export them
 // here
+#[cfg(windows)]
 pub use winapi::shared::windef::RECT;
+#[cfg(windows)]
 pub use winapi::um::dcommon::DWRITE_MEASURING_MODE;
+#[cfg(windows)]
 pub use winapi::um::dcommon::{
     DWRITE_MEASURING_MODE_GDI_CLASSIC, DWRITE_MEASURING_MODE_GDI_NATURAL,
     DWRITE_MEASURING_MODE_NATURAL,
 };
+#[cfg(windows)]
 pub use winapi::um::dwrite::DWRITE_FONT_METRICS as FontMetrics0;
+#[cfg(windows)]
 pub use winapi::um::dwrite::DWRITE_FONT_SIMULATIONS;
+#[cfg(windows)]
 pub use winapi::um::dwrite::DWRITE_GLYPH_OFFSET as GlyphOffset;
+#[cfg(windows)]
 pub use winapi::um::dwrite::DWRITE_RENDERING_MODE;
+#[cfg(windows)]
 pub use winapi::um::dwrite::DWRITE_TEXTURE_TYPE;
+#[cfg(windows)]
 pub use winapi::um::dwrite::{DWRITE_TEXTURE_ALIASED_1x1, DWRITE_TEXTURE_CLEARTYPE_3x1};
+#[cfg(windows)]
 pub use winapi::um::dwrite::{
     DWRITE_FONT_SIMULATIONS_BOLD, DWRITE_FONT_SIMULATIONS_NONE, DWRITE_FONT_SIMULATIONS_OBLIQUE,
 };
+#[cfg(windows)]
 pub use winapi::um::dwrite::{DWRITE_GLYPH_RUN, DWRITE_MATRIX};
+#[cfg(windows)]
 pub use winapi::um::dwrite::{
     DWRITE_RENDERING_MODE_ALIASED, DWRITE_RENDERING_MODE_CLEARTYPE_GDI_CLASSIC,
     DWRITE_RENDERING_MODE_CLEARTYPE_GDI_NATURAL, DWRITE_RENDERING_MODE_CLEARTYPE_NATURAL,
@@ -64,84 +96,136 @@ pub use winapi::um::dwrite::{
     DWRITE_RENDERING_MODE_NATURAL, DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC,
     DWRITE_RENDERING_MODE_OUTLINE,
 };
+#[cfg(windows)]
 pub use winapi::um::dwrite_1::DWRITE_FONT_METRICS1 as FontMetrics1;
+#[cfg(windows)]
 pub use winapi::um::dwrite_3::DWRITE_FONT_AXIS_VALUE;
-use winapi::um::libloaderapi::{GetProcAddress, LoadLibraryW};
 
+#[cfg(any(windows, test))]
 #[macro_use]
 mod com_helpers;
+// Fake DirectWrite objects for the tests; declared here so that it can use
+// the `com_helpers` macros.
+#[cfg(test)]
+mod test_com;
 
+mod outline_builder;
+pub use outline_builder::OutlineBuilder;
+
+mod analysis;
+pub use analysis::{FontFileAnalysis, FontFileFormat, UnsupportedFontReason};
+mod font_face_tables;
+pub use font_face_tables::FontFaceTables;
+mod font_matching;
+pub use font_matching::{match_fonts, FontMatch, MatchDistance};
+mod font_spec;
+pub use font_spec::{FontSize, FontSpec, ParseFontSpecError};
+mod font_variations;
+pub use font_variations::{AxisValueName, NamedInstance, VariationAxis};
+mod glyph_names;
+pub use glyph_names::{code_points_for_glyph_name, GlyphNames};
+mod rendering_mode;
+pub use rendering_mode::{recommended_rendering_mode, RenderingMode, DEFAULT_OUTLINE_THRESHOLD};
+mod tag;
+pub use tag::{ParseTagError, Tag};
+
+// The traits every font backend implements, plus the pure-Rust backend that
+// works on in-memory font buffers on every platform.
+pub mod backend;
+pub mod memory;
+pub mod replay;
+pub mod tables;
+
+#[cfg(any(windows, test))]
 mod bitmap_render_target;
+#[cfg(windows)]
 pub use bitmap_render_target::BitmapRenderTarget;
+#[cfg(any(windows, test))]
+mod factory;
+#[cfg(windows)]
+pub use factory::Factory;
+#[cfg(any(windows, test))]
 mod font;
+#[cfg(windows)]
 pub use font::{Font, FontMetrics, InformationalStringId};
+#[cfg(any(windows, test))]
 mod font_collection;
+#[cfg(windows)]
 pub use font_collection::FontCollection;
+#[cfg(any(windows, test))]
 mod font_face;
-pub use font_face::{FontFace, FontFaceType};
+#[cfg(windows)]
+pub use font_face::FontFace;
+#[cfg(any(windows, test))]
 mod font_fallback;
+#[cfg(windows)]
 pub use font_fallback::{FallbackResult, FontFallback};
+#[cfg(any(windows, test))]
 mod font_family;
+#[cfg(windows)]
 pub use font_family::FontFamily;
+#[cfg(any(windows, test))]
 mod font_file;
+#[cfg(windows)]
 pub use font_file::FontFile;
+#[cfg(any(windows, test))]
 mod gdi_interop;
+#[cfg(windows)]
 pub use gdi_interop::GdiInterop;
-mod outline_builder;
-pub use outline_builder::OutlineBuilder;
+#[cfg(any(windows, test))]
 mod rendering_params;
+#[cfg(windows)]
 pub use rendering_params::RenderingParams;
+#[cfg(any(windows, test))]
 mod text_analysis_source;
+#[cfg(windows)]
 pub use text_analysis_source::TextAnalysisSource;
+#[cfg(any(windows, test))]
 mod glyph_run_analysis;
+#[cfg(windows)]
 pub use glyph_run_analysis::GlyphRunAnalysis;
 
+// The DirectWrite wrappers above implement the `backend` traits here.
+#[cfg(windows)]
+mod dwrite_backend;
+
+// Everywhere else, the public font types are thin handles over a
+// `backend` implementation, which is the in-memory one by default.
+#[cfg(not(windows))]
+mod portable;
+#[cfg(not(windows))]
+pub use portable::{
+    Factory, Font, FontCollection, FontCollectionFamilyIterator, FontFace, FontFamily, FontFile,
+    FontMetrics,
+};
+
 // This is an internal implementation of FontFileLoader, for our utility
 // functions.  We don't wrap the DWriteFontFileLoader interface and
 // related things.
+#[cfg(any(windows, test))]
 mod font_file_loader_impl;
 
 // This is an implementation of `FontCollectionLoader` for client code.
+#[cfg(any(windows, test))]
 mod font_collection_impl;
+#[cfg(windows)]
 pub use font_collection_impl::CustomFontCollectionLoaderImpl;
 
 // This is an implementation of `TextAnalysisSource` for client code.
+#[cfg(any(windows, test))]
 mod text_analysis_source_impl;
+#[cfg(windows)]
 pub use text_analysis_source_impl::{
     CustomTextAnalysisSourceImpl, NumberSubstitution, TextAnalysisSourceMethods,
 };
 
 // This is an internal implementation of `GeometrySink` so that we can
 // expose `IDWriteGeometrySink` in an idiomatic way.
+#[cfg(any(windows, test))]
 mod geometry_sink_impl;
 
+#[cfg(any(windows, test))]
 lazy_static! {
-    static ref DWRITE_FACTORY_RAW_PTR: usize = {
-        unsafe {
-            type DWriteCreateFactoryType =
-                extern "system" fn(DWRITE_FACTORY_TYPE, REFIID, *mut *mut IUnknown) -> HRESULT;
-
-            let dwrite_dll = LoadLibraryW("dwrite.dll".to_wide_null().as_ptr());
-            assert!(!dwrite_dll.is_null());
-            let create_factory_name = CString::new("DWriteCreateFactory").unwrap();
-            let dwrite_create_factory_ptr =
-                GetProcAddress(dwrite_dll, create_factory_name.as_ptr() as LPCSTR);
-            assert!(!dwrite_create_factory_ptr.is_null());
-
-            let dwrite_create_factory = mem::transmute::<*const c_void, DWriteCreateFactoryType>(
-                dwrite_create_factory_ptr as *const _,
-            );
-
-            let mut factory: *mut IDWriteFactory = ptr::null_mut();
-            let hr = dwrite_create_factory(
-                DWRITE_FACTORY_TYPE_SHARED,
-                &IDWriteFactory::uuidof(),
-                &mut factory as *mut *mut IDWriteFactory as *mut *mut IUnknown,
-            );
-            assert!(hr == S_OK);
-            factory as usize
-        }
-    };
     static ref DEFAULT_DWRITE_RENDERING_PARAMS_RAW_PTR: usize = {
         unsafe {
             let mut default_rendering_params: *mut IDWriteRenderingParams = ptr::null_mut();
@@ -156,11 +240,13 @@ lazy_static! {
 // that has a DerefMut impl, so that we can write
 // DWriteFactory().SomeOperation() as opposed to
 // (*DWriteFactory()).SomeOperation()
+#[cfg(any(windows, test))]
 #[allow(non_snake_case)]
 fn DWriteFactory() -> *mut IDWriteFactory {
-    (*DWRITE_FACTORY_RAW_PTR) as *mut IDWriteFactory
+    factory::shared_factory_ptr()
 }
 
+#[cfg(any(windows, test))]
 #[allow(non_snake_case)]
 fn DefaultDWriteRenderParams() -> *mut IDWriteRenderingParams {
     (*DEFAULT_DWRITE_RENDERING_PARAMS_RAW_PTR) as *mut IDWriteRenderingParams
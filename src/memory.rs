@@ -0,0 +1,684 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! A pure-Rust font backend over in-memory font buffers.
+//!
+//! This backend reads the OpenType tables directly, so it works on every
+//! platform. It is what the public font types use off Windows, and it can
+//! be used on Windows too through the [`backend`](crate::backend) traits.
+//!
+//! Font simulations are recorded but not applied, so faces created with them
+//! can't be outlined. Outlines come from the `glyf`, `CFF ` or `CFF2` table;
+//! `CFF2` outlines follow the face's axis values, but `gvar` variations
+//! aren't applied to `glyf` ones, so variable `glyf` faces can only be
+//! outlined at their default instance. Advances come from `hmtx`, without
+//! `HVAR` deltas.
+
+use std::sync::Arc;
+
+use crate::analysis::FontFileAnalysis;
+use crate::backend::{
+    FactoryBackend, FontBackend, FontCollectionBackend, FontFaceBackend, FontFamilyBackend,
+    FontFileBackend,
+};
+use crate::font_matching::match_fonts;
+use crate::tables::avar::AvarTable;
+use crate::tables::cff::CffTable;
+use crate::tables::fvar::FvarTable;
+use crate::tables::glyf::{self, GlyfTable};
+use crate::tables::os2::{Os2Classification, Os2Table};
+use crate::tables::{self, cmap::CmapTable, name, TableDirectory};
+use crate::{
+    FontDescriptor, FontFaceType, FontMetrics0, FontSimulations, FontStretch, FontStyle,
+    FontWeight, GlyphOffset, GlyphRunOutlineError, OutlineBuilder, Tag, DWRITE_FONT_AXIS_VALUE,
+    DWRITE_FONT_SIMULATIONS, DWRITE_FONT_SIMULATIONS_NONE, HRESULT,
+};
+
+const E_NOTIMPL: HRESULT = 0x80004001_u32 as HRESULT;
+const E_INVALIDARG: HRESULT = 0x80070057_u32 as HRESULT;
+const DWRITE_E_FILEFORMAT: HRESULT = 0x88985000_u32 as HRESULT;
+
+/// Creates [`MemoryFontFile`]s.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoryFactory;
+
+impl FactoryBackend for MemoryFactory {
+    fn create_font_file_from_buffer(
+        &self,
+        data: Arc<dyn AsRef<[u8]> + Sync + Send>,
+    ) -> Result<Box<dyn FontFileBackend>, HRESULT> {
+        MemoryFontFile::new_from_buffer(data)
+            .map(|file| Box::new(file) as Box<dyn FontFileBackend>)
+            .ok_or(DWRITE_E_FILEFORMAT)
+    }
+}
+
+/// A font file whose bytes are held in memory.
+#[derive(Clone)]
+pub struct MemoryFontFile {
+    data: Arc<dyn AsRef<[u8]> + Sync + Send>,
+    face_type: FontFaceType,
+    face_count: u32,
+}
+
+impl MemoryFontFile {
+    /// Wraps `data`, returning `None` if it isn't a font this backend can
+    /// read.
+    pub fn new_from_buffer(data: Arc<dyn AsRef<[u8]> + Sync + Send>) -> Option<MemoryFontFile> {
+        let (face_type, face_count) = analyze((*data).as_ref())?;
+        Some(MemoryFontFile {
+            data,
+            face_type,
+            face_count,
+        })
+    }
+
+    /// Returns the number of faces in `buffer`, or 0 if it isn't a font this
+    /// backend can read.
+    pub fn analyze_buffer(buffer: Arc<dyn AsRef<[u8]> + Sync + Send>) -> u32 {
+        analyze((*buffer).as_ref()).map_or(0, |(_, face_count)| face_count)
+    }
+
+    pub fn face_type(&self) -> FontFaceType {
+        self.face_type
+    }
+
+    pub fn face_count(&self) -> u32 {
+        self.face_count
+    }
+
+    pub fn create_face(
+        &self,
+        face_index: u32,
+        simulations: DWRITE_FONT_SIMULATIONS,
+    ) -> Result<MemoryFontFace, HRESULT> {
+        if face_index >= self.face_count {
+            return Err(E_INVALIDARG);
+        }
+        let directory =
+            TableDirectory::parse(self.bytes(), face_index).ok_or(DWRITE_E_FILEFORMAT)?;
+        Ok(MemoryFontFace {
+            file: self.clone(),
+            index: face_index,
+            simulations,
+            directory: Arc::new(directory),
+            axis_values: Arc::new(vec![]),
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        (*self.data).as_ref()
+    }
+}
+
+// The formats this backend can read are exactly those DirectWrite loads from
+// memory.
+fn analyze(data: &[u8]) -> Option<(FontFaceType, u32)> {
+    let analysis = FontFileAnalysis::analyze(data);
+    if !analysis.is_supported() {
+        return None;
+    }
+    Some((analysis.face_type, analysis.face_count))
+}
+
+impl FontFileBackend for MemoryFontFile {
+    fn font_file_bytes(&self) -> Result<Vec<u8>, HRESULT> {
+        Ok(self.bytes().to_vec())
+    }
+
+    fn create_face(
+        &self,
+        face_index: u32,
+        simulations: DWRITE_FONT_SIMULATIONS,
+    ) -> Result<Box<dyn FontFaceBackend>, HRESULT> {
+        MemoryFontFile::create_face(self, face_index, simulations)
+            .map(|face| Box::new(face) as Box<dyn FontFaceBackend>)
+    }
+}
+
+/// One face of a [`MemoryFontFile`].
+#[derive(Clone)]
+pub struct MemoryFontFace {
+    file: MemoryFontFile,
+    index: u32,
+    simulations: DWRITE_FONT_SIMULATIONS,
+    directory: Arc<TableDirectory>,
+    /// The axis values this face was created with, clamped to the axes of
+    /// its `fvar` table.
+    axis_values: Arc<Vec<(Tag, f32)>>,
+}
+
+/// Where a face's glyph outlines come from.
+enum Outlines<'a> {
+    Glyf(GlyfTable<'a>),
+    /// A `CFF ` or `CFF2` table and the normalized variation coordinates.
+    Cff(Box<CffTable<'a>>, Vec<f32>),
+}
+
+impl MemoryFontFace {
+    pub fn file(&self) -> &MemoryFontFile {
+        &self.file
+    }
+
+    fn table(&self, tag: Tag) -> Option<&[u8]> {
+        self.directory.table(self.file.bytes(), tag)
+    }
+
+    fn units_per_em(&self) -> Option<u16> {
+        tables::units_per_em(self.table(Tag::HEAD)?).filter(|&upem| upem != 0)
+    }
+
+    fn advance_width(&self, glyph_id: u16) -> Option<u16> {
+        tables::advance_width(self.table(Tag::HHEA)?, self.table(Tag::HMTX)?, glyph_id)
+    }
+
+    fn fvar(&self) -> Option<FvarTable> {
+        self.table(Tag::FVAR).and_then(FvarTable::parse)
+    }
+
+    /// Returns the normalized variation coordinates of the face's axis
+    /// values, in `fvar` axis order, or an empty `Vec` if it isn't variable.
+    fn normalized_coordinates(&self) -> Vec<f32> {
+        match self.fvar() {
+            Some(fvar) => {
+                let avar = self.table(Tag::AVAR).and_then(AvarTable::parse);
+                fvar.normalize(&self.axis_values, avar.as_ref())
+            }
+            None => vec![],
+        }
+    }
+
+    fn outlines(&self) -> Result<Outlines<'_>, HRESULT> {
+        let head = self.table(Tag::HEAD).ok_or(DWRITE_E_FILEFORMAT)?;
+        if let Some(glyf_table) = self.table(Tag::GLYF) {
+            // Without `gvar` support, other instances would silently come
+            // out as the default one.
+            if self.table(Tag::GVAR).is_some()
+                && self
+                    .normalized_coordinates()
+                    .iter()
+                    .any(|&coordinate| coordinate != 0.)
+            {
+                return Err(E_NOTIMPL);
+            }
+            let loca = self.table(Tag::LOCA).ok_or(DWRITE_E_FILEFORMAT)?;
+            let glyf = GlyfTable::parse(head, loca, glyf_table).ok_or(DWRITE_E_FILEFORMAT)?;
+            return Ok(Outlines::Glyf(glyf));
+        }
+        let cff_table = self
+            .table(Tag::CFF2)
+            .or_else(|| self.table(Tag::CFF))
+            .ok_or(E_NOTIMPL)?;
+        let cff = CffTable::parse(head, cff_table).ok_or(DWRITE_E_FILEFORMAT)?;
+        let coordinates = if cff.is_cff2() {
+            self.normalized_coordinates()
+        } else {
+            vec![]
+        };
+        Ok(Outlines::Cff(Box::new(cff), coordinates))
+    }
+}
+
+impl FontFaceBackend for MemoryFontFace {
+    fn files(&self) -> Result<Vec<Box<dyn FontFileBackend>>, HRESULT> {
+        Ok(vec![Box::new(self.file.clone())])
+    }
+
+    fn face_type(&self) -> FontFaceType {
+        self.file.face_type
+    }
+
+    fn index(&self) -> u32 {
+        self.index
+    }
+
+    fn simulations(&self) -> FontSimulations {
+        FontSimulations::from_bits_truncate(self.simulations)
+    }
+
+    fn glyph_count(&self) -> u16 {
+        self.table(Tag::MAXP)
+            .and_then(tables::num_glyphs)
+            .unwrap_or(0)
+    }
+
+    fn metrics(&self) -> FontMetrics0 {
+        let read_i16 = |table: Option<&[u8]>, offset| {
+            table
+                .and_then(|table| tables::read_u16(table, offset))
+                .map_or(0, |value| value as i16 as i32)
+        };
+        let hhea = self.table(Tag::HHEA);
+        let (hhea_ascender, hhea_descender, hhea_line_gap) =
+            (read_i16(hhea, 4), read_i16(hhea, 6), read_i16(hhea, 8));
+        let post = self.table(Tag::POST);
+        let os2 = self.table(Tag::OS2).and_then(Os2Table::parse);
+
+        // Like DirectWrite, use the typographic metrics only when the font
+        // asks for them, and otherwise the Windows ones, with the rest of the
+        // `hhea` line height as the gap.
+        // Short version 0 tables have neither, so `hhea` has the last word.
+        let typo_metrics = os2
+            .as_ref()
+            .filter(|os2| os2.fs_selection.use_typo_metrics())
+            .and_then(|os2| Some((os2.typo_ascender?, os2.typo_descender?, os2.typo_line_gap?)));
+        let win_metrics = os2
+            .as_ref()
+            .and_then(|os2| Some((os2.win_ascent?, os2.win_descent?)));
+        let (ascent, descent, line_gap) = match (typo_metrics, win_metrics) {
+            (Some((ascender, descender, line_gap)), _) => {
+                (ascender as i32, -(descender as i32), line_gap as i32)
+            }
+            (None, Some((ascent, descent))) => {
+                let (ascent, descent) = (ascent as i32, descent as i32);
+                let hhea_height = hhea_ascender - hhea_descender + hhea_line_gap;
+                (ascent, descent, (hhea_height - ascent - descent).max(0))
+            }
+            (None, None) => (hhea_ascender, -hhea_descender, hhea_line_gap),
+        };
+        let underline_thickness = read_i16(post, 10);
+        FontMetrics0 {
+            designUnitsPerEm: self.units_per_em().unwrap_or(0),
+            ascent: ascent.max(0) as u16,
+            descent: descent.max(0) as u16,
+            lineGap: line_gap as i16,
+            // Rough estimates for fonts whose `OS/2` is too old to say.
+            capHeight: os2
+                .as_ref()
+                .and_then(|os2| os2.cap_height)
+                .map_or(ascent * 7 / 10, |height| height as i32)
+                .max(0) as u16,
+            xHeight: os2
+                .as_ref()
+                .and_then(|os2| os2.x_height)
+                .map_or(ascent / 2, |height| height as i32)
+                .max(0) as u16,
+            underlinePosition: read_i16(post, 8) as i16,
+            underlineThickness: underline_thickness.max(0) as u16,
+            strikethroughPosition: os2
+                .as_ref()
+                .map_or(ascent / 3, |os2| os2.strikeout_position as i32)
+                as i16,
+            strikethroughThickness: os2
+                .as_ref()
+                .map_or(underline_thickness, |os2| os2.strikeout_size as i32)
+                .max(0) as u16,
+        }
+    }
+
+    fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT> {
+        let cmap = match self.table(Tag::CMAP) {
+            Some(data) => CmapTable::parse(data).ok_or(DWRITE_E_FILEFORMAT)?,
+            None => return Ok(vec![0; code_points.len()]),
+        };
+        Ok(code_points
+            .iter()
+            .map(|&code_point| cmap.glyph_index(code_point).unwrap_or(0))
+            .collect())
+    }
+
+    fn font_table(&self, opentype_table_tag: u32) -> Result<Option<Vec<u8>>, HRESULT> {
+        Ok(self
+            .table(Tag::from_dwrite(opentype_table_tag))
+            .map(|table| table.to_vec()))
+    }
+
+    fn glyph_run_outline(
+        &self,
+        em_size: f32,
+        glyph_indices: &[u16],
+        glyph_advances: Option<&[f32]>,
+        glyph_offsets: Option<&[GlyphOffset]>,
+        is_sideways: bool,
+        is_right_to_left: bool,
+        mut outline_builder: Box<dyn OutlineBuilder>,
+    ) -> Result<(), GlyphRunOutlineError> {
+        if glyph_advances.is_some_and(|advances| advances.len() != glyph_indices.len())
+            || glyph_offsets.is_some_and(|offsets| offsets.len() != glyph_indices.len())
+        {
+            return Err(GlyphRunOutlineError::InvalidInput);
+        }
+        // Neither sideways runs nor emboldened or slanted outlines are
+        // implemented.
+        if is_sideways || self.simulations != DWRITE_FONT_SIMULATIONS_NONE {
+            return Err(GlyphRunOutlineError::Win32Error(E_NOTIMPL));
+        }
+
+        let units_per_em = self
+            .units_per_em()
+            .ok_or(GlyphRunOutlineError::Win32Error(DWRITE_E_FILEFORMAT))?;
+        let scale = em_size / units_per_em as f32;
+        let outlines = self.outlines().map_err(GlyphRunOutlineError::Win32Error)?;
+
+        // Like DirectWrite, lay the run out along the baseline starting at
+        // the origin, with y growing downwards.
+        let mut pen_x = 0.;
+        for (i, &glyph_id) in glyph_indices.iter().enumerate() {
+            let advance = match glyph_advances {
+                Some(advances) => advances[i],
+                None => self.advance_width(glyph_id).unwrap_or(0) as f32 * scale,
+            };
+            let (advance_offset, ascender_offset) = match glyph_offsets {
+                Some(offsets) => (offsets[i].advanceOffset, offsets[i].ascenderOffset),
+                None => (0., 0.),
+            };
+            let origin_x = if is_right_to_left {
+                pen_x -= advance;
+                pen_x - advance_offset
+            } else {
+                let origin_x = pen_x + advance_offset;
+                pen_x += advance;
+                origin_x
+            };
+            let origin_y = -ascender_offset;
+
+            let transform = |x, y| (origin_x + x * scale, origin_y - y * scale);
+            let malformed = GlyphRunOutlineError::Win32Error(DWRITE_E_FILEFORMAT);
+            match outlines {
+                Outlines::Glyf(ref glyf) => {
+                    let contours = glyf.contours(glyph_id).ok_or(malformed)?;
+                    glyf::emit_contours(&contours, &mut *outline_builder, transform);
+                }
+                Outlines::Cff(ref cff, ref coordinates) => cff
+                    .emit_outline(glyph_id, coordinates, &mut *outline_builder, transform)
+                    .ok_or(malformed)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT> {
+        let fvar = match self.fvar() {
+            Some(fvar) => fvar,
+            None => return Ok(vec![]),
+        };
+        Ok(fvar
+            .axes
+            .iter()
+            .map(|axis| {
+                let value = self
+                    .axis_values
+                    .iter()
+                    .find(|&&(tag, _)| tag == axis.tag)
+                    .map_or(axis.default_value, |&(_, value)| value);
+                axis.tag.axis_value(value)
+            })
+            .collect())
+    }
+
+    fn create_font_face_with_variations(
+        &self,
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axis_values: &[DWRITE_FONT_AXIS_VALUE],
+    ) -> Option<Box<dyn FontFaceBackend>> {
+        // Like DirectWrite, ignore unknown axes and clamp values to the
+        // axis ranges. Later values for the same axis win.
+        let fvar = self.fvar();
+        let axes = fvar.as_ref().map_or(&[][..], |fvar| &fvar.axes[..]);
+        let mut clamped: Vec<(Tag, f32)> = vec![];
+        for axis_value in axis_values {
+            let tag = Tag::from_dwrite(axis_value.axisTag);
+            if let Some(axis) = axes.iter().find(|axis| axis.tag == tag) {
+                let value = axis_value.value.max(axis.min_value).min(axis.max_value);
+                clamped.retain(|&(t, _)| t != tag);
+                clamped.push((tag, value));
+            }
+        }
+        Some(Box::new(MemoryFontFace {
+            simulations,
+            axis_values: Arc::new(clamped),
+            ..self.clone()
+        }))
+    }
+}
+
+/// A face of a [`MemoryFontCollection`], with the names and classification
+/// read from its `name` and `OS/2` tables.
+#[derive(Clone)]
+pub struct MemoryFont {
+    face: MemoryFontFace,
+    family_name: String,
+    face_name: String,
+    weight: FontWeight,
+    stretch: FontStretch,
+    style: FontStyle,
+}
+
+impl MemoryFont {
+    /// Reads the font description of `face`. Returns `None` if the face has
+    /// no usable family name.
+    pub fn new(face: MemoryFontFace) -> Option<MemoryFont> {
+        let name_table = face.table(Tag::NAME)?;
+        let family_name = [
+            name::NAME_ID_WWS_FAMILY,
+            name::NAME_ID_TYPOGRAPHIC_FAMILY,
+            name::NAME_ID_FAMILY,
+        ]
+        .iter()
+        .find_map(|&name_id| name::find_string(name_table, name_id))?;
+        let face_name = [
+            name::NAME_ID_WWS_SUBFAMILY,
+            name::NAME_ID_TYPOGRAPHIC_SUBFAMILY,
+            name::NAME_ID_SUBFAMILY,
+        ]
+        .iter()
+        .find_map(|&name_id| name::find_string(name_table, name_id))
+        .unwrap_or_else(|| "Regular".to_owned());
+
+        let (weight, stretch, style) = match face.table(Tag::OS2).and_then(Os2Classification::parse)
+        {
+            Some(os2) => {
+                let stretch = match os2.width_class {
+                    1..=9 => FontStretch::from_u32(os2.width_class as u32),
+                    _ => FontStretch::Normal,
+                };
+                let style = if os2.is_italic() {
+                    FontStyle::Italic
+                } else if os2.is_oblique() {
+                    FontStyle::Oblique
+                } else {
+                    FontStyle::Normal
+                };
+                (
+                    FontWeight::from_u32(os2.weight_class as u32),
+                    stretch,
+                    style,
+                )
+            }
+            None => (FontWeight::Regular, FontStretch::Normal, FontStyle::Normal),
+        };
+
+        Some(MemoryFont {
+            face,
+            family_name,
+            face_name,
+            weight,
+            stretch,
+            style,
+        })
+    }
+
+    pub fn face(&self) -> &MemoryFontFace {
+        &self.face
+    }
+}
+
+impl FontBackend for MemoryFont {
+    fn family_name(&self) -> String {
+        self.family_name.clone()
+    }
+
+    fn face_name(&self) -> String {
+        self.face_name.clone()
+    }
+
+    fn weight(&self) -> FontWeight {
+        self.weight
+    }
+
+    fn stretch(&self) -> FontStretch {
+        self.stretch
+    }
+
+    fn style(&self) -> FontStyle {
+        self.style
+    }
+
+    fn simulations(&self) -> FontSimulations {
+        self.face.simulations()
+    }
+
+    fn is_monospace(&self) -> Option<bool> {
+        // `post.isFixedPitch`.
+        let post = self.face.table(Tag::POST)?;
+        tables::read_u32(post, 12).map(|is_fixed_pitch| is_fixed_pitch != 0)
+    }
+
+    fn create_font_face(&self) -> Box<dyn FontFaceBackend> {
+        Box::new(self.face.clone())
+    }
+}
+
+/// The fonts of a [`MemoryFontCollection`] sharing a family name.
+#[derive(Clone)]
+pub struct MemoryFontFamily {
+    name: String,
+    fonts: Vec<MemoryFont>,
+}
+
+impl MemoryFontFamily {
+    pub fn fonts(&self) -> &[MemoryFont] {
+        &self.fonts
+    }
+
+    /// Picks the font CSS would for the given properties, with
+    /// [`match_fonts`].
+    fn first_matching(
+        &self,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Option<&MemoryFont> {
+        let descriptor = |weight, stretch, style| FontDescriptor {
+            family_name: self.name.clone(),
+            weight,
+            stretch,
+            style,
+            oblique_angle: None,
+            simulations: None,
+        };
+        let candidates: Vec<_> = self
+            .fonts
+            .iter()
+            .map(|font| descriptor(font.weight, font.stretch, font.style))
+            .collect();
+        let best = match_fonts(&descriptor(weight, stretch, style), &candidates)
+            .first()?
+            .index;
+        self.fonts.get(best)
+    }
+}
+
+impl FontFamilyBackend for MemoryFontFamily {
+    fn family_name(&self) -> Result<String, HRESULT> {
+        Ok(self.name.clone())
+    }
+
+    fn font_count(&self) -> u32 {
+        self.fonts.len() as u32
+    }
+
+    fn font(&self, index: u32) -> Result<Box<dyn FontBackend>, HRESULT> {
+        self.fonts
+            .get(index as usize)
+            .map(|font| Box::new(font.clone()) as Box<dyn FontBackend>)
+            .ok_or(E_INVALIDARG)
+    }
+
+    fn first_matching_font(
+        &self,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Result<Box<dyn FontBackend>, HRESULT> {
+        self.first_matching(weight, stretch, style)
+            .map(|font| Box::new(font.clone()) as Box<dyn FontBackend>)
+            .ok_or(E_INVALIDARG)
+    }
+}
+
+/// A font collection built from [`MemoryFontFile`]s, grouping their faces
+/// into families by name.
+#[derive(Clone, Default)]
+pub struct MemoryFontCollection {
+    families: Vec<MemoryFontFamily>,
+}
+
+impl MemoryFontCollection {
+    pub fn new() -> MemoryFontCollection {
+        MemoryFontCollection::default()
+    }
+
+    pub fn from_font_files(font_files: &[MemoryFontFile]) -> MemoryFontCollection {
+        let mut collection = MemoryFontCollection::new();
+        for font_file in font_files {
+            collection.add_font_file(font_file);
+        }
+        collection
+    }
+
+    /// Adds every face of `font_file` that has a family name.
+    pub fn add_font_file(&mut self, font_file: &MemoryFontFile) {
+        for face_index in 0..font_file.face_count {
+            let font = match font_file
+                .create_face(face_index, DWRITE_FONT_SIMULATIONS_NONE)
+                .ok()
+                .and_then(MemoryFont::new)
+            {
+                Some(font) => font,
+                None => continue,
+            };
+            match self.family_index(&font.family_name) {
+                Some(index) => self.families[index].fonts.push(font),
+                None => self.families.push(MemoryFontFamily {
+                    name: font.family_name.clone(),
+                    fonts: vec![font],
+                }),
+            }
+        }
+    }
+
+    pub fn families(&self) -> &[MemoryFontFamily] {
+        &self.families
+    }
+
+    fn family_index(&self, family_name: &str) -> Option<usize> {
+        let family_name = family_name.to_lowercase();
+        self.families
+            .iter()
+            .position(|family| family.name.to_lowercase() == family_name)
+    }
+}
+
+impl FontCollectionBackend for MemoryFontCollection {
+    fn font_family_count(&self) -> u32 {
+        self.families.len() as u32
+    }
+
+    fn font_family(&self, index: u32) -> Result<Box<dyn FontFamilyBackend>, HRESULT> {
+        self.families
+            .get(index as usize)
+            .map(|family| Box::new(family.clone()) as Box<dyn FontFamilyBackend>)
+            .ok_or(E_INVALIDARG)
+    }
+
+    fn font_family_by_name(
+        &self,
+        family_name: &str,
+    ) -> Result<Option<Box<dyn FontFamilyBackend>>, HRESULT> {
+        Ok(self
+            .family_index(family_name)
+            .map(|index| Box::new(self.families[index].clone()) as Box<dyn FontFamilyBackend>))
+    }
+}
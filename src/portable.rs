@@ -0,0 +1,462 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// The public font types for targets without DirectWrite. Each one is a
+// cheap, clonable handle over a `backend` implementation; the method names
+// and signatures follow the DirectWrite wrappers so that code using them
+// builds on every platform.
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::backend::{
+    FactoryBackend, FontBackend, FontCollectionBackend, FontFaceBackend, FontFamilyBackend,
+    FontFileBackend,
+};
+use crate::memory::{MemoryFactory, MemoryFontCollection, MemoryFontFile};
+use crate::{
+    FactoryType, FontDescriptor, FontFaceTables, FontFaceType, FontMetrics0, FontMetrics1,
+    FontSimulations, FontStretch, FontStyle, FontWeight, GlyphOffset, GlyphRunOutlineError,
+    OutlineBuilder, Tag, DWRITE_FONT_AXIS_VALUE, DWRITE_FONT_SIMULATIONS, HRESULT,
+};
+
+/// Creates the font objects that don't come from another object. By default
+/// it creates in-memory fonts, but any [`FactoryBackend`] can stand in.
+#[derive(Clone)]
+pub struct Factory {
+    backend: Rc<dyn FactoryBackend>,
+    factory_type: FactoryType,
+}
+
+impl Factory {
+    pub fn new(factory_type: FactoryType) -> Result<Factory, HRESULT> {
+        Ok(Factory::from_backend(Box::new(MemoryFactory), factory_type))
+    }
+
+    /// Returns the factory used by the constructors that don't take a
+    /// [`Factory`].
+    pub fn shared() -> Result<Factory, HRESULT> {
+        Factory::new(FactoryType::Shared)
+    }
+
+    /// Wraps a [`FactoryBackend`]. Unlike the other handles, this isn't
+    /// called `take`: the DirectWrite `Factory::take` wraps an
+    /// `IDWriteFactory`, which may itself be implemented in Rust.
+    pub fn from_backend(backend: Box<dyn FactoryBackend>, factory_type: FactoryType) -> Factory {
+        Factory {
+            backend: Rc::from(backend),
+            factory_type,
+        }
+    }
+
+    pub fn factory_type(&self) -> FactoryType {
+        self.factory_type
+    }
+}
+
+pub struct FontCollectionFamilyIterator {
+    collection: FontCollection,
+    curr: u32,
+    count: u32,
+}
+
+impl Iterator for FontCollectionFamilyIterator {
+    type Item = FontFamily;
+    /// Skips families the backend fails to return.
+    fn next(&mut self) -> Option<FontFamily> {
+        while self.curr < self.count {
+            let family = self.collection.font_family(self.curr);
+            self.curr += 1;
+            if let Ok(family) = family {
+                return Some(family);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone)]
+pub struct FontCollection {
+    backend: Rc<dyn FontCollectionBackend>,
+}
+
+impl FontCollection {
+    pub fn take(backend: Box<dyn FontCollectionBackend>) -> FontCollection {
+        FontCollection {
+            backend: Rc::from(backend),
+        }
+    }
+
+    /// Creates an in-memory collection holding the faces of `font_files`.
+    pub fn from_font_files(font_files: &[FontFile]) -> Result<FontCollection, HRESULT> {
+        let mut collection = MemoryFontCollection::new();
+        for font_file in font_files {
+            let bytes = font_file.font_file_bytes()?;
+            if let Some(memory_file) = MemoryFontFile::new_from_buffer(Arc::new(bytes)) {
+                collection.add_font_file(&memory_file);
+            }
+        }
+        Ok(FontCollection::take(Box::new(collection)))
+    }
+
+    pub fn families_iter(&self) -> FontCollectionFamilyIterator {
+        FontCollectionFamilyIterator {
+            collection: self.clone(),
+            curr: 0,
+            count: self.backend.font_family_count(),
+        }
+    }
+
+    pub fn get_font_family_count(&self) -> u32 {
+        self.backend.font_family_count()
+    }
+
+    /// Returns the [`FontFamily`] at the given index.
+    pub fn font_family(&self, index: u32) -> Result<FontFamily, HRESULT> {
+        self.backend.font_family(index).map(FontFamily::take)
+    }
+
+    /// Find a font matching the given font descriptor in this [`FontCollection`].
+    /// Only exact matches are returned; use [`match_fonts`](crate::match_fonts)
+    /// to rank a family's fonts the way CSS does.
+    pub fn font_from_descriptor(&self, desc: &FontDescriptor) -> Result<Option<Font>, HRESULT> {
+        if let Some(family) = self.font_family_by_name(&desc.family_name)? {
+            let font = family.first_matching_font(desc.weight, desc.stretch, desc.style)?;
+            // Exact matches only here
+            if font.weight() == desc.weight
+                && font.stretch() == desc.stretch
+                && font.style() == desc.style
+            {
+                return Ok(Some(font));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Find a [`FontFamily`] with the given name. Returns `None` if no family
+    /// with that name is found.
+    pub fn font_family_by_name(&self, family_name: &str) -> Result<Option<FontFamily>, HRESULT> {
+        Ok(self
+            .backend
+            .font_family_by_name(family_name)?
+            .map(FontFamily::take))
+    }
+}
+
+#[derive(Clone)]
+pub struct FontFamily {
+    backend: Rc<dyn FontFamilyBackend>,
+}
+
+impl FontFamily {
+    pub fn take(backend: Box<dyn FontFamilyBackend>) -> FontFamily {
+        FontFamily {
+            backend: Rc::from(backend),
+        }
+    }
+
+    pub fn family_name(&self) -> Result<String, HRESULT> {
+        self.backend.family_name()
+    }
+
+    pub fn first_matching_font(
+        &self,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Result<Font, HRESULT> {
+        self.backend
+            .first_matching_font(weight, stretch, style)
+            .map(Font::take)
+    }
+
+    pub fn get_font_count(&self) -> u32 {
+        self.backend.font_count()
+    }
+
+    pub fn font(&self, index: u32) -> Result<Font, HRESULT> {
+        self.backend.font(index).map(Font::take)
+    }
+}
+
+#[derive(Clone)]
+pub struct Font {
+    backend: Rc<dyn FontBackend>,
+}
+
+impl Font {
+    pub fn take(backend: Box<dyn FontBackend>) -> Font {
+        Font {
+            backend: Rc::from(backend),
+        }
+    }
+
+    pub fn to_descriptor(&self) -> FontDescriptor {
+        FontDescriptor {
+            family_name: self.family_name(),
+            stretch: self.stretch(),
+            style: self.style(),
+            weight: self.weight(),
+            oblique_angle: None,
+            simulations: Some(self.simulations()),
+        }
+    }
+
+    pub fn stretch(&self) -> FontStretch {
+        self.backend.stretch()
+    }
+
+    pub fn style(&self) -> FontStyle {
+        self.backend.style()
+    }
+
+    pub fn weight(&self) -> FontWeight {
+        self.backend.weight()
+    }
+
+    pub fn is_monospace(&self) -> Option<bool> {
+        self.backend.is_monospace()
+    }
+
+    pub fn simulations(&self) -> FontSimulations {
+        self.backend.simulations()
+    }
+
+    pub fn family_name(&self) -> String {
+        self.backend.family_name()
+    }
+
+    pub fn face_name(&self) -> String {
+        self.backend.face_name()
+    }
+
+    pub fn create_font_face(&self) -> FontFace {
+        FontFace::take(self.backend.create_font_face())
+    }
+
+    pub fn metrics(&self) -> FontMetrics {
+        self.create_font_face().metrics()
+    }
+}
+
+/// A wrapper around the `DWRITE_FONT_METRICS` and `DWRITE_FONT_METRICS1` types.
+pub enum FontMetrics {
+    Metrics0(FontMetrics0),
+    Metrics1(FontMetrics1),
+}
+
+impl FontMetrics {
+    /// Convert self to the Metrics0 arm (throwing away additional information)
+    pub fn metrics0(self) -> FontMetrics0 {
+        match self {
+            FontMetrics::Metrics0(metrics) => metrics,
+            FontMetrics::Metrics1(metrics) => FontMetrics0 {
+                designUnitsPerEm: metrics.designUnitsPerEm,
+                ascent: metrics.ascent,
+                descent: metrics.descent,
+                lineGap: metrics.lineGap,
+                capHeight: metrics.capHeight,
+                xHeight: metrics.xHeight,
+                underlinePosition: metrics.underlinePosition,
+                underlineThickness: metrics.underlineThickness,
+                strikethroughPosition: metrics.strikethroughPosition,
+                strikethroughThickness: metrics.strikethroughThickness,
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FontFace {
+    backend: Rc<dyn FontFaceBackend>,
+}
+
+impl FontFace {
+    pub fn take(backend: Box<dyn FontFaceBackend>) -> FontFace {
+        FontFace {
+            backend: Rc::from(backend),
+        }
+    }
+
+    pub fn files(&self) -> Result<Vec<FontFile>, HRESULT> {
+        Ok(self
+            .backend
+            .files()?
+            .into_iter()
+            .map(FontFile::take)
+            .collect())
+    }
+
+    pub fn get_glyph_count(&self) -> u16 {
+        self.backend.glyph_count()
+    }
+
+    pub fn metrics(&self) -> FontMetrics {
+        FontMetrics::Metrics0(self.backend.metrics())
+    }
+
+    pub fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT> {
+        self.backend.glyph_indices(code_points)
+    }
+
+    /// Returns the contents of the OpenType table with the given tag.
+    ///
+    /// NB: The bytes of the tag are reversed! You probably want to use the `u32::swap_bytes()`
+    /// method on the tag value before calling this method, or `font_table_by_tag` instead.
+    pub fn font_table(&self, opentype_table_tag: u32) -> Result<Option<Vec<u8>>, HRESULT> {
+        self.backend.font_table(opentype_table_tag)
+    }
+
+    /// Returns the contents of the OpenType table with the given tag.
+    pub fn font_table_by_tag(&self, tag: Tag) -> Result<Option<Vec<u8>>, HRESULT> {
+        self.font_table(tag.to_dwrite())
+    }
+
+    pub fn has_variations(&self) -> bool {
+        self.backend
+            .variations()
+            .is_ok_and(|axis_values| !axis_values.is_empty())
+    }
+
+    /// If this font has variations, return a [`Vec<DWRITE_FONT_AXIS_VALUE`] of the
+    /// variation axes and their values. If the font does not have variations,
+    /// return an empty `Vec`.
+    pub fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT> {
+        self.backend.variations()
+    }
+
+    /// Returns the value of the variation axis with the given tag, or `None` if
+    /// the font doesn't vary along it.
+    pub fn variation_by_tag(&self, tag: Tag) -> Result<Option<f32>, HRESULT> {
+        Ok(self
+            .variations()?
+            .iter()
+            .find(|axis_value| axis_value.axisTag == tag.to_dwrite())
+            .map(|axis_value| axis_value.value))
+    }
+
+    /// Like `create_font_face_with_variations`, but takes `(tag, value)` pairs.
+    pub fn create_font_face_with_variations_by_tag(
+        &self,
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axis_values: &[(Tag, f32)],
+    ) -> Option<FontFace> {
+        let axis_values: Vec<_> = axis_values
+            .iter()
+            .map(|&(tag, value)| tag.axis_value(value))
+            .collect();
+        self.create_font_face_with_variations(simulations, &axis_values)
+    }
+
+    pub fn create_font_face_with_variations(
+        &self,
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axis_values: &[DWRITE_FONT_AXIS_VALUE],
+    ) -> Option<FontFace> {
+        self.backend
+            .create_font_face_with_variations(simulations, axis_values)
+            .map(FontFace::take)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn glyph_run_outline(
+        &self,
+        em_size: f32,
+        glyph_indices: &[u16],
+        glyph_advances: Option<&[f32]>,
+        glyph_offsets: Option<&[GlyphOffset]>,
+        is_sideways: bool,
+        is_right_to_left: bool,
+        outline_builder: Box<dyn OutlineBuilder>,
+    ) -> Result<(), GlyphRunOutlineError> {
+        self.backend.glyph_run_outline(
+            em_size,
+            glyph_indices,
+            glyph_advances,
+            glyph_offsets,
+            is_sideways,
+            is_right_to_left,
+            outline_builder,
+        )
+    }
+
+    #[inline]
+    pub fn get_type(&self) -> FontFaceType {
+        self.backend.face_type()
+    }
+
+    #[inline]
+    pub fn get_index(&self) -> u32 {
+        self.backend.index()
+    }
+
+    pub fn simulations(&self) -> FontSimulations {
+        self.backend.simulations()
+    }
+}
+
+impl FontFaceTables for FontFace {
+    fn font_table_by_tag(&self, tag: Tag) -> Result<Option<Vec<u8>>, HRESULT> {
+        FontFace::font_table_by_tag(self, tag)
+    }
+
+    fn get_glyph_count(&self) -> u16 {
+        FontFace::get_glyph_count(self)
+    }
+
+    fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT> {
+        FontFace::glyph_indices(self, code_points)
+    }
+
+    fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT> {
+        FontFace::variations(self)
+    }
+}
+
+#[derive(Clone)]
+pub struct FontFile {
+    backend: Rc<dyn FontFileBackend>,
+}
+
+impl FontFile {
+    pub fn new_from_buffer(data: Arc<dyn AsRef<[u8]> + Sync + Send>) -> Option<FontFile> {
+        Self::new_from_buffer_with_factory(&Factory::shared().ok()?, data)
+    }
+
+    pub fn new_from_buffer_with_factory(
+        factory: &Factory,
+        data: Arc<dyn AsRef<[u8]> + Sync + Send>,
+    ) -> Option<FontFile> {
+        factory
+            .backend
+            .create_font_file_from_buffer(data)
+            .ok()
+            .map(FontFile::take)
+    }
+
+    pub fn analyze_buffer(buffer: Arc<dyn AsRef<[u8]> + Sync + Send>) -> u32 {
+        MemoryFontFile::analyze_buffer(buffer)
+    }
+
+    pub fn take(backend: Box<dyn FontFileBackend>) -> FontFile {
+        FontFile {
+            backend: Rc::from(backend),
+        }
+    }
+
+    pub fn font_file_bytes(&self) -> Result<Vec<u8>, HRESULT> {
+        self.backend.font_file_bytes()
+    }
+
+    pub fn create_face(
+        &self,
+        face_index: u32,
+        simulations: DWRITE_FONT_SIMULATIONS,
+    ) -> Result<FontFace, HRESULT> {
+        self.backend
+            .create_face(face_index, simulations)
+            .map(FontFace::take)
+    }
+}
@@ -0,0 +1,106 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// Mirrors of the winapi DirectWrite definitions that appear in dwrote's
+// public API, for targets where winapi is empty. Layouts and names match
+// winapi exactly so that code using them compiles unchanged everywhere.
+
+#![allow(non_camel_case_types, non_snake_case)]
+
+pub type HRESULT = i32;
+
+pub type DWRITE_MEASURING_MODE = u32;
+pub const DWRITE_MEASURING_MODE_NATURAL: DWRITE_MEASURING_MODE = 0;
+pub const DWRITE_MEASURING_MODE_GDI_CLASSIC: DWRITE_MEASURING_MODE = 1;
+pub const DWRITE_MEASURING_MODE_GDI_NATURAL: DWRITE_MEASURING_MODE = 2;
+
+pub type DWRITE_FONT_SIMULATIONS = u32;
+pub const DWRITE_FONT_SIMULATIONS_NONE: DWRITE_FONT_SIMULATIONS = 0x0000;
+pub const DWRITE_FONT_SIMULATIONS_BOLD: DWRITE_FONT_SIMULATIONS = 0x0001;
+pub const DWRITE_FONT_SIMULATIONS_OBLIQUE: DWRITE_FONT_SIMULATIONS = 0x0002;
+
+pub type DWRITE_RENDERING_MODE = u32;
+pub const DWRITE_RENDERING_MODE_DEFAULT: DWRITE_RENDERING_MODE = 0;
+pub const DWRITE_RENDERING_MODE_ALIASED: DWRITE_RENDERING_MODE = 1;
+pub const DWRITE_RENDERING_MODE_GDI_CLASSIC: DWRITE_RENDERING_MODE = 2;
+pub const DWRITE_RENDERING_MODE_GDI_NATURAL: DWRITE_RENDERING_MODE = 3;
+pub const DWRITE_RENDERING_MODE_NATURAL: DWRITE_RENDERING_MODE = 4;
+pub const DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC: DWRITE_RENDERING_MODE = 5;
+pub const DWRITE_RENDERING_MODE_OUTLINE: DWRITE_RENDERING_MODE = 6;
+pub const DWRITE_RENDERING_MODE_CLEARTYPE_GDI_CLASSIC: DWRITE_RENDERING_MODE =
+    DWRITE_RENDERING_MODE_GDI_CLASSIC;
+pub const DWRITE_RENDERING_MODE_CLEARTYPE_GDI_NATURAL: DWRITE_RENDERING_MODE =
+    DWRITE_RENDERING_MODE_GDI_NATURAL;
+pub const DWRITE_RENDERING_MODE_CLEARTYPE_NATURAL: DWRITE_RENDERING_MODE =
+    DWRITE_RENDERING_MODE_NATURAL;
+pub const DWRITE_RENDERING_MODE_CLEARTYPE_NATURAL_SYMMETRIC: DWRITE_RENDERING_MODE =
+    DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FontMetrics0 {
+    pub designUnitsPerEm: u16,
+    pub ascent: u16,
+    pub descent: u16,
+    pub lineGap: i16,
+    pub capHeight: u16,
+    pub xHeight: u16,
+    pub underlinePosition: i16,
+    pub underlineThickness: u16,
+    pub strikethroughPosition: i16,
+    pub strikethroughThickness: u16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FontMetrics1 {
+    pub designUnitsPerEm: u16,
+    pub ascent: u16,
+    pub descent: u16,
+    pub lineGap: i16,
+    pub capHeight: u16,
+    pub xHeight: u16,
+    pub underlinePosition: i16,
+    pub underlineThickness: u16,
+    pub strikethroughPosition: i16,
+    pub strikethroughThickness: u16,
+    pub glyphBoxLeft: i16,
+    pub glyphBoxTop: i16,
+    pub glyphBoxRight: i16,
+    pub glyphBoxBottom: i16,
+    pub subscriptPositionX: i16,
+    pub subscriptPositionY: i16,
+    pub subscriptSizeX: i16,
+    pub subscriptSizeY: i16,
+    pub superscriptPositionX: i16,
+    pub superscriptPositionY: i16,
+    pub superscriptSizeX: i16,
+    pub superscriptSizeY: i16,
+    pub hasTypographicMetrics: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct GlyphOffset {
+    pub advanceOffset: f32,
+    pub ascenderOffset: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_MATRIX {
+    pub m11: f32,
+    pub m12: f32,
+    pub m21: f32,
+    pub m22: f32,
+    pub dx: f32,
+    pub dy: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_FONT_AXIS_VALUE {
+    pub axisTag: u32,
+    pub value: f32,
+}
@@ -0,0 +1,102 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Picks a rendering mode from a font's `gasp` table the way
+//! `IDWriteFontFace::GetRecommendedRenderingMode` does with default
+//! rendering parameters, so the decision is the same on every platform.
+
+use crate::tables::gasp::{gasp_range, GaspRange};
+use crate::{
+    DWRITE_MEASURING_MODE, DWRITE_MEASURING_MODE_GDI_CLASSIC, DWRITE_MEASURING_MODE_GDI_NATURAL,
+    DWRITE_RENDERING_MODE, DWRITE_RENDERING_MODE_ALIASED, DWRITE_RENDERING_MODE_GDI_CLASSIC,
+    DWRITE_RENDERING_MODE_GDI_NATURAL, DWRITE_RENDERING_MODE_NATURAL,
+    DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC, DWRITE_RENDERING_MODE_OUTLINE,
+};
+
+/// The size in pixels per em from which glyphs are drawn as anti-aliased
+/// outlines rather than rasterized glyph by glyph, unless the caller picks
+/// another threshold.
+pub const DEFAULT_OUTLINE_THRESHOLD: f32 = 100.;
+
+/// The largest size in pixels per em at which natural measuring renders
+/// without vertical anti-aliasing, unless `gasp` asks for it.
+const NATURAL_MAX_PPEM: f32 = 20.;
+
+/// How glyphs are rasterized; mirrors `DWRITE_RENDERING_MODE` without its
+/// `DEFAULT`, which is only a request for a recommendation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenderingMode {
+    /// Bilevel, without anti-aliasing.
+    Aliased,
+    /// Anti-aliased horizontally, with the glyph widths of GDI.
+    GdiClassic,
+    /// Anti-aliased horizontally, with the natural widths of GDI.
+    GdiNatural,
+    /// Anti-aliased horizontally, with fractional widths.
+    Natural,
+    /// Anti-aliased horizontally and vertically, with fractional widths.
+    NaturalSymmetric,
+    /// Filled as geometry, which is fastest for large sizes.
+    Outline,
+}
+
+impl RenderingMode {
+    /// Converts a `DWRITE_RENDERING_MODE`, returning `None` for `DEFAULT` or
+    /// an unknown value.
+    pub fn from_dwrite(mode: DWRITE_RENDERING_MODE) -> Option<RenderingMode> {
+        match mode {
+            DWRITE_RENDERING_MODE_ALIASED => Some(RenderingMode::Aliased),
+            DWRITE_RENDERING_MODE_GDI_CLASSIC => Some(RenderingMode::GdiClassic),
+            DWRITE_RENDERING_MODE_GDI_NATURAL => Some(RenderingMode::GdiNatural),
+            DWRITE_RENDERING_MODE_NATURAL => Some(RenderingMode::Natural),
+            DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC => Some(RenderingMode::NaturalSymmetric),
+            DWRITE_RENDERING_MODE_OUTLINE => Some(RenderingMode::Outline),
+            _ => None,
+        }
+    }
+
+    pub fn to_dwrite(self) -> DWRITE_RENDERING_MODE {
+        match self {
+            RenderingMode::Aliased => DWRITE_RENDERING_MODE_ALIASED,
+            RenderingMode::GdiClassic => DWRITE_RENDERING_MODE_GDI_CLASSIC,
+            RenderingMode::GdiNatural => DWRITE_RENDERING_MODE_GDI_NATURAL,
+            RenderingMode::Natural => DWRITE_RENDERING_MODE_NATURAL,
+            RenderingMode::NaturalSymmetric => DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC,
+            RenderingMode::Outline => DWRITE_RENDERING_MODE_OUTLINE,
+        }
+    }
+}
+
+/// Recommends how to render text of `em_size` DIPs at `pixels_per_dip`.
+///
+/// Sizes of at least `outline_threshold` pixels per em, or
+/// [`DEFAULT_OUTLINE_THRESHOLD`] if it's `None`, are drawn as outlines.
+/// Below that, GDI measuring modes render with the matching GDI mode, and
+/// natural measuring adds vertical anti-aliasing above 20 pixels per em or
+/// where the `gasp` range for the size asks for symmetric smoothing.
+pub fn recommended_rendering_mode(
+    gasp: &[GaspRange],
+    em_size: f32,
+    pixels_per_dip: f32,
+    measuring_mode: DWRITE_MEASURING_MODE,
+    outline_threshold: Option<f32>,
+) -> RenderingMode {
+    let ppem = em_size * pixels_per_dip;
+    if ppem >= outline_threshold.unwrap_or(DEFAULT_OUTLINE_THRESHOLD) {
+        return RenderingMode::Outline;
+    }
+    match measuring_mode {
+        DWRITE_MEASURING_MODE_GDI_CLASSIC => RenderingMode::GdiClassic,
+        DWRITE_MEASURING_MODE_GDI_NATURAL => RenderingMode::GdiNatural,
+        _ => {
+            let symmetric_smoothing =
+                gasp_range(gasp, ppem).is_some_and(|range| range.symmetric_smoothing);
+            if symmetric_smoothing || ppem > NATURAL_MAX_PPEM {
+                RenderingMode::NaturalSymmetric
+            } else {
+                RenderingMode::Natural
+            }
+        }
+    }
+}
@@ -2,10 +2,10 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::um::dwrite::IDWriteRenderingParams;
 use std::cell::UnsafeCell;
 use std::ptr;
-use winapi::um::dwrite::IDWriteRenderingParams;
-use wio::com::ComPtr;
 
 use super::DWriteFactory;
 
@@ -0,0 +1,997 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Recording font queries on one machine and replaying them on another.
+//!
+//! [`RecordingFontCollection`] wraps any collection backend, typically the
+//! system `FontCollection` on Windows, and writes down the answer to every
+//! query made through it and the families, fonts and faces it hands out.
+//! A [`ReplayFontCollection`] built from the resulting [`Fixture`] gives
+//! the same answers to the same queries on any platform. Off Windows, wrap
+//! it with `FontCollection::take` to use it through the public API.
+//!
+//! With the `serde_serialization` feature, fixtures can be saved in any
+//! serde format. They carry a format version, which loading checks.
+//!
+//! Queries that weren't recorded fail with [`NOT_RECORDED`].
+//!
+//! Faces created from the files of a recorded face, or with other axis
+//! values, aren't recorded themselves; when replaying, they are read from
+//! the recorded file bytes by the [`memory`](crate::memory) backend.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+#[cfg(feature = "serde_serialization")]
+use std::convert::TryFrom;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::backend::{
+    FontBackend, FontCollectionBackend, FontFaceBackend, FontFamilyBackend, FontFileBackend,
+};
+use crate::memory::MemoryFontFile;
+use crate::{
+    FontFaceType, FontMetrics0, FontSimulations, FontStretch, FontStyle, FontWeight, GlyphOffset,
+    GlyphRunOutlineError, OutlineBuilder, Tag, DWRITE_FONT_AXIS_VALUE, DWRITE_FONT_SIMULATIONS,
+    HRESULT,
+};
+
+/// The error the replay backend returns for queries missing from its
+/// fixture; `HRESULT_FROM_WIN32(ERROR_NOT_FOUND)`.
+pub const NOT_RECORDED: HRESULT = 0x80070490_u32 as HRESULT;
+
+#[cfg(feature = "serde_serialization")]
+const VERSION: u32 = 2;
+
+/// Everything recorded about one font collection.
+///
+/// Families and fonts are stored once each, and the answers to queries
+/// refer to them by their position in `families` and `fonts`.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde_serialization", serde(try_from = "UncheckedFixture"))]
+pub struct Fixture {
+    #[cfg(feature = "serde_serialization")]
+    version: FormatVersion,
+    pub family_count: u32,
+    pub families: Vec<FamilyRecord>,
+    /// Answers to `font_family`, by index.
+    pub family_indices: Vec<(u32, Result<usize, HRESULT>)>,
+    /// Answers to `font_family_by_name`, by the name asked for.
+    pub family_lookups: Vec<(String, Result<Option<usize>, HRESULT>)>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+pub struct FamilyRecord {
+    pub family_name: Result<String, HRESULT>,
+    pub font_count: u32,
+    pub fonts: Vec<FontRecord>,
+    /// Answers to `font`, by index.
+    pub font_indices: Vec<(u32, Result<usize, HRESULT>)>,
+    /// Answers to `first_matching_font`.
+    pub matches: Vec<MatchRecord>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+pub struct MatchRecord {
+    pub weight: FontWeight,
+    pub stretch: FontStretch,
+    pub style: FontStyle,
+    pub font: Result<usize, HRESULT>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+pub struct FontRecord {
+    pub family_name: String,
+    pub face_name: String,
+    pub weight: FontWeight,
+    pub stretch: FontStretch,
+    pub style: FontStyle,
+    pub simulations: FontSimulations,
+    pub is_monospace: Option<bool>,
+    pub face: FaceRecord,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+pub struct FaceRecord {
+    pub face_type: FontFaceType,
+    pub index: u32,
+    pub simulations: FontSimulations,
+    pub glyph_count: u16,
+    #[cfg_attr(feature = "serde_serialization", serde(with = "FontMetrics0Def"))]
+    pub metrics: FontMetrics0,
+    /// `(axisTag, value)` pairs.
+    pub variations: Result<Vec<(u32, f32)>, HRESULT>,
+    /// Every code point looked up so far, and its glyph.
+    pub glyph_indices: BTreeMap<u32, u16>,
+    /// Answers to `glyph_indices` that failed, by the code points asked for.
+    pub glyph_index_errors: Vec<(Vec<u32>, HRESULT)>,
+    /// Answers to `font_table`, by DirectWrite-style tag.
+    pub tables: BTreeMap<u32, Result<Option<Vec<u8>>, HRESULT>>,
+    pub outlines: Vec<OutlineRecord>,
+    /// The contents of the face's files, if they were asked for.
+    pub files: Option<Result<Vec<Vec<u8>>, HRESULT>>,
+}
+
+/// A call to `glyph_run_outline` and what it drew.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+pub struct OutlineRecord {
+    pub em_size: f32,
+    pub glyph_indices: Vec<u16>,
+    pub glyph_advances: Option<Vec<f32>>,
+    /// `(advanceOffset, ascenderOffset)` pairs.
+    pub glyph_offsets: Option<Vec<(f32, f32)>>,
+    pub is_sideways: bool,
+    pub is_right_to_left: bool,
+    pub result: Result<Vec<OutlineCommand>, GlyphRunOutlineError>,
+}
+
+/// One [`OutlineBuilder`] call.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+pub enum OutlineCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+impl OutlineCommand {
+    fn apply(&self, builder: &mut dyn OutlineBuilder) {
+        match *self {
+            OutlineCommand::MoveTo(x, y) => builder.move_to(x, y),
+            OutlineCommand::LineTo(x, y) => builder.line_to(x, y),
+            OutlineCommand::CurveTo(cp0x, cp0y, cp1x, cp1y, x, y) => {
+                builder.curve_to(cp0x, cp0y, cp1x, cp1y, x, y)
+            }
+            OutlineCommand::Close => builder.close(),
+        }
+    }
+}
+
+impl OutlineRecord {
+    fn matches(
+        &self,
+        em_size: f32,
+        glyph_indices: &[u16],
+        glyph_advances: Option<&[f32]>,
+        glyph_offsets: Option<&[GlyphOffset]>,
+        is_sideways: bool,
+        is_right_to_left: bool,
+    ) -> bool {
+        self.em_size == em_size
+            && self.glyph_indices == glyph_indices
+            && self.glyph_advances.as_deref() == glyph_advances
+            && self.glyph_offsets == glyph_offsets.map(offset_pairs)
+            && self.is_sideways == is_sideways
+            && self.is_right_to_left == is_right_to_left
+    }
+}
+
+fn axis_value_pairs(axis_values: &[DWRITE_FONT_AXIS_VALUE]) -> Vec<(u32, f32)> {
+    axis_values
+        .iter()
+        .map(|axis_value| (axis_value.axisTag, axis_value.value))
+        .collect()
+}
+
+fn offset_pairs(offsets: &[GlyphOffset]) -> Vec<(f32, f32)> {
+    offsets
+        .iter()
+        .map(|offset| (offset.advanceOffset, offset.ascenderOffset))
+        .collect()
+}
+
+// Sets the answer for `key`, replacing an earlier one.
+fn set_answer<K: PartialEq, V>(answers: &mut Vec<(K, V)>, key: K, value: V) {
+    match answers.iter_mut().find(|(existing, _)| *existing == key) {
+        Some(answer) => answer.1 = value,
+        None => answers.push((key, value)),
+    }
+}
+
+fn find_answer<K: PartialEq, V>(answers: &[(K, V)], key: K) -> Option<&V> {
+    answers
+        .iter()
+        .find(|(existing, _)| *existing == key)
+        .map(|(_, value)| value)
+}
+
+#[cfg(feature = "serde_serialization")]
+impl Fixture {
+    // Checks that every answer refers to a family or font that exists.
+    fn validate(&self) -> Result<(), &'static str> {
+        let families = self.family_indices.iter().map(|(_, family)| family.ok());
+        let lookups = self
+            .family_lookups
+            .iter()
+            .map(|(_, family)| family.ok().flatten());
+        if families
+            .chain(lookups)
+            .flatten()
+            .any(|family| family >= self.families.len())
+        {
+            return Err("family out of range");
+        }
+        for family in &self.families {
+            let fonts = family.font_indices.iter().map(|(_, font)| font.ok());
+            let matches = family.matches.iter().map(|record| record.font.ok());
+            if fonts
+                .chain(matches)
+                .flatten()
+                .any(|font| font >= family.fonts.len())
+            {
+                return Err("font out of range");
+            }
+        }
+        Ok(())
+    }
+}
+
+// Fixtures are checked as they are deserialized, so that replaying one
+// can't index out of bounds.
+#[cfg(feature = "serde_serialization")]
+#[derive(Deserialize)]
+struct UncheckedFixture {
+    version: FormatVersion,
+    family_count: u32,
+    families: Vec<FamilyRecord>,
+    family_indices: Vec<(u32, Result<usize, HRESULT>)>,
+    family_lookups: Vec<(String, Result<Option<usize>, HRESULT>)>,
+}
+
+#[cfg(feature = "serde_serialization")]
+impl TryFrom<UncheckedFixture> for Fixture {
+    type Error = &'static str;
+
+    fn try_from(fixture: UncheckedFixture) -> Result<Fixture, &'static str> {
+        let fixture = Fixture {
+            version: fixture.version,
+            family_count: fixture.family_count,
+            families: fixture.families,
+            family_indices: fixture.family_indices,
+            family_lookups: fixture.family_lookups,
+        };
+        fixture.validate()?;
+        Ok(fixture)
+    }
+}
+
+/// The version of the fixture format, serialized with every fixture so
+/// that ones written in another version are rejected rather than misread.
+#[cfg(feature = "serde_serialization")]
+#[derive(Clone, Copy, Default)]
+struct FormatVersion;
+
+#[cfg(feature = "serde_serialization")]
+impl serde::Serialize for FormatVersion {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(VERSION)
+    }
+}
+
+#[cfg(feature = "serde_serialization")]
+impl<'de> serde::Deserialize<'de> for FormatVersion {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = u32::deserialize(deserializer)?;
+        if version != VERSION {
+            return Err(serde::de::Error::custom(format_args!(
+                "unsupported fixture version {}",
+                version
+            )));
+        }
+        Ok(FormatVersion)
+    }
+}
+
+#[cfg(feature = "serde_serialization")]
+#[derive(Deserialize, Serialize)]
+#[serde(remote = "FontMetrics0", rename_all = "snake_case")]
+#[allow(non_snake_case)]
+struct FontMetrics0Def {
+    designUnitsPerEm: u16,
+    ascent: u16,
+    descent: u16,
+    lineGap: i16,
+    capHeight: u16,
+    xHeight: u16,
+    underlinePosition: i16,
+    underlineThickness: u16,
+    strikethroughPosition: i16,
+    strikethroughThickness: u16,
+}
+
+type SharedFixture = Rc<RefCell<Fixture>>;
+
+/// Wraps a collection backend and records every query made through it, and
+/// through the families, fonts and faces it returns.
+#[derive(Clone)]
+pub struct RecordingFontCollection {
+    inner: Rc<dyn FontCollectionBackend>,
+    fixture: SharedFixture,
+}
+
+impl RecordingFontCollection {
+    pub fn new(inner: Box<dyn FontCollectionBackend>) -> RecordingFontCollection {
+        let fixture = Fixture {
+            family_count: inner.font_family_count(),
+            ..Fixture::default()
+        };
+        RecordingFontCollection {
+            inner: Rc::from(inner),
+            fixture: Rc::new(RefCell::new(fixture)),
+        }
+    }
+
+    /// Returns what has been recorded so far.
+    pub fn fixture(&self) -> Fixture {
+        self.fixture.borrow().clone()
+    }
+
+    fn record_family(&self, inner: Box<dyn FontFamilyBackend>) -> RecordingFontFamily {
+        let family_name = inner.family_name();
+        let mut fixture = self.fixture.borrow_mut();
+        let existing = fixture
+            .families
+            .iter()
+            .position(|family| family_name.is_ok() && family.family_name == family_name);
+        let family = existing.unwrap_or_else(|| {
+            fixture.families.push(FamilyRecord {
+                family_name,
+                font_count: inner.font_count(),
+                fonts: vec![],
+                font_indices: vec![],
+                matches: vec![],
+            });
+            fixture.families.len() - 1
+        });
+        RecordingFontFamily {
+            inner,
+            fixture: self.fixture.clone(),
+            family,
+        }
+    }
+}
+
+impl FontCollectionBackend for RecordingFontCollection {
+    fn font_family_count(&self) -> u32 {
+        self.inner.font_family_count()
+    }
+
+    fn font_family(&self, index: u32) -> Result<Box<dyn FontFamilyBackend>, HRESULT> {
+        let family = self
+            .inner
+            .font_family(index)
+            .map(|family| self.record_family(family));
+        let answer = family
+            .as_ref()
+            .map(|family| family.family)
+            .map_err(|&hr| hr);
+        set_answer(&mut self.fixture.borrow_mut().family_indices, index, answer);
+        Ok(Box::new(family?))
+    }
+
+    fn font_family_by_name(
+        &self,
+        family_name: &str,
+    ) -> Result<Option<Box<dyn FontFamilyBackend>>, HRESULT> {
+        let family = self
+            .inner
+            .font_family_by_name(family_name)
+            .map(|family| family.map(|family| self.record_family(family)));
+        let answer = family
+            .as_ref()
+            .map(|family| family.as_ref().map(|family| family.family))
+            .map_err(|&hr| hr);
+        set_answer(
+            &mut self.fixture.borrow_mut().family_lookups,
+            family_name.to_owned(),
+            answer,
+        );
+        Ok(family?.map(|family| Box::new(family) as Box<dyn FontFamilyBackend>))
+    }
+}
+
+struct RecordingFontFamily {
+    inner: Box<dyn FontFamilyBackend>,
+    fixture: SharedFixture,
+    family: usize,
+}
+
+impl RecordingFontFamily {
+    fn record_font(&self, inner: Box<dyn FontBackend>) -> RecordingFont {
+        let face = inner.create_font_face();
+        let record = FontRecord {
+            family_name: inner.family_name(),
+            face_name: inner.face_name(),
+            weight: inner.weight(),
+            stretch: inner.stretch(),
+            style: inner.style(),
+            simulations: inner.simulations(),
+            is_monospace: inner.is_monospace(),
+            face: FaceRecord {
+                face_type: face.face_type(),
+                index: face.index(),
+                simulations: face.simulations(),
+                glyph_count: face.glyph_count(),
+                metrics: face.metrics(),
+                variations: face
+                    .variations()
+                    .map(|axis_values| axis_value_pairs(&axis_values)),
+                glyph_indices: BTreeMap::new(),
+                glyph_index_errors: vec![],
+                tables: BTreeMap::new(),
+                outlines: vec![],
+                files: None,
+            },
+        };
+
+        // A font is identified within its family by its description.
+        let mut fixture = self.fixture.borrow_mut();
+        let fonts = &mut fixture.families[self.family].fonts;
+        let existing = fonts.iter().position(|font| {
+            font.face_name == record.face_name
+                && font.weight == record.weight
+                && font.stretch == record.stretch
+                && font.style == record.style
+                && font.simulations == record.simulations
+        });
+        let font = existing.unwrap_or_else(|| {
+            fonts.push(record);
+            fonts.len() - 1
+        });
+        RecordingFont {
+            inner,
+            fixture: self.fixture.clone(),
+            family: self.family,
+            font,
+        }
+    }
+}
+
+impl FontFamilyBackend for RecordingFontFamily {
+    fn family_name(&self) -> Result<String, HRESULT> {
+        self.inner.family_name()
+    }
+
+    fn font_count(&self) -> u32 {
+        self.inner.font_count()
+    }
+
+    fn font(&self, index: u32) -> Result<Box<dyn FontBackend>, HRESULT> {
+        let font = self.inner.font(index).map(|font| self.record_font(font));
+        let answer = font.as_ref().map(|font| font.font).map_err(|&hr| hr);
+        let mut fixture = self.fixture.borrow_mut();
+        set_answer(
+            &mut fixture.families[self.family].font_indices,
+            index,
+            answer,
+        );
+        Ok(Box::new(font?))
+    }
+
+    fn first_matching_font(
+        &self,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Result<Box<dyn FontBackend>, HRESULT> {
+        let font = self
+            .inner
+            .first_matching_font(weight, stretch, style)
+            .map(|font| self.record_font(font));
+        let answer = font.as_ref().map(|font| font.font).map_err(|&hr| hr);
+        let mut fixture = self.fixture.borrow_mut();
+        let matches = &mut fixture.families[self.family].matches;
+        matches.retain(|record| {
+            (record.weight, record.stretch, record.style) != (weight, stretch, style)
+        });
+        matches.push(MatchRecord {
+            weight,
+            stretch,
+            style,
+            font: answer,
+        });
+        Ok(Box::new(font?))
+    }
+}
+
+struct RecordingFont {
+    inner: Box<dyn FontBackend>,
+    fixture: SharedFixture,
+    family: usize,
+    font: usize,
+}
+
+impl FontBackend for RecordingFont {
+    fn family_name(&self) -> String {
+        self.inner.family_name()
+    }
+
+    fn face_name(&self) -> String {
+        self.inner.face_name()
+    }
+
+    fn weight(&self) -> FontWeight {
+        self.inner.weight()
+    }
+
+    fn stretch(&self) -> FontStretch {
+        self.inner.stretch()
+    }
+
+    fn style(&self) -> FontStyle {
+        self.inner.style()
+    }
+
+    fn simulations(&self) -> FontSimulations {
+        self.inner.simulations()
+    }
+
+    fn is_monospace(&self) -> Option<bool> {
+        self.inner.is_monospace()
+    }
+
+    fn create_font_face(&self) -> Box<dyn FontFaceBackend> {
+        Box::new(RecordingFontFace {
+            inner: self.inner.create_font_face(),
+            fixture: self.fixture.clone(),
+            family: self.family,
+            font: self.font,
+        })
+    }
+}
+
+struct RecordingFontFace {
+    inner: Box<dyn FontFaceBackend>,
+    fixture: SharedFixture,
+    family: usize,
+    font: usize,
+}
+
+impl RecordingFontFace {
+    fn with_record(&self, f: impl FnOnce(&mut FaceRecord)) {
+        let mut fixture = self.fixture.borrow_mut();
+        f(&mut fixture.families[self.family].fonts[self.font].face)
+    }
+}
+
+impl FontFaceBackend for RecordingFontFace {
+    fn files(&self) -> Result<Vec<Box<dyn FontFileBackend>>, HRESULT> {
+        let files = self.inner.files();
+        let contents = match files {
+            Ok(ref files) => files.iter().map(|file| file.font_file_bytes()).collect(),
+            Err(hr) => Err(hr),
+        };
+        self.with_record(|face| face.files = Some(contents));
+        files
+    }
+
+    fn face_type(&self) -> FontFaceType {
+        self.inner.face_type()
+    }
+
+    fn index(&self) -> u32 {
+        self.inner.index()
+    }
+
+    fn simulations(&self) -> FontSimulations {
+        self.inner.simulations()
+    }
+
+    fn glyph_count(&self) -> u16 {
+        self.inner.glyph_count()
+    }
+
+    fn metrics(&self) -> FontMetrics0 {
+        self.inner.metrics()
+    }
+
+    fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT> {
+        let glyph_indices = self.inner.glyph_indices(code_points);
+        self.with_record(|face| match glyph_indices {
+            Ok(ref glyph_indices) => {
+                face.glyph_index_errors
+                    .retain(|(failed, _)| failed[..] != *code_points);
+                face.glyph_indices.extend(
+                    code_points
+                        .iter()
+                        .cloned()
+                        .zip(glyph_indices.iter().cloned()),
+                )
+            }
+            Err(hr) => set_answer(&mut face.glyph_index_errors, code_points.to_vec(), hr),
+        });
+        glyph_indices
+    }
+
+    fn font_table(&self, opentype_table_tag: u32) -> Result<Option<Vec<u8>>, HRESULT> {
+        let table = self.inner.font_table(opentype_table_tag);
+        self.with_record(|face| {
+            face.tables.insert(opentype_table_tag, table.clone());
+        });
+        table
+    }
+
+    fn glyph_run_outline(
+        &self,
+        em_size: f32,
+        glyph_indices: &[u16],
+        glyph_advances: Option<&[f32]>,
+        glyph_offsets: Option<&[GlyphOffset]>,
+        is_sideways: bool,
+        is_right_to_left: bool,
+        outline_builder: Box<dyn OutlineBuilder>,
+    ) -> Result<(), GlyphRunOutlineError> {
+        let commands = Rc::new(RefCell::new(vec![]));
+        let result = self.inner.glyph_run_outline(
+            em_size,
+            glyph_indices,
+            glyph_advances,
+            glyph_offsets,
+            is_sideways,
+            is_right_to_left,
+            Box::new(RecordingOutlineBuilder {
+                inner: outline_builder,
+                commands: commands.clone(),
+            }),
+        );
+        let record = OutlineRecord {
+            em_size,
+            glyph_indices: glyph_indices.to_vec(),
+            glyph_advances: glyph_advances.map(|advances| advances.to_vec()),
+            glyph_offsets: glyph_offsets.map(offset_pairs),
+            is_sideways,
+            is_right_to_left,
+            result: result.map(|()| commands.borrow().clone()),
+        };
+        self.with_record(|face| {
+            face.outlines.retain(|outline| {
+                !outline.matches(
+                    em_size,
+                    glyph_indices,
+                    glyph_advances,
+                    glyph_offsets,
+                    is_sideways,
+                    is_right_to_left,
+                )
+            });
+            face.outlines.push(record);
+        });
+        result
+    }
+
+    fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT> {
+        self.inner.variations()
+    }
+
+    fn create_font_face_with_variations(
+        &self,
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axis_values: &[DWRITE_FONT_AXIS_VALUE],
+    ) -> Option<Box<dyn FontFaceBackend>> {
+        self.inner
+            .create_font_face_with_variations(simulations, axis_values)
+    }
+}
+
+// Passes outline commands through to the caller's builder, keeping a copy.
+struct RecordingOutlineBuilder {
+    inner: Box<dyn OutlineBuilder>,
+    commands: Rc<RefCell<Vec<OutlineCommand>>>,
+}
+
+impl RecordingOutlineBuilder {
+    fn record(&mut self, command: OutlineCommand) {
+        command.apply(&mut *self.inner);
+        self.commands.borrow_mut().push(command);
+    }
+}
+
+impl OutlineBuilder for RecordingOutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.record(OutlineCommand::MoveTo(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.record(OutlineCommand::LineTo(x, y));
+    }
+
+    fn curve_to(&mut self, cp0x: f32, cp0y: f32, cp1x: f32, cp1y: f32, x: f32, y: f32) {
+        self.record(OutlineCommand::CurveTo(cp0x, cp0y, cp1x, cp1y, x, y));
+    }
+
+    fn close(&mut self) {
+        self.record(OutlineCommand::Close);
+    }
+}
+
+/// A collection backend that answers queries from a [`Fixture`].
+#[derive(Clone)]
+pub struct ReplayFontCollection {
+    fixture: Rc<Fixture>,
+}
+
+impl ReplayFontCollection {
+    pub fn new(fixture: Fixture) -> ReplayFontCollection {
+        ReplayFontCollection {
+            fixture: Rc::new(fixture),
+        }
+    }
+
+    fn family(&self, family: usize) -> Box<dyn FontFamilyBackend> {
+        Box::new(ReplayFontFamily {
+            fixture: self.fixture.clone(),
+            family,
+        })
+    }
+}
+
+impl FontCollectionBackend for ReplayFontCollection {
+    fn font_family_count(&self) -> u32 {
+        self.fixture.family_count
+    }
+
+    fn font_family(&self, index: u32) -> Result<Box<dyn FontFamilyBackend>, HRESULT> {
+        let family = *find_answer(&self.fixture.family_indices, index).ok_or(NOT_RECORDED)?;
+        Ok(self.family(family?))
+    }
+
+    fn font_family_by_name(
+        &self,
+        family_name: &str,
+    ) -> Result<Option<Box<dyn FontFamilyBackend>>, HRESULT> {
+        // Lookups ignore case, so any recorded spelling will do.
+        let family = self
+            .fixture
+            .family_lookups
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(family_name))
+            .map(|(_, family)| *family)
+            .ok_or(NOT_RECORDED)?;
+        Ok(family?.map(|family| self.family(family)))
+    }
+}
+
+struct ReplayFontFamily {
+    fixture: Rc<Fixture>,
+    family: usize,
+}
+
+impl ReplayFontFamily {
+    fn record(&self) -> &FamilyRecord {
+        &self.fixture.families[self.family]
+    }
+
+    fn font(&self, font: usize) -> Box<dyn FontBackend> {
+        Box::new(ReplayFont {
+            fixture: self.fixture.clone(),
+            family: self.family,
+            font,
+        })
+    }
+}
+
+impl FontFamilyBackend for ReplayFontFamily {
+    fn family_name(&self) -> Result<String, HRESULT> {
+        self.record().family_name.clone()
+    }
+
+    fn font_count(&self) -> u32 {
+        self.record().font_count
+    }
+
+    fn font(&self, index: u32) -> Result<Box<dyn FontBackend>, HRESULT> {
+        let font = *find_answer(&self.record().font_indices, index).ok_or(NOT_RECORDED)?;
+        Ok(ReplayFontFamily::font(self, font?))
+    }
+
+    fn first_matching_font(
+        &self,
+        weight: FontWeight,
+        stretch: FontStretch,
+        style: FontStyle,
+    ) -> Result<Box<dyn FontBackend>, HRESULT> {
+        let record = self
+            .record()
+            .matches
+            .iter()
+            .find(|record| {
+                (record.weight, record.stretch, record.style) == (weight, stretch, style)
+            })
+            .ok_or(NOT_RECORDED)?;
+        Ok(ReplayFontFamily::font(self, record.font?))
+    }
+}
+
+struct ReplayFont {
+    fixture: Rc<Fixture>,
+    family: usize,
+    font: usize,
+}
+
+impl ReplayFont {
+    fn record(&self) -> &FontRecord {
+        &self.fixture.families[self.family].fonts[self.font]
+    }
+}
+
+impl FontBackend for ReplayFont {
+    fn family_name(&self) -> String {
+        self.record().family_name.clone()
+    }
+
+    fn face_name(&self) -> String {
+        self.record().face_name.clone()
+    }
+
+    fn weight(&self) -> FontWeight {
+        self.record().weight
+    }
+
+    fn stretch(&self) -> FontStretch {
+        self.record().stretch
+    }
+
+    fn style(&self) -> FontStyle {
+        self.record().style
+    }
+
+    fn simulations(&self) -> FontSimulations {
+        self.record().simulations
+    }
+
+    fn is_monospace(&self) -> Option<bool> {
+        self.record().is_monospace
+    }
+
+    fn create_font_face(&self) -> Box<dyn FontFaceBackend> {
+        Box::new(ReplayFontFace {
+            fixture: self.fixture.clone(),
+            family: self.family,
+            font: self.font,
+        })
+    }
+}
+
+struct ReplayFontFace {
+    fixture: Rc<Fixture>,
+    family: usize,
+    font: usize,
+}
+
+impl ReplayFontFace {
+    fn record(&self) -> &FaceRecord {
+        &self.fixture.families[self.family].fonts[self.font].face
+    }
+}
+
+impl FontFaceBackend for ReplayFontFace {
+    fn files(&self) -> Result<Vec<Box<dyn FontFileBackend>>, HRESULT> {
+        let files = self.record().files.as_ref().ok_or(NOT_RECORDED)?;
+        Ok(files
+            .clone()?
+            .into_iter()
+            .map(|bytes| {
+                Box::new(ReplayFontFile {
+                    bytes: Arc::new(bytes),
+                }) as Box<dyn FontFileBackend>
+            })
+            .collect())
+    }
+
+    fn face_type(&self) -> FontFaceType {
+        self.record().face_type
+    }
+
+    fn index(&self) -> u32 {
+        self.record().index
+    }
+
+    fn simulations(&self) -> FontSimulations {
+        self.record().simulations
+    }
+
+    fn glyph_count(&self) -> u16 {
+        self.record().glyph_count
+    }
+
+    fn metrics(&self) -> FontMetrics0 {
+        self.record().metrics
+    }
+
+    fn glyph_indices(&self, code_points: &[u32]) -> Result<Vec<u16>, HRESULT> {
+        if let Some(&hr) = find_answer(&self.record().glyph_index_errors, code_points.to_vec()) {
+            return Err(hr);
+        }
+        let glyph_indices = &self.record().glyph_indices;
+        code_points
+            .iter()
+            .map(|code_point| glyph_indices.get(code_point).cloned().ok_or(NOT_RECORDED))
+            .collect()
+    }
+
+    fn font_table(&self, opentype_table_tag: u32) -> Result<Option<Vec<u8>>, HRESULT> {
+        self.record()
+            .tables
+            .get(&opentype_table_tag)
+            .cloned()
+            .unwrap_or(Err(NOT_RECORDED))
+    }
+
+    fn glyph_run_outline(
+        &self,
+        em_size: f32,
+        glyph_indices: &[u16],
+        glyph_advances: Option<&[f32]>,
+        glyph_offsets: Option<&[GlyphOffset]>,
+        is_sideways: bool,
+        is_right_to_left: bool,
+        mut outline_builder: Box<dyn OutlineBuilder>,
+    ) -> Result<(), GlyphRunOutlineError> {
+        let record = self
+            .record()
+            .outlines
+            .iter()
+            .find(|outline| {
+                outline.matches(
+                    em_size,
+                    glyph_indices,
+                    glyph_advances,
+                    glyph_offsets,
+                    is_sideways,
+                    is_right_to_left,
+                )
+            })
+            .ok_or(GlyphRunOutlineError::Win32Error(NOT_RECORDED))?;
+        for command in record.result.as_ref().map_err(|&error| error)? {
+            command.apply(&mut *outline_builder);
+        }
+        Ok(())
+    }
+
+    fn variations(&self) -> Result<Vec<DWRITE_FONT_AXIS_VALUE>, HRESULT> {
+        let variations = self.record().variations.as_ref().map_err(|&hr| hr)?;
+        Ok(variations
+            .iter()
+            .map(|&(tag, value)| Tag::from_dwrite(tag).axis_value(value))
+            .collect())
+    }
+
+    fn create_font_face_with_variations(
+        &self,
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axis_values: &[DWRITE_FONT_AXIS_VALUE],
+    ) -> Option<Box<dyn FontFaceBackend>> {
+        let file = self.files().ok()?.into_iter().next()?;
+        file.create_face(self.index(), simulations)
+            .ok()?
+            .create_font_face_with_variations(simulations, axis_values)
+    }
+}
+
+// A recorded font file; faces made from it are read by the memory backend.
+struct ReplayFontFile {
+    bytes: Arc<Vec<u8>>,
+}
+
+impl FontFileBackend for ReplayFontFile {
+    fn font_file_bytes(&self) -> Result<Vec<u8>, HRESULT> {
+        Ok(self.bytes.to_vec())
+    }
+
+    fn create_face(
+        &self,
+        face_index: u32,
+        simulations: DWRITE_FONT_SIMULATIONS,
+    ) -> Result<Box<dyn FontFaceBackend>, HRESULT> {
+        let file = MemoryFontFile::new_from_buffer(self.bytes.clone()).ok_or(NOT_RECORDED)?;
+        FontFileBackend::create_face(&file, face_index, simulations)
+    }
+}
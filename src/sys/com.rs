@@ -0,0 +1,106 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! wio's `ComPtr`, an owned reference to a COM object.
+
+use std::fmt;
+use std::mem;
+use std::ops::Deref;
+use std::ptr::{self, NonNull};
+
+use crate::sys::um::unknwnbase::IUnknown;
+use crate::sys::Interface;
+
+#[repr(transparent)]
+pub struct ComPtr<T>(NonNull<T>);
+
+impl<T> ComPtr<T> {
+    /// Takes over a reference without calling `AddRef`; `ptr` must be a COM
+    /// interface that inherits from `IUnknown`.
+    pub unsafe fn from_raw(ptr: *mut T) -> ComPtr<T>
+    where
+        T: Interface,
+    {
+        ComPtr(NonNull::new(ptr).expect("ptr should not be null"))
+    }
+
+    pub fn up<U>(self) -> ComPtr<U>
+    where
+        T: Deref<Target = U>,
+        U: Interface,
+    {
+        unsafe { ComPtr::from_raw(self.into_raw() as *mut U) }
+    }
+
+    /// Gives up the reference, which the caller must now release.
+    pub fn into_raw(self) -> *mut T {
+        let ptr = self.0.as_ptr();
+        mem::forget(self);
+        ptr
+    }
+
+    fn as_unknown(&self) -> &IUnknown {
+        unsafe { &*(self.as_raw() as *mut IUnknown) }
+    }
+
+    pub fn cast<U>(&self) -> Result<ComPtr<U>, i32>
+    where
+        U: Interface,
+    {
+        let mut obj = ptr::null_mut();
+        let hr = unsafe { self.as_unknown().QueryInterface(&U::uuidof(), &mut obj) };
+        if hr < 0 {
+            return Err(hr);
+        }
+        Ok(unsafe { ComPtr::from_raw(obj as *mut U) })
+    }
+
+    /// Returns the pointer, which is still owned by this `ComPtr`.
+    pub fn as_raw(&self) -> *mut T {
+        self.0.as_ptr()
+    }
+}
+
+impl<T> Deref for ComPtr<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.as_raw() }
+    }
+}
+
+impl<T> Clone for ComPtr<T>
+where
+    T: Interface,
+{
+    fn clone(&self) -> Self {
+        unsafe {
+            self.as_unknown().AddRef();
+            ComPtr::from_raw(self.as_raw())
+        }
+    }
+}
+
+impl<T> fmt::Debug for ComPtr<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T> Drop for ComPtr<T> {
+    fn drop(&mut self) {
+        unsafe {
+            self.as_unknown().Release();
+        }
+    }
+}
+
+impl<T> PartialEq<ComPtr<T>> for ComPtr<T>
+where
+    T: Interface,
+{
+    fn eq(&self, other: &ComPtr<T>) -> bool {
+        self.0 == other.0
+    }
+}
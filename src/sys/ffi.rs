@@ -0,0 +1,29 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! `std::os::windows::ffi`'s UTF-16 conversions, through UTF-8 strings.
+
+use std::ffi::{OsStr, OsString};
+use std::vec;
+
+pub trait OsStrExt {
+    fn encode_wide(&self) -> vec::IntoIter<u16>;
+}
+
+impl OsStrExt for OsStr {
+    fn encode_wide(&self) -> vec::IntoIter<u16> {
+        let wide: Vec<u16> = self.to_string_lossy().encode_utf16().collect();
+        wide.into_iter()
+    }
+}
+
+pub trait OsStringExt {
+    fn from_wide(wide: &[u16]) -> Self;
+}
+
+impl OsStringExt for OsString {
+    fn from_wide(wide: &[u16]) -> OsString {
+        String::from_utf16_lossy(wide).into()
+    }
+}
@@ -0,0 +1,124 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The parts of winapi and wio the DirectWrite wrappers are written against.
+//!
+//! On Windows this is just those crates. Elsewhere, where winapi is empty,
+//! the same paths are filled in by hand so that the wrappers still compile
+//! for the tests, which drive them with the fake objects in `test_com`.
+//! The declarations mirror winapi 0.3 with Windows' type sizes; only what
+//! the wrappers and fakes use is here, and interfaces that only pass
+//! through as pointers are left opaque. The OS functions fail the way they
+//! would without `dwrite.dll`.
+
+#![allow(non_camel_case_types, non_snake_case)]
+#![allow(
+    clippy::missing_safety_doc,
+    clippy::too_many_arguments,
+    clippy::upper_case_acronyms
+)]
+
+#[cfg(windows)]
+pub use std::os::windows::ffi;
+#[cfg(windows)]
+pub use winapi::{ctypes, shared, um, Interface};
+#[cfg(windows)]
+pub mod com {
+    pub use wio::com::ComPtr;
+}
+
+/// Declares a COM interface, its vtable and its `uuidof`, the way
+/// winapi's `RIDL!` does.
+#[cfg(any(not(windows), test))]
+macro_rules! interface {
+    (#[uuid($l:expr, $w1:expr, $w2:expr,
+        $b1:expr, $b2:expr, $b3:expr, $b4:expr, $b5:expr, $b6:expr, $b7:expr, $b8:expr)]
+    interface $interface:ident ($vtbl:ident) $(: $pinterface:ident ($pvtbl:ident))? {$(
+        fn $method:ident($($p:ident : $t:ty,)*) -> $rtr:ty,
+    )*}) => {
+        #[repr(C)]
+        pub struct $vtbl {
+            $(pub parent: $pvtbl,)?
+            $(pub $method: unsafe extern "system" fn(
+                This: *mut $interface,
+                $($p: $t,)*
+            ) -> $rtr,)*
+        }
+
+        #[repr(C)]
+        pub struct $interface {
+            pub lpVtbl: *const $vtbl,
+        }
+
+        impl $interface {
+            $(#[inline]
+            pub unsafe fn $method(&self, $($p: $t,)*) -> $rtr {
+                ((*self.lpVtbl).$method)(self as *const _ as *mut _, $($p,)*)
+            })*
+        }
+
+        $(impl std::ops::Deref for $interface {
+            type Target = $pinterface;
+            #[inline]
+            fn deref(&self) -> &$pinterface {
+                unsafe { &*(self as *const $interface as *const $pinterface) }
+            }
+        })?
+
+        impl $crate::sys::Interface for $interface {
+            #[inline]
+            fn uuidof() -> $crate::sys::shared::guiddef::GUID {
+                $crate::sys::shared::guiddef::GUID {
+                    Data1: $l,
+                    Data2: $w1,
+                    Data3: $w2,
+                    Data4: [$b1, $b2, $b3, $b4, $b5, $b6, $b7, $b8],
+                }
+            }
+        }
+    };
+}
+
+// The declarations used off Windows, compiled on Windows as well for the
+// tests to check their layouts and IIDs against winapi's. Their own paths
+// through `crate::sys` lead to winapi there.
+#[cfg(all(windows, test))]
+#[allow(dead_code, unused_imports)]
+#[path = "shared.rs"]
+pub mod mirror_shared;
+#[cfg(all(windows, test))]
+#[allow(dead_code, unused_imports)]
+#[path = "um/mod.rs"]
+pub mod mirror_um;
+
+#[cfg(not(windows))]
+pub mod com;
+#[cfg(not(windows))]
+pub mod ffi;
+#[cfg(not(windows))]
+pub mod shared;
+#[cfg(not(windows))]
+pub mod um;
+
+/// C types with their sizes on Windows, where `long` is 32 bits.
+#[cfg(not(windows))]
+pub mod ctypes {
+    pub use std::os::raw::c_void;
+    pub type c_char = i8;
+    pub type c_uchar = u8;
+    pub type c_short = i16;
+    pub type c_ushort = u16;
+    pub type c_int = i32;
+    pub type c_uint = u32;
+    pub type c_long = i32;
+    pub type c_ulong = u32;
+    pub type c_float = f32;
+    pub type __uint64 = u64;
+    pub type wchar_t = u16;
+}
+
+#[cfg(not(windows))]
+pub trait Interface {
+    fn uuidof() -> shared::guiddef::GUID;
+}
@@ -0,0 +1,154 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub mod basetsd {
+    use crate::sys::ctypes::{__uint64, c_int, c_short, c_uchar, c_uint, c_ushort};
+
+    pub type INT16 = c_short;
+
+    pub type INT32 = c_int;
+
+    pub type UINT8 = c_uchar;
+
+    pub type UINT16 = c_ushort;
+
+    pub type UINT32 = c_uint;
+
+    pub type UINT64 = __uint64;
+}
+
+pub mod d3d9types {
+    use crate::sys::ctypes::c_float;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct D3DCOLORVALUE {
+        pub r: c_float,
+        pub g: c_float,
+        pub b: c_float,
+        pub a: c_float,
+    }
+}
+
+pub mod guiddef {
+    use crate::sys::ctypes::{c_uchar, c_ulong, c_ushort};
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct GUID {
+        pub Data1: c_ulong,
+        pub Data2: c_ushort,
+        pub Data3: c_ushort,
+        pub Data4: [c_uchar; 8],
+    }
+
+    pub type IID = GUID;
+
+    pub type REFIID = *const IID;
+
+    pub fn IsEqualGUID(g1: &GUID, g2: &GUID) -> bool {
+        (g1.Data1, g1.Data2, g1.Data3, g1.Data4) == (g2.Data1, g2.Data2, g2.Data3, g2.Data4)
+    }
+}
+
+pub mod minwindef {
+    use crate::sys::ctypes::{c_float, c_int, c_uchar, c_uint, c_ulong, c_ushort, c_void};
+
+    pub type ULONG = c_ulong;
+
+    pub const FALSE: BOOL = 0;
+
+    pub const TRUE: BOOL = 1;
+
+    pub type DWORD = c_ulong;
+
+    pub type BOOL = c_int;
+
+    pub type BYTE = c_uchar;
+
+    pub type WORD = c_ushort;
+
+    pub type FLOAT = c_float;
+
+    pub type LPVOID = *mut c_void;
+
+    pub type UINT = c_uint;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct FILETIME {
+        pub dwLowDateTime: DWORD,
+        pub dwHighDateTime: DWORD,
+    }
+}
+
+pub mod ntdef {
+    pub const LOCALE_NAME_MAX_LENGTH: usize = 85;
+}
+
+pub mod windef {
+    use crate::sys::shared::minwindef::DWORD;
+    use crate::sys::um::winnt::LONG;
+
+    pub enum HDC__ {}
+    pub type HDC = *mut HDC__;
+
+    pub enum HMONITOR__ {}
+    pub type HMONITOR = *mut HMONITOR__;
+
+    pub type COLORREF = DWORD;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct RECT {
+        pub left: LONG,
+        pub top: LONG,
+        pub right: LONG,
+        pub bottom: LONG,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct POINT {
+        pub x: LONG,
+        pub y: LONG,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct SIZE {
+        pub cx: LONG,
+        pub cy: LONG,
+    }
+}
+
+#[allow(overflowing_literals)]
+pub mod winerror {
+    use crate::sys::ctypes::c_ulong;
+    use crate::sys::um::winnt::HRESULT;
+
+    pub const E_NOTIMPL: HRESULT = 0x80004001;
+
+    pub const E_INVALIDARG: HRESULT = 0x80070057;
+
+    pub const E_NOINTERFACE: HRESULT = 0x80004002;
+
+    pub const E_FAIL: HRESULT = 0x80004005;
+
+    pub const S_OK: HRESULT = 0;
+
+    pub const DWRITE_E_FILEFORMAT: HRESULT = 0x88985000;
+
+    pub const DWRITE_E_ALREADYREGISTERED: HRESULT = 0x88985006;
+
+    pub const FACILITY_WIN32: HRESULT = 7;
+
+    pub fn HRESULT_FROM_WIN32(x: c_ulong) -> HRESULT {
+        if x as i32 <= 0 {
+            x as i32
+        } else {
+            ((x & 0x0000FFFF) | ((FACILITY_WIN32 as u32) << 16) | 0x80000000) as i32
+        }
+    }
+}
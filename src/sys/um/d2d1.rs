@@ -0,0 +1,63 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use crate::sys::shared::basetsd::UINT32;
+use crate::sys::um::dcommon::{D2D_POINT_2F, D2D_SIZE_U};
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::winnt::HRESULT;
+
+pub type D2D1_POINT_2F = D2D_POINT_2F;
+
+pub type D2D1_SIZE_U = D2D_SIZE_U;
+
+pub type D2D1_FIGURE_BEGIN = u32;
+pub const D2D1_FIGURE_BEGIN_FILLED: D2D1_FIGURE_BEGIN = 0;
+pub const D2D1_FIGURE_BEGIN_HOLLOW: D2D1_FIGURE_BEGIN = 1;
+
+pub type D2D1_FIGURE_END = u32;
+pub const D2D1_FIGURE_END_OPEN: D2D1_FIGURE_END = 0;
+pub const D2D1_FIGURE_END_CLOSED: D2D1_FIGURE_END = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D2D1_BEZIER_SEGMENT {
+    pub point1: D2D1_POINT_2F,
+    pub point2: D2D1_POINT_2F,
+    pub point3: D2D1_POINT_2F,
+}
+
+pub type D2D1_PATH_SEGMENT = u32;
+pub const D2D1_PATH_SEGMENT_NONE: D2D1_PATH_SEGMENT = 0x00000000;
+pub const D2D1_PATH_SEGMENT_FORCE_UNSTROKED: D2D1_PATH_SEGMENT = 0x00000001;
+pub const D2D1_PATH_SEGMENT_FORCE_ROUND_LINE_JOIN: D2D1_PATH_SEGMENT = 0x00000002;
+
+pub type D2D1_FILL_MODE = u32;
+pub const D2D1_FILL_MODE_ALTERNATE: D2D1_FILL_MODE = 0;
+pub const D2D1_FILL_MODE_WINDING: D2D1_FILL_MODE = 1;
+
+interface! {#[uuid(0x2cd9069e, 0x12e2, 0x11dc, 0x9f, 0xed, 0x00, 0x11, 0x43, 0xa0, 0x55, 0xf9)]
+interface ID2D1SimplifiedGeometrySink(ID2D1SimplifiedGeometrySinkVtbl): IUnknown(IUnknownVtbl) {
+    fn SetFillMode(
+        fillMode: D2D1_FILL_MODE,
+    ) -> (),
+    fn SetSegmentFlags(
+        vertexFlags: D2D1_PATH_SEGMENT,
+    ) -> (),
+    fn BeginFigure(
+        startPoint: D2D1_POINT_2F,
+        figureBegin: D2D1_FIGURE_BEGIN,
+    ) -> (),
+    fn AddLines(
+        points: *const D2D1_POINT_2F,
+        pointsCount: UINT32,
+    ) -> (),
+    fn AddBeziers(
+        beziers: *const D2D1_BEZIER_SEGMENT,
+        beziersCount: UINT32,
+    ) -> (),
+    fn EndFigure(
+        figureEnd: D2D1_FIGURE_END,
+    ) -> (),
+    fn Close() -> HRESULT,
+}}
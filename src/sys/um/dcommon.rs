@@ -0,0 +1,54 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub use crate::portable_types::DWRITE_MEASURING_MODE;
+use crate::sys::ctypes::c_void;
+use crate::sys::shared::basetsd::UINT32;
+use crate::sys::shared::minwindef::FLOAT;
+use crate::sys::shared::windef::POINT;
+use crate::sys::um::d2d1::D2D1_SIZE_U;
+
+pub type DWRITE_GLYPH_IMAGE_FORMATS = u32;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_NONE: DWRITE_GLYPH_IMAGE_FORMATS = 0x00000000;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_TRUETYPE: DWRITE_GLYPH_IMAGE_FORMATS = 0x00000001;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_CFF: DWRITE_GLYPH_IMAGE_FORMATS = 0x00000002;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_COLR: DWRITE_GLYPH_IMAGE_FORMATS = 0x00000004;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_SVG: DWRITE_GLYPH_IMAGE_FORMATS = 0x00000008;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_PNG: DWRITE_GLYPH_IMAGE_FORMATS = 0x00000010;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_JPEG: DWRITE_GLYPH_IMAGE_FORMATS = 0x00000020;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_TIFF: DWRITE_GLYPH_IMAGE_FORMATS = 0x00000040;
+pub const DWRITE_GLYPH_IMAGE_FORMATS_PREMULTIPLIED_B8G8R8A8: DWRITE_GLYPH_IMAGE_FORMATS =
+    0x00000080;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_GLYPH_IMAGE_DATA {
+    pub imageData: *const c_void,
+    pub imageDataSize: UINT32,
+    pub uniqueDataId: UINT32,
+    pub pixelsPerEm: UINT32,
+    pub pixelSize: D2D1_SIZE_U,
+    pub horizontalLeftOrigin: D2D1_POINT_2L,
+    pub horizontalRightOrigin: D2D1_POINT_2L,
+    pub verticalTopOrigin: D2D1_POINT_2L,
+    pub verticalBottomOrigin: D2D1_POINT_2L,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D2D_POINT_2F {
+    pub x: FLOAT,
+    pub y: FLOAT,
+}
+
+pub type D2D_POINT_2L = POINT;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct D2D_SIZE_U {
+    pub width: UINT32,
+    pub height: UINT32,
+}
+
+pub type D2D1_POINT_2L = D2D_POINT_2L;
@@ -0,0 +1,702 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub use crate::portable_types::{
+    FontMetrics0 as DWRITE_FONT_METRICS, GlyphOffset as DWRITE_GLYPH_OFFSET,
+    DWRITE_FONT_SIMULATIONS, DWRITE_MATRIX, DWRITE_RENDERING_MODE, DWRITE_RENDERING_MODE_ALIASED,
+    DWRITE_RENDERING_MODE_DEFAULT, DWRITE_RENDERING_MODE_GDI_CLASSIC,
+    DWRITE_RENDERING_MODE_GDI_NATURAL, DWRITE_RENDERING_MODE_NATURAL,
+    DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC, DWRITE_RENDERING_MODE_OUTLINE,
+};
+use crate::sys::ctypes::c_void;
+use crate::sys::shared::basetsd::{INT16, INT32, UINT16, UINT32, UINT64};
+use crate::sys::shared::minwindef::{BOOL, BYTE, FILETIME, FLOAT};
+use crate::sys::shared::windef::{COLORREF, HDC, HMONITOR, RECT, SIZE};
+use crate::sys::um::d2d1::ID2D1SimplifiedGeometrySink;
+use crate::sys::um::dcommon::DWRITE_MEASURING_MODE;
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::wingdi::LOGFONTW;
+use crate::sys::um::winnt::{HRESULT, WCHAR};
+
+pub type DWRITE_FONT_FILE_TYPE = u32;
+pub const DWRITE_FONT_FILE_TYPE_UNKNOWN: DWRITE_FONT_FILE_TYPE = 0;
+pub const DWRITE_FONT_FILE_TYPE_CFF: DWRITE_FONT_FILE_TYPE = 1;
+pub const DWRITE_FONT_FILE_TYPE_TRUETYPE: DWRITE_FONT_FILE_TYPE = 2;
+pub const DWRITE_FONT_FILE_TYPE_OPENTYPE_COLLECTION: DWRITE_FONT_FILE_TYPE = 3;
+pub const DWRITE_FONT_FILE_TYPE_TYPE1_PFM: DWRITE_FONT_FILE_TYPE = 4;
+pub const DWRITE_FONT_FILE_TYPE_TYPE1_PFB: DWRITE_FONT_FILE_TYPE = 5;
+pub const DWRITE_FONT_FILE_TYPE_VECTOR: DWRITE_FONT_FILE_TYPE = 6;
+pub const DWRITE_FONT_FILE_TYPE_BITMAP: DWRITE_FONT_FILE_TYPE = 7;
+pub const DWRITE_FONT_FILE_TYPE_TRUETYPE_COLLECTION: DWRITE_FONT_FILE_TYPE =
+    DWRITE_FONT_FILE_TYPE_OPENTYPE_COLLECTION;
+
+pub type DWRITE_FONT_FACE_TYPE = u32;
+pub const DWRITE_FONT_FACE_TYPE_CFF: DWRITE_FONT_FACE_TYPE = 0;
+pub const DWRITE_FONT_FACE_TYPE_TRUETYPE: DWRITE_FONT_FACE_TYPE = 1;
+pub const DWRITE_FONT_FACE_TYPE_OPENTYPE_COLLECTION: DWRITE_FONT_FACE_TYPE = 2;
+pub const DWRITE_FONT_FACE_TYPE_TYPE1: DWRITE_FONT_FACE_TYPE = 3;
+pub const DWRITE_FONT_FACE_TYPE_VECTOR: DWRITE_FONT_FACE_TYPE = 4;
+pub const DWRITE_FONT_FACE_TYPE_BITMAP: DWRITE_FONT_FACE_TYPE = 5;
+pub const DWRITE_FONT_FACE_TYPE_UNKNOWN: DWRITE_FONT_FACE_TYPE = 6;
+pub const DWRITE_FONT_FACE_TYPE_RAW_CFF: DWRITE_FONT_FACE_TYPE = 7;
+pub const DWRITE_FONT_FACE_TYPE_TRUETYPE_COLLECTION: DWRITE_FONT_FACE_TYPE =
+    DWRITE_FONT_FACE_TYPE_OPENTYPE_COLLECTION;
+
+pub type DWRITE_FONT_WEIGHT = u32;
+pub const DWRITE_FONT_WEIGHT_THIN: DWRITE_FONT_WEIGHT = 100;
+pub const DWRITE_FONT_WEIGHT_EXTRA_LIGHT: DWRITE_FONT_WEIGHT = 200;
+pub const DWRITE_FONT_WEIGHT_ULTRA_LIGHT: DWRITE_FONT_WEIGHT = 200;
+pub const DWRITE_FONT_WEIGHT_LIGHT: DWRITE_FONT_WEIGHT = 300;
+pub const DWRITE_FONT_WEIGHT_SEMI_LIGHT: DWRITE_FONT_WEIGHT = 350;
+pub const DWRITE_FONT_WEIGHT_NORMAL: DWRITE_FONT_WEIGHT = 400;
+pub const DWRITE_FONT_WEIGHT_REGULAR: DWRITE_FONT_WEIGHT = 400;
+pub const DWRITE_FONT_WEIGHT_MEDIUM: DWRITE_FONT_WEIGHT = 500;
+pub const DWRITE_FONT_WEIGHT_DEMI_BOLD: DWRITE_FONT_WEIGHT = 600;
+pub const DWRITE_FONT_WEIGHT_SEMI_BOLD: DWRITE_FONT_WEIGHT = 600;
+pub const DWRITE_FONT_WEIGHT_BOLD: DWRITE_FONT_WEIGHT = 700;
+pub const DWRITE_FONT_WEIGHT_EXTRA_BOLD: DWRITE_FONT_WEIGHT = 800;
+pub const DWRITE_FONT_WEIGHT_ULTRA_BOLD: DWRITE_FONT_WEIGHT = 800;
+pub const DWRITE_FONT_WEIGHT_BLACK: DWRITE_FONT_WEIGHT = 900;
+pub const DWRITE_FONT_WEIGHT_HEAVY: DWRITE_FONT_WEIGHT = 900;
+pub const DWRITE_FONT_WEIGHT_EXTRA_BLACK: DWRITE_FONT_WEIGHT = 950;
+pub const DWRITE_FONT_WEIGHT_ULTRA_BLACK: DWRITE_FONT_WEIGHT = 950;
+
+pub type DWRITE_FONT_STRETCH = u32;
+pub const DWRITE_FONT_STRETCH_UNDEFINED: DWRITE_FONT_STRETCH = 0;
+pub const DWRITE_FONT_STRETCH_ULTRA_CONDENSED: DWRITE_FONT_STRETCH = 1;
+pub const DWRITE_FONT_STRETCH_EXTRA_CONDENSED: DWRITE_FONT_STRETCH = 2;
+pub const DWRITE_FONT_STRETCH_CONDENSED: DWRITE_FONT_STRETCH = 3;
+pub const DWRITE_FONT_STRETCH_SEMI_CONDENSED: DWRITE_FONT_STRETCH = 4;
+pub const DWRITE_FONT_STRETCH_NORMAL: DWRITE_FONT_STRETCH = 5;
+pub const DWRITE_FONT_STRETCH_MEDIUM: DWRITE_FONT_STRETCH = 5;
+pub const DWRITE_FONT_STRETCH_SEMI_EXPANDED: DWRITE_FONT_STRETCH = 6;
+pub const DWRITE_FONT_STRETCH_EXPANDED: DWRITE_FONT_STRETCH = 7;
+pub const DWRITE_FONT_STRETCH_EXTRA_EXPANDED: DWRITE_FONT_STRETCH = 8;
+pub const DWRITE_FONT_STRETCH_ULTRA_EXPANDED: DWRITE_FONT_STRETCH = 9;
+
+pub type DWRITE_FONT_STYLE = u32;
+pub const DWRITE_FONT_STYLE_NORMAL: DWRITE_FONT_STYLE = 0;
+pub const DWRITE_FONT_STYLE_OBLIQUE: DWRITE_FONT_STYLE = 1;
+pub const DWRITE_FONT_STYLE_ITALIC: DWRITE_FONT_STYLE = 2;
+
+pub type DWRITE_INFORMATIONAL_STRING_ID = u32;
+pub const DWRITE_INFORMATIONAL_STRING_NONE: DWRITE_INFORMATIONAL_STRING_ID = 0;
+pub const DWRITE_INFORMATIONAL_STRING_COPYRIGHT_NOTICE: DWRITE_INFORMATIONAL_STRING_ID = 1;
+pub const DWRITE_INFORMATIONAL_STRING_VERSION_STRINGS: DWRITE_INFORMATIONAL_STRING_ID = 2;
+pub const DWRITE_INFORMATIONAL_STRING_TRADEMARK: DWRITE_INFORMATIONAL_STRING_ID = 3;
+pub const DWRITE_INFORMATIONAL_STRING_MANUFACTURER: DWRITE_INFORMATIONAL_STRING_ID = 4;
+pub const DWRITE_INFORMATIONAL_STRING_DESIGNER: DWRITE_INFORMATIONAL_STRING_ID = 5;
+pub const DWRITE_INFORMATIONAL_STRING_DESIGNER_URL: DWRITE_INFORMATIONAL_STRING_ID = 6;
+pub const DWRITE_INFORMATIONAL_STRING_DESCRIPTION: DWRITE_INFORMATIONAL_STRING_ID = 7;
+pub const DWRITE_INFORMATIONAL_STRING_FONT_VENDOR_URL: DWRITE_INFORMATIONAL_STRING_ID = 8;
+pub const DWRITE_INFORMATIONAL_STRING_LICENSE_DESCRIPTION: DWRITE_INFORMATIONAL_STRING_ID = 9;
+pub const DWRITE_INFORMATIONAL_STRING_LICENSE_INFO_URL: DWRITE_INFORMATIONAL_STRING_ID = 10;
+pub const DWRITE_INFORMATIONAL_STRING_WIN32_FAMILY_NAMES: DWRITE_INFORMATIONAL_STRING_ID = 11;
+pub const DWRITE_INFORMATIONAL_STRING_WIN32_SUBFAMILY_NAMES: DWRITE_INFORMATIONAL_STRING_ID = 12;
+pub const DWRITE_INFORMATIONAL_STRING_PREFERRED_FAMILY_NAMES: DWRITE_INFORMATIONAL_STRING_ID = 13;
+pub const DWRITE_INFORMATIONAL_STRING_PREFERRED_SUBFAMILY_NAMES: DWRITE_INFORMATIONAL_STRING_ID =
+    14;
+pub const DWRITE_INFORMATIONAL_STRING_SAMPLE_TEXT: DWRITE_INFORMATIONAL_STRING_ID = 15;
+pub const DWRITE_INFORMATIONAL_STRING_FULL_NAME: DWRITE_INFORMATIONAL_STRING_ID = 16;
+pub const DWRITE_INFORMATIONAL_STRING_POSTSCRIPT_NAME: DWRITE_INFORMATIONAL_STRING_ID = 17;
+pub const DWRITE_INFORMATIONAL_STRING_POSTSCRIPT_CID_NAME: DWRITE_INFORMATIONAL_STRING_ID = 18;
+pub const DWRITE_INFORMATIONAL_STRING_WWS_FAMILY_NAME: DWRITE_INFORMATIONAL_STRING_ID = 19;
+pub const DWRITE_INFORMATIONAL_STRING_DESIGN_SCRIPT_LANGUAGE_TAG: DWRITE_INFORMATIONAL_STRING_ID =
+    20;
+pub const DWRITE_INFORMATIONAL_STRING_SUPPORTED_SCRIPT_LANGUAGE_TAG:
+    DWRITE_INFORMATIONAL_STRING_ID = 21;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_GLYPH_METRICS {
+    pub leftSideBearing: INT32,
+    pub advanceWidth: UINT32,
+    pub rightSideBearing: INT32,
+    pub topSideBearing: INT32,
+    pub advanceHeight: UINT32,
+    pub bottomSideBearing: INT32,
+    pub verticalOriginY: INT32,
+}
+
+pub type DWRITE_FACTORY_TYPE = u32;
+pub const DWRITE_FACTORY_TYPE_SHARED: DWRITE_FACTORY_TYPE = 0;
+pub const DWRITE_FACTORY_TYPE_ISOLATED: DWRITE_FACTORY_TYPE = 1;
+
+interface! {#[uuid(0x727cad4e, 0xd6af, 0x4c9e, 0x8a, 0x08, 0xd6, 0x95, 0xb1, 0x1c, 0xaa, 0x49)]
+interface IDWriteFontFileLoader(IDWriteFontFileLoaderVtbl): IUnknown(IUnknownVtbl) {
+    fn CreateStreamFromKey(
+        fontFileReferenceKey: *const c_void,
+        fontFileReferenceKeySize: UINT32,
+        fontFileStream: *mut *mut IDWriteFontFileStream,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0xb2d9f3ec, 0xc9fe, 0x4a11, 0xa2, 0xec, 0xd8, 0x62, 0x08, 0xf7, 0xc0, 0xa2)]
+interface IDWriteLocalFontFileLoader(IDWriteLocalFontFileLoaderVtbl):
+    IDWriteFontFileLoader(IDWriteFontFileLoaderVtbl) {
+    fn GetFilePathLengthFromKey(
+        fontFileReferenceKey: *const c_void,
+        fontFileReferenceKeySize: UINT32,
+        filePathLength: *mut UINT32,
+    ) -> HRESULT,
+    fn GetFilePathFromKey(
+        fontFileReferenceKey: *const c_void,
+        fontFileReferenceKeySize: UINT32,
+        filePath: *mut WCHAR,
+        filePathSize: UINT32,
+    ) -> HRESULT,
+    fn GetLastWriteTimeFromKey(
+        fontFileReferenceKey: *const c_void,
+        fontFileReferenceKeySize: UINT32,
+        lastWriteTime: *mut FILETIME,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0x6d4865fe, 0x0ab8, 0x4d91, 0x8f, 0x62, 0x5d, 0xd6, 0xbe, 0x34, 0xa3, 0xe0)]
+interface IDWriteFontFileStream(IDWriteFontFileStreamVtbl): IUnknown(IUnknownVtbl) {
+    fn ReadFileFragment(
+        fragmentStart: *mut *const c_void,
+        fileOffset: UINT64,
+        fragmentSize: UINT64,
+        fragmentContext: *mut *mut c_void,
+    ) -> HRESULT,
+    fn ReleaseFileFragment(
+        fragmentContext: *mut c_void,
+    ) -> (),
+    fn GetFileSize(
+        fileSize: *mut UINT64,
+    ) -> HRESULT,
+    fn GetLastWriteTime(
+        lastWriteTime: *mut UINT64,
+    ) -> HRESULT,
+}}
+
+pub type DWRITE_OUTLINE_THRESHOLD = u32;
+pub const DWRITE_OUTLINE_THRESHOLD_ANTIALIASED: DWRITE_OUTLINE_THRESHOLD = 0;
+pub const DWRITE_OUTLINE_THRESHOLD_ALIASED: DWRITE_OUTLINE_THRESHOLD = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_UNICODE_RANGE {
+    pub first: UINT32,
+    pub last: UINT32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_CARET_METRICS {
+    pub slopeRise: INT16,
+    pub slopeRun: INT16,
+    pub offset: INT16,
+}
+
+interface! {#[uuid(0x739d886a, 0xcef5, 0x47dc, 0x87, 0x69, 0x1a, 0x8b, 0x41, 0xbe, 0xbb, 0xb0)]
+interface IDWriteFontFile(IDWriteFontFileVtbl): IUnknown(IUnknownVtbl) {
+    fn GetReferenceKey(
+        fontFileReferenceKey: *mut *const c_void,
+        fontFileReferenceKeySize: *mut UINT32,
+    ) -> HRESULT,
+    fn GetLoader(
+        fontFileLoader: *mut *mut IDWriteFontFileLoader,
+    ) -> HRESULT,
+    fn Analyze(
+        isSupportedFontType: *mut BOOL,
+        fontFileType: *mut DWRITE_FONT_FILE_TYPE,
+        fontFaceType: *mut DWRITE_FONT_FACE_TYPE,
+        numberOfFaces: *mut UINT32,
+    ) -> HRESULT,
+}}
+
+pub type DWRITE_PIXEL_GEOMETRY = u32;
+pub const DWRITE_PIXEL_GEOMETRY_FLAT: DWRITE_PIXEL_GEOMETRY = 0;
+pub const DWRITE_PIXEL_GEOMETRY_RGB: DWRITE_PIXEL_GEOMETRY = 1;
+pub const DWRITE_PIXEL_GEOMETRY_BGR: DWRITE_PIXEL_GEOMETRY = 2;
+
+interface! {#[uuid(0x2f0da53a, 0x2add, 0x47cd, 0x82, 0xee, 0xd9, 0xec, 0x34, 0x68, 0x8e, 0x75)]
+interface IDWriteRenderingParams(IDWriteRenderingParamsVtbl): IUnknown(IUnknownVtbl) {
+    fn GetGamma() -> FLOAT,
+    fn GetEnhancedContrast() -> FLOAT,
+    fn GetClearTypeLevel() -> FLOAT,
+    fn GetPixelGeometry() -> DWRITE_PIXEL_GEOMETRY,
+    fn GetRenderingMode() -> DWRITE_RENDERING_MODE,
+}}
+
+pub type IDWriteGeometrySink = ID2D1SimplifiedGeometrySink;
+
+interface! {#[uuid(0x5f49804d, 0x7024, 0x4d43, 0xbf, 0xa9, 0xd2, 0x59, 0x84, 0xf5, 0x38, 0x49)]
+interface IDWriteFontFace(IDWriteFontFaceVtbl): IUnknown(IUnknownVtbl) {
+    fn GetType() -> DWRITE_FONT_FACE_TYPE,
+    fn GetFiles(
+        numberOfFiles: *mut UINT32,
+        fontFiles: *mut *mut IDWriteFontFile,
+    ) -> HRESULT,
+    fn GetIndex() -> UINT32,
+    fn GetSimulations() -> DWRITE_FONT_SIMULATIONS,
+    fn IsSymbolFont() -> BOOL,
+    fn GetMetrics(
+        fontFaceMetrics: *mut DWRITE_FONT_METRICS,
+    ) -> (),
+    fn GetGlyphCount() -> UINT16,
+    fn GetDesignGlyphMetrics(
+        glyphIndices: *const UINT16,
+        glyphCount: UINT32,
+        glyphMetrics: *mut DWRITE_GLYPH_METRICS,
+        isSideways: BOOL,
+    ) -> HRESULT,
+    fn GetGlyphIndices(
+        codePoints: *const UINT32,
+        codePointCount: UINT32,
+        glyphIndices: *mut UINT16,
+    ) -> HRESULT,
+    fn TryGetFontTable(
+        openTypeTableTag: UINT32,
+        tableData: *mut *const c_void,
+        tableSize: *mut UINT32,
+        tableContext: *mut *mut c_void,
+        exists: *mut BOOL,
+    ) -> HRESULT,
+    fn ReleaseFontTable(
+        tableContext: *mut c_void,
+    ) -> HRESULT,
+    fn GetGlyphRunOutline(
+        emSize: FLOAT,
+        glyphIndices: *const UINT16,
+        glyphAdvances: *const FLOAT,
+        glyphOffsets: *const DWRITE_GLYPH_OFFSET,
+        glyphCount: UINT32,
+        isSideways: BOOL,
+        isRightToLeft: BOOL,
+        geometrySink: *mut IDWriteGeometrySink,
+    ) -> HRESULT,
+    fn GetRecommendedRenderingMode(
+        emSize: FLOAT,
+        pixelsPerDip: FLOAT,
+        measuringMode: DWRITE_MEASURING_MODE,
+        renderingParams: *mut IDWriteRenderingParams,
+        renderingMode: *mut DWRITE_RENDERING_MODE,
+    ) -> HRESULT,
+    fn GetGdiCompatibleMetrics(
+        emSize: FLOAT,
+        pixelsPerDip: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        fontFaceMetrics: *mut DWRITE_FONT_METRICS,
+    ) -> HRESULT,
+    fn GetGdiCompatibleGlyphMetrics(
+        enSize: FLOAT,
+        pixelsPerDip: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        useGdiNatrual: BOOL,
+        glyphIndices: *const UINT16,
+        glyphCount: UINT32,
+        glyphMetrics: *mut DWRITE_GLYPH_METRICS,
+        isSideways: BOOL,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0xcca920e4, 0x52f0, 0x492b, 0xbf, 0xa8, 0x29, 0xc7, 0x2e, 0xe0, 0xa4, 0x68)]
+interface IDWriteFontCollectionLoader(IDWriteFontCollectionLoaderVtbl):
+        IUnknown(IUnknownVtbl) {
+    fn CreateEnumeratorFromKey(
+        factory: *mut IDWriteFactory,
+        collectionKey: *const c_void,
+        collectionKeySize: UINT32,
+        fontFileEnumerator: *mut *mut IDWriteFontFileEnumerator,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0x72755049, 0x5ff7, 0x435d, 0x83, 0x48, 0x4b, 0xe9, 0x7c, 0xfa, 0x6c, 0x7c)]
+interface IDWriteFontFileEnumerator(IDWriteFontFileEnumeratorVtbl): IUnknown(IUnknownVtbl) {
+    fn MoveNext(
+        hasCurrentFile: *mut BOOL,
+    ) -> HRESULT,
+    fn GetCurrentFontFile(
+        fontFile: *mut *mut IDWriteFontFile,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0x08256209, 0x099a, 0x4b34, 0xb8, 0x6d, 0xc2, 0x2b, 0x11, 0x0e, 0x77, 0x71)]
+interface IDWriteLocalizedStrings(IDWriteLocalizedStringsVtbl): IUnknown(IUnknownVtbl) {
+    fn GetCount() -> UINT32,
+    fn FindLocaleName(
+        localeName: *const WCHAR,
+        index: *mut UINT32,
+        exists: *mut BOOL,
+    ) -> HRESULT,
+    fn GetLocaleNameLength(
+        index: UINT32,
+        length: *mut UINT32,
+    ) -> HRESULT,
+    fn GetLocaleName(
+        index: UINT32,
+        localeName: *mut WCHAR,
+        size: UINT32,
+    ) -> HRESULT,
+    fn GetStringLength(
+        index: UINT32,
+        length: *mut UINT32,
+    ) -> HRESULT,
+    fn GetString(
+        index: UINT32,
+        stringBuffer: *mut WCHAR,
+        size: UINT32,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0xa84cee02, 0x3eea, 0x4eee, 0xa8, 0x27, 0x87, 0xc1, 0xa0, 0x2a, 0x0f, 0xcc)]
+interface IDWriteFontCollection(IDWriteFontCollectionVtbl): IUnknown(IUnknownVtbl) {
+    fn GetFontFamilyCount() -> UINT32,
+    fn GetFontFamily(
+        index: UINT32,
+        fontFamily: *mut *mut IDWriteFontFamily,
+    ) -> HRESULT,
+    fn FindFamilyName(
+        familyName: *const WCHAR,
+        index: *mut UINT32,
+        exists: *mut BOOL,
+    ) -> HRESULT,
+    fn GetFontFromFontFace(
+        fontFace: *mut IDWriteFontFace,
+        font: *mut *mut IDWriteFont,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0x1a0d8438, 0x1d97, 0x4ec1, 0xae, 0xf9, 0xa2, 0xfb, 0x86, 0xed, 0x6a, 0xcb)]
+interface IDWriteFontList(IDWriteFontListVtbl): IUnknown(IUnknownVtbl) {
+    fn GetFontCollection(
+        fontCollection: *mut *mut IDWriteFontCollection,
+    ) -> HRESULT,
+    fn GetFontCount() -> UINT32,
+    fn GetFont(
+        index: UINT32,
+        font: *mut *mut IDWriteFont,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0xda20d8ef, 0x812a, 0x4c43, 0x98, 0x02, 0x62, 0xec, 0x4a, 0xbd, 0x7a, 0xdd)]
+interface IDWriteFontFamily(IDWriteFontFamilyVtbl): IDWriteFontList(IDWriteFontListVtbl) {
+    fn GetFamilyNames(
+        names: *mut *mut IDWriteLocalizedStrings,
+    ) -> HRESULT,
+    fn GetFirstMatchingFont(
+        weight: DWRITE_FONT_WEIGHT,
+        stretch: DWRITE_FONT_STRETCH,
+        style: DWRITE_FONT_STYLE,
+        matchingFont: *mut *mut IDWriteFont,
+    ) -> HRESULT,
+    fn GetMatchingFonts(
+        weight: DWRITE_FONT_WEIGHT,
+        stretch: DWRITE_FONT_STRETCH,
+        style: DWRITE_FONT_STYLE,
+        matchingFonts: *mut *mut IDWriteFontList,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0xacd16696, 0x8c14, 0x4f5d, 0x87, 0x7e, 0xfe, 0x3f, 0xc1, 0xd3, 0x27, 0x37)]
+interface IDWriteFont(IDWriteFontVtbl): IUnknown(IUnknownVtbl) {
+    fn GetFontFamily(
+        fontFamily: *mut *mut IDWriteFontFamily,
+    ) -> HRESULT,
+    fn GetWeight() -> DWRITE_FONT_WEIGHT,
+    fn GetStretch() -> DWRITE_FONT_STRETCH,
+    fn GetStyle() -> DWRITE_FONT_STYLE,
+    fn IsSymbolFont() -> BOOL,
+    fn GetFaceNames(
+        names: *mut *mut IDWriteLocalizedStrings,
+    ) -> HRESULT,
+    fn GetInformationalStrings(
+        informationalStringId: DWRITE_INFORMATIONAL_STRING_ID,
+        informationalStrings: *mut *mut IDWriteLocalizedStrings,
+        exists: *mut BOOL,
+    ) -> HRESULT,
+    fn GetSimulations() -> DWRITE_FONT_SIMULATIONS,
+    fn GetMetrics(
+        fontMetrics: *mut DWRITE_FONT_METRICS,
+    ) -> (),
+    fn HasCharacter(
+        unicodeValue: UINT32,
+        exists: *mut BOOL,
+    ) -> HRESULT,
+    fn CreateFontFace(
+        fontFace: *mut *mut IDWriteFontFace,
+    ) -> HRESULT,
+}}
+
+pub type DWRITE_READING_DIRECTION = u32;
+pub const DWRITE_READING_DIRECTION_LEFT_TO_RIGHT: DWRITE_READING_DIRECTION = 0;
+pub const DWRITE_READING_DIRECTION_RIGHT_TO_LEFT: DWRITE_READING_DIRECTION = 1;
+pub const DWRITE_READING_DIRECTION_TOP_TO_BOTTOM: DWRITE_READING_DIRECTION = 2;
+pub const DWRITE_READING_DIRECTION_BOTTOM_TO_TOP: DWRITE_READING_DIRECTION = 3;
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteTextFormat {}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteTypography {}
+
+pub type DWRITE_NUMBER_SUBSTITUTION_METHOD = u32;
+pub const DWRITE_NUMBER_SUBSTITUTION_METHOD_FROM_CULTURE: DWRITE_NUMBER_SUBSTITUTION_METHOD = 0;
+pub const DWRITE_NUMBER_SUBSTITUTION_METHOD_CONTEXTUAL: DWRITE_NUMBER_SUBSTITUTION_METHOD = 1;
+pub const DWRITE_NUMBER_SUBSTITUTION_METHOD_NONE: DWRITE_NUMBER_SUBSTITUTION_METHOD = 2;
+pub const DWRITE_NUMBER_SUBSTITUTION_METHOD_NATIONAL: DWRITE_NUMBER_SUBSTITUTION_METHOD = 3;
+pub const DWRITE_NUMBER_SUBSTITUTION_METHOD_TRADITIONAL: DWRITE_NUMBER_SUBSTITUTION_METHOD = 4;
+
+interface! {#[uuid(0x14885cc9, 0xbab0, 0x4f90, 0xb6, 0xed, 0x5c, 0x36, 0x6a, 0x2c, 0xd0, 0x3d)]
+interface IDWriteNumberSubstitution(IDWriteNumberSubstitutionVtbl): IUnknown(IUnknownVtbl) {}}
+
+interface! {#[uuid(0x688e1a58, 0x5094, 0x47c8, 0xad, 0xc8, 0xfb, 0xce, 0xa6, 0x0a, 0xe9, 0x2b)]
+interface IDWriteTextAnalysisSource(IDWriteTextAnalysisSourceVtbl): IUnknown(IUnknownVtbl) {
+    fn GetTextAtPosition(
+        textPosition: UINT32,
+        textString: *mut *const WCHAR,
+        textLength: *mut UINT32,
+    ) -> HRESULT,
+    fn GetTextBeforePosition(
+        textPosition: UINT32,
+        textString: *mut *const WCHAR,
+        textLength: *mut UINT32,
+    ) -> HRESULT,
+    fn GetParagraphReadingDirection() -> DWRITE_READING_DIRECTION,
+    fn GetLocaleName(
+        textPosition: UINT32,
+        textLength: *mut UINT32,
+        localeName: *mut *const WCHAR,
+    ) -> HRESULT,
+    fn GetNumberSubstitution(
+        textPosition: UINT32,
+        textLength: *mut UINT32,
+        numberSubstitution: *mut *mut IDWriteNumberSubstitution,
+    ) -> HRESULT,
+}}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteTextAnalyzer {}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_GLYPH_RUN {
+    pub fontFace: *mut IDWriteFontFace,
+    pub fontEmSize: FLOAT,
+    pub glyphCount: UINT32,
+    pub glyphIndices: *const UINT16,
+    pub glyphAdvances: *const FLOAT,
+    pub glyphOffsets: *const DWRITE_GLYPH_OFFSET,
+    pub isSideways: BOOL,
+    pub bidiLevel: UINT32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_GLYPH_RUN_DESCRIPTION {
+    pub localeName: *const WCHAR,
+    pub string: *const WCHAR,
+    pub stringLength: UINT32,
+    pub clusterMap: *const UINT16,
+    pub textPosition: UINT32,
+}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteInlineObject {}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteTextLayout {}
+
+interface! {#[uuid(0x5e5a32a3, 0x8dff, 0x4773, 0x9f, 0xf6, 0x06, 0x96, 0xea, 0xb7, 0x72, 0x67)]
+interface IDWriteBitmapRenderTarget(IDWriteBitmapRenderTargetVtbl): IUnknown(IUnknownVtbl) {
+    fn DrawGlyphRun(
+        baselineOriginX: FLOAT,
+        baselineOriginY: FLOAT,
+        measuringMode: DWRITE_MEASURING_MODE,
+        glyphRun: *const DWRITE_GLYPH_RUN,
+        renderingParams: *mut IDWriteRenderingParams,
+        textColor: COLORREF,
+        blackBoxRect: *mut RECT,
+    ) -> HRESULT,
+    fn GetMemoryDC() -> HDC,
+    fn GetPixelsPerDip() -> FLOAT,
+    fn SetPixelsPerDip(
+        pixelsPerDip: FLOAT,
+    ) -> HRESULT,
+    fn GetCurrentTransform(
+        transform: *mut DWRITE_MATRIX,
+    ) -> HRESULT,
+    fn SetCurrentTransform(
+        transform: *const DWRITE_MATRIX,
+    ) -> HRESULT,
+    fn GetSize(
+        size: *mut SIZE,
+    ) -> HRESULT,
+    fn Resize(
+        width: UINT32,
+        height: UINT32,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0x1edd9491, 0x9853, 0x4299, 0x89, 0x8f, 0x64, 0x32, 0x98, 0x3b, 0x6f, 0x3a)]
+interface IDWriteGdiInterop(IDWriteGdiInteropVtbl): IUnknown(IUnknownVtbl) {
+    fn CreateFontFromLOGFONT(
+        logFont: *const LOGFONTW,
+        font: *mut *mut IDWriteFont,
+    ) -> HRESULT,
+    fn ConvertFontToLOGFONT(
+        font: *mut IDWriteFont,
+        logFont: *mut LOGFONTW,
+        isSystemFont: *mut BOOL,
+    ) -> HRESULT,
+    fn ConvertFontFaceToLOGFONT(
+        font: *mut IDWriteFontFace,
+        logFont: *mut LOGFONTW,
+    ) -> HRESULT,
+    fn CreateFontFaceFromHdc(
+        hdc: HDC,
+        fontFace: *mut *mut IDWriteFontFace,
+    ) -> HRESULT,
+    fn CreateBitmapRenderTarget(
+        hdc: HDC,
+        width: UINT32,
+        height: UINT32,
+        renderTarget: *mut *mut IDWriteBitmapRenderTarget,
+    ) -> HRESULT,
+}}
+
+pub type DWRITE_TEXTURE_TYPE = u32;
+pub const DWRITE_TEXTURE_ALIASED_1x1: DWRITE_TEXTURE_TYPE = 0;
+pub const DWRITE_TEXTURE_CLEARTYPE_3x1: DWRITE_TEXTURE_TYPE = 1;
+
+interface! {#[uuid(0x7d97dbf7, 0xe085, 0x42d4, 0x81, 0xe3, 0x6a, 0x88, 0x3b, 0xde, 0xd1, 0x18)]
+interface IDWriteGlyphRunAnalysis(IDWriteGlyphRunAnalysisVtbl): IUnknown(IUnknownVtbl) {
+    fn GetAlphaTextureBounds(
+        textureType: DWRITE_TEXTURE_TYPE,
+        textureBounds: *mut RECT,
+    ) -> HRESULT,
+    fn CreateAlphaTexture(
+        textureType: DWRITE_TEXTURE_TYPE,
+        textureBounds: *const RECT,
+        alphaValues: *mut BYTE,
+        bufferSize: UINT32,
+    ) -> HRESULT,
+    fn GetAlphaBlendParams(
+        renderingParams: *mut IDWriteRenderingParams,
+        blendGamma: *mut FLOAT,
+        blendEnhancedContrast: *mut FLOAT,
+        blendClearTypeLevel: *mut FLOAT,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0xb859ee5a, 0xd838, 0x4b5b, 0xa2, 0xe8, 0x1a, 0xdc, 0x7d, 0x93, 0xdb, 0x48)]
+interface IDWriteFactory(IDWriteFactoryVtbl): IUnknown(IUnknownVtbl) {
+    fn GetSystemFontCollection(
+        fontCollection: *mut *mut IDWriteFontCollection,
+        checkForUpdates: BOOL,
+    ) -> HRESULT,
+    fn CreateCustomFontCollection(
+        collectionLoader: *mut IDWriteFontCollectionLoader,
+        collectionKey: *const c_void,
+        collectionKeySize: UINT32,
+        fontCollection: *mut *mut IDWriteFontCollection,
+    ) -> HRESULT,
+    fn RegisterFontCollectionLoader(
+        fontCollectionLoader: *mut IDWriteFontCollectionLoader,
+    ) -> HRESULT,
+    fn UnregisterFontCollectionLoader(
+        fontCollectionLoader: *mut IDWriteFontCollectionLoader,
+    ) -> HRESULT,
+    fn CreateFontFileReference(
+        filePath: *const WCHAR,
+        lastWriteTime: *const FILETIME,
+        fontFile: *mut *mut IDWriteFontFile,
+    ) -> HRESULT,
+    fn CreateCustomFontFileReference(
+        fontFileReferenceKey: *const c_void,
+        fontFileReferenceKeySize: UINT32,
+        fontFileLoader: *mut IDWriteFontFileLoader,
+        fontFile: *mut *mut IDWriteFontFile,
+    ) -> HRESULT,
+    fn CreateFontFace(
+        fontFaceType: DWRITE_FONT_FACE_TYPE,
+        numberOfFiles: UINT32,
+        fontFiles: *const *mut IDWriteFontFile,
+        faceIndex: UINT32,
+        fontFaceSimulationFlags: DWRITE_FONT_SIMULATIONS,
+        fontFace: *mut *mut IDWriteFontFace,
+    ) -> HRESULT,
+    fn CreateRenderingParams(
+        renderingParams: *mut *mut IDWriteRenderingParams,
+    ) -> HRESULT,
+    fn CreateMonitorRenderingParams(
+        monitor: HMONITOR,
+        renderingParams: *mut *mut IDWriteRenderingParams,
+    ) -> HRESULT,
+    fn CreateCustomRenderingParams(
+        gamma: FLOAT,
+        enhancedContrast: FLOAT,
+        clearTypeLevel: FLOAT,
+        pixelGeometry: DWRITE_PIXEL_GEOMETRY,
+        renderingMode: DWRITE_RENDERING_MODE,
+        renderingParams: *mut *mut IDWriteRenderingParams,
+    ) -> HRESULT,
+    fn RegisterFontFileLoader(
+        fontFileLoader: *mut IDWriteFontFileLoader,
+    ) -> HRESULT,
+    fn UnregisterFontFileLoader(
+        fontFileLoader: *mut IDWriteFontFileLoader,
+    ) -> HRESULT,
+    fn CreateTextFormat(
+        fontFamilyName: *const WCHAR,
+        fontCollection: *mut IDWriteFontCollection,
+        fontWeight: DWRITE_FONT_WEIGHT,
+        fontStyle: DWRITE_FONT_STYLE,
+        fontStretch: DWRITE_FONT_STRETCH,
+        fontSize: FLOAT,
+        localeName: *const WCHAR,
+        textFormat: *mut *mut IDWriteTextFormat,
+    ) -> HRESULT,
+    fn CreateTypography(
+        typography: *mut *mut IDWriteTypography,
+    ) -> HRESULT,
+    fn GetGdiInterop(
+        gdiInterop: *mut *mut IDWriteGdiInterop,
+    ) -> HRESULT,
+    fn CreateTextLayout(
+        string: *const WCHAR,
+        stringLength: UINT32,
+        textFormat: *mut IDWriteTextFormat,
+        maxWidth: FLOAT,
+        maxHeight: FLOAT,
+        textLayout: *mut *mut IDWriteTextLayout,
+    ) -> HRESULT,
+    fn CreateGdiCompatibleTextLayout(
+        string: *const WCHAR,
+        stringLength: UINT32,
+        textFormat: *mut IDWriteTextFormat,
+        layoutWidth: FLOAT,
+        layoutHeight: FLOAT,
+        pixelsPerDip: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        useGdiNatrual: BOOL,
+        textLayout: *mut *mut IDWriteTextLayout,
+    ) -> HRESULT,
+    fn CreateEllipsisTrimmingSign(
+        textFormat: *mut IDWriteTextFormat,
+        trimmingSign: *mut *mut IDWriteInlineObject,
+    ) -> HRESULT,
+    fn CreateTextAnalyzer(
+        textAnalyzer: *mut *mut IDWriteTextAnalyzer,
+    ) -> HRESULT,
+    fn CreateNumberSubstitution(
+        substitutionMethod: DWRITE_NUMBER_SUBSTITUTION_METHOD,
+        localeName: *const WCHAR,
+        ignoreUserOverride: BOOL,
+        numberSubstitution: *mut *mut IDWriteNumberSubstitution,
+    ) -> HRESULT,
+    fn CreateGlyphRunAnalysis(
+        glyphRun: *const DWRITE_GLYPH_RUN,
+        pixelsPerDip: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        renderingMode: DWRITE_RENDERING_MODE,
+        measuringMode: DWRITE_MEASURING_MODE,
+        baselineOriginX: FLOAT,
+        baselineOriginY: FLOAT,
+        glyphRunAnalysis: *mut *mut IDWriteGlyphRunAnalysis,
+    ) -> HRESULT,
+}}
@@ -0,0 +1,185 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub use crate::portable_types::FontMetrics1 as DWRITE_FONT_METRICS1;
+use crate::sys::shared::basetsd::{INT32, UINT16, UINT32, UINT8};
+use crate::sys::shared::minwindef::{BOOL, FLOAT};
+use crate::sys::um::dcommon::DWRITE_MEASURING_MODE;
+use crate::sys::um::dwrite::{
+    IDWriteFactory, IDWriteFactoryVtbl, IDWriteFont, IDWriteFontCollection, IDWriteFontFace,
+    IDWriteFontFaceVtbl, IDWriteFontVtbl, DWRITE_CARET_METRICS, DWRITE_MATRIX,
+    DWRITE_OUTLINE_THRESHOLD, DWRITE_PIXEL_GEOMETRY, DWRITE_RENDERING_MODE, DWRITE_UNICODE_RANGE,
+};
+use crate::sys::um::winnt::HRESULT;
+
+interface! {#[uuid(0xa71efdb4, 0x9fdb, 0x4838, 0xad, 0x90, 0xcf, 0xc3, 0xbe, 0x8c, 0x3d, 0xaf)]
+interface IDWriteFontFace1(IDWriteFontFace1Vtbl): IDWriteFontFace(IDWriteFontFaceVtbl) {
+    fn GetMetrics(
+        fontMetrics: *mut DWRITE_FONT_METRICS1,
+    ) -> (),
+    fn GetGdiCompatibleMetrics(
+        emSize: FLOAT,
+        pixelsPerDip: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        fontMetrics: *mut DWRITE_FONT_METRICS1,
+    ) -> HRESULT,
+    fn GetCaretMetrics(
+        caretMetrics: *mut DWRITE_CARET_METRICS,
+    ) -> (),
+    fn GetUnicodeRanges(
+        maxRangeCount: UINT32,
+        unicodeRanges: *mut DWRITE_UNICODE_RANGE,
+        actualRangeCount: *mut UINT32,
+    ) -> HRESULT,
+    fn IsMonospacedFont() -> BOOL,
+    fn GetDesignGlyphAdvances(
+        glyphCount: UINT32,
+        glyphIndices: *const UINT16,
+        glyphAdvances: *mut INT32,
+        isSideways: BOOL,
+    ) -> HRESULT,
+    fn GetGdiCompatibleGlyphAdvances(
+        emSize: FLOAT,
+        pixelsPerDip: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        useGdiNatural: BOOL,
+        isSideways: BOOL,
+        glyphCount: UINT32,
+        glyphIndices: *const UINT16,
+        glyphAdvances: *mut INT32,
+    ) -> HRESULT,
+    fn GetKerningPairAdjustments(
+        glyphCount: UINT32,
+        glyphIndices: *const UINT16,
+        glyphAdvanceAdjustments: *mut INT32,
+    ) -> HRESULT,
+    fn HasKerningPairs() -> BOOL,
+    fn GetRecommendedRenderingMode(
+        fontEmSize: FLOAT,
+        dpiX: FLOAT,
+        dpiY: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        isSideways: BOOL,
+        outlineThreshold: DWRITE_OUTLINE_THRESHOLD,
+        measuringMode: DWRITE_MEASURING_MODE,
+        renderingMode: *mut DWRITE_RENDERING_MODE,
+    ) -> HRESULT,
+    fn GetVerticalGlyphVariants(
+        glyphCount: UINT32,
+        nominalGlyphIndices: *const UINT16,
+        verticalGlyphIndices: *mut UINT16,
+    ) -> HRESULT,
+    fn HasVerticalGlyphVariants() -> BOOL,
+}}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_PANOSE_text {
+    pub familyKind: UINT8,
+    pub serifStyle: UINT8,
+    pub weight: UINT8,
+    pub proportion: UINT8,
+    pub contrast: UINT8,
+    pub strokeVariation: UINT8,
+    pub armStyle: UINT8,
+    pub letterform: UINT8,
+    pub midline: UINT8,
+    pub xHeight: UINT8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_PANOSE_script {
+    pub familyKind: UINT8,
+    pub toolKind: UINT8,
+    pub weight: UINT8,
+    pub spacing: UINT8,
+    pub aspectRatio: UINT8,
+    pub contrast: UINT8,
+    pub scriptTopology: UINT8,
+    pub scriptForm: UINT8,
+    pub finials: UINT8,
+    pub xAscent: UINT8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_PANOSE_decorative {
+    pub familyKind: UINT8,
+    pub decorativeClass: UINT8,
+    pub weight: UINT8,
+    pub aspect: UINT8,
+    pub contrast: UINT8,
+    pub serifVariant: UINT8,
+    pub fill: UINT8,
+    pub lining: UINT8,
+    pub decorativeTopology: UINT8,
+    pub characterRange: UINT8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_PANOSE_symbol {
+    pub familyKind: UINT8,
+    pub symbolKind: UINT8,
+    pub weight: UINT8,
+    pub spacing: UINT8,
+    pub aspectRatioAndContrast: UINT8,
+    pub aspectRatio94: UINT8,
+    pub aspectRatio119: UINT8,
+    pub aspectRatio157: UINT8,
+    pub aspectRatio163: UINT8,
+    pub aspectRatio211: UINT8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union DWRITE_PANOSE {
+    pub values: [UINT8; 10],
+    pub familyKind: UINT8,
+    pub text: DWRITE_PANOSE_text,
+    pub script: DWRITE_PANOSE_script,
+    pub decorative: DWRITE_PANOSE_decorative,
+    pub symbol: DWRITE_PANOSE_symbol,
+}
+
+interface! {#[uuid(0x30572f99, 0xdac6, 0x41db, 0xa1, 0x6e, 0x04, 0x86, 0x30, 0x7e, 0x60, 0x6a)]
+interface IDWriteFactory1(IDWriteFactory1Vtbl): IDWriteFactory(IDWriteFactoryVtbl) {
+    fn GetEudcFontCollection(
+        fontCollection: *mut *mut IDWriteFontCollection,
+        checkForUpdates: BOOL,
+    ) -> HRESULT,
+    fn CreateCustomRenderingParams(
+        gamma: FLOAT,
+        enhancedContrast: FLOAT,
+        enhancedContrastGrayscale: FLOAT,
+        clearTypeLevel: FLOAT,
+        pixelGeometry: DWRITE_PIXEL_GEOMETRY,
+        renderingMode: DWRITE_RENDERING_MODE,
+        renderingParams: *mut *mut IDWriteRenderingParams1,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0xacd16696, 0x8c14, 0x4f5d, 0x87, 0x7e, 0xfe, 0x3f, 0xc1, 0xd3, 0x27, 0x38)]
+interface IDWriteFont1(IDWriteFont1Vtbl): IDWriteFont(IDWriteFontVtbl) {
+    fn GetMetrics(
+        fontMetrics: *mut DWRITE_FONT_METRICS1,
+    ) -> (),
+    fn GetPanose(
+        panose: *mut DWRITE_PANOSE,
+    ) -> (),
+    fn GetUnicodeRanges(
+        maxRangeCount: UINT32,
+        unicodeRanges: *mut DWRITE_UNICODE_RANGE,
+        actualRangeCount: *mut UINT32,
+    ) -> HRESULT,
+    fn IsMonospacedFont() -> BOOL,
+}}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteRenderingParams1 {}
+
+pub type DWRITE_TEXT_ANTIALIAS_MODE = u32;
+pub const DWRITE_TEXT_ANTIALIAS_MODE_CLEARTYPE: DWRITE_TEXT_ANTIALIAS_MODE = 0x0;
+pub const DWRITE_TEXT_ANTIALIAS_MODE_GRAYSCALE: DWRITE_TEXT_ANTIALIAS_MODE = 0x1;
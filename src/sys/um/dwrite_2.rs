@@ -0,0 +1,121 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use crate::sys::ctypes::wchar_t;
+use crate::sys::shared::basetsd::UINT32;
+use crate::sys::shared::d3d9types::D3DCOLORVALUE;
+use crate::sys::shared::minwindef::{BOOL, FLOAT};
+use crate::sys::um::dcommon::DWRITE_MEASURING_MODE;
+use crate::sys::um::dwrite::{
+    IDWriteFont, IDWriteFontCollection, IDWriteGlyphRunAnalysis, IDWriteRenderingParams,
+    IDWriteTextAnalysisSource, DWRITE_FONT_STRETCH, DWRITE_FONT_STYLE, DWRITE_FONT_WEIGHT,
+    DWRITE_GLYPH_RUN, DWRITE_GLYPH_RUN_DESCRIPTION, DWRITE_MATRIX, DWRITE_OUTLINE_THRESHOLD,
+    DWRITE_PIXEL_GEOMETRY, DWRITE_RENDERING_MODE,
+};
+use crate::sys::um::dwrite_1::{
+    IDWriteFactory1, IDWriteFactory1Vtbl, IDWriteFontFace1, IDWriteFontFace1Vtbl,
+    DWRITE_TEXT_ANTIALIAS_MODE,
+};
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::winnt::HRESULT;
+
+pub type DWRITE_GRID_FIT_MODE = u32;
+pub const DWRITE_GRID_FIT_MODE_DEFAULT: DWRITE_GRID_FIT_MODE = 0x0;
+pub const DWRITE_GRID_FIT_MODE_DISABLED: DWRITE_GRID_FIT_MODE = 0x1;
+pub const DWRITE_GRID_FIT_MODE_ENABLED: DWRITE_GRID_FIT_MODE = 0x2;
+
+interface! {#[uuid(0xefa008f9, 0xf7a1, 0x48bf, 0xb0, 0x5c, 0xf2, 0x24, 0x71, 0x3c, 0xc0, 0xff)]
+interface IDWriteFontFallback(IDWriteFontFallbackVtbl): IUnknown(IUnknownVtbl) {
+    fn MapCharacters(
+        analysisSource: *mut IDWriteTextAnalysisSource,
+        textPosition: UINT32,
+        textLength: UINT32,
+        baseFontCollection: *mut IDWriteFontCollection,
+        baseFamilyName: *mut wchar_t,
+        baseWeight: DWRITE_FONT_WEIGHT,
+        baseStyle: DWRITE_FONT_STYLE,
+        baseStretch: DWRITE_FONT_STRETCH,
+        mappedLength: *mut UINT32,
+        mappedFont: *mut *mut IDWriteFont,
+        scale: *mut FLOAT,
+    ) -> HRESULT,
+}}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteFontFallbackBuilder {}
+
+pub type DWRITE_COLOR_F = D3DCOLORVALUE;
+
+interface! {#[uuid(0xd8b768ff, 0x64bc, 0x4e66, 0x98, 0x2b, 0xec, 0x8e, 0x87, 0xf6, 0x93, 0xf7)]
+interface IDWriteFontFace2(IDWriteFontFace2Vtbl):
+    IDWriteFontFace1(IDWriteFontFace1Vtbl) {
+    fn IsColorFont() -> BOOL,
+    fn GetColorPaletteCount() -> UINT32,
+    fn GetPaletteEntryCount() -> UINT32,
+    fn GetPaletteEntries(
+        colorPaletteIndex: UINT32,
+        firstEntryIndex: UINT32,
+        entryCount: UINT32,
+        paletteEntries: *mut DWRITE_COLOR_F,
+    ) -> HRESULT,
+    fn GetRecommendedRenderingMode(
+        fontEmSize: FLOAT,
+        dpiX: FLOAT,
+        dpiY: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        isSideways: BOOL,
+        outlineThreshold: DWRITE_OUTLINE_THRESHOLD,
+        measuringMode: DWRITE_MEASURING_MODE,
+        renderingParams: *mut IDWriteRenderingParams,
+        renderingMode: *mut DWRITE_RENDERING_MODE,
+        gridFitMode: *mut DWRITE_GRID_FIT_MODE,
+    ) -> HRESULT,
+}}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteColorGlyphRunEnumerator {}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteRenderingParams2 {}
+
+interface! {#[uuid(0x0439fc60, 0xca44, 0x4994, 0x8d, 0xee, 0x3a, 0x9a, 0xf7, 0xb7, 0x32, 0xec)]
+interface IDWriteFactory2(IDWriteFactory2Vtbl): IDWriteFactory1(IDWriteFactory1Vtbl) {
+    fn GetSystemFontFallback(
+        fontFallback: *mut *mut IDWriteFontFallback,
+    ) -> HRESULT,
+    fn CreateFontFallbackBuilder(
+        fontFallbackBuilder: *mut *mut IDWriteFontFallbackBuilder,
+    ) -> HRESULT,
+    fn TranslateColorGlyphRun(
+        baselineOriginX: FLOAT,
+        baselineOriginY: FLOAT,
+        glyphRun: *const DWRITE_GLYPH_RUN,
+        glyphRunDescription: *const DWRITE_GLYPH_RUN_DESCRIPTION,
+        measuringMode: DWRITE_MEASURING_MODE,
+        worldToDeviceTransform: *const DWRITE_MATRIX,
+        colorPaletteIndex: UINT32,
+        colorLayers: *mut *mut IDWriteColorGlyphRunEnumerator,
+    ) -> HRESULT,
+    fn CreateCustomRenderingParams(
+        gamma: FLOAT,
+        enhancedContrast: FLOAT,
+        grayscaleEnhancedContrast: FLOAT,
+        clearTypeLevel: FLOAT,
+        pixelGeometry: DWRITE_PIXEL_GEOMETRY,
+        renderingMode: DWRITE_RENDERING_MODE,
+        gridFitMode: DWRITE_GRID_FIT_MODE,
+        renderingParams: *mut *mut IDWriteRenderingParams2,
+    ) -> HRESULT,
+    fn CreateGlyphRunAnalysis(
+        glyphRun: *const DWRITE_GLYPH_RUN,
+        transform: *const DWRITE_MATRIX,
+        renderingMode: DWRITE_RENDERING_MODE,
+        measuringMode: DWRITE_MEASURING_MODE,
+        gridFitMode: DWRITE_GRID_FIT_MODE,
+        antialiasMode: DWRITE_TEXT_ANTIALIAS_MODE,
+        baselineOriginX: FLOAT,
+        baselineOriginY: FLOAT,
+        glyphRunAnalysis: *mut *mut IDWriteGlyphRunAnalysis,
+    ) -> HRESULT,
+}}
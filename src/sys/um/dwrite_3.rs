@@ -0,0 +1,205 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub use crate::portable_types::DWRITE_FONT_AXIS_VALUE;
+use crate::sys::ctypes::c_void;
+use crate::sys::shared::basetsd::{UINT16, UINT32};
+use crate::sys::shared::minwindef::{BOOL, FLOAT};
+use crate::sys::um::dcommon::{
+    DWRITE_GLYPH_IMAGE_DATA, DWRITE_GLYPH_IMAGE_FORMATS, DWRITE_MEASURING_MODE,
+};
+use crate::sys::um::dwrite::{
+    IDWriteFontFace, IDWriteFontFile, IDWriteLocalizedStrings, IDWriteRenderingParams,
+    DWRITE_FONT_SIMULATIONS, DWRITE_FONT_STRETCH, DWRITE_FONT_STYLE, DWRITE_FONT_WEIGHT,
+    DWRITE_INFORMATIONAL_STRING_ID, DWRITE_MATRIX, DWRITE_OUTLINE_THRESHOLD,
+    DWRITE_RENDERING_MODE_ALIASED, DWRITE_RENDERING_MODE_DEFAULT,
+    DWRITE_RENDERING_MODE_GDI_CLASSIC, DWRITE_RENDERING_MODE_GDI_NATURAL,
+    DWRITE_RENDERING_MODE_NATURAL, DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC,
+    DWRITE_RENDERING_MODE_OUTLINE,
+};
+use crate::sys::um::dwrite_1::DWRITE_PANOSE;
+use crate::sys::um::dwrite_2::{IDWriteFontFace2, IDWriteFontFace2Vtbl, DWRITE_GRID_FIT_MODE};
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::winnt::{HRESULT, WCHAR};
+
+pub type DWRITE_RENDERING_MODE1 = u32;
+pub const DWRITE_RENDERING_MODE1_DEFAULT: DWRITE_RENDERING_MODE1 = DWRITE_RENDERING_MODE_DEFAULT;
+pub const DWRITE_RENDERING_MODE1_ALIASED: DWRITE_RENDERING_MODE1 = DWRITE_RENDERING_MODE_ALIASED;
+pub const DWRITE_RENDERING_MODE1_GDI_CLASSIC: DWRITE_RENDERING_MODE1 =
+    DWRITE_RENDERING_MODE_GDI_CLASSIC;
+pub const DWRITE_RENDERING_MODE1_GDI_NATURAL: DWRITE_RENDERING_MODE1 =
+    DWRITE_RENDERING_MODE_GDI_NATURAL;
+pub const DWRITE_RENDERING_MODE1_NATURAL: DWRITE_RENDERING_MODE1 = DWRITE_RENDERING_MODE_NATURAL;
+pub const DWRITE_RENDERING_MODE1_NATURAL_SYMMETRIC: DWRITE_RENDERING_MODE1 =
+    DWRITE_RENDERING_MODE_NATURAL_SYMMETRIC;
+pub const DWRITE_RENDERING_MODE1_OUTLINE: DWRITE_RENDERING_MODE1 = DWRITE_RENDERING_MODE_OUTLINE;
+pub const DWRITE_RENDERING_MODE1_NATURAL_SYMMETRIC_DOWNSAMPLED: DWRITE_RENDERING_MODE1 =
+    DWRITE_RENDERING_MODE1_OUTLINE + 1;
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteFontFaceReference {}
+
+interface! {#[uuid(0xd37d7598, 0x09be, 0x4222, 0xa2, 0x36, 0x20, 0x81, 0x34, 0x1c, 0xc1, 0xf2)]
+interface IDWriteFontFace3(IDWriteFontFace3Vtbl):
+    IDWriteFontFace2(IDWriteFontFace2Vtbl) {
+    fn GetFontFaceReference(
+        fontFaceReference: *mut *mut IDWriteFontFaceReference,
+    ) -> HRESULT,
+    fn GetPanose(
+        panose: *mut DWRITE_PANOSE,
+    ) -> (),
+    fn GetWeight() -> DWRITE_FONT_WEIGHT,
+    fn GetStretch() -> DWRITE_FONT_STRETCH,
+    fn GetStyle() -> DWRITE_FONT_STYLE,
+    fn GetFamilyNames(
+        names: *mut *mut IDWriteLocalizedStrings,
+    ) -> HRESULT,
+    fn GetFaceNames(
+        names: *mut *mut IDWriteLocalizedStrings,
+    ) -> HRESULT,
+    fn GetInformationalStrings(
+        informationalStringID: DWRITE_INFORMATIONAL_STRING_ID,
+        informationalStrings: *mut *mut IDWriteLocalizedStrings,
+        exists: *mut BOOL,
+    ) -> HRESULT,
+    fn HasCharacter(
+        unicodeValue: UINT32,
+    ) -> BOOL,
+    fn GetRecommendedRenderingMode(
+        fontEmSize: FLOAT,
+        dpiX: FLOAT,
+        dpiY: FLOAT,
+        transform: *const DWRITE_MATRIX,
+        isSideways: BOOL,
+        outlineThreshold: DWRITE_OUTLINE_THRESHOLD,
+        measuringMode: DWRITE_MEASURING_MODE,
+        renderingParams: *mut IDWriteRenderingParams,
+        renderingMode: *mut DWRITE_RENDERING_MODE1,
+        gridFitMode: *mut DWRITE_GRID_FIT_MODE,
+    ) -> HRESULT,
+    fn IsCharacterLocal(
+        unicodeValue: UINT32,
+    ) -> BOOL,
+    fn IsGlyphLocal(
+        glyphId: UINT16,
+    ) -> BOOL,
+    fn AreCharactersLocal(
+        characters: *const WCHAR,
+        characterCount: UINT32,
+        enqueueIfNotLocal: BOOL,
+        isLocal: *mut BOOL,
+    ) -> HRESULT,
+    fn AreGlyphsLocal(
+        glyphIndices: *const UINT16,
+        glyphCount: UINT32,
+        enqueueIfNotLocal: BOOL,
+        isLocal: *mut BOOL,
+    ) -> HRESULT,
+}}
+
+interface! {#[uuid(0x27f2a904, 0x4eb8, 0x441d, 0x96, 0x78, 0x05, 0x63, 0xf5, 0x3e, 0x3e, 0x2f)]
+interface IDWriteFontFace4(IDWriteFontFace4Vtbl): IDWriteFontFace3(IDWriteFontFace3Vtbl) {
+    fn GetGlyphImageFormats_2(
+        glyph: UINT16,
+        ppemFirst: UINT32,
+        ppemLast: UINT32,
+        formats: *mut DWRITE_GLYPH_IMAGE_FORMATS,
+    ) -> HRESULT,
+    fn GetGlyphImageFormats_1() -> DWRITE_GLYPH_IMAGE_FORMATS,
+    fn GetGlyphImageData(
+        glyph: UINT16,
+        ppem: UINT32,
+        format: DWRITE_GLYPH_IMAGE_FORMATS,
+        data: *mut DWRITE_GLYPH_IMAGE_DATA,
+        context: *mut *mut c_void,
+    ) -> HRESULT,
+    fn ReleaseGlyphImageData(
+        context: *mut c_void,
+    ) -> (),
+}}
+
+pub type DWRITE_FONT_AXIS_TAG = u32;
+pub const DWRITE_FONT_AXIS_TAG_WEIGHT: DWRITE_FONT_AXIS_TAG = 0x74686777;
+pub const DWRITE_FONT_AXIS_TAG_WIDTH: DWRITE_FONT_AXIS_TAG = 0x68746477;
+pub const DWRITE_FONT_AXIS_TAG_SLANT: DWRITE_FONT_AXIS_TAG = 0x746e6c73;
+pub const DWRITE_FONT_AXIS_TAG_OPTICAL_SIZE: DWRITE_FONT_AXIS_TAG = 0x7a73706f;
+pub const DWRITE_FONT_AXIS_TAG_ITALIC: DWRITE_FONT_AXIS_TAG = 0x6c617469;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DWRITE_FONT_AXIS_RANGE {
+    pub axisTag: DWRITE_FONT_AXIS_TAG,
+    pub minValue: FLOAT,
+    pub maxValue: FLOAT,
+}
+
+pub type DWRITE_FONT_AXIS_ATTRIBUTES = u32;
+pub const DWRITE_FONT_AXIS_ATTRIBUTES_NONE: DWRITE_FONT_AXIS_ATTRIBUTES = 0;
+pub const DWRITE_FONT_AXIS_ATTRIBUTES_VARIABLE: DWRITE_FONT_AXIS_ATTRIBUTES = 1;
+pub const DWRITE_FONT_AXIS_ATTRIBUTES_HIDDEN: DWRITE_FONT_AXIS_ATTRIBUTES = 2;
+
+interface! {#[uuid(0x98eff3a5, 0xb667, 0x479a, 0xb1, 0x45, 0xe2, 0xfa, 0x5b, 0x9f, 0xdc, 0x29)]
+interface IDWriteFontFace5(IDWriteFontFace5Vtbl): IDWriteFontFace4(IDWriteFontFace4Vtbl) {
+    fn GetFontAxisValueCount() -> UINT32,
+    fn GetFontAxisValues(
+        values: *mut DWRITE_FONT_AXIS_VALUE,
+        valueCount: UINT32,
+    ) -> HRESULT,
+    fn HasVariations() -> BOOL,
+    fn GetFontResource(
+        resource: *mut *mut IDWriteFontResource,
+    ) -> HRESULT,
+    fn Equals(
+        fontFace: *mut IDWriteFontFace,
+    ) -> BOOL,
+}}
+
+/// Not described here; only passed around by pointer.
+pub enum IDWriteFontFaceReference1 {}
+
+interface! {#[uuid(0x1f803a76, 0x6871, 0x48e8, 0x98, 0x7f, 0xb9, 0x75, 0x55, 0x1c, 0x50, 0xf2)]
+interface IDWriteFontResource(IDWriteFontResourceVtbl): IUnknown(IUnknownVtbl) {
+    fn GetFontFile(
+        fontFile: *mut *mut IDWriteFontFile,
+    ) -> HRESULT,
+    fn GetFontFaceIndex() -> UINT32,
+    fn GetFontAxisCount() -> UINT32,
+    fn GetDefaultFontAxisValues(
+        values: *const DWRITE_FONT_AXIS_VALUE,
+        numValues: UINT32,
+    ) -> HRESULT,
+    fn GetFontAxisRanges(
+        ranges: *const DWRITE_FONT_AXIS_RANGE,
+        numRanges: UINT32,
+    ) -> HRESULT,
+    fn GetFontAxisAttributes(
+        axis: UINT32,
+    ) -> DWRITE_FONT_AXIS_ATTRIBUTES,
+    fn GetAxisNames(
+        axis: UINT32,
+        names: *mut *mut IDWriteLocalizedStrings,
+    ) -> HRESULT,
+    fn GetAxisValueNameCount(
+        axis: UINT32,
+    ) -> UINT32,
+    fn GetAxisValueNames(
+        axis: UINT32,
+        axisValue: UINT32,
+        axisRange: *mut DWRITE_FONT_AXIS_RANGE,
+        names: *mut *mut IDWriteLocalizedStrings,
+    ) -> HRESULT,
+    fn HasVariations() -> BOOL,
+    fn CreateFontFace(
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axisValues: *const DWRITE_FONT_AXIS_VALUE,
+        numValues: UINT32,
+        fontFace: *mut *mut IDWriteFontFace5,
+    ) -> HRESULT,
+    fn CreateFontFaceReference(
+        simulations: DWRITE_FONT_SIMULATIONS,
+        axisValues: *const DWRITE_FONT_AXIS_VALUE,
+        numValues: UINT32,
+        reference: *mut *mut IDWriteFontFaceReference1,
+    ) -> HRESULT,
+}}
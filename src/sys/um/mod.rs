@@ -0,0 +1,136 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+pub mod d2d1;
+pub mod dcommon;
+pub mod dwrite;
+pub mod dwrite_1;
+pub mod dwrite_2;
+pub mod dwrite_3;
+
+pub mod errhandlingapi {
+    use crate::sys::shared::minwindef::DWORD;
+
+    /// `ERROR_MOD_NOT_FOUND`, from `LoadLibraryW` not finding `dwrite.dll`.
+    pub unsafe fn GetLastError() -> DWORD {
+        126
+    }
+}
+
+pub mod libloaderapi {
+    use crate::sys::ctypes::c_void;
+    use crate::sys::um::winnt::{LPCSTR, LPCWSTR};
+    use std::ptr;
+
+    pub type HMODULE = *mut c_void;
+    pub type FARPROC = *mut c_void;
+
+    pub unsafe fn LoadLibraryW(_lpFileName: LPCWSTR) -> HMODULE {
+        ptr::null_mut()
+    }
+
+    pub unsafe fn GetProcAddress(_hModule: HMODULE, _lpProcName: LPCSTR) -> FARPROC {
+        ptr::null_mut()
+    }
+}
+
+pub mod unknwnbase {
+    use crate::sys::ctypes::c_void;
+    use crate::sys::shared::guiddef::REFIID;
+    use crate::sys::shared::minwindef::ULONG;
+    use crate::sys::um::winnt::HRESULT;
+
+    interface! {#[uuid(0x00000000, 0x0000, 0x0000, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46)]
+    interface IUnknown(IUnknownVtbl) {
+        fn QueryInterface(
+            riid: REFIID,
+            ppvObject: *mut *mut c_void,
+        ) -> HRESULT,
+        fn AddRef() -> ULONG,
+        fn Release() -> ULONG,
+    }}
+}
+
+pub mod wingdi {
+    use crate::sys::ctypes::{c_int, c_void};
+    use crate::sys::shared::windef::{COLORREF, HDC};
+    use std::ptr;
+
+    use crate::sys::shared::minwindef::{BYTE, LPVOID, UINT, WORD};
+    use crate::sys::um::winnt::{LONG, WCHAR};
+
+    pub const OBJ_BITMAP: UINT = 7;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct BITMAP {
+        pub bmType: LONG,
+        pub bmWidth: LONG,
+        pub bmHeight: LONG,
+        pub bmWidthBytes: LONG,
+        pub bmPlanes: WORD,
+        pub bmBitsPixel: WORD,
+        pub bmBits: LPVOID,
+    }
+
+    pub const LF_FACESIZE: usize = 32;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct LOGFONTW {
+        pub lfHeight: LONG,
+        pub lfWidth: LONG,
+        pub lfEscapement: LONG,
+        pub lfOrientation: LONG,
+        pub lfWeight: LONG,
+        pub lfItalic: BYTE,
+        pub lfUnderline: BYTE,
+        pub lfStrikeOut: BYTE,
+        pub lfCharSet: BYTE,
+        pub lfOutPrecision: BYTE,
+        pub lfClipPrecision: BYTE,
+        pub lfQuality: BYTE,
+        pub lfPitchAndFamily: BYTE,
+        pub lfFaceName: [WCHAR; LF_FACESIZE],
+    }
+
+    pub type HGDIOBJ = *mut c_void;
+
+    pub fn RGB(r: BYTE, g: BYTE, b: BYTE) -> COLORREF {
+        r as COLORREF | ((g as COLORREF) << 8) | ((b as COLORREF) << 16)
+    }
+
+    pub unsafe fn GetCurrentObject(_hdc: HDC, _tp: UINT) -> HGDIOBJ {
+        ptr::null_mut()
+    }
+
+    pub unsafe fn GetObjectW(_h: HGDIOBJ, _c: c_int, _pv: LPVOID) -> c_int {
+        0
+    }
+}
+
+pub mod winnls {
+    use crate::sys::ctypes::c_int;
+    use crate::sys::um::winnt::LPWSTR;
+
+    /// Fails, so the user's locale never matches.
+    pub unsafe fn GetUserDefaultLocaleName(_lpLocaleName: LPWSTR, _cchLocaleName: c_int) -> c_int {
+        0
+    }
+}
+
+pub mod winnt {
+    pub use crate::portable_types::HRESULT;
+    use crate::sys::ctypes::{c_char, c_long, wchar_t};
+
+    pub type CHAR = c_char;
+
+    pub type LONG = c_long;
+
+    pub type WCHAR = wchar_t;
+
+    pub type LPCSTR = *const CHAR;
+    pub type LPCWSTR = *const WCHAR;
+    pub type LPWSTR = *mut WCHAR;
+}
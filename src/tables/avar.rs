@@ -0,0 +1,58 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `avar` table: piecewise-linear adjustments to normalized axis
+//! coordinates.
+
+use super::Stream;
+
+/// One segment map per `fvar` axis, each a list of `(from, to)` points in
+/// increasing order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AvarTable {
+    pub segment_maps: Vec<Vec<(f32, f32)>>,
+}
+
+impl AvarTable {
+    pub fn parse(data: &[u8]) -> Option<AvarTable> {
+        let mut s = Stream::new(data);
+        s.skip(6)?;
+        let axis_count = s.read_u16()?;
+        let mut segment_maps = Vec::with_capacity(axis_count as usize);
+        for _ in 0..axis_count {
+            let count = s.read_u16()?;
+            let mut map = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                map.push((s.read_f2dot14()?, s.read_f2dot14()?));
+            }
+            segment_maps.push(map);
+        }
+        Some(AvarTable { segment_maps })
+    }
+
+    /// Adjusts the normalized coordinate `value` of axis `axis_index`.
+    pub fn map(&self, axis_index: usize, value: f32) -> f32 {
+        let map = match self.segment_maps.get(axis_index) {
+            Some(map) if !map.is_empty() => map,
+            _ => return value,
+        };
+        let (first, last) = (map[0], map[map.len() - 1]);
+        if value <= first.0 {
+            return first.1;
+        }
+        if value >= last.0 {
+            return last.1;
+        }
+        let end = map
+            .iter()
+            .position(|&(from, _)| from > value)
+            .unwrap_or(map.len() - 1);
+        let (from0, to0) = map[end - 1];
+        let (from1, to1) = map[end];
+        if from1 == from0 {
+            return to0;
+        }
+        to0 + (value - from0) * (to1 - to0) / (from1 - from0)
+    }
+}
@@ -0,0 +1,563 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Embedded bitmaps from the `sbix`, `CBDT`/`CBLC` and `EBDT`/`EBLC`
+//! tables.
+//!
+//! Color bitmaps usually hold PNG images, which are returned as they are.
+//! Uncompressed bitmaps are unpacked to a byte per pixel, or four for 32-bit
+//! color, and composite bitmaps are assembled from their components.
+
+use super::{read_u32, Stream};
+use crate::Tag;
+
+/// How deeply composite and `dupe` bitmaps may refer to other glyphs.
+const MAX_COMPONENT_DEPTH: u8 = 8;
+
+/// One size of embedded bitmaps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BitmapStrike {
+    /// `sbix`, `CBLC` or `EBLC`.
+    pub table: Tag,
+    pub ppem: u16,
+    /// The resolution the strike was designed for, which is always 72 for
+    /// `CBLC` and `EBLC`.
+    pub ppi: u16,
+    /// Bits per pixel, which is 32 for color bitmaps.
+    pub bit_depth: u8,
+}
+
+/// The pixels of an embedded bitmap. Uncompressed pixels are in rows from
+/// top to bottom with no padding.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BitmapData {
+    Png(Vec<u8>),
+    Jpeg(Vec<u8>),
+    Tiff(Vec<u8>),
+    /// Coverage from 0 to 255, a byte per pixel.
+    Mask(Vec<u8>),
+    /// Premultiplied blue, green, red and alpha, four bytes per pixel.
+    Bgra(Vec<u8>),
+}
+
+/// A glyph's embedded bitmap and where to draw it, in pixels of its strike.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GlyphBitmap {
+    pub strike: BitmapStrike,
+    pub data: BitmapData,
+    /// The size of the image, or zero for TIFF images, whose size isn't
+    /// read.
+    pub width: u32,
+    pub height: u32,
+    /// How far the left edge is right of the glyph origin.
+    pub left: i32,
+    /// How far the top edge is above the baseline.
+    pub top: i32,
+    /// The horizontal advance, which `sbix` bitmaps leave to `hmtx`.
+    pub advance: Option<u16>,
+}
+
+/// Reads the size of a PNG or baseline JPEG image.
+fn image_size(data: &BitmapData) -> Option<(u32, u32)> {
+    match data {
+        BitmapData::Png(png) => {
+            if png.get(12..16)? != b"IHDR" {
+                return None;
+            }
+            Some((read_u32(png, 16)?, read_u32(png, 20)?))
+        }
+        BitmapData::Jpeg(jpeg) => {
+            let mut s = Stream::new_at(jpeg, 2)?;
+            loop {
+                if s.read_u8()? != 0xff {
+                    return None;
+                }
+                let marker = s.read_u8()?;
+                let length = s.read_u16()? as usize;
+                // Start of frame markers, other than DHT, JPG and DAC.
+                if (0xc0..=0xcf).contains(&marker) && ![0xc4, 0xc8, 0xcc].contains(&marker) {
+                    s.skip(1)?;
+                    let height = s.read_u16()? as u32;
+                    let width = s.read_u16()? as u32;
+                    return Some((width, height));
+                }
+                s.skip(length.checked_sub(2)?)?;
+            }
+        }
+        _ => Some((0, 0)),
+    }
+}
+
+/// Orders strikes by how well they suit `ppem`: the smallest that is at
+/// least as big, then the others from biggest to smallest.
+fn preference(strike: &BitmapStrike, ppem: f32) -> (bool, i32) {
+    if (strike.ppem as f32) < ppem {
+        (true, -(strike.ppem as i32))
+    } else {
+        (false, strike.ppem as i32)
+    }
+}
+
+#[derive(Clone)]
+pub struct SbixTable {
+    data: Vec<u8>,
+    strikes: Vec<(BitmapStrike, usize)>,
+    num_glyphs: u16,
+}
+
+impl SbixTable {
+    /// Parses an `sbix` table, which needs the glyph count from `maxp`.
+    pub fn parse(data: &[u8], num_glyphs: u16) -> Option<SbixTable> {
+        let mut s = Stream::new(data);
+        s.skip(4)?;
+        let count = s.read_u32()?;
+        let mut strikes = Vec::with_capacity(count.min(256) as usize);
+        for _ in 0..count {
+            let offset = s.read_u32()? as usize;
+            let mut strike = Stream::new_at(data, offset)?;
+            let ppem = strike.read_u16()?;
+            let ppi = strike.read_u16()?;
+            strike.skip(4 * (num_glyphs as usize + 1))?;
+            let strike = BitmapStrike {
+                table: Tag::SBIX,
+                ppem,
+                ppi,
+                bit_depth: 32,
+            };
+            strikes.push((strike, offset));
+        }
+        Some(SbixTable {
+            data: data.to_vec(),
+            strikes,
+            num_glyphs,
+        })
+    }
+
+    pub fn strikes(&self) -> Vec<BitmapStrike> {
+        self.strikes.iter().map(|&(strike, _)| strike).collect()
+    }
+
+    /// Returns the bitmap of `glyph_id` in strike `index`, or `None` if the
+    /// strike has none or its graphic type isn't an image format.
+    pub fn glyph_bitmap(&self, glyph_id: u16, index: usize) -> Option<GlyphBitmap> {
+        self.glyph_bitmap_at_depth(glyph_id, index, 0)
+    }
+
+    fn glyph_bitmap_at_depth(&self, glyph_id: u16, index: usize, depth: u8) -> Option<GlyphBitmap> {
+        if glyph_id >= self.num_glyphs || depth > MAX_COMPONENT_DEPTH {
+            return None;
+        }
+        let (strike, offset) = *self.strikes.get(index)?;
+        let offsets = offset + 4 + 4 * glyph_id as usize;
+        let start = offset + read_u32(&self.data, offsets)? as usize;
+        let end = offset + read_u32(&self.data, offsets + 4)? as usize;
+        let mut s = Stream::new(self.data.get(start..end)?);
+        let origin_x = s.read_i16()?;
+        let origin_y = s.read_i16()?;
+        let graphic_type = s.read_tag()?;
+        let image = s.read_bytes(end - start - 8)?.to_vec();
+        let data = match &graphic_type.0 {
+            b"png " => BitmapData::Png(image),
+            b"jpg " => BitmapData::Jpeg(image),
+            b"tiff" => BitmapData::Tiff(image),
+            b"dupe" => {
+                let glyph_id = super::read_u16(&image, 0)?;
+                return self.glyph_bitmap_at_depth(glyph_id, index, depth + 1);
+            }
+            _ => return None,
+        };
+        let (width, height) = image_size(&data)?;
+        Some(GlyphBitmap {
+            strike,
+            data,
+            width,
+            height,
+            left: origin_x as i32,
+            // The origin is the bottom left corner of the image.
+            top: origin_y as i32 + height as i32,
+            advance: None,
+        })
+    }
+}
+
+/// The horizontal metrics of an `EBDT` or `CBDT` bitmap.
+#[derive(Clone, Copy, Debug)]
+struct Metrics {
+    height: u8,
+    width: u8,
+    bearing_x: i8,
+    bearing_y: i8,
+    advance: u8,
+}
+
+impl Metrics {
+    fn read(s: &mut Stream, big: bool) -> Option<Metrics> {
+        let metrics = Metrics {
+            height: s.read_u8()?,
+            width: s.read_u8()?,
+            bearing_x: s.read_u8()? as i8,
+            bearing_y: s.read_u8()? as i8,
+            advance: s.read_u8()?,
+        };
+        if big {
+            // The vertical metrics.
+            s.skip(3)?;
+        }
+        Some(metrics)
+    }
+}
+
+/// Where a glyph's image is, as found through an index subtable.
+struct ImageLocation {
+    format: u16,
+    data: (usize, usize),
+    /// The metrics that index formats 2 and 5 hold for every glyph.
+    metrics: Option<Metrics>,
+}
+
+/// The `CBLC` and `CBDT` tables, or the `EBLC` and `EBDT` tables, which
+/// share their layout.
+#[derive(Clone)]
+pub struct EblcTable {
+    location: Vec<u8>,
+    data: Vec<u8>,
+    /// Each strike and the offset of its index subtable array.
+    strikes: Vec<(BitmapStrike, usize, u32)>,
+}
+
+impl EblcTable {
+    /// Parses a `CBLC` or `EBLC` table and its `CBDT` or `EBDT` data.
+    pub fn parse(location: &[u8], data: &[u8]) -> Option<EblcTable> {
+        let mut s = Stream::new(location);
+        let table = match s.read_u16()? {
+            2 => Tag::EBLC,
+            3 => Tag::CBLC,
+            _ => return None,
+        };
+        s.skip(2)?;
+        let count = s.read_u32()?;
+        let mut strikes = Vec::with_capacity(count.min(256) as usize);
+        for _ in 0..count {
+            let array_offset = s.read_u32()? as usize;
+            s.skip(4)?;
+            let subtable_count = s.read_u32()?;
+            // The color reference, line metrics and glyph range.
+            s.skip(4 + 24 + 4)?;
+            s.skip(1)?;
+            let ppem = s.read_u8()? as u16;
+            let bit_depth = s.read_u8()?;
+            s.skip(1)?;
+            let strike = BitmapStrike {
+                table,
+                ppem,
+                ppi: 72,
+                bit_depth,
+            };
+            strikes.push((strike, array_offset, subtable_count));
+        }
+        Some(EblcTable {
+            location: location.to_vec(),
+            data: data.to_vec(),
+            strikes,
+        })
+    }
+
+    pub fn strikes(&self) -> Vec<BitmapStrike> {
+        self.strikes.iter().map(|&(strike, _, _)| strike).collect()
+    }
+
+    /// Returns the bitmap of `glyph_id` in strike `index`, or `None` if the
+    /// strike has none or it uses the compressed format 4.
+    pub fn glyph_bitmap(&self, glyph_id: u16, index: usize) -> Option<GlyphBitmap> {
+        self.glyph_bitmap_at_depth(glyph_id, index, 0)
+    }
+
+    fn glyph_bitmap_at_depth(&self, glyph_id: u16, index: usize, depth: u8) -> Option<GlyphBitmap> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return None;
+        }
+        let strike = self.strikes.get(index)?.0;
+        let location = self.locate(glyph_id, index)?;
+        let mut s = Stream::new(self.data.get(location.data.0..location.data.1)?);
+        let bit_aligned = matches!(location.format, 2 | 5 | 7);
+        let metrics = match location.format {
+            1 | 2 | 8 | 17 => Metrics::read(&mut s, false)?,
+            6 | 7 | 9 | 18 => Metrics::read(&mut s, true)?,
+            5 | 19 => location.metrics?,
+            _ => return None,
+        };
+        let (width, height) = (metrics.width as u32, metrics.height as u32);
+        let data = match location.format {
+            17..=19 => {
+                let length = s.read_u32()? as usize;
+                BitmapData::Png(s.read_bytes(length)?.to_vec())
+            }
+            8 | 9 => {
+                if location.format == 8 {
+                    s.skip(1)?;
+                }
+                let count = s.read_u16()?;
+                let mut canvas = Canvas::new(width, height, strike.bit_depth);
+                for _ in 0..count {
+                    let component = s.read_u16()?;
+                    let x = s.read_u8()? as i8 as i32;
+                    let y = s.read_u8()? as i8 as i32;
+                    let bitmap = self.glyph_bitmap_at_depth(component, index, depth + 1)?;
+                    canvas.draw(&bitmap, x, y)?;
+                }
+                canvas.into_data()
+            }
+            _ => {
+                let pixels = unpack(s, width, height, strike.bit_depth, bit_aligned)?;
+                if strike.bit_depth == 32 {
+                    BitmapData::Bgra(pixels)
+                } else {
+                    BitmapData::Mask(pixels)
+                }
+            }
+        };
+        Some(GlyphBitmap {
+            strike,
+            data,
+            width,
+            height,
+            left: metrics.bearing_x as i32,
+            top: metrics.bearing_y as i32,
+            advance: Some(metrics.advance as u16),
+        })
+    }
+
+    fn locate(&self, glyph_id: u16, index: usize) -> Option<ImageLocation> {
+        let (_, array_offset, subtable_count) = *self.strikes.get(index)?;
+        let mut array = Stream::new_at(&self.location, array_offset)?;
+        for _ in 0..subtable_count {
+            let first = array.read_u16()?;
+            let last = array.read_u16()?;
+            let offset = array.read_u32()? as usize;
+            if glyph_id < first || glyph_id > last {
+                continue;
+            }
+            let mut s = Stream::new_at(&self.location, array_offset + offset)?;
+            let index_format = s.read_u16()?;
+            let format = s.read_u16()?;
+            let image_offset = s.read_u32()? as usize;
+            let i = (glyph_id - first) as usize;
+            let mut metrics = None;
+            let (start, end) = match index_format {
+                1 | 3 => {
+                    let size = if index_format == 1 { 4 } else { 2 };
+                    s.skip(size * i)?;
+                    let mut read = || match size {
+                        4 => s.read_u32().map(|offset| offset as usize),
+                        _ => s.read_u16().map(|offset| offset as usize),
+                    };
+                    (read()?, read()?)
+                }
+                2 => {
+                    let size = s.read_u32()? as usize;
+                    metrics = Some(Metrics::read(&mut s, true)?);
+                    (size * i, size * (i + 1))
+                }
+                4 => {
+                    let count = s.read_u32()?;
+                    let mut start = None;
+                    for _ in 0..count {
+                        let id = s.read_u16()?;
+                        let offset = s.read_u16()? as usize;
+                        if id == glyph_id {
+                            start = Some(offset);
+                            break;
+                        }
+                    }
+                    // The next entry's offset ends the glyph's data.
+                    let start = start?;
+                    s.skip(2)?;
+                    (start, s.read_u16()? as usize)
+                }
+                5 => {
+                    let size = s.read_u32()? as usize;
+                    metrics = Some(Metrics::read(&mut s, true)?);
+                    let count = s.read_u32()? as usize;
+                    let ids = s.read_bytes(2 * count)?;
+                    let i = ids
+                        .chunks(2)
+                        .position(|id| u16::from_be_bytes([id[0], id[1]]) == glyph_id)?;
+                    (size * i, size * (i + 1))
+                }
+                _ => return None,
+            };
+            // Glyphs without bitmaps have empty ranges.
+            if start >= end {
+                return None;
+            }
+            return Some(ImageLocation {
+                format,
+                data: (image_offset + start, image_offset + end),
+                metrics,
+            });
+        }
+        None
+    }
+}
+
+/// Unpacks rows of 1, 2, 4, 8 or 32-bit pixels, which may run on without
+/// padding to whole bytes if `bit_aligned` is set.
+fn unpack(
+    mut s: Stream,
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    bit_aligned: bool,
+) -> Option<Vec<u8>> {
+    let (width, height) = (width as usize, height as usize);
+    if bit_depth == 32 {
+        return s
+            .read_bytes(4 * width * height)
+            .map(|pixels| pixels.to_vec());
+    }
+    if ![1, 2, 4, 8].contains(&bit_depth) {
+        return None;
+    }
+    let depth = bit_depth as usize;
+    let row_bits = if bit_aligned {
+        width * depth
+    } else {
+        (width * depth).div_ceil(8) * 8
+    };
+    let bytes = s.read_bytes((row_bits * height).div_ceil(8))?;
+    let max = (1u32 << depth) - 1;
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let bit = y * row_bits + x * depth;
+            let byte = bytes[bit / 8] as u32;
+            let value = (byte >> (8 - depth - bit % 8)) & max;
+            pixels.push((value * 255 / max) as u8);
+        }
+    }
+    Some(pixels)
+}
+
+/// The image of a composite bitmap, which its components are drawn into.
+struct Canvas {
+    width: u32,
+    height: u32,
+    bgra: bool,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, bit_depth: u8) -> Canvas {
+        let bgra = bit_depth == 32;
+        let size = if bgra { 4 } else { 1 };
+        Canvas {
+            width,
+            height,
+            bgra,
+            pixels: vec![0; size * width as usize * height as usize],
+        }
+    }
+
+    /// Draws a component with its top left corner at `(x, y)`, failing for
+    /// images that would need decoding.
+    fn draw(&mut self, bitmap: &GlyphBitmap, x: i32, y: i32) -> Option<()> {
+        let (pixels, size) = match (&bitmap.data, self.bgra) {
+            (BitmapData::Mask(pixels), false) => (pixels, 1),
+            (BitmapData::Bgra(pixels), true) => (pixels, 4),
+            _ => return None,
+        };
+        for row in 0..bitmap.height as i32 {
+            for column in 0..bitmap.width as i32 {
+                let (to_x, to_y) = (x + column, y + row);
+                if to_x < 0 || to_y < 0 || to_x >= self.width as i32 || to_y >= self.height as i32 {
+                    continue;
+                }
+                let from = size * (row * bitmap.width as i32 + column) as usize;
+                let to = size * (to_y * self.width as i32 + to_x) as usize;
+                if size == 1 {
+                    self.pixels[to] = self.pixels[to].max(pixels[from]);
+                } else {
+                    // Source over, with premultiplied alpha.
+                    let alpha = pixels[from + 3] as u32;
+                    for channel in 0..4 {
+                        let below = self.pixels[to + channel] as u32 * (255 - alpha) / 255;
+                        self.pixels[to + channel] = (pixels[from + channel] as u32 + below) as u8;
+                    }
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn into_data(self) -> BitmapData {
+        if self.bgra {
+            BitmapData::Bgra(self.pixels)
+        } else {
+            BitmapData::Mask(self.pixels)
+        }
+    }
+}
+
+/// Every source of embedded bitmaps in a face.
+#[derive(Clone, Default)]
+pub struct BitmapTables {
+    pub sbix: Option<SbixTable>,
+    pub cblc: Option<EblcTable>,
+    pub eblc: Option<EblcTable>,
+}
+
+impl BitmapTables {
+    /// Parses whichever of the tables the face has, skipping malformed ones.
+    pub fn parse(
+        sbix: Option<&[u8]>,
+        cblc: Option<&[u8]>,
+        cbdt: Option<&[u8]>,
+        eblc: Option<&[u8]>,
+        ebdt: Option<&[u8]>,
+        num_glyphs: u16,
+    ) -> BitmapTables {
+        let eblc_table =
+            |location: Option<&[u8]>, data: Option<&[u8]>| EblcTable::parse(location?, data?);
+        BitmapTables {
+            sbix: sbix.and_then(|sbix| SbixTable::parse(sbix, num_glyphs)),
+            cblc: eblc_table(cblc, cbdt),
+            eblc: eblc_table(eblc, ebdt),
+        }
+    }
+
+    /// Lists the strikes of `sbix`, then `CBLC`, then `EBLC`.
+    pub fn strikes(&self) -> Vec<BitmapStrike> {
+        let mut strikes = vec![];
+        strikes.extend(self.sbix.iter().flat_map(|sbix| sbix.strikes()));
+        for table in self.cblc.iter().chain(&self.eblc) {
+            strikes.extend(table.strikes());
+        }
+        strikes
+    }
+
+    /// Returns the bitmap of `glyph_id` from the strike that best suits
+    /// `ppem`: the smallest at least that big, or else the biggest. Color
+    /// tables are searched first, and strikes without the glyph are
+    /// skipped.
+    pub fn glyph_bitmap(&self, glyph_id: u16, ppem: f32) -> Option<GlyphBitmap> {
+        let best = |strikes: Vec<BitmapStrike>| {
+            let mut indices: Vec<usize> = (0..strikes.len()).collect();
+            indices.sort_by_key(|&index| preference(&strikes[index], ppem));
+            indices
+        };
+        if let Some(ref sbix) = self.sbix {
+            let bitmap = best(sbix.strikes())
+                .into_iter()
+                .find_map(|index| sbix.glyph_bitmap(glyph_id, index));
+            if bitmap.is_some() {
+                return bitmap;
+            }
+        }
+        self.cblc.iter().chain(&self.eblc).find_map(|table| {
+            best(table.strikes())
+                .into_iter()
+                .find_map(|index| table.glyph_bitmap(glyph_id, index))
+        })
+    }
+}
@@ -0,0 +1,1089 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! PostScript outlines from the `CFF ` and `CFF2` tables.
+//!
+//! [`CffTable::outline`] runs a glyph's Type 2 or CFF2 charstring and feeds
+//! the result to an [`OutlineBuilder`] the way
+//! `IDWriteFontFace::GetGlyphRunOutline` does for a single glyph at the
+//! origin. Hints are skipped, and the `blend` operator of variable `CFF2`
+//! fonts is applied at the given normalized coordinates.
+
+use std::convert::TryFrom;
+
+use super::var_store::ItemVariationStore;
+use super::Stream;
+use crate::outline_builder::OutlineBuilder;
+
+const TOP_DICT_CHARSET: u16 = 15;
+const TOP_DICT_CHAR_STRINGS: u16 = 17;
+const TOP_DICT_PRIVATE: u16 = 18;
+const TOP_DICT_VSTORE: u16 = 24;
+const TOP_DICT_CHARSTRING_TYPE: u16 = 1206;
+const TOP_DICT_ROS: u16 = 1230;
+const TOP_DICT_FD_ARRAY: u16 = 1236;
+const TOP_DICT_FD_SELECT: u16 = 1237;
+
+const PRIVATE_DICT_SUBRS: u16 = 19;
+const PRIVATE_DICT_VSINDEX: u16 = 22;
+
+/// The deepest nesting of subroutine calls the specification allows.
+const MAX_CALL_DEPTH: u8 = 10;
+
+/// The Standard Encoding's string ID for each character code, which `seac`
+/// style `endchar` accents refer to.
+#[rustfmt::skip]
+const STANDARD_ENCODING: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
+    33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48,
+    49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64,
+    65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80,
+    81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110,
+    0, 111, 112, 113, 114, 0, 115, 116, 117, 118, 119, 120, 121, 122, 0, 123,
+    0, 124, 125, 126, 127, 128, 129, 130, 131, 0, 132, 133, 0, 134, 135, 136,
+    137, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 138, 0, 139, 0, 0, 0, 0, 140, 141, 142, 143, 0, 0, 0, 0,
+    0, 144, 0, 0, 0, 145, 0, 0, 146, 147, 148, 149, 0, 0, 0, 0,
+];
+
+/// The names of string IDs 0 to 390, which fonts don't store.
+#[rustfmt::skip]
+const STANDARD_STRINGS: [&str; 391] = [
+    ".notdef", "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand",
+    "quoteright", "parenleft", "parenright", "asterisk", "plus", "comma", "hyphen", "period",
+    "slash", "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "colon", "semicolon", "less", "equal", "greater", "question", "at", "A", "B", "C", "D", "E",
+    "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X",
+    "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum", "underscore", "quoteleft",
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s",
+    "t", "u", "v", "w", "x", "y", "z", "braceleft", "bar", "braceright", "asciitilde", "exclamdown",
+    "cent", "sterling", "fraction", "yen", "florin", "section", "currency", "quotesingle",
+    "quotedblleft", "guillemotleft", "guilsinglleft", "guilsinglright", "fi", "fl", "endash",
+    "dagger", "daggerdbl", "periodcentered", "paragraph", "bullet", "quotesinglbase",
+    "quotedblbase", "quotedblright", "guillemotright", "ellipsis", "perthousand", "questiondown",
+    "grave", "acute", "circumflex", "tilde", "macron", "breve", "dotaccent", "dieresis", "ring",
+    "cedilla", "hungarumlaut", "ogonek", "caron", "emdash", "AE", "ordfeminine", "Lslash", "Oslash",
+    "OE", "ordmasculine", "ae", "dotlessi", "lslash", "oslash", "oe", "germandbls", "onesuperior",
+    "logicalnot", "mu", "trademark", "Eth", "onehalf", "plusminus", "Thorn", "onequarter", "divide",
+    "brokenbar", "degree", "thorn", "threequarters", "twosuperior", "registered", "minus", "eth",
+    "multiply", "threesuperior", "copyright", "Aacute", "Acircumflex", "Adieresis", "Agrave",
+    "Aring", "Atilde", "Ccedilla", "Eacute", "Ecircumflex", "Edieresis", "Egrave", "Iacute",
+    "Icircumflex", "Idieresis", "Igrave", "Ntilde", "Oacute", "Ocircumflex", "Odieresis", "Ograve",
+    "Otilde", "Scaron", "Uacute", "Ucircumflex", "Udieresis", "Ugrave", "Yacute", "Ydieresis",
+    "Zcaron", "aacute", "acircumflex", "adieresis", "agrave", "aring", "atilde", "ccedilla",
+    "eacute", "ecircumflex", "edieresis", "egrave", "iacute", "icircumflex", "idieresis", "igrave",
+    "ntilde", "oacute", "ocircumflex", "odieresis", "ograve", "otilde", "scaron", "uacute",
+    "ucircumflex", "udieresis", "ugrave", "yacute", "ydieresis", "zcaron", "exclamsmall",
+    "Hungarumlautsmall", "dollaroldstyle", "dollarsuperior", "ampersandsmall", "Acutesmall",
+    "parenleftsuperior", "parenrightsuperior", "twodotenleader", "onedotenleader", "zerooldstyle",
+    "oneoldstyle", "twooldstyle", "threeoldstyle", "fouroldstyle", "fiveoldstyle", "sixoldstyle",
+    "sevenoldstyle", "eightoldstyle", "nineoldstyle", "commasuperior", "threequartersemdash",
+    "periodsuperior", "questionsmall", "asuperior", "bsuperior", "centsuperior", "dsuperior",
+    "esuperior", "isuperior", "lsuperior", "msuperior", "nsuperior", "osuperior", "rsuperior",
+    "ssuperior", "tsuperior", "ff", "ffi", "ffl", "parenleftinferior", "parenrightinferior",
+    "Circumflexsmall", "hyphensuperior", "Gravesmall", "Asmall", "Bsmall", "Csmall", "Dsmall",
+    "Esmall", "Fsmall", "Gsmall", "Hsmall", "Ismall", "Jsmall", "Ksmall", "Lsmall", "Msmall",
+    "Nsmall", "Osmall", "Psmall", "Qsmall", "Rsmall", "Ssmall", "Tsmall", "Usmall", "Vsmall",
+    "Wsmall", "Xsmall", "Ysmall", "Zsmall", "colonmonetary", "onefitted", "rupiah", "Tildesmall",
+    "exclamdownsmall", "centoldstyle", "Lslashsmall", "Scaronsmall", "Zcaronsmall", "Dieresissmall",
+    "Brevesmall", "Caronsmall", "Dotaccentsmall", "Macronsmall", "figuredash", "hypheninferior",
+    "Ogoneksmall", "Ringsmall", "Cedillasmall", "questiondownsmall", "oneeighth", "threeeighths",
+    "fiveeighths", "seveneighths", "onethird", "twothirds", "zerosuperior", "foursuperior",
+    "fivesuperior", "sixsuperior", "sevensuperior", "eightsuperior", "ninesuperior", "zeroinferior",
+    "oneinferior", "twoinferior", "threeinferior", "fourinferior", "fiveinferior", "sixinferior",
+    "seveninferior", "eightinferior", "nineinferior", "centinferior", "dollarinferior",
+    "periodinferior", "commainferior", "Agravesmall", "Aacutesmall", "Acircumflexsmall",
+    "Atildesmall", "Adieresissmall", "Aringsmall", "AEsmall", "Ccedillasmall", "Egravesmall",
+    "Eacutesmall", "Ecircumflexsmall", "Edieresissmall", "Igravesmall", "Iacutesmall",
+    "Icircumflexsmall", "Idieresissmall", "Ethsmall", "Ntildesmall", "Ogravesmall", "Oacutesmall",
+    "Ocircumflexsmall", "Otildesmall", "Odieresissmall", "OEsmall", "Oslashsmall", "Ugravesmall",
+    "Uacutesmall", "Ucircumflexsmall", "Udieresissmall", "Yacutesmall", "Thornsmall",
+    "Ydieresissmall", "001.000", "001.001", "001.002", "001.003", "Black", "Bold", "Book", "Light",
+    "Medium", "Regular", "Roman", "Semibold",
+];
+
+/// The string ID of each glyph in the predefined Expert charset.
+#[rustfmt::skip]
+const EXPERT_CHARSET: [u16; 166] = [
+    0, 1, 229, 230, 231, 232, 233, 234, 235, 236, 237, 238, 13, 14, 15, 99, 239, 240, 241, 242, 243,
+    244, 245, 246, 247, 248, 27, 28, 249, 250, 251, 252, 253, 254, 255, 256, 257, 258, 259, 260,
+    261, 262, 263, 264, 265, 266, 109, 110, 267, 268, 269, 270, 271, 272, 273, 274, 275, 276, 277,
+    278, 279, 280, 281, 282, 283, 284, 285, 286, 287, 288, 289, 290, 291, 292, 293, 294, 295, 296,
+    297, 298, 299, 300, 301, 302, 303, 304, 305, 306, 307, 308, 309, 310, 311, 312, 313, 314, 315,
+    316, 317, 318, 158, 155, 163, 319, 320, 321, 322, 323, 324, 325, 326, 150, 164, 169, 327, 328,
+    329, 330, 331, 332, 333, 334, 335, 336, 337, 338, 339, 340, 341, 342, 343, 344, 345, 346, 347,
+    348, 349, 350, 351, 352, 353, 354, 355, 356, 357, 358, 359, 360, 361, 362, 363, 364, 365, 366,
+    367, 368, 369, 370, 371, 372, 373, 374, 375, 376, 377, 378,
+];
+
+/// The string ID of each glyph in the predefined Expert Subset charset.
+#[rustfmt::skip]
+const EXPERT_SUBSET_CHARSET: [u16; 87] = [
+    0, 1, 231, 232, 235, 236, 237, 238, 13, 14, 15, 99, 239, 240, 241, 242, 243, 244, 245, 246, 247,
+    248, 27, 28, 249, 250, 251, 253, 254, 255, 256, 257, 258, 259, 260, 261, 262, 263, 264, 265,
+    266, 109, 110, 267, 268, 269, 270, 272, 300, 301, 302, 305, 314, 315, 158, 155, 163, 320, 321,
+    322, 323, 324, 325, 326, 150, 164, 169, 327, 328, 329, 330, 331, 332, 333, 334, 335, 336, 337,
+    338, 339, 340, 341, 342, 343, 344, 345, 346,
+];
+
+/// An INDEX: a list of byte strings with 1-based offsets.
+#[derive(Clone, Copy, Default)]
+struct Index<'a> {
+    offsets: &'a [u8],
+    offset_size: usize,
+    data: &'a [u8],
+}
+
+impl<'a> Index<'a> {
+    /// Reads an INDEX, whose count is 32 bits wide in `CFF2`.
+    fn read(s: &mut Stream<'a>, cff2: bool) -> Option<Index<'a>> {
+        let count = if cff2 {
+            s.read_u32()? as usize
+        } else {
+            s.read_u16()? as usize
+        };
+        if count == 0 {
+            return Some(Index::default());
+        }
+        let offset_size = s.read_u8()? as usize;
+        if !(1..=4).contains(&offset_size) {
+            return None;
+        }
+        let offsets = s.read_bytes(count.checked_add(1)? * offset_size)?;
+        let mut index = Index {
+            offsets,
+            offset_size,
+            data: &[],
+        };
+        let data_len = index.offset(count)?.checked_sub(1)?;
+        index.data = s.read_bytes(data_len)?;
+        Some(index)
+    }
+
+    fn len(&self) -> usize {
+        match self.offset_size {
+            0 => 0,
+            size => self.offsets.len() / size - 1,
+        }
+    }
+
+    fn offset(&self, i: usize) -> Option<usize> {
+        let start = i * self.offset_size;
+        let bytes = self.offsets.get(start..start + self.offset_size)?;
+        Some(bytes.iter().fold(0, |value, &b| value << 8 | b as usize))
+    }
+
+    fn get(&self, i: usize) -> Option<&'a [u8]> {
+        if i >= self.len() {
+            return None;
+        }
+        let start = self.offset(i)?.checked_sub(1)?;
+        let end = self.offset(i + 1)?.checked_sub(1)?;
+        self.data.get(start..end)
+    }
+}
+
+/// Calls `f` with each operator of a DICT and its operands. Two-byte
+/// operators are numbered `1200 + second byte`.
+fn parse_dict<F>(data: &[u8], mut f: F) -> Option<()>
+where
+    F: FnMut(u16, &[f64]) -> Option<()>,
+{
+    let mut s = Stream::new(data);
+    let mut operands = Vec::new();
+    while let Some(b0) = s.read_u8() {
+        match b0 {
+            12 => {
+                f(1200 + s.read_u8()? as u16, &operands)?;
+                operands.clear();
+            }
+            // The values of blended `CFF2` Private DICT entries only matter
+            // for hinting, so they are dropped along with their deltas.
+            23 => operands.clear(),
+            0..=21 | 22 | 24 => {
+                f(b0 as u16, &operands)?;
+                operands.clear();
+            }
+            28 => operands.push(s.read_i16()? as f64),
+            29 => operands.push(s.read_u32()? as i32 as f64),
+            30 => operands.push(read_real(&mut s)?),
+            32..=254 => operands.push(read_small_integer(&mut s, b0)? as f64),
+            _ => return None,
+        }
+    }
+    Some(())
+}
+
+/// Reads the one- and two-byte integers shared by DICTs and charstrings.
+fn read_small_integer(s: &mut Stream, b0: u8) -> Option<i32> {
+    let b0 = b0 as i32;
+    Some(match b0 {
+        32..=246 => b0 - 139,
+        247..=250 => (b0 - 247) * 256 + s.read_u8()? as i32 + 108,
+        251..=254 => -(b0 - 251) * 256 - s.read_u8()? as i32 - 108,
+        _ => return None,
+    })
+}
+
+fn read_real(s: &mut Stream) -> Option<f64> {
+    let mut text = String::new();
+    loop {
+        let byte = s.read_u8()?;
+        for &nibble in &[byte >> 4, byte & 0x0f] {
+            match nibble {
+                0..=9 => text.push((b'0' + nibble) as char),
+                0xa => text.push('.'),
+                0xb => text.push('E'),
+                0xc => text.push_str("E-"),
+                0xe => text.push('-'),
+                0xf => return text.parse().ok(),
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Which glyphs use which Font DICT of a CID-keyed or `CFF2` font.
+#[derive(Clone, Debug)]
+enum FdSelect {
+    Glyphs(Vec<u8>),
+    /// Sorted `(first glyph, font DICT)` ranges.
+    Ranges(Vec<(u32, u16)>),
+}
+
+impl FdSelect {
+    fn parse(data: &[u8], offset: usize, glyph_count: usize) -> Option<FdSelect> {
+        let mut s = Stream::new_at(data, offset)?;
+        match s.read_u8()? {
+            0 => Some(FdSelect::Glyphs(s.read_bytes(glyph_count)?.to_vec())),
+            3 => {
+                let count = s.read_u16()?;
+                let mut ranges = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    ranges.push((s.read_u16()? as u32, s.read_u8()? as u16));
+                }
+                Some(FdSelect::Ranges(ranges))
+            }
+            4 => {
+                let count = s.read_u32()?;
+                let mut ranges = Vec::with_capacity(count.min(0xffff) as usize);
+                for _ in 0..count {
+                    ranges.push((s.read_u32()?, s.read_u16()?));
+                }
+                Some(FdSelect::Ranges(ranges))
+            }
+            _ => None,
+        }
+    }
+
+    fn font_dict(&self, glyph_id: u16) -> Option<usize> {
+        match self {
+            FdSelect::Glyphs(fds) => fds.get(glyph_id as usize).map(|&fd| fd as usize),
+            FdSelect::Ranges(ranges) => {
+                let i = ranges.partition_point(|&(first, _)| first <= glyph_id as u32);
+                Some(ranges.get(i.checked_sub(1)?)?.1 as usize)
+            }
+        }
+    }
+}
+
+/// The string ID, or CID, of each glyph.
+#[derive(Clone, Debug)]
+enum Charset {
+    IsoAdobe,
+    /// The predefined expert charsets, which `seac` accents can't refer
+    /// into.
+    Expert,
+    ExpertSubset,
+    Custom(Vec<u16>),
+}
+
+impl Charset {
+    fn parse(data: &[u8], offset: usize, glyph_count: usize) -> Option<Charset> {
+        match offset {
+            0 => return Some(Charset::IsoAdobe),
+            1 => return Some(Charset::Expert),
+            2 => return Some(Charset::ExpertSubset),
+            _ => {}
+        }
+        let mut s = Stream::new_at(data, offset)?;
+        let format = s.read_u8()?;
+        let mut ids = Vec::with_capacity(glyph_count);
+        ids.push(0);
+        while ids.len() < glyph_count {
+            match format {
+                0 => ids.push(s.read_u16()?),
+                1 | 2 => {
+                    let first = s.read_u16()?;
+                    let left = if format == 1 {
+                        s.read_u8()? as u16
+                    } else {
+                        s.read_u16()?
+                    };
+                    ids.extend((0..=left).map(|i| first.wrapping_add(i)));
+                }
+                _ => return None,
+            }
+        }
+        ids.truncate(glyph_count);
+        Some(Charset::Custom(ids))
+    }
+
+    fn glyph_for_sid(&self, sid: u16) -> Option<u16> {
+        match self {
+            // The ISOAdobe charset lists the first 229 standard strings in
+            // order.
+            Charset::IsoAdobe if sid <= 228 => Some(sid),
+            Charset::Custom(ids) => ids.iter().position(|&id| id == sid).map(|g| g as u16),
+            _ => None,
+        }
+    }
+
+    /// Returns the string ID, or CID, of a glyph.
+    fn sid(&self, glyph_id: u16) -> Option<u16> {
+        let glyph_id = glyph_id as usize;
+        match self {
+            Charset::IsoAdobe if glyph_id <= 228 => Some(glyph_id as u16),
+            Charset::IsoAdobe => None,
+            Charset::Expert => EXPERT_CHARSET.get(glyph_id).cloned(),
+            Charset::ExpertSubset => EXPERT_SUBSET_CHARSET.get(glyph_id).cloned(),
+            Charset::Custom(ids) => ids.get(glyph_id).cloned(),
+        }
+    }
+}
+
+/// What a glyph's charstring needs from the Private DICT of its font.
+#[derive(Clone, Copy, Default)]
+struct PrivateDict<'a> {
+    subrs: Index<'a>,
+    vsindex: u16,
+}
+
+impl<'a> PrivateDict<'a> {
+    /// Reads the Private DICT a Top or Font DICT points to.
+    fn parse(data: &'a [u8], dict: &[u8], cff2: bool) -> Option<PrivateDict<'a>> {
+        let mut range = None;
+        parse_dict(dict, |op, operands| {
+            if op == TOP_DICT_PRIVATE {
+                range = Some((int(operands, 0)?, int(operands, 1)?));
+            }
+            Some(())
+        })?;
+        let (size, offset) = match range {
+            Some(range) => range,
+            None => return Some(PrivateDict::default()),
+        };
+        let private = data.get(offset..offset.checked_add(size)?)?;
+
+        let mut subrs_offset = None;
+        let mut vsindex = 0;
+        parse_dict(private, |op, operands| {
+            match op {
+                PRIVATE_DICT_SUBRS => subrs_offset = Some(int(operands, 0)?),
+                PRIVATE_DICT_VSINDEX => vsindex = int(operands, 0)? as u16,
+                _ => {}
+            }
+            Some(())
+        })?;
+        let subrs = match subrs_offset {
+            Some(subrs_offset) => {
+                Index::read(&mut Stream::new_at(data, offset + subrs_offset)?, cff2)?
+            }
+            None => Index::default(),
+        };
+        Some(PrivateDict { subrs, vsindex })
+    }
+}
+
+/// Reads operand `i` as a non-negative integer, such as an offset.
+fn int(operands: &[f64], i: usize) -> Option<usize> {
+    let value = *operands.get(i)?;
+    if value < 0. || value > u32::MAX as f64 {
+        return None;
+    }
+    Some(value as usize)
+}
+
+/// The charstrings of a `CFF ` or `CFF2` table, with what's needed to run
+/// them.
+#[derive(Clone)]
+pub struct CffTable<'a> {
+    cff2: bool,
+    units_per_em: u16,
+    char_strings: Index<'a>,
+    global_subrs: Index<'a>,
+    /// One per Font DICT, or a single one for fonts that aren't CID-keyed.
+    private_dicts: Vec<PrivateDict<'a>>,
+    fd_select: Option<FdSelect>,
+    charset: Charset,
+    /// The String INDEX, which holds the names of string IDs from 391 up.
+    strings: Index<'a>,
+    /// Whether the charset holds CIDs rather than string IDs.
+    cid_keyed: bool,
+    var_store: Option<ItemVariationStore>,
+}
+
+impl<'a> CffTable<'a> {
+    /// Parses a `CFF ` (version 1) or `CFF2` table, reading the units per em
+    /// from `head`.
+    pub fn parse(head: &[u8], data: &'a [u8]) -> Option<CffTable<'a>> {
+        let units_per_em = super::units_per_em(head).filter(|&upem| upem != 0)?;
+        let mut s = Stream::new(data);
+        let major = s.read_u8()?;
+        s.skip(1)?;
+        let header_size = s.read_u8()? as usize;
+        let cff2 = match major {
+            1 => false,
+            2 => true,
+            _ => return None,
+        };
+
+        let (top_dict, strings, global_subrs) = if cff2 {
+            let top_dict_length = s.read_u16()? as usize;
+            let mut s = Stream::new_at(data, header_size)?;
+            let top_dict = s.read_bytes(top_dict_length)?;
+            (top_dict, Index::default(), Index::read(&mut s, true)?)
+        } else {
+            let mut s = Stream::new_at(data, header_size)?;
+            // Only the first font of a FontSet is used in OpenType.
+            Index::read(&mut s, false)?;
+            let top_dict = Index::read(&mut s, false)?.get(0)?;
+            let strings = Index::read(&mut s, false)?;
+            (top_dict, strings, Index::read(&mut s, false)?)
+        };
+
+        let mut char_strings_offset = None;
+        let mut charset_offset = 0;
+        let mut fd_array_offset = None;
+        let mut fd_select_offset = None;
+        let mut var_store_offset = None;
+        let mut charstring_type = 2;
+        let mut cid_keyed = false;
+        parse_dict(top_dict, |op, operands| {
+            match op {
+                TOP_DICT_ROS => cid_keyed = true,
+                TOP_DICT_CHAR_STRINGS => char_strings_offset = Some(int(operands, 0)?),
+                TOP_DICT_CHARSET => charset_offset = int(operands, 0)?,
+                TOP_DICT_FD_ARRAY => fd_array_offset = Some(int(operands, 0)?),
+                TOP_DICT_FD_SELECT => fd_select_offset = Some(int(operands, 0)?),
+                TOP_DICT_VSTORE => var_store_offset = Some(int(operands, 0)?),
+                TOP_DICT_CHARSTRING_TYPE => charstring_type = int(operands, 0)?,
+                _ => {}
+            }
+            Some(())
+        })?;
+        if charstring_type != 2 {
+            return None;
+        }
+
+        let char_strings = Index::read(&mut Stream::new_at(data, char_strings_offset?)?, cff2)?;
+        let glyph_count = char_strings.len();
+
+        let private_dicts = match fd_array_offset {
+            Some(offset) => {
+                let fd_array = Index::read(&mut Stream::new_at(data, offset)?, cff2)?;
+                (0..fd_array.len())
+                    .map(|i| PrivateDict::parse(data, fd_array.get(i)?, cff2))
+                    .collect::<Option<Vec<_>>>()?
+            }
+            None if cff2 => return None,
+            None => vec![PrivateDict::parse(data, top_dict, cff2)?],
+        };
+        let fd_select = match fd_select_offset {
+            Some(offset) => Some(FdSelect::parse(data, offset, glyph_count)?),
+            None => None,
+        };
+        let charset = if cff2 {
+            Charset::IsoAdobe
+        } else {
+            Charset::parse(data, charset_offset, glyph_count)?
+        };
+        // The store is preceded by its length.
+        let var_store = match var_store_offset {
+            Some(offset) => Some(ItemVariationStore::parse(data.get(offset + 2..)?)?),
+            None => None,
+        };
+
+        Some(CffTable {
+            cff2,
+            units_per_em,
+            char_strings,
+            global_subrs,
+            private_dicts,
+            fd_select,
+            charset,
+            strings,
+            cid_keyed,
+            var_store,
+        })
+    }
+
+    /// Whether this is a `CFF2` table, whose glyphs can vary.
+    pub fn is_cff2(&self) -> bool {
+        self.cff2
+    }
+
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    pub fn glyph_count(&self) -> u16 {
+        self.char_strings.len().min(u16::MAX as usize) as u16
+    }
+
+    /// Returns a glyph's name from the charset, or a name like `cid01234`
+    /// for CID-keyed fonts. `CFF2` tables don't name their glyphs.
+    pub fn glyph_name(&self, glyph_id: u16) -> Option<String> {
+        if self.cff2 || glyph_id >= self.glyph_count() {
+            return None;
+        }
+        let sid = self.charset.sid(glyph_id)?;
+        if self.cid_keyed {
+            return Some(format!("cid{:05}", sid));
+        }
+        match STANDARD_STRINGS.get(sid as usize) {
+            Some(&name) => Some(name.into()),
+            None => {
+                let name = self.strings.get(sid as usize - STANDARD_STRINGS.len())?;
+                std::str::from_utf8(name).ok().map(|name| name.into())
+            }
+        }
+    }
+
+    /// Returns the first glyph with the given name.
+    pub fn glyph_by_name(&self, name: &str) -> Option<u16> {
+        (0..self.glyph_count()).find(|&glyph_id| self.glyph_name(glyph_id).as_deref() == Some(name))
+    }
+
+    /// Feeds the outline of a glyph at `em_size` to `builder`, with the
+    /// glyph origin at (0, 0) and y growing downwards, like DirectWrite.
+    ///
+    /// `coordinates` are the normalized variation coordinates, one per
+    /// `fvar` axis, and only affect `CFF2` tables. Returns `None`, without
+    /// calling `builder`, if the glyph is missing or its charstring is
+    /// malformed.
+    pub fn outline(
+        &self,
+        glyph_id: u16,
+        em_size: f32,
+        coordinates: &[f32],
+        builder: &mut dyn OutlineBuilder,
+    ) -> Option<()> {
+        let scale = em_size / self.units_per_em as f32;
+        self.emit_outline(glyph_id, coordinates, builder, |x, y| {
+            (x * scale, -y * scale)
+        })
+    }
+
+    /// Runs a glyph's charstring and, if it succeeds, feeds the outline to
+    /// `builder` after mapping every point through `transform`.
+    pub(crate) fn emit_outline<F>(
+        &self,
+        glyph_id: u16,
+        coordinates: &[f32],
+        builder: &mut dyn OutlineBuilder,
+        transform: F,
+    ) -> Option<()>
+    where
+        F: Fn(f32, f32) -> (f32, f32),
+    {
+        let mut path = Path::default();
+        self.run_glyph(glyph_id, coordinates, &mut path, (0., 0.), true)?;
+        path.emit(builder, transform);
+        Some(())
+    }
+
+    fn run_glyph(
+        &self,
+        glyph_id: u16,
+        coordinates: &[f32],
+        path: &mut Path,
+        origin: (f32, f32),
+        allow_seac: bool,
+    ) -> Option<()> {
+        let char_string = self.char_strings.get(glyph_id as usize)?;
+        let fd = match self.fd_select {
+            Some(ref fd_select) => fd_select.font_dict(glyph_id)?,
+            None => 0,
+        };
+        let private = self.private_dicts.get(fd)?;
+        let mut charstring = Charstring {
+            table: self,
+            subrs: private.subrs,
+            coordinates,
+            path,
+            stack: Vec::new(),
+            x: origin.0,
+            y: origin.1,
+            stem_count: 0,
+            width_parsed: false,
+            vsindex: private.vsindex,
+            scalars: None,
+            seac: None,
+        };
+        charstring.run(char_string, 0)?;
+        let seac = charstring.seac;
+        charstring.path.close();
+
+        if let Some((dx, dy, base, accent)) = seac {
+            if !allow_seac {
+                return None;
+            }
+            let glyph = |code: u8| {
+                self.charset
+                    .glyph_for_sid(STANDARD_ENCODING[code as usize] as u16)
+            };
+            self.run_glyph(glyph(base)?, coordinates, path, origin, false)?;
+            let accent_origin = (origin.0 + dx, origin.1 + dy);
+            self.run_glyph(glyph(accent)?, coordinates, path, accent_origin, false)?;
+        }
+        Some(())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+/// The outline of a glyph in font units, collected so that nothing reaches
+/// the builder unless the whole charstring runs.
+#[derive(Default)]
+struct Path {
+    commands: Vec<PathCommand>,
+    open: bool,
+}
+
+impl Path {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.commands.push(PathCommand::MoveTo(x, y));
+        self.open = true;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) -> Option<()> {
+        if !self.open {
+            return None;
+        }
+        self.commands.push(PathCommand::LineTo(x, y));
+        Some(())
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) -> Option<()> {
+        if !self.open {
+            return None;
+        }
+        self.commands
+            .push(PathCommand::CurveTo(x1, y1, x2, y2, x, y));
+        Some(())
+    }
+
+    /// Closes the current contour. Charstrings close contours implicitly,
+    /// with a line back to the start where needed, which `close` implies.
+    fn close(&mut self) {
+        if self.open {
+            self.commands.push(PathCommand::Close);
+            self.open = false;
+        }
+    }
+
+    fn emit<F>(&self, builder: &mut dyn OutlineBuilder, transform: F)
+    where
+        F: Fn(f32, f32) -> (f32, f32),
+    {
+        for &command in &self.commands {
+            match command {
+                PathCommand::MoveTo(x, y) => {
+                    let (x, y) = transform(x, y);
+                    builder.move_to(x, y);
+                }
+                PathCommand::LineTo(x, y) => {
+                    let (x, y) = transform(x, y);
+                    builder.line_to(x, y);
+                }
+                PathCommand::CurveTo(x1, y1, x2, y2, x, y) => {
+                    let (x1, y1) = transform(x1, y1);
+                    let (x2, y2) = transform(x2, y2);
+                    let (x, y) = transform(x, y);
+                    builder.curve_to(x1, y1, x2, y2, x, y);
+                }
+                PathCommand::Close => builder.close(),
+            }
+        }
+    }
+}
+
+/// The state of a running charstring.
+struct Charstring<'a, 'b> {
+    table: &'b CffTable<'a>,
+    subrs: Index<'a>,
+    coordinates: &'b [f32],
+    path: &'b mut Path,
+    stack: Vec<f32>,
+    x: f32,
+    y: f32,
+    stem_count: usize,
+    width_parsed: bool,
+    vsindex: u16,
+    /// The region scalars for `vsindex`, computed on the first `blend`.
+    scalars: Option<Vec<f32>>,
+    /// The accent offset, base character and accent character of an
+    /// `endchar` that builds an accented glyph.
+    seac: Option<(f32, f32, u8, u8)>,
+}
+
+impl<'a, 'b> Charstring<'a, 'b> {
+    fn max_stack(&self) -> usize {
+        if self.table.cff2 {
+            513
+        } else {
+            48
+        }
+    }
+
+    fn push(&mut self, value: f32) -> Option<()> {
+        if self.stack.len() >= self.max_stack() {
+            return None;
+        }
+        self.stack.push(value);
+        Some(())
+    }
+
+    /// Drops the advance width a Type 2 charstring may put before the
+    /// operands of its first stack-clearing operator.
+    fn parse_width(&mut self, has_width: bool) {
+        if !self.width_parsed && !self.table.cff2 && has_width {
+            self.stack.remove(0);
+        }
+        self.width_parsed = true;
+    }
+
+    fn line_to(&mut self, dx: f32, dy: f32) -> Option<()> {
+        self.x += dx;
+        self.y += dy;
+        self.path.line_to(self.x, self.y)
+    }
+
+    fn curve_to(&mut self, d: [f32; 6]) -> Option<()> {
+        let (x1, y1) = (self.x + d[0], self.y + d[1]);
+        let (x2, y2) = (x1 + d[2], y1 + d[3]);
+        self.x = x2 + d[4];
+        self.y = y2 + d[5];
+        self.path.curve_to(x1, y1, x2, y2, self.x, self.y)
+    }
+
+    /// Runs a charstring or subroutine. Returns `Some(true)` once `endchar`
+    /// ends the glyph.
+    fn run(&mut self, code: &'a [u8], depth: u8) -> Option<bool> {
+        if depth > MAX_CALL_DEPTH {
+            return None;
+        }
+        let mut s = Stream::new(code);
+        while let Some(op) = s.read_u8() {
+            match op {
+                // hstem, vstem, hstemhm, vstemhm
+                1 | 3 | 18 | 23 => {
+                    self.parse_width(self.stack.len() % 2 == 1);
+                    self.stem_count += self.stack.len() / 2;
+                    self.stack.clear();
+                }
+                // hintmask, cntrmask
+                19 | 20 => {
+                    // Operands here are an implied vstem.
+                    self.parse_width(self.stack.len() % 2 == 1);
+                    self.stem_count += self.stack.len() / 2;
+                    self.stack.clear();
+                    s.skip(self.stem_count.div_ceil(8))?;
+                }
+                // rmoveto
+                21 => {
+                    self.parse_width(self.stack.len() > 2);
+                    let (dx, dy) = (*self.stack.first()?, *self.stack.get(1)?);
+                    self.move_to(dx, dy);
+                }
+                // hmoveto
+                22 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let dx = *self.stack.first()?;
+                    self.move_to(dx, 0.);
+                }
+                // vmoveto
+                4 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let dy = *self.stack.first()?;
+                    self.move_to(0., dy);
+                }
+                // rlineto
+                5 => {
+                    if self.stack.len() % 2 == 1 {
+                        return None;
+                    }
+                    for i in (0..self.stack.len()).step_by(2) {
+                        self.line_to(self.stack[i], self.stack[i + 1])?;
+                    }
+                    self.stack.clear();
+                }
+                // hlineto, vlineto
+                6 | 7 => {
+                    let mut horizontal = op == 6;
+                    for i in 0..self.stack.len() {
+                        let d = self.stack[i];
+                        if horizontal {
+                            self.line_to(d, 0.)?;
+                        } else {
+                            self.line_to(0., d)?;
+                        }
+                        horizontal = !horizontal;
+                    }
+                    self.stack.clear();
+                }
+                // rrcurveto
+                8 => {
+                    if self.stack.len() / 6 * 6 != self.stack.len() {
+                        return None;
+                    }
+                    for i in (0..self.stack.len()).step_by(6) {
+                        self.curve_to(self.curve_args(i))?;
+                    }
+                    self.stack.clear();
+                }
+                // rcurveline
+                24 => {
+                    let len = self.stack.len();
+                    if len < 8 || (len - 2) / 6 * 6 != len - 2 {
+                        return None;
+                    }
+                    for i in (0..len - 2).step_by(6) {
+                        self.curve_to(self.curve_args(i))?;
+                    }
+                    self.line_to(self.stack[len - 2], self.stack[len - 1])?;
+                    self.stack.clear();
+                }
+                // rlinecurve
+                25 => {
+                    let len = self.stack.len();
+                    if len < 8 || (len - 6) % 2 == 1 {
+                        return None;
+                    }
+                    for i in (0..len - 6).step_by(2) {
+                        self.line_to(self.stack[i], self.stack[i + 1])?;
+                    }
+                    self.curve_to(self.curve_args(len - 6))?;
+                    self.stack.clear();
+                }
+                // vvcurveto, hhcurveto
+                26 | 27 => {
+                    let len = self.stack.len();
+                    let mut i = len % 4;
+                    if i > 1 {
+                        return None;
+                    }
+                    let mut first = if i == 1 { self.stack[0] } else { 0. };
+                    while i < len {
+                        let s = &self.stack[i..i + 4];
+                        let d = if op == 26 {
+                            [first, s[0], s[1], s[2], 0., s[3]]
+                        } else {
+                            [s[0], first, s[1], s[2], s[3], 0.]
+                        };
+                        self.curve_to(d)?;
+                        first = 0.;
+                        i += 4;
+                    }
+                    self.stack.clear();
+                }
+                // vhcurveto, hvcurveto
+                30 | 31 => {
+                    let len = self.stack.len();
+                    if len < 4 || len % 4 > 1 {
+                        return None;
+                    }
+                    let mut horizontal = op == 31;
+                    let mut i = 0;
+                    while i + 4 <= len {
+                        let s = &self.stack[i..i + 4];
+                        // The last curve may end off the axis.
+                        let last = if i + 5 == len { self.stack[i + 4] } else { 0. };
+                        let d = if horizontal {
+                            [s[0], 0., s[1], s[2], last, s[3]]
+                        } else {
+                            [0., s[0], s[1], s[2], s[3], last]
+                        };
+                        self.curve_to(d)?;
+                        horizontal = !horizontal;
+                        i += 4;
+                    }
+                    self.stack.clear();
+                }
+                // callsubr, callgsubr
+                10 | 29 => {
+                    let subrs = if op == 10 {
+                        self.subrs
+                    } else {
+                        self.table.global_subrs
+                    };
+                    let index = self.stack.pop()? as i32 + bias(subrs.len());
+                    let subr = subrs.get(usize::try_from(index).ok()?)?;
+                    if self.run(subr, depth + 1)? {
+                        return Some(true);
+                    }
+                }
+                // return
+                11 => {
+                    if self.table.cff2 {
+                        return None;
+                    }
+                    return Some(false);
+                }
+                // endchar
+                14 => {
+                    if self.table.cff2 {
+                        return None;
+                    }
+                    let len = self.stack.len();
+                    self.parse_width(len == 1 || len == 5);
+                    if self.stack.len() == 4 {
+                        let accent = (self.stack[2] as i32, self.stack[3] as i32);
+                        self.seac = Some((
+                            self.stack[0],
+                            self.stack[1],
+                            u8::try_from(accent.0).ok()?,
+                            u8::try_from(accent.1).ok()?,
+                        ));
+                    }
+                    self.stack.clear();
+                    return Some(true);
+                }
+                // vsindex
+                15 if self.table.cff2 => {
+                    self.vsindex = self.stack.pop()? as u16;
+                    self.scalars = None;
+                    self.stack.clear();
+                }
+                // blend
+                16 if self.table.cff2 => self.blend()?,
+                12 => {
+                    self.flex(s.read_u8()?)?;
+                    self.stack.clear();
+                }
+                28 => {
+                    let value = s.read_i16()? as f32;
+                    self.push(value)?;
+                }
+                255 => {
+                    let value = s.read_u32()? as i32 as f32 / 65536.;
+                    self.push(value)?;
+                }
+                32..=254 => {
+                    let value = read_small_integer(&mut s, op)? as f32;
+                    self.push(value)?;
+                }
+                _ => return None,
+            }
+        }
+        Some(false)
+    }
+
+    fn move_to(&mut self, dx: f32, dy: f32) {
+        self.x += dx;
+        self.y += dy;
+        self.path.move_to(self.x, self.y);
+        self.stack.clear();
+    }
+
+    fn curve_args(&self, i: usize) -> [f32; 6] {
+        let mut d = [0.; 6];
+        d.copy_from_slice(&self.stack[i..i + 6]);
+        d
+    }
+
+    fn flex(&mut self, op: u8) -> Option<()> {
+        let a = &self.stack;
+        match (op, a.len()) {
+            // dotsection, a deprecated hint.
+            (0, _) => {}
+            // hflex
+            (34, 7) => {
+                let d = [a[0], 0., a[1], a[2], a[3], 0.];
+                let e = [a[4], 0., a[5], -a[2], a[6], 0.];
+                self.curve_to(d)?;
+                self.curve_to(e)?;
+            }
+            // flex
+            (35, 13) => {
+                let d = [a[0], a[1], a[2], a[3], a[4], a[5]];
+                let e = [a[6], a[7], a[8], a[9], a[10], a[11]];
+                self.curve_to(d)?;
+                self.curve_to(e)?;
+            }
+            // hflex1
+            (36, 9) => {
+                let d = [a[0], a[1], a[2], a[3], a[4], 0.];
+                let e = [a[5], 0., a[6], a[7], a[8], -(a[1] + a[3] + a[7])];
+                self.curve_to(d)?;
+                self.curve_to(e)?;
+            }
+            // flex1
+            (37, 11) => {
+                let dx: f32 = a[..10].iter().step_by(2).sum();
+                let dy: f32 = a[1..10].iter().step_by(2).sum();
+                // The last point is level with the start along whichever
+                // direction moved least.
+                let last = if dx.abs() > dy.abs() {
+                    (a[10], -dy)
+                } else {
+                    (-dx, a[10])
+                };
+                let d = [a[0], a[1], a[2], a[3], a[4], a[5]];
+                let e = [a[6], a[7], a[8], a[9], last.0, last.1];
+                self.curve_to(d)?;
+                self.curve_to(e)?;
+            }
+            // The arithmetic and storage operators, which fonts don't use in
+            // practice, and malformed flexes.
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Replaces `n` default values and their deltas with the values at the
+    /// current coordinates.
+    fn blend(&mut self) -> Option<()> {
+        let n = self.stack.pop()? as usize;
+        if self.scalars.is_none() {
+            let store = self.table.var_store.as_ref()?;
+            self.scalars = Some(store.region_scalars(self.vsindex, self.coordinates)?);
+        }
+        let scalars = self.scalars.as_ref()?;
+        let k = scalars.len();
+        let base = self.stack.len().checked_sub(n.checked_mul(k + 1)?)?;
+        for i in 0..n {
+            let deltas = &self.stack[base + n + i * k..base + n + (i + 1) * k];
+            let delta: f32 = deltas.iter().zip(scalars).map(|(d, s)| d * s).sum();
+            self.stack[base + i] += delta;
+        }
+        self.stack.truncate(base + n);
+        Some(())
+    }
+}
+
+/// The number added to subroutine numbers in charstrings.
+fn bias(count: usize) -> i32 {
+    if count < 1240 {
+        107
+    } else if count < 33900 {
+        1131
+    } else {
+        32768
+    }
+}
@@ -0,0 +1,438 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `cmap` table: character to glyph mapping.
+//!
+//! `GetGlyphIndices` only maps code points to glyphs. [`CmapTable`] also
+//! maps glyphs back to the code points that reach them, as PDF `ToUnicode`
+//! maps and copying text out of rendered glyphs need, and looks up Unicode
+//! variation sequences such as U+2764 U+FE0F.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use super::Stream;
+
+/// A run of consecutive code points whose glyphs either count up from
+/// `glyph` or, for format 13, are all `glyph`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Segment {
+    start: u32,
+    end: u32,
+    glyph: u32,
+    many_to_one: bool,
+}
+
+impl Segment {
+    fn glyph_index(&self, code_point: u32) -> Option<u16> {
+        let glyph = if self.many_to_one {
+            self.glyph
+        } else {
+            self.glyph.checked_add(code_point - self.start)?
+        };
+        u16::try_from(glyph).ok().filter(|&glyph| glyph != 0)
+    }
+}
+
+/// One subtable's mapping from character codes to glyphs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CmapSubtable {
+    pub platform_id: u16,
+    pub encoding_id: u16,
+    /// 0, 4, 6, 12 or 13.
+    pub format: u16,
+    /// The Macintosh language the subtable is for, plus one, or 0.
+    pub language: u32,
+    /// Sorted by `start`.
+    segments: Vec<Segment>,
+}
+
+impl CmapSubtable {
+    /// Returns the glyph for a character code, or `None` if it's unmapped.
+    pub fn glyph_index(&self, code: u32) -> Option<u16> {
+        let index = match self.segments.binary_search_by(|s| s.start.cmp(&code)) {
+            Ok(index) => index,
+            Err(0) => return None,
+            Err(index) => index - 1,
+        };
+        let segment = &self.segments[index];
+        if code > segment.end {
+            return None;
+        }
+        segment.glyph_index(code)
+    }
+
+    /// Every mapped character code and its glyph, in code order.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        self.segments.iter().flat_map(|segment| {
+            (segment.start..=segment.end)
+                .filter_map(move |code| segment.glyph_index(code).map(|glyph| (code, glyph)))
+        })
+    }
+
+    /// Every character code that maps to `glyph`, in code order.
+    pub fn codes_for_glyph(&self, glyph: u16) -> Vec<u32> {
+        let glyph = glyph as u32;
+        let mut codes = vec![];
+        if glyph == 0 {
+            return codes;
+        }
+        for segment in &self.segments {
+            if segment.many_to_one {
+                if segment.glyph == glyph {
+                    codes.extend(segment.start..=segment.end);
+                }
+            } else if glyph >= segment.glyph && glyph - segment.glyph <= segment.end - segment.start
+            {
+                codes.push(segment.start + (glyph - segment.glyph));
+            }
+        }
+        codes
+    }
+
+    fn parse(platform_id: u16, encoding_id: u16, data: &[u8]) -> Option<CmapSubtable> {
+        let format = Stream::new(data).read_u16()?;
+        let mut segments = Segments::default();
+        let language = match format {
+            0 => parse_format0(data, &mut segments)?,
+            4 => parse_format4(data, &mut segments)?,
+            6 => parse_format6(data, &mut segments)?,
+            12 | 13 => parse_format12(data, format == 13, &mut segments)?,
+            _ => return None,
+        };
+        let mut segments = segments.0;
+        segments.sort_by_key(|segment| segment.start);
+        Some(CmapSubtable {
+            platform_id,
+            encoding_id,
+            format,
+            language,
+            segments,
+        })
+    }
+
+    // DirectWrite's preference among subtables for `GetGlyphIndices`: full
+    // repertoire over BMP-only, and Windows Unicode over everything else.
+    fn unicode_rank(&self) -> Option<u32> {
+        match (self.platform_id, self.encoding_id) {
+            (3, 10) => Some(6),
+            (0, 4) | (0, 6) => Some(5),
+            (3, 1) => Some(4),
+            (0, _) => Some(3),
+            (3, 0) => Some(2),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Segments(Vec<Segment>);
+
+impl Segments {
+    /// Adds a run, joining it onto the previous one where it continues it.
+    fn push(&mut self, start: u32, end: u32, glyph: u32, many_to_one: bool) {
+        if let Some(last) = self.0.last_mut() {
+            if !many_to_one
+                && !last.many_to_one
+                && last.end.checked_add(1) == Some(start)
+                && last.glyph.checked_add(start - last.start) == Some(glyph)
+            {
+                last.end = end;
+                return;
+            }
+        }
+        self.0.push(Segment {
+            start,
+            end,
+            glyph,
+            many_to_one,
+        });
+    }
+}
+
+fn parse_format0(data: &[u8], segments: &mut Segments) -> Option<u32> {
+    let mut s = Stream::new(data);
+    s.skip(4)?;
+    let language = s.read_u16()? as u32;
+    for (code, &glyph) in s.read_bytes(256)?.iter().enumerate() {
+        if glyph != 0 {
+            segments.push(code as u32, code as u32, glyph as u32, false);
+        }
+    }
+    Some(language)
+}
+
+fn parse_format4(data: &[u8], segments: &mut Segments) -> Option<u32> {
+    let mut s = Stream::new(data);
+    s.skip(4)?;
+    let language = s.read_u16()? as u32;
+    let seg_count = (s.read_u16()? / 2) as usize;
+    let end_codes = 14;
+    let start_codes = end_codes + 2 * seg_count + 2;
+    let id_deltas = start_codes + 2 * seg_count;
+    let id_range_offsets = id_deltas + 2 * seg_count;
+
+    for segment in 0..seg_count {
+        let end = super::read_u16(data, end_codes + 2 * segment)?;
+        let start = super::read_u16(data, start_codes + 2 * segment)?;
+        let delta = super::read_u16(data, id_deltas + 2 * segment)?;
+        let range_offset_position = id_range_offsets + 2 * segment;
+        let range_offset = super::read_u16(data, range_offset_position)?;
+        if start > end {
+            continue;
+        }
+        if range_offset != 0 {
+            for code in start..=end {
+                let glyph_position =
+                    range_offset_position + range_offset as usize + 2 * (code - start) as usize;
+                let glyph = match super::read_u16(data, glyph_position) {
+                    Some(0) | None => continue,
+                    Some(glyph) => glyph.wrapping_add(delta),
+                };
+                segments.push(code as u32, code as u32, glyph as u32, false);
+            }
+            continue;
+        }
+        // Glyphs are the code plus delta, modulo 65536, so a run can wrap
+        // around to glyph 0 and start again.
+        let first_glyph = start.wrapping_add(delta);
+        let (start, end) = (start as u32, end as u32);
+        let wrap = start + (0xffff - first_glyph as u32);
+        if wrap < end {
+            push_skipping_notdef(segments, start, wrap, first_glyph as u32);
+            push_skipping_notdef(segments, wrap + 1, end, 0);
+        } else {
+            push_skipping_notdef(segments, start, end, first_glyph as u32);
+        }
+    }
+    Some(language)
+}
+
+// Leaves out a leading code that maps to glyph 0, such as the 0xFFFF
+// terminator that every format 4 subtable ends with.
+fn push_skipping_notdef(segments: &mut Segments, start: u32, end: u32, glyph: u32) {
+    match glyph {
+        0 if start < end => segments.push(start + 1, end, 1, false),
+        0 => {}
+        _ => segments.push(start, end, glyph, false),
+    }
+}
+
+fn parse_format6(data: &[u8], segments: &mut Segments) -> Option<u32> {
+    let mut s = Stream::new(data);
+    s.skip(4)?;
+    let language = s.read_u16()? as u32;
+    let first_code = s.read_u16()? as u32;
+    let entry_count = s.read_u16()? as u32;
+    for code in first_code..first_code + entry_count {
+        let glyph = s.read_u16()?;
+        if glyph != 0 {
+            segments.push(code, code, glyph as u32, false);
+        }
+    }
+    Some(language)
+}
+
+fn parse_format12(data: &[u8], many_to_one: bool, segments: &mut Segments) -> Option<u32> {
+    let mut s = Stream::new(data);
+    s.skip(8)?;
+    let language = s.read_u32()?;
+    let num_groups = s.read_u32()?;
+    for _ in 0..num_groups {
+        let start = s.read_u32()?;
+        let end = s.read_u32()?;
+        let glyph = s.read_u32()?;
+        if start <= end && end <= 0x10ffff {
+            segments.push(start, end, glyph, many_to_one);
+        }
+    }
+    Some(language)
+}
+
+/// The variation sequences a font supports for one variation selector, from
+/// a format 14 subtable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariationSelector {
+    /// The selector, such as U+FE0F for emoji presentation.
+    pub selector: u32,
+    /// Inclusive ranges of base characters whose sequence with `selector`
+    /// shows the same glyph as the character alone.
+    pub default_ranges: Vec<(u32, u32)>,
+    /// Base characters whose sequence with `selector` shows another glyph.
+    pub non_default: Vec<(u32, u16)>,
+}
+
+/// How a font shows a variation sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariationGlyph {
+    /// With the base character's usual glyph.
+    Default,
+    /// With a glyph of its own.
+    Glyph(u16),
+}
+
+impl VariationSelector {
+    /// Looks up the sequence of `base` followed by this selector, returning
+    /// `None` if the font doesn't support it.
+    pub fn lookup(&self, base: u32) -> Option<VariationGlyph> {
+        if let Ok(index) = self
+            .non_default
+            .binary_search_by_key(&base, |&(code, _)| code)
+        {
+            return Some(VariationGlyph::Glyph(self.non_default[index].1));
+        }
+        self.default_ranges
+            .iter()
+            .find(|&&(start, end)| (start..=end).contains(&base))
+            .map(|_| VariationGlyph::Default)
+    }
+
+    fn parse(data: &[u8], s: &mut Stream) -> Option<VariationSelector> {
+        let selector = s.read_u24()?;
+        let default_offset = s.read_u32()? as usize;
+        let non_default_offset = s.read_u32()? as usize;
+
+        let mut default_ranges = vec![];
+        if default_offset != 0 {
+            let mut s = Stream::new_at(data, default_offset)?;
+            for _ in 0..s.read_u32()? {
+                let start = s.read_u24()?;
+                let additional_count = s.read_u8()? as u32;
+                default_ranges.push((start, start + additional_count));
+            }
+        }
+        let mut non_default = vec![];
+        if non_default_offset != 0 {
+            let mut s = Stream::new_at(data, non_default_offset)?;
+            for _ in 0..s.read_u32()? {
+                non_default.push((s.read_u24()?, s.read_u16()?));
+            }
+            non_default.sort_by_key(|&(code, _)| code);
+        }
+        Some(VariationSelector {
+            selector,
+            default_ranges,
+            non_default,
+        })
+    }
+}
+
+/// Every subtable of a `cmap` table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CmapTable {
+    /// The subtables in formats 0, 4, 6, 12 and 13, in table order.
+    /// Subtables in other formats or that are malformed are left out.
+    pub subtables: Vec<CmapSubtable>,
+    /// The variation selectors from the format 14 subtable, if any.
+    pub variation_selectors: Vec<VariationSelector>,
+    unicode: Option<usize>,
+}
+
+impl CmapTable {
+    pub fn parse(data: &[u8]) -> Option<CmapTable> {
+        let mut s = Stream::new(data);
+        s.skip(2)?;
+        let num_tables = s.read_u16()?;
+
+        let mut subtables: Vec<CmapSubtable> = vec![];
+        let mut variation_selectors = vec![];
+        for _ in 0..num_tables {
+            let platform_id = s.read_u16()?;
+            let encoding_id = s.read_u16()?;
+            let offset = s.read_u32()? as usize;
+            let subtable = match data.get(offset..) {
+                Some(subtable) => subtable,
+                None => continue,
+            };
+            if Stream::new(subtable).read_u16() == Some(14) {
+                if let Some(selectors) = parse_format14(subtable) {
+                    variation_selectors = selectors;
+                }
+                continue;
+            }
+            subtables.extend(CmapSubtable::parse(platform_id, encoding_id, subtable));
+        }
+
+        let unicode = subtables
+            .iter()
+            .enumerate()
+            .filter_map(|(index, subtable)| subtable.unicode_rank().map(|rank| (rank, index)))
+            // The first subtable wins among those ranked equally.
+            .max_by_key(|&(rank, index)| (rank, std::cmp::Reverse(index)))
+            .map(|(_, index)| index);
+        Some(CmapTable {
+            subtables,
+            variation_selectors,
+            unicode,
+        })
+    }
+
+    /// The Unicode subtable DirectWrite would pick for `GetGlyphIndices`.
+    pub fn unicode_subtable(&self) -> Option<&CmapSubtable> {
+        self.unicode.map(|index| &self.subtables[index])
+    }
+
+    /// Returns the subtable for a platform and encoding, such as (1, 0) for
+    /// Mac Roman.
+    pub fn subtable(&self, platform_id: u16, encoding_id: u16) -> Option<&CmapSubtable> {
+        self.subtables
+            .iter()
+            .find(|s| s.platform_id == platform_id && s.encoding_id == encoding_id)
+    }
+
+    /// Returns the glyph for `code_point`, or `None` if it's unmapped.
+    pub fn glyph_index(&self, code_point: u32) -> Option<u16> {
+        self.unicode_subtable()?.glyph_index(code_point)
+    }
+
+    /// Every mapped code point and its glyph, in code point order.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        self.unicode_subtable()
+            .into_iter()
+            .flat_map(CmapSubtable::mappings)
+    }
+
+    /// Every code point that maps to `glyph`, in code point order.
+    pub fn code_points_for_glyph(&self, glyph: u16) -> Vec<u32> {
+        self.unicode_subtable()
+            .map_or_else(Vec::new, |subtable| subtable.codes_for_glyph(glyph))
+    }
+
+    /// Maps each glyph reachable from a code point to every code point that
+    /// reaches it.
+    pub fn reverse_mappings(&self) -> BTreeMap<u16, Vec<u32>> {
+        let mut reverse = BTreeMap::new();
+        for (code_point, glyph) in self.mappings() {
+            reverse
+                .entry(glyph)
+                .or_insert_with(Vec::new)
+                .push(code_point);
+        }
+        reverse
+    }
+
+    /// Returns the glyph for `base` followed by variation selector
+    /// `selector`, or `None` if the font doesn't support the sequence.
+    pub fn variation_glyph_index(&self, base: u32, selector: u32) -> Option<u16> {
+        let variation = self
+            .variation_selectors
+            .iter()
+            .find(|variation| variation.selector == selector)?;
+        match variation.lookup(base)? {
+            VariationGlyph::Default => self.glyph_index(base),
+            VariationGlyph::Glyph(glyph) => Some(glyph),
+        }
+    }
+}
+
+fn parse_format14(data: &[u8]) -> Option<Vec<VariationSelector>> {
+    let mut s = Stream::new(data);
+    s.skip(6)?;
+    let count = s.read_u32()?;
+    let mut selectors = vec![];
+    for _ in 0..count {
+        selectors.push(VariationSelector::parse(data, &mut s)?);
+    }
+    Some(selectors)
+}
@@ -0,0 +1,794 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Color glyphs from the `COLR` table.
+//!
+//! [`ColrTable::paint`] resolves a glyph into a tree of [`Paint`]s: the
+//! layers of version 0 glyphs, and the paint graph of version 1 glyphs with
+//! its gradients, transforms and compositing. The leaves that draw are
+//! [`Paint::Glyph`]s, whose outlines come from `FontFace::glyph_run_outline`
+//! like any other glyph's, and colors refer to `CPAL` palette entries.
+
+use std::ops::Range;
+
+use super::cpal::{Color, Palette};
+use super::var_store::{DeltaSetIndexMap, ItemVariationStore};
+use super::{read_u16, read_u32, Stream};
+
+const FOREGROUND: u16 = 0xffff;
+const NO_VARIATION_INDEX: u32 = 0xffff_ffff;
+
+/// How deeply paints may nest, which also stops cycles.
+const MAX_PAINT_DEPTH: u8 = 64;
+/// How many paints one glyph may expand to, since shared subgraphs are
+/// copied into the tree.
+const MAX_PAINT_COUNT: usize = 0x10000;
+
+/// A `CPAL` palette entry, or the text's foreground color, with an extra
+/// alpha to multiply in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PaletteColor {
+    /// The palette entry, or `None` for the foreground color.
+    pub palette_index: Option<u16>,
+    pub alpha: f32,
+}
+
+impl PaletteColor {
+    /// Looks the color up in `palette`, returning `None` if the entry is
+    /// missing.
+    pub fn resolve(&self, palette: &Palette, foreground: Color) -> Option<Color> {
+        let color = match self.palette_index {
+            Some(index) => *palette.colors.get(index as usize)?,
+            None => foreground,
+        };
+        let alpha = (color.alpha as f32 * self.alpha).round().clamp(0., 255.);
+        Some(Color {
+            alpha: alpha as u8,
+            ..color
+        })
+    }
+}
+
+/// A layer of a version 0 color glyph: a glyph filled with one color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Layer {
+    pub glyph_id: u16,
+    /// The `CPAL` palette entry, or `None` for the foreground color.
+    pub palette_index: Option<u16>,
+}
+
+/// What a gradient does outside its color line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Extend {
+    Pad,
+    Repeat,
+    Reflect,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorStop {
+    pub offset: f32,
+    pub color: PaletteColor,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorLine {
+    pub extend: Extend,
+    pub stops: Vec<ColorStop>,
+}
+
+/// A 2×3 affine transform, mapping (x, y) to
+/// (xx·x + xy·y + dx, yx·x + yy·y + dy) in font units with y growing
+/// upwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    pub xx: f32,
+    pub yx: f32,
+    pub xy: f32,
+    pub yy: f32,
+    pub dx: f32,
+    pub dy: f32,
+}
+
+impl Affine {
+    pub const IDENTITY: Affine = Affine {
+        xx: 1.,
+        yx: 0.,
+        xy: 0.,
+        yy: 1.,
+        dx: 0.,
+        dy: 0.,
+    };
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.xx * x + self.xy * y + self.dx,
+            self.yx * x + self.yy * y + self.dy,
+        )
+    }
+
+    fn translate(dx: f32, dy: f32) -> Affine {
+        Affine {
+            dx,
+            dy,
+            ..Affine::IDENTITY
+        }
+    }
+
+    fn scale(sx: f32, sy: f32) -> Affine {
+        Affine {
+            xx: sx,
+            yy: sy,
+            ..Affine::IDENTITY
+        }
+    }
+
+    /// A counter-clockwise rotation by `angle` half turns.
+    fn rotate(angle: f32) -> Affine {
+        let (sin, cos) = (angle * std::f32::consts::PI).sin_cos();
+        Affine {
+            xx: cos,
+            yx: sin,
+            xy: -sin,
+            yy: cos,
+            ..Affine::IDENTITY
+        }
+    }
+
+    /// A skew by angles in half turns, with positive `x_angle` leaning
+    /// the y axis clockwise and positive `y_angle` leaning the x axis
+    /// counter-clockwise.
+    fn skew(x_angle: f32, y_angle: f32) -> Affine {
+        let pi = std::f32::consts::PI;
+        Affine {
+            xy: -(x_angle * pi).tan(),
+            yx: (y_angle * pi).tan(),
+            ..Affine::IDENTITY
+        }
+    }
+
+    /// Applies a transform without translation around `(cx, cy)` rather
+    /// than the origin.
+    fn around(self, cx: f32, cy: f32) -> Affine {
+        Affine {
+            dx: cx - (self.xx * cx + self.xy * cy),
+            dy: cy - (self.yx * cx + self.yy * cy),
+            ..self
+        }
+    }
+}
+
+/// How `PaintComposite` combines its source and backdrop, in the order of
+/// the `COLR` specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompositeMode {
+    Clear,
+    Source,
+    Destination,
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+    Plus,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Multiply,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl CompositeMode {
+    fn from_u8(mode: u8) -> Option<CompositeMode> {
+        use CompositeMode::*;
+        const MODES: [CompositeMode; 28] = [
+            Clear,
+            Source,
+            Destination,
+            SourceOver,
+            DestinationOver,
+            SourceIn,
+            DestinationIn,
+            SourceOut,
+            DestinationOut,
+            SourceAtop,
+            DestinationAtop,
+            Xor,
+            Plus,
+            Screen,
+            Overlay,
+            Darken,
+            Lighten,
+            ColorDodge,
+            ColorBurn,
+            HardLight,
+            SoftLight,
+            Difference,
+            Exclusion,
+            Multiply,
+            Hue,
+            Saturation,
+            Color,
+            Luminosity,
+        ];
+        MODES.get(mode as usize).cloned()
+    }
+}
+
+/// A node of a color glyph's paint tree. Coordinates are in font units with
+/// y growing upwards, and angles are in degrees counter-clockwise.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    /// Paints drawn in order, each composited over the ones before.
+    Layers(Vec<Paint>),
+    Solid(PaletteColor),
+    LinearGradient {
+        color_line: ColorLine,
+        p0: (f32, f32),
+        p1: (f32, f32),
+        /// With `p0`, sets the direction of the lines of equal color.
+        p2: (f32, f32),
+    },
+    RadialGradient {
+        color_line: ColorLine,
+        c0: (f32, f32),
+        r0: f32,
+        c1: (f32, f32),
+        r1: f32,
+    },
+    SweepGradient {
+        color_line: ColorLine,
+        center: (f32, f32),
+        start_angle: f32,
+        end_angle: f32,
+    },
+    /// Fills the outline of `glyph_id` with `paint`.
+    Glyph {
+        glyph_id: u16,
+        paint: Box<Paint>,
+    },
+    /// Draws another color glyph, whose paint tree is `paint`.
+    ColrGlyph {
+        glyph_id: u16,
+        paint: Box<Paint>,
+    },
+    Transform {
+        transform: Affine,
+        paint: Box<Paint>,
+    },
+    Composite {
+        mode: CompositeMode,
+        source: Box<Paint>,
+        backdrop: Box<Paint>,
+    },
+}
+
+impl Paint {
+    /// Lists the glyphs whose outlines the tree fills, in drawing order.
+    pub fn glyphs(&self) -> Vec<u16> {
+        let mut glyphs = vec![];
+        self.collect_glyphs(&mut glyphs);
+        glyphs
+    }
+
+    fn collect_glyphs(&self, glyphs: &mut Vec<u16>) {
+        match self {
+            Paint::Layers(layers) => {
+                for layer in layers {
+                    layer.collect_glyphs(glyphs);
+                }
+            }
+            Paint::Glyph { glyph_id, paint } => {
+                glyphs.push(*glyph_id);
+                paint.collect_glyphs(glyphs);
+            }
+            Paint::ColrGlyph { paint, .. } | Paint::Transform { paint, .. } => {
+                paint.collect_glyphs(glyphs)
+            }
+            Paint::Composite {
+                source, backdrop, ..
+            } => {
+                backdrop.collect_glyphs(glyphs);
+                source.collect_glyphs(glyphs);
+            }
+            Paint::Solid(_)
+            | Paint::LinearGradient { .. }
+            | Paint::RadialGradient { .. }
+            | Paint::SweepGradient { .. } => {}
+        }
+    }
+}
+
+/// The bounds a version 1 color glyph is clipped to, in font units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClipBox {
+    pub x_min: f32,
+    pub y_min: f32,
+    pub x_max: f32,
+    pub y_max: f32,
+}
+
+/// The units of a variable value, which its deltas are scaled by.
+#[derive(Clone, Copy)]
+enum Value {
+    FWord,
+    UFWord,
+    F2Dot14,
+    Fixed,
+}
+
+impl Value {
+    fn read(self, s: &mut Stream) -> Option<f32> {
+        Some(match self {
+            Value::FWord => s.read_i16()? as f32,
+            Value::UFWord => s.read_u16()? as f32,
+            Value::F2Dot14 => s.read_f2dot14()?,
+            Value::Fixed => s.read_u32()? as i32 as f32 / 65536.,
+        })
+    }
+
+    fn delta_scale(self) -> f32 {
+        match self {
+            Value::FWord | Value::UFWord => 1.,
+            Value::F2Dot14 => 1. / 16384.,
+            Value::Fixed => 1. / 65536.,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ColrTable {
+    data: Vec<u8>,
+    version: u16,
+    base_glyph_records: Range<usize>,
+    layer_records: Range<usize>,
+    base_glyph_list: Option<usize>,
+    layer_list: Option<usize>,
+    clip_list: Option<usize>,
+    var_index_map: Option<DeltaSetIndexMap>,
+    var_store: Option<ItemVariationStore>,
+}
+
+impl ColrTable {
+    pub fn parse(data: &[u8]) -> Option<ColrTable> {
+        let mut s = Stream::new(data);
+        let version = s.read_u16()?;
+        if version > 1 {
+            return None;
+        }
+        let base_glyph_count = s.read_u16()? as usize;
+        let base_glyphs_offset = s.read_u32()? as usize;
+        let layers_offset = s.read_u32()? as usize;
+        let layer_count = s.read_u16()? as usize;
+        let base_glyph_records = base_glyphs_offset..base_glyphs_offset + 6 * base_glyph_count;
+        let layer_records = layers_offset..layers_offset + 4 * layer_count;
+        data.get(base_glyph_records.clone())?;
+        data.get(layer_records.clone())?;
+
+        let mut table = ColrTable {
+            data: data.to_vec(),
+            version,
+            base_glyph_records,
+            layer_records,
+            base_glyph_list: None,
+            layer_list: None,
+            clip_list: None,
+            var_index_map: None,
+            var_store: None,
+        };
+        if version == 1 {
+            let mut offset = || {
+                s.read_u32()
+                    .map(|offset| Some(offset as usize).filter(|&o| o != 0))
+            };
+            table.base_glyph_list = offset()?;
+            table.layer_list = offset()?;
+            table.clip_list = offset()?;
+            let var_index_map = offset()?;
+            let var_store = offset()?;
+            if let Some(offset) = var_index_map {
+                table.var_index_map = Some(DeltaSetIndexMap::parse(data.get(offset..)?)?);
+            }
+            if let Some(offset) = var_store {
+                table.var_store = Some(ItemVariationStore::parse(data.get(offset..)?)?);
+            }
+        }
+        Some(table)
+    }
+
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// Returns the layers of a version 0 color glyph, bottom first, or
+    /// `None` if the glyph has none.
+    pub fn layers(&self, glyph_id: u16) -> Option<Vec<Layer>> {
+        let record = find_record(&self.data[self.base_glyph_records.clone()], 6, glyph_id)?;
+        let first = read_u16(record, 2)? as usize;
+        let count = read_u16(record, 4)? as usize;
+        let records = self.data[self.layer_records.clone()].get(4 * first..4 * (first + count))?;
+        Some(
+            records
+                .chunks(4)
+                .map(|record| Layer {
+                    glyph_id: u16::from_be_bytes([record[0], record[1]]),
+                    palette_index: Some(u16::from_be_bytes([record[2], record[3]]))
+                        .filter(|&index| index != FOREGROUND),
+                })
+                .collect(),
+        )
+    }
+
+    /// Resolves a color glyph into its paint tree at the normalized
+    /// variation `coordinates`, or returns `None` if the glyph isn't a
+    /// color glyph or its paints are malformed.
+    ///
+    /// Version 1 paints take precedence, and version 0 layers become
+    /// [`Paint::Layers`] of solid-filled glyphs.
+    pub fn paint(&self, glyph_id: u16, coordinates: &[f32]) -> Option<Paint> {
+        if let Some(offset) = self.base_paint_offset(glyph_id) {
+            let mut reader = PaintReader {
+                table: self,
+                coordinates,
+                remaining: MAX_PAINT_COUNT,
+            };
+            return reader.paint(offset, 0);
+        }
+        let layers = self.layers(glyph_id)?;
+        Some(Paint::Layers(
+            layers
+                .into_iter()
+                .map(|layer| Paint::Glyph {
+                    glyph_id: layer.glyph_id,
+                    paint: Box::new(Paint::Solid(PaletteColor {
+                        palette_index: layer.palette_index,
+                        alpha: 1.,
+                    })),
+                })
+                .collect(),
+        ))
+    }
+
+    /// Returns the clip box of a version 1 color glyph, if it has one.
+    pub fn clip_box(&self, glyph_id: u16, coordinates: &[f32]) -> Option<ClipBox> {
+        let list = self.clip_list?;
+        let mut s = Stream::new_at(&self.data, list)?;
+        if s.read_u8()? != 1 {
+            return None;
+        }
+        let count = s.read_u32()? as usize;
+        let clips = s.read_bytes(count.checked_mul(7)?)?;
+        // Clips are sorted by glyph range.
+        let index = clips
+            .chunks(7)
+            .position(|clip| u16::from_be_bytes([clip[2], clip[3]]) >= glyph_id)?;
+        let clip = &clips[7 * index..7 * index + 7];
+        if u16::from_be_bytes([clip[0], clip[1]]) > glyph_id {
+            return None;
+        }
+        let offset = u32::from_be_bytes([0, clip[4], clip[5], clip[6]]) as usize;
+
+        let mut s = Stream::new_at(&self.data, list + offset)?;
+        let format = s.read_u8()?;
+        let mut values = [0.; 4];
+        for value in &mut values {
+            *value = s.read_i16()? as f32;
+        }
+        if format == 2 {
+            let base = s.read_u32()?;
+            self.apply_deltas(&mut values, &[Value::FWord; 4], base, coordinates);
+        }
+        Some(ClipBox {
+            x_min: values[0],
+            y_min: values[1],
+            x_max: values[2],
+            y_max: values[3],
+        })
+    }
+
+    fn base_paint_offset(&self, glyph_id: u16) -> Option<usize> {
+        let list = self.base_glyph_list?;
+        let count = read_u32(&self.data, list)? as usize;
+        let records = self.data.get(list + 4..list + 4 + count.checked_mul(6)?)?;
+        let record = find_record(records, 6, glyph_id)?;
+        Some(list + read_u32(record, 2)? as usize)
+    }
+
+    /// Adds the deltas of variable values whose variation indices start at
+    /// `base`. Missing deltas count as zero.
+    fn apply_deltas(&self, values: &mut [f32], kinds: &[Value], base: u32, coordinates: &[f32]) {
+        let store = match self.var_store {
+            Some(ref store) if base != NO_VARIATION_INDEX => store,
+            _ => return,
+        };
+        for (i, (value, kind)) in values.iter_mut().zip(kinds).enumerate() {
+            let index = base.wrapping_add(i as u32);
+            let (outer, inner) = match self.var_index_map {
+                Some(ref map) => match map.map(index) {
+                    Some(entry) => entry,
+                    None => continue,
+                },
+                None => ((index >> 16) as u16, index as u16),
+            };
+            if let Some(delta) = store.delta(outer, inner, coordinates) {
+                *value += delta * kind.delta_scale();
+            }
+        }
+    }
+}
+
+/// A face's `COLR` table together with the normalized coordinates of its
+/// axis values, so that its color glyphs can be resolved one by one without
+/// reading either again.
+#[derive(Clone)]
+pub struct ColorGlyphs {
+    table: ColrTable,
+    coordinates: Vec<f32>,
+}
+
+impl ColorGlyphs {
+    pub fn new(table: ColrTable, coordinates: Vec<f32>) -> ColorGlyphs {
+        ColorGlyphs { table, coordinates }
+    }
+
+    pub fn table(&self) -> &ColrTable {
+        &self.table
+    }
+
+    /// The normalized variation coordinates, in `fvar` axis order.
+    pub fn coordinates(&self) -> &[f32] {
+        &self.coordinates
+    }
+
+    /// Resolves a color glyph into its paint tree. See [`ColrTable::paint`].
+    pub fn paint(&self, glyph_id: u16) -> Option<Paint> {
+        self.table.paint(glyph_id, &self.coordinates)
+    }
+
+    /// Returns the clip box of a version 1 color glyph, if it has one.
+    pub fn clip_box(&self, glyph_id: u16) -> Option<ClipBox> {
+        self.table.clip_box(glyph_id, &self.coordinates)
+    }
+}
+
+/// Binary searches records that start with a glyph ID.
+fn find_record(records: &[u8], size: usize, glyph_id: u16) -> Option<&[u8]> {
+    let count = records.len() / size;
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let mid = (low + high) / 2;
+        let record = &records[mid * size..(mid + 1) * size];
+        match u16::from_be_bytes([record[0], record[1]]).cmp(&glyph_id) {
+            std::cmp::Ordering::Less => low = mid + 1,
+            std::cmp::Ordering::Greater => high = mid,
+            std::cmp::Ordering::Equal => return Some(record),
+        }
+    }
+    None
+}
+
+struct PaintReader<'a> {
+    table: &'a ColrTable,
+    coordinates: &'a [f32],
+    remaining: usize,
+}
+
+impl<'a> PaintReader<'a> {
+    /// Reads variable values, followed by their variation index base if
+    /// `variable` is set.
+    fn values(&self, s: &mut Stream, kinds: &[Value], variable: bool) -> Option<Vec<f32>> {
+        let mut values = kinds
+            .iter()
+            .map(|kind| kind.read(s))
+            .collect::<Option<Vec<_>>>()?;
+        if variable {
+            let base = s.read_u32()?;
+            self.table
+                .apply_deltas(&mut values, kinds, base, self.coordinates);
+        }
+        Some(values)
+    }
+
+    fn color_line(&self, offset: usize, variable: bool) -> Option<ColorLine> {
+        let mut s = Stream::new_at(&self.table.data, offset)?;
+        let extend = match s.read_u8()? {
+            1 => Extend::Repeat,
+            2 => Extend::Reflect,
+            // Unknown modes fall back to padding.
+            _ => Extend::Pad,
+        };
+        let count = s.read_u16()?;
+        let mut stops = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let offset = s.read_f2dot14()?;
+            let palette_index = s.read_u16()?;
+            let mut values = [offset, s.read_f2dot14()?];
+            if variable {
+                let base = s.read_u32()?;
+                self.table
+                    .apply_deltas(&mut values, &[Value::F2Dot14; 2], base, self.coordinates);
+            }
+            stops.push(ColorStop {
+                offset: values[0],
+                color: PaletteColor {
+                    palette_index: Some(palette_index).filter(|&index| index != FOREGROUND),
+                    alpha: values[1],
+                },
+            });
+        }
+        Some(ColorLine { extend, stops })
+    }
+
+    fn paint(&mut self, offset: usize, depth: u8) -> Option<Paint> {
+        if depth >= MAX_PAINT_DEPTH || self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let table = self.table;
+        let data = &table.data[..];
+        let mut s = Stream::new_at(data, offset)?;
+        let format = s.read_u8()?;
+        let variable = format % 2 == 1 && format != 1 && format != 11 && format < 32;
+        // Most paints start with the offset of the paint they apply to.
+        let child = |s: &mut Stream| Some(offset + s.read_u24()? as usize);
+        use Value::*;
+
+        Some(match format {
+            1 => {
+                let count = s.read_u8()? as usize;
+                let first = s.read_u32()? as usize;
+                let list = self.table.layer_list?;
+                let layer_count = read_u32(data, list)? as usize;
+                if first.checked_add(count)? > layer_count {
+                    return None;
+                }
+                let mut layers = Vec::with_capacity(count);
+                for i in first..first + count {
+                    let layer = list + read_u32(data, list + 4 + 4 * i)? as usize;
+                    layers.push(self.paint(layer, depth + 1)?);
+                }
+                Paint::Layers(layers)
+            }
+            2 | 3 => {
+                let palette_index = s.read_u16()?;
+                let alpha = self.values(&mut s, &[F2Dot14], variable)?[0];
+                Paint::Solid(PaletteColor {
+                    palette_index: Some(palette_index).filter(|&index| index != FOREGROUND),
+                    alpha,
+                })
+            }
+            4 | 5 => {
+                let color_line = child(&mut s)?;
+                let v = self.values(&mut s, &[FWord; 6], variable)?;
+                Paint::LinearGradient {
+                    color_line: self.color_line(color_line, variable)?,
+                    p0: (v[0], v[1]),
+                    p1: (v[2], v[3]),
+                    p2: (v[4], v[5]),
+                }
+            }
+            6 | 7 => {
+                let color_line = child(&mut s)?;
+                let kinds = [FWord, FWord, UFWord, FWord, FWord, UFWord];
+                let v = self.values(&mut s, &kinds, variable)?;
+                Paint::RadialGradient {
+                    color_line: self.color_line(color_line, variable)?,
+                    c0: (v[0], v[1]),
+                    r0: v[2],
+                    c1: (v[3], v[4]),
+                    r1: v[5],
+                }
+            }
+            8 | 9 => {
+                let color_line = child(&mut s)?;
+                let v = self.values(&mut s, &[FWord, FWord, F2Dot14, F2Dot14], variable)?;
+                Paint::SweepGradient {
+                    color_line: self.color_line(color_line, variable)?,
+                    center: (v[0], v[1]),
+                    start_angle: v[2] * 180.,
+                    end_angle: v[3] * 180.,
+                }
+            }
+            10 => {
+                let paint = child(&mut s)?;
+                let glyph_id = s.read_u16()?;
+                Paint::Glyph {
+                    glyph_id,
+                    paint: Box::new(self.paint(paint, depth + 1)?),
+                }
+            }
+            11 => {
+                let glyph_id = s.read_u16()?;
+                let paint = self.table.base_paint_offset(glyph_id)?;
+                Paint::ColrGlyph {
+                    glyph_id,
+                    paint: Box::new(self.paint(paint, depth + 1)?),
+                }
+            }
+            12..=31 => {
+                let paint = child(&mut s)?;
+                let transform = match format {
+                    12 | 13 => {
+                        let mut s = Stream::new_at(data, child(&mut s)?)?;
+                        let v = self.values(&mut s, &[Fixed; 6], variable)?;
+                        Affine {
+                            xx: v[0],
+                            yx: v[1],
+                            xy: v[2],
+                            yy: v[3],
+                            dx: v[4],
+                            dy: v[5],
+                        }
+                    }
+                    14 | 15 => {
+                        let v = self.values(&mut s, &[FWord; 2], variable)?;
+                        Affine::translate(v[0], v[1])
+                    }
+                    16 | 17 => {
+                        let v = self.values(&mut s, &[F2Dot14; 2], variable)?;
+                        Affine::scale(v[0], v[1])
+                    }
+                    18 | 19 => {
+                        let v = self.values(&mut s, &[F2Dot14, F2Dot14, FWord, FWord], variable)?;
+                        Affine::scale(v[0], v[1]).around(v[2], v[3])
+                    }
+                    20 | 21 => {
+                        let v = self.values(&mut s, &[F2Dot14], variable)?;
+                        Affine::scale(v[0], v[0])
+                    }
+                    22 | 23 => {
+                        let v = self.values(&mut s, &[F2Dot14, FWord, FWord], variable)?;
+                        Affine::scale(v[0], v[0]).around(v[1], v[2])
+                    }
+                    24 | 25 => {
+                        let v = self.values(&mut s, &[F2Dot14], variable)?;
+                        Affine::rotate(v[0])
+                    }
+                    26 | 27 => {
+                        let v = self.values(&mut s, &[F2Dot14, FWord, FWord], variable)?;
+                        Affine::rotate(v[0]).around(v[1], v[2])
+                    }
+                    28 | 29 => {
+                        let v = self.values(&mut s, &[F2Dot14; 2], variable)?;
+                        Affine::skew(v[0], v[1])
+                    }
+                    _ => {
+                        let v = self.values(&mut s, &[F2Dot14, F2Dot14, FWord, FWord], variable)?;
+                        Affine::skew(v[0], v[1]).around(v[2], v[3])
+                    }
+                };
+                Paint::Transform {
+                    transform,
+                    paint: Box::new(self.paint(paint, depth + 1)?),
+                }
+            }
+            32 => {
+                let source = child(&mut s)?;
+                let mode = CompositeMode::from_u8(s.read_u8()?)?;
+                let backdrop = child(&mut s)?;
+                Paint::Composite {
+                    mode,
+                    source: Box::new(self.paint(source, depth + 1)?),
+                    backdrop: Box::new(self.paint(backdrop, depth + 1)?),
+                }
+            }
+            _ => return None,
+        })
+    }
+}
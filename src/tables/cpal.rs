@@ -0,0 +1,128 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Color palettes from the `CPAL` table, which `COLR` glyphs index into.
+
+use super::Stream;
+
+const USABLE_WITH_LIGHT_BACKGROUND: u32 = 0x0001;
+const USABLE_WITH_DARK_BACKGROUND: u32 = 0x0002;
+
+const NO_NAME_ID: u16 = 0xffff;
+
+/// A color with straight, not premultiplied, alpha.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+/// One palette, with the same number of colors as every other palette in
+/// the table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    pub colors: Vec<Color>,
+    pub usable_with_light_background: bool,
+    pub usable_with_dark_background: bool,
+    /// The `name` ID of the palette's user-visible name.
+    pub label_name_id: Option<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpalTable {
+    pub palettes: Vec<Palette>,
+    /// The `name` ID of each palette entry's user-visible name, such as
+    /// "skin tone", or empty if the table doesn't name them.
+    pub entry_label_name_ids: Vec<Option<u16>>,
+}
+
+impl CpalTable {
+    pub fn parse(data: &[u8]) -> Option<CpalTable> {
+        let mut s = Stream::new(data);
+        let version = s.read_u16()?;
+        let entry_count = s.read_u16()? as usize;
+        let palette_count = s.read_u16()? as usize;
+        let color_count = s.read_u16()? as usize;
+        let colors_offset = s.read_u32()? as usize;
+        let mut first_indices = Vec::with_capacity(palette_count);
+        for _ in 0..palette_count {
+            first_indices.push(s.read_u16()? as usize);
+        }
+        let (types_offset, labels_offset, entry_labels_offset) = if version >= 1 {
+            (s.read_u32()?, s.read_u32()?, s.read_u32()?)
+        } else {
+            (0, 0, 0)
+        };
+
+        let records = Stream::new_at(data, colors_offset)?.read_bytes(4 * color_count)?;
+        let colors: Vec<Color> = records
+            .chunks(4)
+            .map(|bgra| Color {
+                blue: bgra[0],
+                green: bgra[1],
+                red: bgra[2],
+                alpha: bgra[3],
+            })
+            .collect();
+
+        // The optional version 1 arrays, which have one value per palette or
+        // per entry.
+        let array = |offset: u32, count: usize, size: usize| -> Option<Option<Stream>> {
+            if offset == 0 {
+                return Some(None);
+            }
+            let mut s = Stream::new_at(data, offset as usize)?;
+            let start = s;
+            s.skip(count * size)?;
+            Some(Some(start))
+        };
+        let mut types = array(types_offset, palette_count, 4)?;
+        let mut labels = array(labels_offset, palette_count, 2)?;
+        let mut entry_labels = array(entry_labels_offset, entry_count, 2)?;
+
+        let mut palettes = Vec::with_capacity(palette_count);
+        for first in first_indices {
+            let palette_type = match types {
+                Some(ref mut types) => types.read_u32()?,
+                None => 0,
+            };
+            let label = match labels {
+                Some(ref mut labels) => labels.read_u16()?,
+                None => NO_NAME_ID,
+            };
+            palettes.push(Palette {
+                colors: colors.get(first..first.checked_add(entry_count)?)?.to_vec(),
+                usable_with_light_background: palette_type & USABLE_WITH_LIGHT_BACKGROUND != 0,
+                usable_with_dark_background: palette_type & USABLE_WITH_DARK_BACKGROUND != 0,
+                label_name_id: Some(label).filter(|&id| id != NO_NAME_ID),
+            });
+        }
+        let entry_label_name_ids = match entry_labels {
+            Some(ref mut entry_labels) => {
+                let mut ids = Vec::with_capacity(entry_count);
+                for _ in 0..entry_count {
+                    let id = entry_labels.read_u16()?;
+                    ids.push(Some(id).filter(|&id| id != NO_NAME_ID));
+                }
+                ids
+            }
+            None => vec![],
+        };
+        Some(CpalTable {
+            palettes,
+            entry_label_name_ids,
+        })
+    }
+
+    /// Returns color `entry` of palette `palette`.
+    pub fn color(&self, palette: usize, entry: u16) -> Option<Color> {
+        self.palettes
+            .get(palette)?
+            .colors
+            .get(entry as usize)
+            .cloned()
+    }
+}
@@ -0,0 +1,137 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `fvar` table: a variable font's axes and named instances.
+
+use super::avar::AvarTable;
+use super::Stream;
+use crate::Tag;
+
+const AXIS_HIDDEN: u16 = 0x0001;
+
+/// One variation axis, in user coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariationAxisRecord {
+    pub tag: Tag,
+    pub min_value: f32,
+    pub default_value: f32,
+    pub max_value: f32,
+    /// Whether the font asks for the axis to be left out of user interfaces.
+    pub hidden: bool,
+    /// The `name` table ID of the axis name.
+    pub name_id: u16,
+}
+
+impl VariationAxisRecord {
+    /// Maps a user coordinate to -1…1, with the default at 0, before any
+    /// `avar` adjustment.
+    pub fn normalize(&self, value: f32) -> f32 {
+        let value = value.max(self.min_value).min(self.max_value);
+        if value < self.default_value {
+            (value - self.default_value) / (self.default_value - self.min_value)
+        } else if value > self.default_value {
+            (value - self.default_value) / (self.max_value - self.default_value)
+        } else {
+            0.
+        }
+    }
+}
+
+/// A named instance, such as "Semibold Condensed".
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceRecord {
+    /// The `name` table ID of the instance's subfamily name.
+    pub subfamily_name_id: u16,
+    /// The `name` table ID of the instance's PostScript name, if it has one.
+    pub postscript_name_id: Option<u16>,
+    /// One user coordinate per axis, in axis order.
+    pub coordinates: Vec<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FvarTable {
+    pub axes: Vec<VariationAxisRecord>,
+    pub instances: Vec<InstanceRecord>,
+}
+
+impl FvarTable {
+    pub fn parse(data: &[u8]) -> Option<FvarTable> {
+        let mut s = Stream::new(data);
+        s.skip(4)?;
+        let axes_offset = s.read_u16()? as usize;
+        s.skip(2)?;
+        let axis_count = s.read_u16()? as usize;
+        let axis_size = s.read_u16()? as usize;
+        let instance_count = s.read_u16()?;
+        let instance_size = s.read_u16()? as usize;
+        if axis_size < 20 || instance_size < 4 + 4 * axis_count {
+            return None;
+        }
+
+        let mut axes = Vec::with_capacity(axis_count);
+        for index in 0..axis_count {
+            let mut s = Stream::new_at(data, axes_offset + index * axis_size)?;
+            let tag = s.read_tag()?;
+            let min_value = read_fixed(&mut s)?;
+            let default_value = read_fixed(&mut s)?;
+            let max_value = read_fixed(&mut s)?;
+            let flags = s.read_u16()?;
+            axes.push(VariationAxisRecord {
+                tag,
+                min_value,
+                default_value,
+                max_value,
+                hidden: flags & AXIS_HIDDEN != 0,
+                name_id: s.read_u16()?,
+            });
+        }
+
+        let instances_offset = axes_offset + axis_count * axis_size;
+        let mut instances = Vec::with_capacity(instance_count as usize);
+        for index in 0..instance_count as usize {
+            let mut s = Stream::new_at(data, instances_offset + index * instance_size)?;
+            let subfamily_name_id = s.read_u16()?;
+            s.skip(2)?;
+            let coordinates = (0..axis_count)
+                .map(|_| read_fixed(&mut s))
+                .collect::<Option<_>>()?;
+            let postscript_name_id = if instance_size >= 6 + 4 * axis_count {
+                Some(s.read_u16()?).filter(|&id| id != 0xffff)
+            } else {
+                None
+            };
+            instances.push(InstanceRecord {
+                subfamily_name_id,
+                postscript_name_id,
+                coordinates,
+            });
+        }
+        Some(FvarTable { axes, instances })
+    }
+
+    /// Maps user coordinates to the normalized coordinates that variation
+    /// data is keyed by, one per axis in axis order. Axes missing from
+    /// `values` take their defaults.
+    pub fn normalize(&self, values: &[(Tag, f32)], avar: Option<&AvarTable>) -> Vec<f32> {
+        self.axes
+            .iter()
+            .enumerate()
+            .map(|(index, axis)| {
+                let value = values
+                    .iter()
+                    .rev()
+                    .find(|&&(tag, _)| tag == axis.tag)
+                    .map_or(0., |&(_, value)| axis.normalize(value));
+                match avar {
+                    Some(avar) => avar.map(index, value),
+                    None => value,
+                }
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn read_fixed(s: &mut Stream) -> Option<f32> {
+    s.read_u32().map(|value| value as i32 as f32 / 65536.)
+}
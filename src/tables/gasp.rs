@@ -0,0 +1,76 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `gasp` table, which says how a font wants to be grid-fitted and
+//! anti-aliased at each pixel size.
+
+use super::Stream;
+
+const GASP_GRIDFIT: u16 = 0x0001;
+const GASP_DOGRAY: u16 = 0x0002;
+const GASP_SYMMETRIC_GRIDFIT: u16 = 0x0004;
+const GASP_SYMMETRIC_SMOOTHING: u16 = 0x0008;
+
+/// The behavior of the sizes from the end of the previous range up to and
+/// including `max_ppem`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GaspRange {
+    pub max_ppem: u16,
+    pub grid_fit: bool,
+    pub grayscale: bool,
+    /// Grid-fit only vertically, for ClearType. Always false in version 0
+    /// tables, which predate the flag.
+    pub symmetric_grid_fit: bool,
+    /// Anti-alias vertically as well as horizontally, for ClearType.
+    /// Always false in version 0 tables.
+    pub symmetric_smoothing: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GaspTable {
+    pub version: u16,
+    /// The ranges in increasing `max_ppem` order; the last one should end
+    /// at 0xffff.
+    pub ranges: Vec<GaspRange>,
+}
+
+impl GaspTable {
+    pub fn parse(data: &[u8]) -> Option<GaspTable> {
+        let mut s = Stream::new(data);
+        let version = s.read_u16()?;
+        if version > 1 {
+            return None;
+        }
+        let count = s.read_u16()?;
+        let mut ranges = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let max_ppem = s.read_u16()?;
+            let mut behavior = s.read_u16()?;
+            if version == 0 {
+                behavior &= GASP_GRIDFIT | GASP_DOGRAY;
+            }
+            ranges.push(GaspRange {
+                max_ppem,
+                grid_fit: behavior & GASP_GRIDFIT != 0,
+                grayscale: behavior & GASP_DOGRAY != 0,
+                symmetric_grid_fit: behavior & GASP_SYMMETRIC_GRIDFIT != 0,
+                symmetric_smoothing: behavior & GASP_SYMMETRIC_SMOOTHING != 0,
+            });
+        }
+        Some(GaspTable { version, ranges })
+    }
+
+    /// Returns the range that covers `ppem`, or `None` if it's past the
+    /// last one.
+    pub fn range(&self, ppem: f32) -> Option<&GaspRange> {
+        gasp_range(&self.ranges, ppem)
+    }
+}
+
+/// Returns the first of `ranges` that goes up to at least `ppem`.
+pub(crate) fn gasp_range(ranges: &[GaspRange], ppem: f32) -> Option<&GaspRange> {
+    ranges
+        .iter()
+        .find(|range| ppem <= f32::from(range.max_ppem))
+}
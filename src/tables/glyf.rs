@@ -0,0 +1,391 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! TrueType outlines from the `glyf` and `loca` tables.
+//!
+//! [`GlyfTable::outline`] feeds a glyph to an [`OutlineBuilder`] the way
+//! `IDWriteFontFace::GetGlyphRunOutline` does for a single glyph at the
+//! origin, so outlines are available without DirectWrite.
+
+use super::{read_u16, read_u32, Stream};
+use crate::outline_builder::OutlineBuilder;
+
+const ON_CURVE_POINT: u8 = 0x01;
+const X_SHORT_VECTOR: u8 = 0x02;
+const Y_SHORT_VECTOR: u8 = 0x04;
+const REPEAT_FLAG: u8 = 0x08;
+const X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0x10;
+const Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR: u8 = 0x20;
+
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const SCALED_COMPONENT_OFFSET: u16 = 0x0800;
+const UNSCALED_COMPONENT_OFFSET: u16 = 0x1000;
+
+/// How deeply composite glyphs may nest, which also stops cycles.
+const MAX_COMPONENT_DEPTH: u8 = 16;
+
+/// A point of a TrueType contour, in font design units with y growing
+/// upwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+    pub on_curve: bool,
+}
+
+/// The glyph outlines of a TrueType font, borrowed from its `glyf` and
+/// `loca` tables.
+#[derive(Clone, Copy, Debug)]
+pub struct GlyfTable<'a> {
+    loca: &'a [u8],
+    glyf: &'a [u8],
+    long_loca: bool,
+    units_per_em: u16,
+}
+
+impl<'a> GlyfTable<'a> {
+    /// Reads the `loca` format and units per em from `head`.
+    pub fn parse(head: &[u8], loca: &'a [u8], glyf: &'a [u8]) -> Option<GlyfTable<'a>> {
+        Some(GlyfTable {
+            loca,
+            glyf,
+            long_loca: super::long_loca(head)?,
+            units_per_em: super::units_per_em(head).filter(|&upem| upem != 0)?,
+        })
+    }
+
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    /// Returns the `glyf` bytes of `glyph_id`, which are empty for glyphs
+    /// without an outline.
+    pub fn glyph_data(&self, glyph_id: u16) -> Option<&'a [u8]> {
+        glyph_data(self.loca, self.glyf, self.long_loca, glyph_id)
+    }
+
+    /// Decodes the contours of a glyph in font units, with the components of
+    /// composite glyphs transformed and placed.
+    pub fn contours(&self, glyph_id: u16) -> Option<Vec<Vec<Point>>> {
+        self.resolve(glyph_id, 0)
+    }
+
+    /// Feeds the outline of a glyph at `em_size` to `builder`, with the
+    /// glyph origin at (0, 0) and y growing downwards, like DirectWrite.
+    ///
+    /// Returns `None`, without calling `builder`, if the glyph is missing
+    /// or malformed.
+    pub fn outline(
+        &self,
+        glyph_id: u16,
+        em_size: f32,
+        builder: &mut dyn OutlineBuilder,
+    ) -> Option<()> {
+        let contours = self.contours(glyph_id)?;
+        let scale = em_size / self.units_per_em as f32;
+        emit_contours(&contours, builder, |x, y| (x * scale, -y * scale));
+        Some(())
+    }
+
+    fn resolve(&self, glyph_id: u16, depth: u8) -> Option<Vec<Vec<Point>>> {
+        let data = self.glyph_data(glyph_id)?;
+        if data.is_empty() {
+            return Some(vec![]);
+        }
+        if read_u16(data, 0)? as i16 >= 0 {
+            return contours(data);
+        }
+        if depth >= MAX_COMPONENT_DEPTH {
+            return None;
+        }
+
+        let mut s = Stream::new(data);
+        s.skip(10)?;
+        let mut contours: Vec<Vec<Point>> = vec![];
+        loop {
+            let flags = s.read_u16()?;
+            let component_id = s.read_u16()?;
+            let xy_values = flags & ARGS_ARE_XY_VALUES != 0;
+            let (arg1, arg2) = match (flags & ARG_1_AND_2_ARE_WORDS != 0, xy_values) {
+                (true, true) => (s.read_i16()? as i32, s.read_i16()? as i32),
+                (true, false) => (s.read_u16()? as i32, s.read_u16()? as i32),
+                (false, true) => (s.read_u8()? as i8 as i32, s.read_u8()? as i8 as i32),
+                (false, false) => (s.read_u8()? as i32, s.read_u8()? as i32),
+            };
+            let transform = if flags & WE_HAVE_A_SCALE != 0 {
+                let scale = s.read_f2dot14()?;
+                Transform::new(scale, 0., 0., scale)
+            } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                Transform::new(s.read_f2dot14()?, 0., 0., s.read_f2dot14()?)
+            } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                Transform::new(
+                    s.read_f2dot14()?,
+                    s.read_f2dot14()?,
+                    s.read_f2dot14()?,
+                    s.read_f2dot14()?,
+                )
+            } else {
+                Transform::new(1., 0., 0., 1.)
+            };
+
+            let mut component = self.resolve(component_id, depth + 1)?;
+            for point in component.iter_mut().flatten() {
+                let (x, y) = transform.apply(point.x, point.y);
+                point.x = x;
+                point.y = y;
+            }
+
+            let (dx, dy) = if xy_values {
+                let (dx, dy) = (arg1 as f32, arg2 as f32);
+                // Offsets are in the composite's units unless the font asks
+                // for them to be scaled along with the component.
+                if flags & (SCALED_COMPONENT_OFFSET | UNSCALED_COMPONENT_OFFSET)
+                    == SCALED_COMPONENT_OFFSET
+                {
+                    (dx * transform.x_scale(), dy * transform.y_scale())
+                } else {
+                    (dx, dy)
+                }
+            } else {
+                // Move the component so that its point `arg2` lands on point
+                // `arg1` of the glyph so far.
+                let parent = contours.iter().flatten().nth(arg1 as usize)?;
+                let child = component.iter().flatten().nth(arg2 as usize)?;
+                (parent.x - child.x, parent.y - child.y)
+            };
+            for point in component.iter_mut().flatten() {
+                point.x += dx;
+                point.y += dy;
+            }
+            contours.extend(component);
+
+            if flags & MORE_COMPONENTS == 0 {
+                return Some(contours);
+            }
+        }
+    }
+}
+
+/// The linear part of a component transform, mapping (x, y) to
+/// (xx·x + yx·y, xy·x + yy·y).
+#[derive(Clone, Copy)]
+struct Transform {
+    xx: f32,
+    xy: f32,
+    yx: f32,
+    yy: f32,
+}
+
+impl Transform {
+    /// Takes the values in the order they're stored in the `glyf` table.
+    fn new(xx: f32, xy: f32, yx: f32, yy: f32) -> Transform {
+        Transform { xx, xy, yx, yy }
+    }
+
+    fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.xx * x + self.yx * y, self.xy * x + self.yy * y)
+    }
+
+    fn x_scale(&self) -> f32 {
+        self.xx.hypot(self.yx)
+    }
+
+    fn y_scale(&self) -> f32 {
+        self.yy.hypot(self.xy)
+    }
+}
+
+fn glyph_data<'a>(loca: &[u8], glyf: &'a [u8], long_loca: bool, glyph_id: u16) -> Option<&'a [u8]> {
+    let index = glyph_id as usize;
+    let (start, end) = if long_loca {
+        (read_u32(loca, 4 * index)?, read_u32(loca, 4 * index + 4)?)
+    } else {
+        (
+            read_u16(loca, 2 * index)? as u32 * 2,
+            read_u16(loca, 2 * index + 2)? as u32 * 2,
+        )
+    };
+    if end < start {
+        return None;
+    }
+    glyf.get(start as usize..end as usize)
+}
+
+/// Decodes the contours of a simple glyph, in font units.
+fn contours(data: &[u8]) -> Option<Vec<Vec<Point>>> {
+    let mut s = Stream::new(data);
+    let number_of_contours = s.read_i16()?;
+    s.skip(8)?;
+
+    let mut end_points = Vec::with_capacity(number_of_contours as usize);
+    for _ in 0..number_of_contours {
+        end_points.push(s.read_u16()?);
+    }
+    let point_count = match end_points.last() {
+        Some(&last) => last as usize + 1,
+        None => return Some(vec![]),
+    };
+    let instruction_length = s.read_u16()?;
+    s.skip(instruction_length as usize)?;
+
+    let mut flags = Vec::with_capacity(point_count);
+    while flags.len() < point_count {
+        let flag = s.read_u8()?;
+        flags.push(flag);
+        if flag & REPEAT_FLAG != 0 {
+            for _ in 0..s.read_u8()? {
+                flags.push(flag);
+            }
+        }
+    }
+    flags.truncate(point_count);
+
+    let xs = read_coordinates(
+        &mut s,
+        &flags,
+        X_SHORT_VECTOR,
+        X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR,
+    )?;
+    let ys = read_coordinates(
+        &mut s,
+        &flags,
+        Y_SHORT_VECTOR,
+        Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR,
+    )?;
+
+    let mut contours = Vec::with_capacity(end_points.len());
+    let mut start = 0;
+    for &end in &end_points {
+        let end = end as usize + 1;
+        if end < start || end > point_count {
+            return None;
+        }
+        contours.push(
+            (start..end)
+                .map(|i| Point {
+                    x: xs[i] as f32,
+                    y: ys[i] as f32,
+                    on_curve: flags[i] & ON_CURVE_POINT != 0,
+                })
+                .collect(),
+        );
+        start = end;
+    }
+    Some(contours)
+}
+
+fn read_coordinates(
+    s: &mut Stream,
+    flags: &[u8],
+    short_flag: u8,
+    same_or_positive_flag: u8,
+) -> Option<Vec<i32>> {
+    let mut value = 0i32;
+    let mut coordinates = Vec::with_capacity(flags.len());
+    for &flag in flags {
+        if flag & short_flag != 0 {
+            let delta = s.read_u8()? as i32;
+            value += if flag & same_or_positive_flag != 0 {
+                delta
+            } else {
+                -delta
+            };
+        } else if flag & same_or_positive_flag == 0 {
+            value += s.read_i16()? as i32;
+        }
+        coordinates.push(value);
+    }
+    Some(coordinates)
+}
+
+/// Feeds quadratic contours to `builder` as lines and cubic curves, after
+/// mapping every point through `transform`.
+pub(crate) fn emit_contours<F>(
+    contours: &[Vec<Point>],
+    builder: &mut dyn OutlineBuilder,
+    transform: F,
+) where
+    F: Fn(f32, f32) -> (f32, f32),
+{
+    for contour in contours {
+        let points: Vec<Point> = contour
+            .iter()
+            .map(|p| {
+                let (x, y) = transform(p.x, p.y);
+                Point {
+                    x,
+                    y,
+                    on_curve: p.on_curve,
+                }
+            })
+            .collect();
+        emit_contour(&points, builder);
+    }
+}
+
+fn emit_contour(points: &[Point], builder: &mut dyn OutlineBuilder) {
+    let n = points.len();
+    if n == 0 {
+        return;
+    }
+
+    // Start at an on-curve point, synthesizing one between the last and
+    // first points if every point is off-curve. The walk then ends back at
+    // the start.
+    let (start, mut sequence) = match points.iter().position(|p| p.on_curve) {
+        Some(first_on) => {
+            let mut sequence = points[first_on + 1..].to_vec();
+            sequence.extend_from_slice(&points[..first_on]);
+            (points[first_on], sequence)
+        }
+        None => (midpoint(points[n - 1], points[0]), points.to_vec()),
+    };
+    sequence.push(start);
+
+    builder.move_to(start.x, start.y);
+    let mut current = start;
+    let mut control: Option<Point> = None;
+    let last = sequence.len() - 1;
+    for (i, &point) in sequence.iter().enumerate() {
+        if point.on_curve {
+            match control.take() {
+                Some(c) => quad_to(builder, current, c, point),
+                // The closing line back to the start is implied by `close`.
+                None if i == last => {}
+                None => builder.line_to(point.x, point.y),
+            }
+            current = point;
+        } else {
+            if let Some(c) = control {
+                let mid = midpoint(c, point);
+                quad_to(builder, current, c, mid);
+                current = mid;
+            }
+            control = Some(point);
+        }
+    }
+    builder.close();
+}
+
+fn midpoint(a: Point, b: Point) -> Point {
+    Point {
+        x: (a.x + b.x) / 2.,
+        y: (a.y + b.y) / 2.,
+        on_curve: true,
+    }
+}
+
+fn quad_to(builder: &mut dyn OutlineBuilder, from: Point, control: Point, to: Point) {
+    // Degree elevation of the quadratic Bézier.
+    let c1x = from.x + 2. / 3. * (control.x - from.x);
+    let c1y = from.y + 2. / 3. * (control.y - from.y);
+    let c2x = to.x + 2. / 3. * (control.x - to.x);
+    let c2y = to.y + 2. / 3. * (control.y - to.y);
+    builder.curve_to(c1x, c1y, c2x, c2y, to.x, to.y);
+}
@@ -0,0 +1,240 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Kerning pairs from the legacy `kern` table and from `GPOS` pair
+//! adjustment lookups.
+//!
+//! `GetKerningPairAdjustments` answers for one pair at a time and only reads
+//! the `kern` table. [`kerning_pairs`] lists every pair up front, so layout
+//! code can build its own kerning tables.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::layout::{self, LayoutTable, LookupList, GPOS_EXTENSION};
+use super::Stream;
+use crate::Tag;
+
+const GPOS_PAIR_ADJUSTMENT: u16 = 2;
+
+const VALUE_X_ADVANCE: u16 = 0x0004;
+
+/// Two glyphs and the adjustment to the first glyph's advance when they're
+/// adjacent, in font design units.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KerningPair {
+    pub first_glyph: u16,
+    pub second_glyph: u16,
+    pub x_advance: i32,
+}
+
+/// Lists the kerning pairs a shaper would apply, sorted by glyph pair.
+///
+/// Like DirectWrite, this reads the lookups of the `GPOS` `kern` feature
+/// when the font has any, and the `kern` table otherwise. `num_glyphs` is
+/// needed to list the glyphs in class 0 of a class-based pair adjustment.
+pub fn kerning_pairs(
+    gpos: Option<&[u8]>,
+    kern: Option<&[u8]>,
+    num_glyphs: u16,
+) -> Vec<KerningPair> {
+    let gpos = gpos.and_then(|gpos| gpos_kerning_pairs(gpos, num_glyphs));
+    match gpos {
+        Some(pairs) => pairs,
+        None => kern.and_then(parse_kern).unwrap_or_default(),
+    }
+}
+
+/// Lists the horizontal pairs in the format 0 subtables of a `kern` table,
+/// in either the Microsoft or the Apple layout. Cross-stream and minimum
+/// value subtables are skipped.
+pub fn parse_kern(data: &[u8]) -> Option<Vec<KerningPair>> {
+    let mut s = Stream::new(data);
+    let apple = s.read_u16()? == 1;
+    let num_tables = if apple {
+        s.skip(2)?;
+        s.read_u32()?
+    } else {
+        s.read_u16()? as u32
+    };
+
+    let mut values = BTreeMap::new();
+    for _ in 0..num_tables {
+        let start = s.offset();
+        let (length, format, horizontal, override_values) = if apple {
+            let length = s.read_u32()? as usize;
+            let coverage = s.read_u16()?;
+            s.skip(2)?;
+            // Vertical, cross-stream and variation subtables.
+            (length, coverage & 0xff, coverage & 0xe000 == 0, false)
+        } else {
+            s.skip(2)?;
+            let length = s.read_u16()? as usize;
+            let coverage = s.read_u16()?;
+            // Horizontal, and neither minimum values nor cross-stream.
+            let horizontal = coverage & 0x0007 == 0x0001;
+            (length, coverage >> 8, horizontal, coverage & 0x0008 != 0)
+        };
+        if format == 0 && horizontal {
+            let mut pairs = s;
+            let count = pairs.read_u16()?;
+            pairs.skip(6)?;
+            for _ in 0..count {
+                let glyphs = (pairs.read_u16()?, pairs.read_u16()?);
+                let value = pairs.read_i16()? as i32;
+                let total = values.entry(glyphs).or_insert(0);
+                *total = if override_values {
+                    value
+                } else {
+                    *total + value
+                };
+            }
+        }
+        // Microsoft subtables of format 0 often overflow the 16-bit length,
+        // so step over their pairs rather than trusting it.
+        let mut next = Stream::new_at(data, start + length);
+        if !apple && format == 0 {
+            let count = s.read_u16()? as usize;
+            next = Stream::new_at(data, s.offset() + 6 + 6 * count);
+        }
+        s = match next {
+            Some(next) => next,
+            None => break,
+        };
+    }
+    Some(collect_pairs(values))
+}
+
+/// Lists the pairs from the `GPOS` pair adjustment lookups of the `kern`
+/// feature, for every script and language, or `None` if the table has no
+/// `kern` feature. Adjustments from separate lookups add up.
+pub fn gpos_kerning_pairs(data: &[u8], num_glyphs: u16) -> Option<Vec<KerningPair>> {
+    let lookups = LayoutTable::parse(data)?.feature_lookups(Tag::KERN);
+    let lookup_list = LookupList::parse(data, GPOS_EXTENSION)?;
+    if lookups.is_empty() {
+        return None;
+    }
+
+    let mut values = BTreeMap::new();
+    for index in lookups {
+        let lookup = match lookup_list.lookup(index) {
+            Some(lookup) if lookup.lookup_type == GPOS_PAIR_ADJUSTMENT => lookup,
+            _ => continue,
+        };
+        let mut pairs = LookupPairs::default();
+        for subtable in lookup.subtables {
+            // Skip malformed subtables, as a shaper would.
+            let _ = pairs.add_subtable(subtable, num_glyphs);
+        }
+        for (glyphs, value) in pairs.values {
+            *values.entry(glyphs).or_insert(0) += value;
+        }
+    }
+    Some(collect_pairs(values))
+}
+
+fn collect_pairs(values: BTreeMap<(u16, u16), i32>) -> Vec<KerningPair> {
+    values
+        .into_iter()
+        .filter(|&(_, x_advance)| x_advance != 0)
+        .map(|((first_glyph, second_glyph), x_advance)| KerningPair {
+            first_glyph,
+            second_glyph,
+            x_advance,
+        })
+        .collect()
+}
+
+/// The pairs one lookup adjusts. The first subtable to match a pair decides
+/// it, and a class-based subtable matches every pair whose first glyph it
+/// covers.
+#[derive(Default)]
+struct LookupPairs {
+    values: HashMap<(u16, u16), i32>,
+    decided_first_glyphs: HashSet<u16>,
+}
+
+impl LookupPairs {
+    fn decide(&mut self, first_glyph: u16, second_glyph: u16, x_advance: i32) {
+        if !self.decided_first_glyphs.contains(&first_glyph) {
+            self.values
+                .entry((first_glyph, second_glyph))
+                .or_insert(x_advance);
+        }
+    }
+
+    fn add_subtable(&mut self, data: &[u8], num_glyphs: u16) -> Option<()> {
+        let mut s = Stream::new(data);
+        let format = s.read_u16()?;
+        let coverage = layout::coverage(data.get(s.read_u16()? as usize..)?)?;
+        let value_format1 = s.read_u16()?;
+        let value_format2 = s.read_u16()?;
+        let record_size = value_record_size(value_format1) + value_record_size(value_format2);
+
+        match format {
+            1 => {
+                let pair_set_count = s.read_u16()?;
+                for &first_glyph in coverage.iter().take(pair_set_count as usize) {
+                    let mut pair_set = Stream::new_at(data, s.read_u16()? as usize)?;
+                    for _ in 0..pair_set.read_u16()? {
+                        let second_glyph = pair_set.read_u16()?;
+                        let record = pair_set.read_bytes(record_size)?;
+                        let x_advance = x_advance(record, value_format1)?;
+                        self.decide(first_glyph, second_glyph, x_advance);
+                    }
+                }
+            }
+            2 => {
+                let class_def1 = layout::class_def(data.get(s.read_u16()? as usize..)?)?;
+                let class_def2 = layout::class_def(data.get(s.read_u16()? as usize..)?)?;
+                let class1_count = s.read_u16()? as usize;
+                let class2_count = s.read_u16()? as usize;
+                let records = s.read_bytes(class1_count * class2_count * record_size)?;
+                let class1: HashMap<u16, u16> = class_def1.into_iter().collect();
+                let mut class2_glyphs = vec![vec![]; class2_count];
+                for &(glyph, class) in &class_def2 {
+                    if let Some(glyphs) = class2_glyphs.get_mut(class as usize) {
+                        glyphs.push(glyph);
+                    }
+                }
+                // Class 0 holds every glyph no other class does.
+                if let Some(unclassified) = class2_glyphs.first_mut() {
+                    let classified: HashSet<u16> = class_def2.iter().map(|&(g, _)| g).collect();
+                    unclassified.extend((0..num_glyphs).filter(|g| !classified.contains(g)));
+                }
+
+                for &first_glyph in &coverage {
+                    let class = *class1.get(&first_glyph).unwrap_or(&0) as usize;
+                    if class >= class1_count {
+                        continue;
+                    }
+                    for (class2, glyphs) in class2_glyphs.iter().enumerate() {
+                        let offset = (class * class2_count + class2) * record_size;
+                        let x_advance = x_advance(&records[offset..], value_format1)?;
+                        if x_advance == 0 {
+                            continue;
+                        }
+                        for &second_glyph in glyphs {
+                            self.decide(first_glyph, second_glyph, x_advance);
+                        }
+                    }
+                    self.decided_first_glyphs.insert(first_glyph);
+                }
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+fn value_record_size(format: u16) -> usize {
+    2 * (format & 0x00ff).count_ones() as usize
+}
+
+fn x_advance(record: &[u8], format: u16) -> Option<i32> {
+    if format & VALUE_X_ADVANCE == 0 {
+        return Some(0);
+    }
+    let offset = value_record_size(format & (VALUE_X_ADVANCE - 1));
+    Some(super::read_u16(record, offset)? as i16 as i32)
+}
@@ -0,0 +1,418 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `GSUB` and `GPOS` tables: the scripts, language systems and features
+//! a font supports, and the lookups behind them.
+//!
+//! DirectWrite applies features without saying which ones a font has.
+//! [`LayoutTable`] lists them, so that user interfaces can offer only the
+//! features that do something.
+
+use super::Stream;
+use crate::Tag;
+
+pub(crate) const GPOS_EXTENSION: u16 = 9;
+
+/// A script's default language system, or one of its languages.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageSystem {
+    /// The language tag, or `dflt` for the default language system.
+    pub tag: Tag,
+    /// The index of a feature that is always applied.
+    pub required_feature: Option<u16>,
+    /// Indices into [`LayoutTable::features`].
+    pub feature_indices: Vec<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Script {
+    /// The script tag, such as `latn`, or `DFLT`.
+    pub tag: Tag,
+    pub default_language: Option<LanguageSystem>,
+    pub languages: Vec<LanguageSystem>,
+}
+
+impl Script {
+    /// Returns the language system for `tag`, or the default one if the
+    /// script has no such language.
+    pub fn language(&self, tag: Tag) -> Option<&LanguageSystem> {
+        self.languages
+            .iter()
+            .find(|language| language.tag == tag)
+            .or(self.default_language.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Feature {
+    /// The feature tag, such as `smcp` or `ss01`.
+    pub tag: Tag,
+    /// Indices of the lookups the feature applies, in order.
+    pub lookup_indices: Vec<u16>,
+    /// For stylistic sets and character variants, the `name` table ID of
+    /// the feature's user interface name.
+    pub ui_name_id: Option<u16>,
+}
+
+/// A range of normalized coordinates on one axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxisCondition {
+    pub axis_index: u16,
+    pub min_value: f32,
+    pub max_value: f32,
+}
+
+/// Features that use other lookups where every condition holds, such as
+/// heavier dollar signs above some weight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeatureVariation {
+    /// Conditions in formats other than 1 are never met, as the spec asks.
+    pub conditions: Vec<Option<AxisCondition>>,
+    /// `(feature index, replacement lookup indices)` pairs.
+    pub substitutions: Vec<(u16, Vec<u16>)>,
+}
+
+impl FeatureVariation {
+    /// Whether every condition holds at `coordinates`, which are normalized
+    /// and in `fvar` axis order.
+    pub fn matches(&self, coordinates: &[f32]) -> bool {
+        self.conditions.iter().all(|condition| match condition {
+            Some(condition) => {
+                let value = coordinates
+                    .get(condition.axis_index as usize)
+                    .cloned()
+                    .unwrap_or(0.);
+                condition.min_value <= value && value <= condition.max_value
+            }
+            None => false,
+        })
+    }
+}
+
+/// The script, feature and feature variation lists of a `GSUB` or `GPOS`
+/// table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutTable {
+    pub scripts: Vec<Script>,
+    pub features: Vec<Feature>,
+    /// From version 1.1.
+    pub feature_variations: Vec<FeatureVariation>,
+}
+
+impl LayoutTable {
+    pub fn parse(data: &[u8]) -> Option<LayoutTable> {
+        let mut s = Stream::new(data);
+        s.skip(2)?;
+        let minor_version = s.read_u16()?;
+        let script_list = s.read_u16()? as usize;
+        let feature_list = s.read_u16()? as usize;
+        s.skip(2)?;
+        let feature_variations = if minor_version >= 1 {
+            match s.read_u32()? as usize {
+                0 => vec![],
+                offset => parse_feature_variations(data, offset)?,
+            }
+        } else {
+            vec![]
+        };
+        Some(LayoutTable {
+            scripts: parse_scripts(data, script_list)?,
+            features: parse_features(data, feature_list)?,
+            feature_variations,
+        })
+    }
+
+    pub fn script(&self, tag: Tag) -> Option<&Script> {
+        self.scripts.iter().find(|script| script.tag == tag)
+    }
+
+    /// Every feature tag in the table, sorted and without duplicates.
+    pub fn feature_tags(&self) -> Vec<Tag> {
+        let mut tags: Vec<_> = self.features.iter().map(|feature| feature.tag).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// The features a language system uses, including its required
+    /// feature, falling back to the script's default language system and
+    /// then to the `DFLT` script, as shapers do.
+    pub fn language_features(&self, script: Tag, language: Tag) -> Vec<&Feature> {
+        let language = self
+            .script(script)
+            .or_else(|| self.script(Tag(*b"DFLT")))
+            .and_then(|script| script.language(language));
+        let language = match language {
+            Some(language) => language,
+            None => return vec![],
+        };
+        language
+            .required_feature
+            .iter()
+            .chain(&language.feature_indices)
+            .filter_map(|&index| self.features.get(index as usize))
+            .collect()
+    }
+
+    /// The lookups feature `feature_index` applies at normalized variation
+    /// coordinates `coordinates`, taking the first matching feature
+    /// variation into account.
+    pub fn feature_lookups_at(&self, feature_index: u16, coordinates: &[f32]) -> Option<&[u16]> {
+        let feature = self.features.get(feature_index as usize)?;
+        let variation = self
+            .feature_variations
+            .iter()
+            .find(|variation| variation.matches(coordinates));
+        let substitution = variation.and_then(|variation| {
+            variation
+                .substitutions
+                .iter()
+                .find(|&&(index, _)| index == feature_index)
+        });
+        Some(match substitution {
+            Some((_, lookups)) => lookups,
+            None => &feature.lookup_indices,
+        })
+    }
+
+    /// The lookup indices of every feature with tag `tag`, in feature list
+    /// order and without duplicates.
+    pub(crate) fn feature_lookups(&self, tag: Tag) -> Vec<u16> {
+        let mut lookups = vec![];
+        for feature in self.features.iter().filter(|feature| feature.tag == tag) {
+            for &index in &feature.lookup_indices {
+                if !lookups.contains(&index) {
+                    lookups.push(index);
+                }
+            }
+        }
+        lookups
+    }
+}
+
+fn parse_language_system(data: &[u8], offset: usize, tag: Tag) -> Option<LanguageSystem> {
+    let mut s = Stream::new_at(data, offset)?;
+    s.skip(2)?;
+    let required_feature = Some(s.read_u16()?).filter(|&index| index != 0xffff);
+    let count = s.read_u16()?;
+    let feature_indices = (0..count).map(|_| s.read_u16()).collect::<Option<_>>()?;
+    Some(LanguageSystem {
+        tag,
+        required_feature,
+        feature_indices,
+    })
+}
+
+fn parse_scripts(data: &[u8], offset: usize) -> Option<Vec<Script>> {
+    let mut s = Stream::new_at(data, offset)?;
+    let mut scripts = vec![];
+    for _ in 0..s.read_u16()? {
+        let tag = s.read_tag()?;
+        let script = offset + s.read_u16()? as usize;
+        let mut r = Stream::new_at(data, script)?;
+        let default_language = match r.read_u16()? as usize {
+            0 => None,
+            default => Some(parse_language_system(
+                data,
+                script + default,
+                Tag(*b"dflt"),
+            )?),
+        };
+        let mut languages = vec![];
+        for _ in 0..r.read_u16()? {
+            let tag = r.read_tag()?;
+            let language = script + r.read_u16()? as usize;
+            languages.push(parse_language_system(data, language, tag)?);
+        }
+        scripts.push(Script {
+            tag,
+            default_language,
+            languages,
+        });
+    }
+    Some(scripts)
+}
+
+fn parse_feature(data: &[u8], offset: usize, tag: Tag) -> Option<Feature> {
+    let mut s = Stream::new_at(data, offset)?;
+    let params = s.read_u16()? as usize;
+    let count = s.read_u16()?;
+    let lookup_indices = (0..count).map(|_| s.read_u16()).collect::<Option<_>>()?;
+    // Stylistic set and character variant parameters both start with a
+    // version or format, then the name ID.
+    let bytes = tag.to_bytes();
+    let named = &bytes[..2] == b"ss" || &bytes[..2] == b"cv";
+    let ui_name_id = if named && params != 0 {
+        super::read_u16(data, offset + params + 2)
+    } else {
+        None
+    };
+    Some(Feature {
+        tag,
+        lookup_indices,
+        ui_name_id,
+    })
+}
+
+fn parse_features(data: &[u8], offset: usize) -> Option<Vec<Feature>> {
+    let mut s = Stream::new_at(data, offset)?;
+    let mut features = vec![];
+    for _ in 0..s.read_u16()? {
+        let tag = s.read_tag()?;
+        let feature = offset + s.read_u16()? as usize;
+        features.push(parse_feature(data, feature, tag)?);
+    }
+    Some(features)
+}
+
+fn parse_feature_variations(data: &[u8], offset: usize) -> Option<Vec<FeatureVariation>> {
+    let mut s = Stream::new_at(data, offset)?;
+    s.skip(4)?;
+    let mut variations = vec![];
+    for _ in 0..s.read_u32()? {
+        let condition_set = offset + s.read_u32()? as usize;
+        let substitution = offset + s.read_u32()? as usize;
+
+        let mut conditions = vec![];
+        let mut r = Stream::new_at(data, condition_set)?;
+        for _ in 0..r.read_u16()? {
+            let mut c = Stream::new_at(data, condition_set + r.read_u32()? as usize)?;
+            let condition = match c.read_u16()? {
+                1 => Some(AxisCondition {
+                    axis_index: c.read_u16()?,
+                    min_value: c.read_i16()? as f32 / 16384.,
+                    max_value: c.read_i16()? as f32 / 16384.,
+                }),
+                _ => None,
+            };
+            conditions.push(condition);
+        }
+
+        let mut substitutions = vec![];
+        let mut r = Stream::new_at(data, substitution)?;
+        r.skip(4)?;
+        for _ in 0..r.read_u16()? {
+            let feature_index = r.read_u16()?;
+            let feature = substitution + r.read_u32()? as usize;
+            let feature = parse_feature(data, feature, Tag([0; 4]))?;
+            substitutions.push((feature_index, feature.lookup_indices));
+        }
+        variations.push(FeatureVariation {
+            conditions,
+            substitutions,
+        });
+    }
+    Some(variations)
+}
+
+/// The lookup list of a `GSUB` or `GPOS` table.
+pub(crate) struct LookupList<'a> {
+    data: &'a [u8],
+    offset: usize,
+    extension_type: u16,
+}
+
+impl<'a> LookupList<'a> {
+    /// Finds the lookup list of a table whose extension lookups have type
+    /// `extension_type`, which is 7 in `GSUB` and 9 in `GPOS`.
+    pub fn parse(data: &'a [u8], extension_type: u16) -> Option<LookupList<'a>> {
+        Some(LookupList {
+            data,
+            offset: super::read_u16(data, 8)? as usize,
+            extension_type,
+        })
+    }
+
+    /// Returns a lookup, with extension subtables resolved to the subtables
+    /// they wrap.
+    pub fn lookup(&self, index: u16) -> Option<Lookup<'a>> {
+        let mut s = Stream::new_at(self.data, self.offset)?;
+        if index >= s.read_u16()? {
+            return None;
+        }
+        s.skip(2 * index as usize)?;
+        let start = self.offset + s.read_u16()? as usize;
+        let data = self.data.get(start..)?;
+        let mut s = Stream::new(data);
+        let mut lookup_type = s.read_u16()?;
+        s.skip(2)?;
+        let mut subtables = vec![];
+        for _ in 0..s.read_u16()? {
+            let subtable = data.get(s.read_u16()? as usize..)?;
+            if lookup_type == self.extension_type {
+                let mut extension = Stream::new(subtable);
+                extension.skip(2)?;
+                let extension_type = extension.read_u16()?;
+                let offset = extension.read_u32()? as usize;
+                subtables.push((extension_type, subtable.get(offset..)?));
+            } else {
+                subtables.push((lookup_type, subtable));
+            }
+        }
+        // Every subtable of an extension lookup wraps the same type.
+        if let Some(&(subtable_type, _)) = subtables.first() {
+            lookup_type = subtable_type;
+        }
+        Some(Lookup {
+            lookup_type,
+            subtables: subtables.into_iter().map(|(_, data)| data).collect(),
+        })
+    }
+}
+
+pub(crate) struct Lookup<'a> {
+    pub lookup_type: u16,
+    pub subtables: Vec<&'a [u8]>,
+}
+
+/// Returns the glyphs a coverage table lists, in coverage index order.
+pub(crate) fn coverage(data: &[u8]) -> Option<Vec<u16>> {
+    let mut s = Stream::new(data);
+    let format = s.read_u16()?;
+    let count = s.read_u16()?;
+    let mut glyphs = vec![];
+    match format {
+        1 => {
+            for _ in 0..count {
+                glyphs.push(s.read_u16()?);
+            }
+        }
+        2 => {
+            for _ in 0..count {
+                let start = s.read_u16()?;
+                let end = s.read_u16()?;
+                s.skip(2)?;
+                glyphs.extend(start..=end);
+            }
+        }
+        _ => return None,
+    }
+    Some(glyphs)
+}
+
+/// Returns the glyphs a class definition table assigns a nonzero class,
+/// with their classes.
+pub(crate) fn class_def(data: &[u8]) -> Option<Vec<(u16, u16)>> {
+    let mut s = Stream::new(data);
+    let mut classes = vec![];
+    match s.read_u16()? {
+        1 => {
+            let start = s.read_u16()?;
+            for glyph in start..start.saturating_add(s.read_u16()?) {
+                classes.push((glyph, s.read_u16()?));
+            }
+        }
+        2 => {
+            for _ in 0..s.read_u16()? {
+                let start = s.read_u16()?;
+                let end = s.read_u16()?;
+                let class = s.read_u16()?;
+                classes.extend((start..=end).map(|glyph| (glyph, class)));
+            }
+        }
+        _ => return None,
+    }
+    classes.retain(|&(_, class)| class != 0);
+    Some(classes)
+}
@@ -0,0 +1,185 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Pure-Rust readers for OpenType tables, used by the in-memory backend and
+//! public for tables whose contents DirectWrite doesn't expose.
+//!
+//! Everything here works on borrowed bytes, such as those returned by
+//! `FontFace::font_table`, and reports malformed data by returning `None`
+//! rather than panicking.
+
+pub mod avar;
+pub mod bitmap;
+pub mod cff;
+pub mod cmap;
+pub mod colr;
+pub mod cpal;
+pub mod fvar;
+pub mod gasp;
+pub mod glyf;
+pub mod kern;
+pub mod layout;
+pub mod name;
+pub mod os2;
+pub mod post;
+pub mod stat;
+pub mod svg;
+mod var_store;
+
+use crate::Tag;
+
+/// A big-endian cursor over a byte slice.
+#[derive(Clone, Copy)]
+pub(crate) struct Stream<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Stream<'a> {
+    pub fn new(data: &'a [u8]) -> Stream<'a> {
+        Stream { data, offset: 0 }
+    }
+
+    pub fn new_at(data: &'a [u8], offset: usize) -> Option<Stream<'a>> {
+        if offset > data.len() {
+            return None;
+        }
+        Some(Stream { data, offset })
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn skip(&mut self, len: usize) -> Option<()> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(len)?;
+        let bytes = self.data.get(self.offset..end)?;
+        self.offset = end;
+        Some(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Option<u8> {
+        self.read_bytes(1).map(|b| b[0])
+    }
+
+    pub fn read_u16(&mut self) -> Option<u16> {
+        self.read_bytes(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub fn read_i16(&mut self) -> Option<i16> {
+        self.read_u16().map(|v| v as i16)
+    }
+
+    pub fn read_u24(&mut self) -> Option<u32> {
+        self.read_bytes(3)
+            .map(|b| u32::from_be_bytes([0, b[0], b[1], b[2]]))
+    }
+
+    pub fn read_u32(&mut self) -> Option<u32> {
+        self.read_bytes(4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn read_f2dot14(&mut self) -> Option<f32> {
+        self.read_i16().map(|v| v as f32 / 16384.)
+    }
+
+    pub fn read_tag(&mut self) -> Option<Tag> {
+        self.read_bytes(4).map(|b| Tag([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Stream::new_at(data, offset)?.read_u16()
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Stream::new_at(data, offset)?.read_u32()
+}
+
+#[derive(Clone, Copy, Debug)]
+struct TableRecord {
+    tag: Tag,
+    offset: u32,
+    length: u32,
+}
+
+/// The table directory of a single face inside an sfnt or TrueType
+/// collection.
+#[derive(Clone, Debug)]
+pub(crate) struct TableDirectory {
+    records: Vec<TableRecord>,
+}
+
+impl TableDirectory {
+    pub fn parse(data: &[u8], face_index: u32) -> Option<TableDirectory> {
+        let mut offset = 0;
+        if data.get(0..4)? == b"ttcf" {
+            let num_fonts = read_u32(data, 8)?;
+            if face_index >= num_fonts {
+                return None;
+            }
+            offset = read_u32(data, 12 + 4 * face_index as usize)? as usize;
+        } else if face_index != 0 {
+            return None;
+        }
+
+        let mut s = Stream::new_at(data, offset)?;
+        s.skip(4)?;
+        let num_tables = s.read_u16()?;
+        s.skip(6)?;
+        let mut records = Vec::with_capacity(num_tables as usize);
+        for _ in 0..num_tables {
+            let tag = s.read_tag()?;
+            s.skip(4)?;
+            let offset = s.read_u32()?;
+            let length = s.read_u32()?;
+            records.push(TableRecord {
+                tag,
+                offset,
+                length,
+            });
+        }
+        Some(TableDirectory { records })
+    }
+
+    /// Returns the bytes of the table with the given tag, or `None` if the
+    /// face has no such table or its record points outside `data`.
+    pub fn table<'a>(&self, data: &'a [u8], tag: Tag) -> Option<&'a [u8]> {
+        let record = self.records.iter().find(|record| record.tag == tag)?;
+        let start = record.offset as usize;
+        let end = start.checked_add(record.length as usize)?;
+        data.get(start..end)
+    }
+}
+
+/// `head.unitsPerEm`.
+pub(crate) fn units_per_em(head: &[u8]) -> Option<u16> {
+    read_u16(head, 18)
+}
+
+/// `head.indexToLocFormat`; `true` when `loca` uses 32-bit offsets.
+pub(crate) fn long_loca(head: &[u8]) -> Option<bool> {
+    read_u16(head, 50).map(|format| format == 1)
+}
+
+/// `maxp.numGlyphs`.
+pub(crate) fn num_glyphs(maxp: &[u8]) -> Option<u16> {
+    read_u16(maxp, 4)
+}
+
+/// The advance width of `glyph_id` in font units, from `hhea` and `hmtx`.
+pub(crate) fn advance_width(hhea: &[u8], hmtx: &[u8], glyph_id: u16) -> Option<u16> {
+    let number_of_h_metrics = read_u16(hhea, 34)?;
+    if number_of_h_metrics == 0 {
+        return None;
+    }
+    // Glyphs past the last long metric share its advance.
+    let index = glyph_id.min(number_of_h_metrics - 1);
+    read_u16(hmtx, 4 * index as usize)
+}
@@ -0,0 +1,287 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `name` table: family, face and informational strings.
+//!
+//! DirectWrite only hands out one string per name, picked by locale.
+//! [`NameTable`] decodes every record, so that all of a font's localized
+//! names can be listed on any platform.
+
+use super::Stream;
+
+pub const NAME_ID_COPYRIGHT: u16 = 0;
+pub const NAME_ID_FAMILY: u16 = 1;
+pub const NAME_ID_SUBFAMILY: u16 = 2;
+pub const NAME_ID_UNIQUE_ID: u16 = 3;
+pub const NAME_ID_FULL_NAME: u16 = 4;
+pub const NAME_ID_VERSION: u16 = 5;
+pub const NAME_ID_POSTSCRIPT_NAME: u16 = 6;
+pub const NAME_ID_TRADEMARK: u16 = 7;
+pub const NAME_ID_MANUFACTURER: u16 = 8;
+pub const NAME_ID_DESIGNER: u16 = 9;
+pub const NAME_ID_DESCRIPTION: u16 = 10;
+pub const NAME_ID_VENDOR_URL: u16 = 11;
+pub const NAME_ID_DESIGNER_URL: u16 = 12;
+pub const NAME_ID_LICENSE: u16 = 13;
+pub const NAME_ID_LICENSE_URL: u16 = 14;
+pub const NAME_ID_TYPOGRAPHIC_FAMILY: u16 = 16;
+pub const NAME_ID_TYPOGRAPHIC_SUBFAMILY: u16 = 17;
+pub const NAME_ID_COMPATIBLE_FULL: u16 = 18;
+pub const NAME_ID_SAMPLE_TEXT: u16 = 19;
+pub const NAME_ID_POSTSCRIPT_CID: u16 = 20;
+pub const NAME_ID_WWS_FAMILY: u16 = 21;
+pub const NAME_ID_WWS_SUBFAMILY: u16 = 22;
+pub const NAME_ID_VARIATIONS_POSTSCRIPT_PREFIX: u16 = 25;
+
+pub const PLATFORM_UNICODE: u16 = 0;
+pub const PLATFORM_MACINTOSH: u16 = 1;
+pub const PLATFORM_ISO: u16 = 2;
+pub const PLATFORM_WINDOWS: u16 = 3;
+
+const MAC_ENCODING_ROMAN: u16 = 0;
+const LANGUAGE_EN_US: u16 = 0x0409;
+
+/// One string from the `name` table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameRecord {
+    pub platform_id: u16,
+    pub encoding_id: u16,
+    pub language_id: u16,
+    pub name_id: u16,
+    /// The undecoded string.
+    pub bytes: Vec<u8>,
+    /// The decoded string, or `None` for encodings other than UTF-16BE, Mac
+    /// Roman and the ISO platform's ASCII and Latin-1.
+    pub string: Option<String>,
+}
+
+impl NameRecord {
+    fn is_unicode(&self) -> bool {
+        self.platform_id == PLATFORM_UNICODE || self.platform_id == PLATFORM_WINDOWS
+    }
+}
+
+/// Every record in a `name` table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameTable {
+    pub records: Vec<NameRecord>,
+    /// The BCP 47 tags that format 1 tables refer to with language IDs from
+    /// 0x8000 up.
+    pub language_tags: Vec<String>,
+}
+
+impl NameTable {
+    /// Parses a `name` table. Records whose strings lie outside the table
+    /// are skipped.
+    pub fn parse(data: &[u8]) -> Option<NameTable> {
+        let mut s = Stream::new(data);
+        let format = s.read_u16()?;
+        let count = s.read_u16()?;
+        let storage_offset = s.read_u16()? as usize;
+        let storage = |offset: u16, length: u16| {
+            let start = storage_offset + offset as usize;
+            data.get(start..start + length as usize)
+        };
+
+        let mut records = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let platform_id = s.read_u16()?;
+            let encoding_id = s.read_u16()?;
+            let language_id = s.read_u16()?;
+            let name_id = s.read_u16()?;
+            let length = s.read_u16()?;
+            let offset = s.read_u16()?;
+            let bytes = match storage(offset, length) {
+                Some(bytes) => bytes,
+                None => continue,
+            };
+            records.push(NameRecord {
+                platform_id,
+                encoding_id,
+                language_id,
+                name_id,
+                bytes: bytes.to_vec(),
+                string: decode(platform_id, encoding_id, bytes),
+            });
+        }
+
+        let mut language_tags = vec![];
+        if format == 1 {
+            let count = s.read_u16()?;
+            for _ in 0..count {
+                let length = s.read_u16()?;
+                let offset = s.read_u16()?;
+                let tag = storage(offset, length).map(decode_utf16_be);
+                language_tags.push(tag.unwrap_or_default());
+            }
+        }
+        Some(NameTable {
+            records,
+            language_tags,
+        })
+    }
+
+    /// Returns the records for `name_id`, in table order.
+    pub fn records_for(&self, name_id: u16) -> impl Iterator<Item = &NameRecord> {
+        self.records
+            .iter()
+            .filter(move |record| record.name_id == name_id)
+    }
+
+    /// Finds the string for `name_id`, the way `get_locale_string` falls back
+    /// when the system locale is unavailable: US English first, then
+    /// whichever Unicode record comes first.
+    pub fn find(&self, name_id: u16) -> Option<&str> {
+        let unicode: Vec<_> = self
+            .records_for(name_id)
+            .filter(|record| record.is_unicode() && record.string.is_some())
+            .collect();
+        let record = unicode
+            .iter()
+            .find(|record| {
+                record.platform_id == PLATFORM_WINDOWS && record.language_id == LANGUAGE_EN_US
+            })
+            .or_else(|| unicode.first())?;
+        record.string.as_deref()
+    }
+
+    /// Returns the BCP 47 language tag for a record, such as `en-US`, if its
+    /// language is known.
+    pub fn language_tag<'a>(&'a self, record: &NameRecord) -> Option<&'a str> {
+        match (record.platform_id, record.language_id) {
+            (PLATFORM_UNICODE, _) | (PLATFORM_ISO, _) => None,
+            (_, id) if id >= 0x8000 => self
+                .language_tags
+                .get(id as usize - 0x8000)
+                .map(String::as_str)
+                .filter(|tag| !tag.is_empty()),
+            (PLATFORM_WINDOWS, id) => lookup(&WINDOWS_LANGUAGES, id),
+            (PLATFORM_MACINTOSH, id) => lookup(&MAC_LANGUAGES, id),
+            _ => None,
+        }
+    }
+}
+
+/// Finds the string for `name_id`, as [`NameTable::find`] does.
+pub(crate) fn find_string(data: &[u8], name_id: u16) -> Option<String> {
+    NameTable::parse(data)?.find(name_id).map(str::to_owned)
+}
+
+fn decode(platform_id: u16, encoding_id: u16, bytes: &[u8]) -> Option<String> {
+    match (platform_id, encoding_id) {
+        // Windows strings are always UTF-16BE, whatever the encoding ID.
+        (PLATFORM_UNICODE, _) | (PLATFORM_WINDOWS, _) | (PLATFORM_ISO, 1) => {
+            Some(decode_utf16_be(bytes))
+        }
+        (PLATFORM_MACINTOSH, MAC_ENCODING_ROMAN) => Some(decode_mac_roman(bytes)),
+        (PLATFORM_ISO, 0) if bytes.is_ascii() => Some(bytes.iter().map(|&b| b as char).collect()),
+        (PLATFORM_ISO, 2) => Some(bytes.iter().map(|&b| b as char).collect()),
+        _ => None,
+    }
+}
+
+fn decode_utf16_be(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+// The upper half of Mac OS Roman; the lower half is ASCII.
+const MAC_ROMAN: [char; 128] = [
+    'Ä', 'Å', 'Ç', 'É', 'Ñ', 'Ö', 'Ü', 'á', 'à', 'â', 'ä', 'ã', 'å', 'ç', 'é', 'è', //
+    'ê', 'ë', 'í', 'ì', 'î', 'ï', 'ñ', 'ó', 'ò', 'ô', 'ö', 'õ', 'ú', 'ù', 'û', 'ü', //
+    '†', '°', '¢', '£', '§', '•', '¶', 'ß', '®', '©', '™', '´', '¨', '≠', 'Æ', 'Ø', //
+    '∞', '±', '≤', '≥', '¥', 'µ', '∂', '∑', '∏', 'π', '∫', 'ª', 'º', 'Ω', 'æ', 'ø', //
+    '¿', '¡', '¬', '√', 'ƒ', '≈', '∆', '«', '»', '…', '\u{a0}', 'À', 'Ã', 'Õ', 'Œ', 'œ', //
+    '–', '—', '“', '”', '‘', '’', '÷', '◊', 'ÿ', 'Ÿ', '⁄', '€', '‹', '›', 'ﬁ', 'ﬂ', //
+    '‡', '·', '‚', '„', '‰', 'Â', 'Ê', 'Á', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', 'Ó', 'Ô', //
+    '\u{f8ff}', 'Ò', 'Ú', 'Û', 'Ù', 'ı', 'ˆ', '˜', '¯', '˘', '˙', '˚', '¸', '˝', '˛', 'ˇ', //
+];
+
+fn decode_mac_roman(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0..=0x7f => b as char,
+            _ => MAC_ROMAN[b as usize - 0x80],
+        })
+        .collect()
+}
+
+fn lookup(languages: &[(u16, &'static str)], id: u16) -> Option<&'static str> {
+    languages
+        .iter()
+        .find(|&&(language_id, _)| language_id == id)
+        .map(|&(_, tag)| tag)
+}
+
+// The most common Windows LCIDs.
+const WINDOWS_LANGUAGES: [(u16, &str); 40] = [
+    (0x0401, "ar-SA"),
+    (0x0402, "bg-BG"),
+    (0x0403, "ca-ES"),
+    (0x0404, "zh-TW"),
+    (0x0405, "cs-CZ"),
+    (0x0406, "da-DK"),
+    (0x0407, "de-DE"),
+    (0x0408, "el-GR"),
+    (0x0409, "en-US"),
+    (0x040B, "fi-FI"),
+    (0x040C, "fr-FR"),
+    (0x040D, "he-IL"),
+    (0x040E, "hu-HU"),
+    (0x0410, "it-IT"),
+    (0x0411, "ja-JP"),
+    (0x0412, "ko-KR"),
+    (0x0413, "nl-NL"),
+    (0x0414, "nb-NO"),
+    (0x0415, "pl-PL"),
+    (0x0416, "pt-BR"),
+    (0x0418, "ro-RO"),
+    (0x0419, "ru-RU"),
+    (0x041A, "hr-HR"),
+    (0x041B, "sk-SK"),
+    (0x041D, "sv-SE"),
+    (0x041E, "th-TH"),
+    (0x041F, "tr-TR"),
+    (0x0421, "id-ID"),
+    (0x0422, "uk-UA"),
+    (0x0424, "sl-SI"),
+    (0x042A, "vi-VN"),
+    (0x0439, "hi-IN"),
+    (0x0804, "zh-CN"),
+    (0x0807, "de-CH"),
+    (0x0809, "en-GB"),
+    (0x080A, "es-MX"),
+    (0x0816, "pt-PT"),
+    (0x0C04, "zh-HK"),
+    (0x0C0A, "es-ES"),
+    (0x1004, "zh-SG"),
+];
+
+// Macintosh language codes, which are indices rather than LCIDs.
+const MAC_LANGUAGES: [(u16, &str); 20] = [
+    (0, "en"),
+    (1, "fr"),
+    (2, "de"),
+    (3, "it"),
+    (4, "nl"),
+    (5, "sv"),
+    (6, "es"),
+    (7, "da"),
+    (8, "pt"),
+    (9, "no"),
+    (10, "he"),
+    (11, "ja"),
+    (12, "ar"),
+    (13, "fi"),
+    (14, "el"),
+    (17, "tr"),
+    (19, "zh-Hant"),
+    (23, "ko"),
+    (32, "ru"),
+    (33, "zh-Hans"),
+];
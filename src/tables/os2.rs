@@ -0,0 +1,409 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `OS/2` table: weight, width and style classification, vertical
+//! metrics, character coverage and embedding permissions.
+
+use super::Stream;
+use crate::Tag;
+
+const FS_SELECTION_ITALIC: u16 = 1 << 0;
+const FS_SELECTION_OBLIQUE: u16 = 1 << 9;
+
+/// The length of a version 0 table with the typographic and Windows metrics,
+/// which Apple's 68-byte version 0 tables leave out.
+const OS2_V0_LENGTH: usize = 78;
+
+/// The subset of `OS/2` that describes a font's weight, width and slope.
+///
+/// Unlike [`Os2Table`], this only needs the first 64 bytes, so it still
+/// classifies fonts whose table is truncated.
+pub(crate) struct Os2Classification {
+    pub weight_class: u16,
+    pub width_class: u16,
+    pub fs_selection: u16,
+}
+
+impl Os2Classification {
+    pub fn parse(data: &[u8]) -> Option<Os2Classification> {
+        let mut s = Stream::new(data);
+        s.skip(4)?;
+        let weight_class = s.read_u16()?;
+        let width_class = s.read_u16()?;
+        let mut s = Stream::new_at(data, 62)?;
+        let fs_selection = s.read_u16()?;
+        Some(Os2Classification {
+            weight_class,
+            width_class,
+            fs_selection,
+        })
+    }
+
+    pub fn is_italic(&self) -> bool {
+        self.fs_selection & FS_SELECTION_ITALIC != 0
+    }
+
+    pub fn is_oblique(&self) -> bool {
+        self.fs_selection & FS_SELECTION_OBLIQUE != 0
+    }
+}
+
+/// The ten-digit PANOSE classification. What each digit means depends on
+/// `family_type`; the names here are the ones for Latin text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Panose {
+    pub family_type: u8,
+    pub serif_style: u8,
+    pub weight: u8,
+    pub proportion: u8,
+    pub contrast: u8,
+    pub stroke_variation: u8,
+    pub arm_style: u8,
+    pub letterform: u8,
+    pub midline: u8,
+    pub x_height: u8,
+}
+
+impl Panose {
+    pub fn from_bytes(bytes: [u8; 10]) -> Panose {
+        Panose {
+            family_type: bytes[0],
+            serif_style: bytes[1],
+            weight: bytes[2],
+            proportion: bytes[3],
+            contrast: bytes[4],
+            stroke_variation: bytes[5],
+            arm_style: bytes[6],
+            letterform: bytes[7],
+            midline: bytes[8],
+            x_height: bytes[9],
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 10] {
+        [
+            self.family_type,
+            self.serif_style,
+            self.weight,
+            self.proportion,
+            self.contrast,
+            self.stroke_variation,
+            self.arm_style,
+            self.letterform,
+            self.midline,
+            self.x_height,
+        ]
+    }
+
+    /// Whether `proportion` says the font is monospaced, for Latin text.
+    pub fn is_monospaced(&self) -> bool {
+        self.family_type == 2 && self.proportion == 9
+    }
+}
+
+/// `ulUnicodeRange1` to `ulUnicodeRange4`: which of the 123 Unicode blocks
+/// the OpenType spec lists the font claims to cover.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UnicodeRanges(pub [u32; 4]);
+
+impl UnicodeRanges {
+    /// Whether range `bit` is set, such as 0 for Basic Latin or 48 for CJK
+    /// Symbols and Punctuation.
+    pub fn contains(&self, bit: u32) -> bool {
+        bit < 128 && self.0[bit as usize / 32] & (1 << (bit % 32)) != 0
+    }
+
+    /// The set bits, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..128).filter(move |&bit| self.contains(bit))
+    }
+}
+
+/// `ulCodePageRange1` and `ulCodePageRange2`: which code pages the font is
+/// functional for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CodePageRanges(pub [u32; 2]);
+
+// The Windows code page for each defined bit. Bits 30 and 31, the OEM and
+// symbol character sets, have no code page number.
+const CODE_PAGES: [(u32, u16); 32] = [
+    (0, 1252),
+    (1, 1250),
+    (2, 1251),
+    (3, 1253),
+    (4, 1254),
+    (5, 1255),
+    (6, 1256),
+    (7, 1257),
+    (8, 1258),
+    (16, 874),
+    (17, 932),
+    (18, 936),
+    (19, 949),
+    (20, 950),
+    (21, 1361),
+    (29, 10000),
+    (48, 869),
+    (49, 866),
+    (50, 865),
+    (51, 864),
+    (52, 863),
+    (53, 862),
+    (54, 861),
+    (55, 860),
+    (56, 857),
+    (57, 855),
+    (58, 852),
+    (59, 775),
+    (60, 737),
+    (61, 708),
+    (62, 850),
+    (63, 437),
+];
+
+impl CodePageRanges {
+    pub const SYMBOL_BIT: u32 = 31;
+
+    pub fn contains(&self, bit: u32) -> bool {
+        bit < 64 && self.0[bit as usize / 32] & (1 << (bit % 32)) != 0
+    }
+
+    /// The Windows code page numbers of the set bits, such as 1252 for
+    /// Latin 1 or 932 for Japanese Shift-JIS.
+    pub fn code_pages(&self) -> Vec<u16> {
+        CODE_PAGES
+            .iter()
+            .filter(|&&(bit, _)| self.contains(bit))
+            .map(|&(_, code_page)| code_page)
+            .collect()
+    }
+}
+
+/// `fsSelection`, the style flags.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FsSelection(pub u16);
+
+impl FsSelection {
+    fn has(&self, bit: u16) -> bool {
+        self.0 & (1 << bit) != 0
+    }
+    pub fn italic(&self) -> bool {
+        self.has(0)
+    }
+    pub fn underscore(&self) -> bool {
+        self.has(1)
+    }
+    pub fn negative(&self) -> bool {
+        self.has(2)
+    }
+    pub fn outlined(&self) -> bool {
+        self.has(3)
+    }
+    pub fn strikeout(&self) -> bool {
+        self.has(4)
+    }
+    pub fn bold(&self) -> bool {
+        self.has(5)
+    }
+    pub fn regular(&self) -> bool {
+        self.has(6)
+    }
+    /// Whether the typographic metrics, rather than the Windows ones, give
+    /// the line spacing.
+    pub fn use_typo_metrics(&self) -> bool {
+        self.has(7)
+    }
+    /// Whether the family is named after weight, width and slope alone.
+    pub fn wws(&self) -> bool {
+        self.has(8)
+    }
+    pub fn oblique(&self) -> bool {
+        self.has(9)
+    }
+}
+
+/// How a font may be embedded in documents, from the low bits of `fsType`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EmbeddingUsage {
+    /// May be embedded and installed permanently.
+    Installable,
+    /// May not be embedded without the legal owner's permission.
+    Restricted,
+    /// May be embedded in documents that are only viewed and printed.
+    PreviewAndPrint,
+    /// May be embedded in documents that are edited.
+    Editable,
+}
+
+/// The embedding permissions given by `fsType`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EmbeddingPermissions {
+    pub usage: EmbeddingUsage,
+    /// The font may not be subset before embedding.
+    pub no_subsetting: bool,
+    /// Only the font's bitmaps may be embedded.
+    pub bitmap_only: bool,
+}
+
+/// An `OS/2` table of any version. Fields that the table's version doesn't
+/// have are `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Os2Table {
+    pub version: u16,
+    pub x_avg_char_width: i16,
+    pub weight_class: u16,
+    pub width_class: u16,
+    pub fs_type: u16,
+    pub subscript_x_size: i16,
+    pub subscript_y_size: i16,
+    pub subscript_x_offset: i16,
+    pub subscript_y_offset: i16,
+    pub superscript_x_size: i16,
+    pub superscript_y_size: i16,
+    pub superscript_x_offset: i16,
+    pub superscript_y_offset: i16,
+    pub strikeout_size: i16,
+    pub strikeout_position: i16,
+    pub family_class: i16,
+    pub panose: Panose,
+    pub unicode_ranges: UnicodeRanges,
+    pub vendor_id: Tag,
+    pub fs_selection: FsSelection,
+    pub first_char_index: u16,
+    pub last_char_index: u16,
+    /// Missing from the 68-byte version 0 tables of some old Apple fonts.
+    pub typo_ascender: Option<i16>,
+    pub typo_descender: Option<i16>,
+    pub typo_line_gap: Option<i16>,
+    pub win_ascent: Option<u16>,
+    pub win_descent: Option<u16>,
+    /// Version 1 and later.
+    pub code_page_ranges: Option<CodePageRanges>,
+    /// Version 2 and later.
+    pub x_height: Option<i16>,
+    pub cap_height: Option<i16>,
+    pub default_char: Option<u16>,
+    pub break_char: Option<u16>,
+    pub max_context: Option<u16>,
+    /// Version 5 and later, in points.
+    pub lower_optical_point_size: Option<f32>,
+    pub upper_optical_point_size: Option<f32>,
+}
+
+impl Os2Table {
+    /// Parses an `OS/2` table, which must be as long as its version
+    /// requires. Version 0 tables may stop before the typographic and
+    /// Windows metrics.
+    pub fn parse(data: &[u8]) -> Option<Os2Table> {
+        let mut s = Stream::new(data);
+        let version = s.read_u16()?;
+        let x_avg_char_width = s.read_i16()?;
+        let weight_class = s.read_u16()?;
+        let width_class = s.read_u16()?;
+        let fs_type = s.read_u16()?;
+        let subscript_x_size = s.read_i16()?;
+        let subscript_y_size = s.read_i16()?;
+        let subscript_x_offset = s.read_i16()?;
+        let subscript_y_offset = s.read_i16()?;
+        let superscript_x_size = s.read_i16()?;
+        let superscript_y_size = s.read_i16()?;
+        let superscript_x_offset = s.read_i16()?;
+        let superscript_y_offset = s.read_i16()?;
+        let strikeout_size = s.read_i16()?;
+        let strikeout_position = s.read_i16()?;
+        let family_class = s.read_i16()?;
+        let mut panose = [0; 10];
+        panose.copy_from_slice(s.read_bytes(10)?);
+        let unicode_ranges =
+            UnicodeRanges([s.read_u32()?, s.read_u32()?, s.read_u32()?, s.read_u32()?]);
+        let vendor_id = s.read_tag()?;
+        let fs_selection = FsSelection(s.read_u16()?);
+        let first_char_index = s.read_u16()?;
+        let last_char_index = s.read_u16()?;
+        let metrics = if version == 0 && data.len() < OS2_V0_LENGTH {
+            None
+        } else {
+            Some((
+                s.read_i16()?,
+                s.read_i16()?,
+                s.read_i16()?,
+                s.read_u16()?,
+                s.read_u16()?,
+            ))
+        };
+
+        let mut table = Os2Table {
+            version,
+            x_avg_char_width,
+            weight_class,
+            width_class,
+            fs_type,
+            subscript_x_size,
+            subscript_y_size,
+            subscript_x_offset,
+            subscript_y_offset,
+            superscript_x_size,
+            superscript_y_size,
+            superscript_x_offset,
+            superscript_y_offset,
+            strikeout_size,
+            strikeout_position,
+            family_class,
+            panose: Panose::from_bytes(panose),
+            unicode_ranges,
+            vendor_id,
+            fs_selection,
+            first_char_index,
+            last_char_index,
+            typo_ascender: metrics.map(|metrics| metrics.0),
+            typo_descender: metrics.map(|metrics| metrics.1),
+            typo_line_gap: metrics.map(|metrics| metrics.2),
+            win_ascent: metrics.map(|metrics| metrics.3),
+            win_descent: metrics.map(|metrics| metrics.4),
+            code_page_ranges: None,
+            x_height: None,
+            cap_height: None,
+            default_char: None,
+            break_char: None,
+            max_context: None,
+            lower_optical_point_size: None,
+            upper_optical_point_size: None,
+        };
+        if version >= 1 {
+            table.code_page_ranges = Some(CodePageRanges([s.read_u32()?, s.read_u32()?]));
+        }
+        if version >= 2 {
+            table.x_height = Some(s.read_i16()?);
+            table.cap_height = Some(s.read_i16()?);
+            table.default_char = Some(s.read_u16()?);
+            table.break_char = Some(s.read_u16()?);
+            table.max_context = Some(s.read_u16()?);
+        }
+        if version >= 5 {
+            // Stored in twentieths of a point.
+            table.lower_optical_point_size = Some(s.read_u16()? as f32 / 20.);
+            table.upper_optical_point_size = Some(s.read_u16()? as f32 / 20.);
+        }
+        Some(table)
+    }
+
+    /// Interprets `fsType`. Fonts from before version 3 may set several
+    /// usage bits, in which case the least restrictive one applies.
+    pub fn embedding_permissions(&self) -> EmbeddingPermissions {
+        let usage = if self.fs_type & 0x000f == 0 {
+            EmbeddingUsage::Installable
+        } else if self.fs_type & 0x0008 != 0 {
+            EmbeddingUsage::Editable
+        } else if self.fs_type & 0x0004 != 0 {
+            EmbeddingUsage::PreviewAndPrint
+        } else {
+            EmbeddingUsage::Restricted
+        };
+        EmbeddingPermissions {
+            usage,
+            no_subsetting: self.fs_type & 0x0100 != 0,
+            bitmap_only: self.fs_type & 0x0200 != 0,
+        }
+    }
+}
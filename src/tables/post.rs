@@ -0,0 +1,136 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `post` table: PostScript metrics and, in formats 1, 2 and 2.5, glyph
+//! names.
+
+use std::convert::TryFrom;
+
+use super::Stream;
+
+/// The standard Macintosh glyph names, which formats 1, 2 and 2.5 refer to
+/// by index.
+#[rustfmt::skip]
+const MACINTOSH_GLYPH_NAMES: [&str; 258] = [
+    ".notdef", ".null", "nonmarkingreturn", "space", "exclam", "quotedbl", "numbersign", "dollar",
+    "percent", "ampersand", "quotesingle", "parenleft", "parenright", "asterisk", "plus", "comma",
+    "hyphen", "period", "slash", "zero", "one", "two", "three", "four", "five", "six", "seven",
+    "eight", "nine", "colon", "semicolon", "less", "equal", "greater", "question", "at", "A", "B",
+    "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R", "S", "T", "U",
+    "V", "W", "X", "Y", "Z", "bracketleft", "backslash", "bracketright", "asciicircum",
+    "underscore", "grave", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+    "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z", "braceleft", "bar", "braceright",
+    "asciitilde", "Adieresis", "Aring", "Ccedilla", "Eacute", "Ntilde", "Odieresis", "Udieresis",
+    "aacute", "agrave", "acircumflex", "adieresis", "atilde", "aring", "ccedilla", "eacute",
+    "egrave", "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex", "idieresis", "ntilde",
+    "oacute", "ograve", "ocircumflex", "odieresis", "otilde", "uacute", "ugrave", "ucircumflex",
+    "udieresis", "dagger", "degree", "cent", "sterling", "section", "bullet", "paragraph",
+    "germandbls", "registered", "copyright", "trademark", "acute", "dieresis", "notequal", "AE",
+    "Oslash", "infinity", "plusminus", "lessequal", "greaterequal", "yen", "mu", "partialdiff",
+    "summation", "product", "pi", "integral", "ordfeminine", "ordmasculine", "Omega", "ae",
+    "oslash", "questiondown", "exclamdown", "logicalnot", "radical", "florin", "approxequal",
+    "Delta", "guillemotleft", "guillemotright", "ellipsis", "nonbreakingspace", "Agrave", "Atilde",
+    "Otilde", "OE", "oe", "endash", "emdash", "quotedblleft", "quotedblright", "quoteleft",
+    "quoteright", "divide", "lozenge", "ydieresis", "Ydieresis", "fraction", "currency",
+    "guilsinglleft", "guilsinglright", "fi", "fl", "daggerdbl", "periodcentered", "quotesinglbase",
+    "quotedblbase", "perthousand", "Acircumflex", "Ecircumflex", "Aacute", "Edieresis", "Egrave",
+    "Iacute", "Icircumflex", "Idieresis", "Igrave", "Oacute", "Ocircumflex", "apple", "Ograve",
+    "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex", "tilde", "macron", "breve",
+    "dotaccent", "ring", "cedilla", "hungarumlaut", "ogonek", "caron", "Lslash", "lslash", "Scaron",
+    "scaron", "Zcaron", "zcaron", "brokenbar", "Eth", "eth", "Yacute", "yacute", "Thorn", "thorn",
+    "minus", "multiply", "onesuperior", "twosuperior", "threesuperior", "onehalf", "onequarter",
+    "threequarters", "franc", "Gbreve", "gbreve", "Idotaccent", "Scedilla", "scedilla", "Cacute",
+    "cacute", "Ccaron", "ccaron", "dcroat",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PostTable {
+    /// The table version, as a 16.16 fixed-point number such as
+    /// `0x00025000` for 2.5.
+    pub version: u32,
+    /// In degrees counter-clockwise from the vertical.
+    pub italic_angle: f32,
+    pub underline_position: i16,
+    pub underline_thickness: i16,
+    pub is_fixed_pitch: bool,
+    /// The name of each glyph, which is empty for formats without names.
+    pub glyph_names: Vec<String>,
+}
+
+impl PostTable {
+    pub fn parse(data: &[u8]) -> Option<PostTable> {
+        let mut s = Stream::new(data);
+        let version = s.read_u32()?;
+        let italic_angle = s.read_u32()? as i32 as f32 / 65536.;
+        let underline_position = s.read_i16()?;
+        let underline_thickness = s.read_i16()?;
+        let is_fixed_pitch = s.read_u32()? != 0;
+        // The memory usage hints.
+        s.skip(16)?;
+
+        let glyph_names = match version {
+            0x00010000 => MACINTOSH_GLYPH_NAMES
+                .iter()
+                .map(|&name| name.into())
+                .collect(),
+            0x00020000 => {
+                let count = s.read_u16()? as usize;
+                let indices = s.read_bytes(2 * count)?;
+                // Pascal strings, which glyphs refer to from index 258 up.
+                let mut names = vec![];
+                while let Some(length) = s.read_u8() {
+                    names.push(String::from_utf8_lossy(s.read_bytes(length as usize)?));
+                }
+                indices
+                    .chunks(2)
+                    .map(|index| {
+                        let index = u16::from_be_bytes([index[0], index[1]]) as usize;
+                        match MACINTOSH_GLYPH_NAMES.get(index) {
+                            Some(&name) => Some(name.into()),
+                            None => names
+                                .get(index - MACINTOSH_GLYPH_NAMES.len())
+                                .map(|name| name.to_string()),
+                        }
+                    })
+                    .collect::<Option<_>>()?
+            }
+            0x00025000 => {
+                let count = s.read_u16()? as usize;
+                let offsets = s.read_bytes(count)?;
+                offsets
+                    .iter()
+                    .enumerate()
+                    .map(|(glyph_id, &offset)| {
+                        let index = glyph_id as isize + offset as i8 as isize;
+                        let name = MACINTOSH_GLYPH_NAMES.get(usize::try_from(index).ok()?)?;
+                        Some(name.to_string())
+                    })
+                    .collect::<Option<_>>()?
+            }
+            _ => vec![],
+        };
+        Some(PostTable {
+            version,
+            italic_angle,
+            underline_position,
+            underline_thickness,
+            is_fixed_pitch,
+            glyph_names,
+        })
+    }
+
+    pub fn glyph_name(&self, glyph_id: u16) -> Option<&str> {
+        self.glyph_names
+            .get(glyph_id as usize)
+            .map(|name| name.as_str())
+    }
+
+    /// Returns the first glyph with the given name.
+    pub fn glyph_by_name(&self, name: &str) -> Option<u16> {
+        self.glyph_names
+            .iter()
+            .position(|glyph_name| glyph_name == name)
+            .map(|glyph_id| glyph_id as u16)
+    }
+}
@@ -0,0 +1,139 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `STAT` table: style attributes, naming the values along each design
+//! axis so that applications can compose face names.
+
+use super::fvar::read_fixed;
+use super::Stream;
+use crate::Tag;
+
+const OLDER_SIBLING_FONT_ATTRIBUTE: u16 = 0x0001;
+const ELIDABLE_AXIS_VALUE_NAME: u16 = 0x0002;
+
+/// A design axis, which need not be a variation axis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatAxis {
+    pub tag: Tag,
+    pub name_id: u16,
+    /// Where names for values on this axis go when composing a face name.
+    pub ordering: u16,
+}
+
+/// A named value, or for format 2 a named range, on one or more design axes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AxisValue {
+    /// 1 to 4.
+    pub format: u16,
+    /// `(design axis index, value)` pairs; several only in format 4.
+    pub values: Vec<(u16, f32)>,
+    /// The inclusive range of values the name covers, in format 2.
+    pub range: Option<(f32, f32)>,
+    /// The value this one is the style-linked counterpart of, such as 700
+    /// for 400 on `wght`, in format 3.
+    pub linked_value: Option<f32>,
+    pub name_id: u16,
+    /// The value describes a font from an older family, kept for
+    /// compatibility.
+    pub older_sibling: bool,
+    /// The name can be left out of composed names, as "Regular" usually is.
+    pub elidable: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatTable {
+    pub axes: Vec<StatAxis>,
+    pub values: Vec<AxisValue>,
+    /// The name to use when every part of a face name is elided, from
+    /// version 1.1.
+    pub elided_fallback_name_id: Option<u16>,
+}
+
+impl StatTable {
+    pub fn parse(data: &[u8]) -> Option<StatTable> {
+        let mut s = Stream::new(data);
+        s.skip(2)?;
+        let minor_version = s.read_u16()?;
+        let axis_size = s.read_u16()? as usize;
+        let axis_count = s.read_u16()? as usize;
+        let axes_offset = s.read_u32()? as usize;
+        let value_count = s.read_u16()?;
+        let values_offset = s.read_u32()? as usize;
+        let elided_fallback_name_id = if minor_version >= 1 {
+            Some(s.read_u16()?)
+        } else {
+            None
+        };
+        if axis_count > 0 && axis_size < 8 {
+            return None;
+        }
+
+        let mut axes = Vec::with_capacity(axis_count);
+        for index in 0..axis_count {
+            let mut s = Stream::new_at(data, axes_offset + index * axis_size)?;
+            axes.push(StatAxis {
+                tag: s.read_tag()?,
+                name_id: s.read_u16()?,
+                ordering: s.read_u16()?,
+            });
+        }
+
+        let mut values = Vec::with_capacity(value_count as usize);
+        let mut offsets = Stream::new_at(data, values_offset)?;
+        for _ in 0..value_count {
+            let offset = values_offset + offsets.read_u16()? as usize;
+            // Skip value formats from later versions.
+            if let Some(value) = parse_axis_value(data.get(offset..)?) {
+                values.push(value);
+            }
+        }
+        Some(StatTable {
+            axes,
+            values,
+            elided_fallback_name_id,
+        })
+    }
+
+    /// The values on axis `axis_index` named by formats 1 to 3.
+    pub fn values_for_axis(&self, axis_index: u16) -> impl Iterator<Item = &AxisValue> {
+        self.values
+            .iter()
+            .filter(move |value| value.format != 4 && value.values[0].0 == axis_index)
+    }
+}
+
+fn parse_axis_value(data: &[u8]) -> Option<AxisValue> {
+    let mut s = Stream::new(data);
+    let format = s.read_u16()?;
+    let (axis_index, axis_count) = match format {
+        1..=3 => (s.read_u16()?, 1),
+        4 => (0, s.read_u16()?),
+        _ => return None,
+    };
+    let flags = s.read_u16()?;
+    let name_id = s.read_u16()?;
+    let mut value = AxisValue {
+        format,
+        values: vec![],
+        range: None,
+        linked_value: None,
+        name_id,
+        older_sibling: flags & OLDER_SIBLING_FONT_ATTRIBUTE != 0,
+        elidable: flags & ELIDABLE_AXIS_VALUE_NAME != 0,
+    };
+    if format == 4 {
+        for _ in 0..axis_count {
+            let axis_index = s.read_u16()?;
+            value.values.push((axis_index, read_fixed(&mut s)?));
+        }
+    } else {
+        value.values.push((axis_index, read_fixed(&mut s)?));
+    }
+    match format {
+        2 => value.range = Some((read_fixed(&mut s)?, read_fixed(&mut s)?)),
+        3 => value.linked_value = Some(read_fixed(&mut s)?),
+        _ => {}
+    }
+    Some(value)
+}
@@ -0,0 +1,144 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! SVG glyph documents from the `SVG ` table.
+//!
+//! One document can hold the glyphs of a whole range, each as the element
+//! with ID `glyph<glyph ID>`, so renderers need to know which glyph to draw
+//! as well as the document.
+
+use std::ops::Range;
+
+use super::{read_u16, Stream};
+
+const GZIP_MAGIC: [u8; 3] = [0x1f, 0x8b, 8];
+
+const GZIP_HEADER_CRC: u8 = 0x02;
+const GZIP_EXTRA: u8 = 0x04;
+const GZIP_NAME: u8 = 0x08;
+const GZIP_COMMENT: u8 = 0x10;
+
+/// The most a compressed document may inflate to.
+const MAX_DOCUMENT_SIZE: usize = 1 << 26;
+
+/// An SVG document and the glyphs it draws.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SvgDocument {
+    /// The first and last glyph of the document's range.
+    pub start_glyph: u16,
+    pub end_glyph: u16,
+    /// The document's offset in the table, which is the same for every
+    /// range that shares it.
+    pub offset: u32,
+    /// The document's text, decompressed if it was gzip-compressed.
+    pub data: Vec<u8>,
+}
+
+/// A parsed `SVG ` table, which owns a copy of the table so that it can be
+/// kept and queried per glyph.
+#[derive(Clone)]
+pub struct SvgTable {
+    data: Vec<u8>,
+    document_list: usize,
+    records: Range<usize>,
+}
+
+impl SvgTable {
+    pub fn parse(data: &[u8]) -> Option<SvgTable> {
+        let mut s = Stream::new(data);
+        if s.read_u16()? != 0 {
+            return None;
+        }
+        let document_list = s.read_u32()? as usize;
+        let mut s = Stream::new_at(data, document_list)?;
+        let count = s.read_u16()? as usize;
+        let start = document_list + 2;
+        let records = start..start + 12 * count;
+        data.get(records.clone())?;
+        Some(SvgTable {
+            data: data.to_vec(),
+            document_list,
+            records,
+        })
+    }
+
+    /// Lists the `(start_glyph, end_glyph)` range of every document record,
+    /// in glyph order.
+    pub fn glyph_ranges(&self) -> Vec<(u16, u16)> {
+        self.records()
+            .chunks(12)
+            .map(|record| {
+                (
+                    u16::from_be_bytes([record[0], record[1]]),
+                    u16::from_be_bytes([record[2], record[3]]),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the document that draws `glyph_id`, or `None` if there is
+    /// none or it's malformed.
+    pub fn glyph_document(&self, glyph_id: u16) -> Option<SvgDocument> {
+        // Records are sorted by glyph range, which don't overlap.
+        let records = self.records();
+        let (mut low, mut high) = (0, records.len() / 12);
+        while low < high {
+            let middle = (low + high) / 2;
+            if read_u16(records, 12 * middle + 2)? < glyph_id {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        let mut s = Stream::new(records.get(12 * low..)?);
+        let start_glyph = s.read_u16()?;
+        let end_glyph = s.read_u16()?;
+        if start_glyph > glyph_id {
+            return None;
+        }
+        let offset = s.read_u32()?;
+        let length = s.read_u32()? as usize;
+        let start = self.document_list.checked_add(offset as usize)?;
+        let document = self.data.get(start..start.checked_add(length)?)?;
+        let data = if document.starts_with(&GZIP_MAGIC) {
+            gunzip(document)?
+        } else {
+            document.to_vec()
+        };
+        Some(SvgDocument {
+            start_glyph,
+            end_glyph,
+            offset,
+            data,
+        })
+    }
+
+    fn records(&self) -> &[u8] {
+        &self.data[self.records.clone()]
+    }
+}
+
+/// Inflates a gzip member, skipping its optional header fields. The CRC
+/// isn't checked.
+fn gunzip(data: &[u8]) -> Option<Vec<u8>> {
+    let mut s = Stream::new(data);
+    s.skip(3)?;
+    let flags = s.read_u8()?;
+    // The modification time, extra flags and operating system.
+    s.skip(6)?;
+    if flags & GZIP_EXTRA != 0 {
+        let length = s.read_bytes(2)?;
+        s.skip(u16::from_le_bytes([length[0], length[1]]) as usize)?;
+    }
+    for &field in &[GZIP_NAME, GZIP_COMMENT] {
+        if flags & field != 0 {
+            while s.read_u8()? != 0 {}
+        }
+    }
+    if flags & GZIP_HEADER_CRC != 0 {
+        s.skip(2)?;
+    }
+    let deflated = data.get(s.offset()..)?;
+    miniz_oxide::inflate::decompress_to_vec_with_limit(deflated, MAX_DOCUMENT_SIZE).ok()
+}
@@ -0,0 +1,209 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Item variation stores, which hold the deltas of variable values and the
+//! regions of the design space where they apply.
+
+use super::Stream;
+
+/// The start, peak and end of a region along one axis, in normalized
+/// coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct RegionAxis {
+    start: f32,
+    peak: f32,
+    end: f32,
+}
+
+impl RegionAxis {
+    fn scalar(&self, coordinate: f32) -> f32 {
+        let RegionAxis { start, peak, end } = *self;
+        // Regions that don't vary along the axis, or are malformed, apply
+        // everywhere.
+        if peak == 0. || start > peak || peak > end || (start < 0. && end > 0.) {
+            return 1.;
+        }
+        if coordinate == peak {
+            1.
+        } else if coordinate <= start || coordinate >= end {
+            0.
+        } else if coordinate < peak {
+            (coordinate - start) / (peak - start)
+        } else {
+            (end - coordinate) / (end - peak)
+        }
+    }
+}
+
+/// One item variation data subtable: rows of deltas, one column per
+/// region it refers to.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct ItemVariationData {
+    region_indices: Vec<u16>,
+    /// How many leading columns are words, as opposed to bytes; or 32-bit
+    /// and 16-bit values when `long_words` is set.
+    word_count: usize,
+    long_words: bool,
+    rows: Vec<u8>,
+}
+
+impl ItemVariationData {
+    fn row_size(&self) -> usize {
+        let (word, short) = if self.long_words { (4, 2) } else { (2, 1) };
+        self.word_count * word + (self.region_indices.len() - self.word_count) * short
+    }
+
+    fn deltas(&self, inner: u16) -> Option<Vec<f32>> {
+        let size = self.row_size();
+        let start = inner as usize * size;
+        let mut s = Stream::new(self.rows.get(start..start + size)?);
+        (0..self.region_indices.len())
+            .map(|column| {
+                Some(match (column < self.word_count, self.long_words) {
+                    (true, true) => s.read_u32()? as i32 as f32,
+                    (true, false) | (false, true) => s.read_i16()? as f32,
+                    (false, false) => s.read_u8()? as i8 as f32,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ItemVariationStore {
+    regions: Vec<Vec<RegionAxis>>,
+    data: Vec<ItemVariationData>,
+}
+
+impl ItemVariationStore {
+    pub fn parse(data: &[u8]) -> Option<ItemVariationStore> {
+        let mut s = Stream::new(data);
+        if s.read_u16()? != 1 {
+            return None;
+        }
+        let region_list_offset = s.read_u32()? as usize;
+        let data_count = s.read_u16()?;
+
+        let mut regions_stream = Stream::new_at(data, region_list_offset)?;
+        let axis_count = regions_stream.read_u16()?;
+        let region_count = regions_stream.read_u16()?;
+        let mut regions = Vec::with_capacity(region_count as usize);
+        for _ in 0..region_count {
+            let mut axes = Vec::with_capacity(axis_count as usize);
+            for _ in 0..axis_count {
+                axes.push(RegionAxis {
+                    start: regions_stream.read_f2dot14()?,
+                    peak: regions_stream.read_f2dot14()?,
+                    end: regions_stream.read_f2dot14()?,
+                });
+            }
+            regions.push(axes);
+        }
+
+        let mut item_data = Vec::with_capacity(data_count as usize);
+        for _ in 0..data_count {
+            let mut d = Stream::new_at(data, s.read_u32()? as usize)?;
+            let item_count = d.read_u16()? as usize;
+            let word_delta_count = d.read_u16()?;
+            let count = d.read_u16()?;
+            let mut region_indices = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                let index = d.read_u16()?;
+                if index >= region_count {
+                    return None;
+                }
+                region_indices.push(index);
+            }
+            let word_count = (word_delta_count & 0x7fff) as usize;
+            if word_count > region_indices.len() {
+                return None;
+            }
+            let mut item = ItemVariationData {
+                region_indices,
+                word_count,
+                long_words: word_delta_count & 0x8000 != 0,
+                rows: vec![],
+            };
+            item.rows = d.read_bytes(item.row_size() * item_count)?.to_vec();
+            item_data.push(item);
+        }
+        Some(ItemVariationStore {
+            regions,
+            data: item_data,
+        })
+    }
+
+    /// Returns how much each region of item variation data `outer` applies
+    /// at the normalized `coordinates`, in the data's region order.
+    pub fn region_scalars(&self, outer: u16, coordinates: &[f32]) -> Option<Vec<f32>> {
+        let indices = &self.data.get(outer as usize)?.region_indices;
+        Some(
+            indices
+                .iter()
+                .map(|&index| {
+                    self.regions[index as usize]
+                        .iter()
+                        .enumerate()
+                        .map(|(axis, region)| {
+                            region.scalar(coordinates.get(axis).cloned().unwrap_or(0.))
+                        })
+                        .product()
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the delta of item `inner` of item variation data `outer` at
+    /// the normalized `coordinates`.
+    pub fn delta(&self, outer: u16, inner: u16, coordinates: &[f32]) -> Option<f32> {
+        let deltas = self.data.get(outer as usize)?.deltas(inner)?;
+        let scalars = self.region_scalars(outer, coordinates)?;
+        Some(
+            deltas
+                .iter()
+                .zip(scalars)
+                .map(|(delta, scalar)| delta * scalar)
+                .sum(),
+        )
+    }
+}
+
+/// Maps variation indices to `(outer, inner)` item variation store indices.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct DeltaSetIndexMap {
+    entries: Vec<(u16, u16)>,
+}
+
+impl DeltaSetIndexMap {
+    pub fn parse(data: &[u8]) -> Option<DeltaSetIndexMap> {
+        let mut s = Stream::new(data);
+        let format = s.read_u8()?;
+        let entry_format = s.read_u8()?;
+        let count = match format {
+            0 => s.read_u16()? as usize,
+            1 => s.read_u32()? as usize,
+            _ => return None,
+        };
+        let inner_bits = (entry_format & 0x0f) as u32 + 1;
+        let entry_size = ((entry_format >> 4) & 0x03) as usize + 1;
+        let bytes = s.read_bytes(count.checked_mul(entry_size)?)?;
+        let entries = bytes
+            .chunks(entry_size)
+            .map(|entry| {
+                let value = entry.iter().fold(0u32, |value, &b| value << 8 | b as u32);
+                (
+                    (value >> inner_bits) as u16,
+                    (value & ((1 << inner_bits) - 1)) as u16,
+                )
+            })
+            .collect();
+        Some(DeltaSetIndexMap { entries })
+    }
+
+    /// Maps a variation index. Indices past the end use the last entry.
+    pub fn map(&self, index: u32) -> Option<(u16, u16)> {
+        let last = self.entries.len().checked_sub(1)?;
+        Some(self.entries[(index as usize).min(last)])
+    }
+}
@@ -0,0 +1,234 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::{error, fmt};
+
+use crate::DWRITE_FONT_AXIS_VALUE;
+
+/// A four-byte OpenType tag, naming a table, a variation axis, a script or a
+/// feature.
+///
+/// DirectWrite passes tags around as `u32`s with the first character in the
+/// lowest byte (see `DWRITE_MAKE_OPENTYPE_TAG`), the reverse of how they're
+/// stored in fonts; [`Tag::to_dwrite`] and [`Tag::from_dwrite`] convert.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tag(pub [u8; 4]);
+
+impl Tag {
+    pub const AVAR: Tag = Tag(*b"avar");
+    pub const CBDT: Tag = Tag(*b"CBDT");
+    pub const CBLC: Tag = Tag(*b"CBLC");
+    pub const CFF: Tag = Tag(*b"CFF ");
+    pub const CFF2: Tag = Tag(*b"CFF2");
+    pub const CMAP: Tag = Tag(*b"cmap");
+    pub const COLR: Tag = Tag(*b"COLR");
+    pub const CPAL: Tag = Tag(*b"CPAL");
+    pub const EBDT: Tag = Tag(*b"EBDT");
+    pub const EBLC: Tag = Tag(*b"EBLC");
+    pub const FVAR: Tag = Tag(*b"fvar");
+    pub const GASP: Tag = Tag(*b"gasp");
+    pub const GDEF: Tag = Tag(*b"GDEF");
+    pub const GLYF: Tag = Tag(*b"glyf");
+    pub const GPOS: Tag = Tag(*b"GPOS");
+    pub const GSUB: Tag = Tag(*b"GSUB");
+    pub const GVAR: Tag = Tag(*b"gvar");
+    pub const HEAD: Tag = Tag(*b"head");
+    pub const HHEA: Tag = Tag(*b"hhea");
+    pub const HMTX: Tag = Tag(*b"hmtx");
+    pub const KERN: Tag = Tag(*b"kern");
+    pub const LOCA: Tag = Tag(*b"loca");
+    pub const MAXP: Tag = Tag(*b"maxp");
+    pub const NAME: Tag = Tag(*b"name");
+    pub const OS2: Tag = Tag(*b"OS/2");
+    pub const POST: Tag = Tag(*b"post");
+    pub const SBIX: Tag = Tag(*b"sbix");
+    pub const STAT: Tag = Tag(*b"STAT");
+    pub const SVG: Tag = Tag(*b"SVG ");
+    pub const VHEA: Tag = Tag(*b"vhea");
+    pub const VMTX: Tag = Tag(*b"vmtx");
+
+    /// The registered variation axes.
+    pub const ITAL: Tag = Tag(*b"ital");
+    pub const OPSZ: Tag = Tag(*b"opsz");
+    pub const SLNT: Tag = Tag(*b"slnt");
+    pub const WDTH: Tag = Tag(*b"wdth");
+    pub const WGHT: Tag = Tag(*b"wght");
+
+    pub const fn new(bytes: &[u8; 4]) -> Tag {
+        Tag(*bytes)
+    }
+
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0
+    }
+
+    /// Converts from DirectWrite's byte order.
+    pub const fn from_dwrite(tag: u32) -> Tag {
+        Tag(tag.to_le_bytes())
+    }
+
+    /// Converts to DirectWrite's byte order, as `font_table` and
+    /// `DWRITE_FONT_AXIS_VALUE::axisTag` expect.
+    pub const fn to_dwrite(self) -> u32 {
+        u32::from_le_bytes(self.0)
+    }
+
+    /// Converts from the byte order tags are stored in within fonts.
+    pub const fn from_u32(tag: u32) -> Tag {
+        Tag(tag.to_be_bytes())
+    }
+
+    pub const fn to_u32(self) -> u32 {
+        u32::from_be_bytes(self.0)
+    }
+
+    /// Pairs this axis tag with a value, for `create_font_face_with_variations`.
+    pub fn axis_value(self, value: f32) -> DWRITE_FONT_AXIS_VALUE {
+        DWRITE_FONT_AXIS_VALUE {
+            axisTag: self.to_dwrite(),
+            value,
+        }
+    }
+}
+
+impl From<[u8; 4]> for Tag {
+    fn from(bytes: [u8; 4]) -> Tag {
+        Tag(bytes)
+    }
+}
+
+impl From<&[u8; 4]> for Tag {
+    fn from(bytes: &[u8; 4]) -> Tag {
+        Tag(*bytes)
+    }
+}
+
+impl From<Tag> for [u8; 4] {
+    fn from(tag: Tag) -> [u8; 4] {
+        tag.0
+    }
+}
+
+/// Writes printable characters as they are, backslashes as `\\` and other
+/// bytes as `\xNN` escapes.
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &byte in &self.0 {
+            if byte == b'\\' {
+                write!(f, "\\\\")?;
+            } else if (0x20..0x7f).contains(&byte) {
+                write!(f, "{}", byte as char)?;
+            } else {
+                write!(f, "\\x{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tag(\"{}\")", self)
+    }
+}
+
+/// The error returned when a string isn't a valid [`Tag`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseTagError;
+
+impl fmt::Display for ParseTagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tags must be one to four printable ASCII characters")
+    }
+}
+
+impl error::Error for ParseTagError {}
+
+impl FromStr for Tag {
+    type Err = ParseTagError;
+
+    /// Parses one to four printable ASCII characters, padding short tags
+    /// with spaces the way OpenType does (`"CFF"` is `"CFF "`).
+    fn from_str(s: &str) -> Result<Tag, ParseTagError> {
+        let bytes = s.as_bytes();
+        if bytes.is_empty() || bytes.len() > 4 || !bytes.iter().all(|b| (0x20..0x7f).contains(b)) {
+            return Err(ParseTagError);
+        }
+        let mut tag = [b' '; 4];
+        tag[..bytes.len()].copy_from_slice(bytes);
+        Ok(Tag(tag))
+    }
+}
+
+impl TryFrom<&str> for Tag {
+    type Error = ParseTagError;
+
+    fn try_from(s: &str) -> Result<Tag, ParseTagError> {
+        s.parse()
+    }
+}
+
+/// Tags serialize as their `Display` strings, whose escapes deserialize back
+/// to the same bytes, so that every tag round-trips in every format.
+#[cfg(feature = "serde_serialization")]
+impl serde::Serialize for Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde_serialization")]
+impl<'de> serde::Deserialize<'de> for Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Tag, D::Error> {
+        struct TagVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for TagVisitor {
+            type Value = Tag;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "one to four tag characters or escapes")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Tag, E> {
+                unescape(s).ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(s), &self))
+            }
+        }
+
+        deserializer.deserialize_str(TagVisitor)
+    }
+}
+
+/// Parses what `Display` writes, and short tags like `from_str` does.
+#[cfg(feature = "serde_serialization")]
+fn unescape(s: &str) -> Option<Tag> {
+    let mut bytes = vec![];
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        bytes.push(match byte {
+            b'\\' => match rest {
+                [b'\\', tail @ ..] => {
+                    rest = tail;
+                    b'\\'
+                }
+                [b'x', high, low, tail @ ..] => {
+                    rest = tail;
+                    let digit = |byte: u8| (byte as char).to_digit(16);
+                    (digit(*high)? * 16 + digit(*low)?) as u8
+                }
+                _ => return None,
+            },
+            0x20..=0x7e => byte,
+            _ => return None,
+        });
+    }
+    if bytes.is_empty() || bytes.len() > 4 {
+        return None;
+    }
+    let mut tag = [b' '; 4];
+    tag[..bytes.len()].copy_from_slice(&bytes);
+    Some(Tag(tag))
+}
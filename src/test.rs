@@ -3,18 +3,28 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use super::*;
+#[cfg(not(windows))]
+use crate::backend::FactoryBackend;
+use crate::backend::{FontCollectionBackend, FontFaceBackend, FontFileBackend};
+#[cfg(not(windows))]
+use crate::memory::MemoryFactory;
+use crate::memory::{MemoryFontCollection, MemoryFontFile};
+use crate::test_com;
+use crate::test_font;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 
+#[cfg(windows)]
 #[test]
 fn test_system_family_iter() {
     let system_fc = FontCollection::system();
     let count = system_fc.families_iter().count();
     assert!(count > 0);
-    assert!(system_fc
-        .families_iter()
-        .any(|f| f.name() == "Arial"));
+    assert!(system_fc.families_iter().any(|f| f.name() == "Arial"));
 }
 
+#[cfg(windows)]
 #[test]
 fn test_descriptor_round_trip() {
     let system_fc = FontCollection::system();
@@ -34,6 +44,7 @@ fn test_descriptor_round_trip() {
     assert_eq!(descriptor, descriptor2);
 }
 
+#[cfg(windows)]
 #[test]
 fn test_get_font_file_bytes() {
     let system_fc = FontCollection::system();
@@ -52,6 +63,7 @@ fn test_get_font_file_bytes() {
     assert!(!bytes.is_empty());
 }
 
+#[cfg(windows)]
 #[test]
 fn test_font_file_is_monospace() {
     let system_fc = FontCollection::system();
@@ -73,6 +85,7 @@ fn test_font_file_is_monospace() {
     assert!(courier_new_font.is_monospace() == Some(true));
 }
 
+#[cfg(windows)]
 #[test]
 fn test_create_font_file_from_bytes() {
     let system_fc = FontCollection::system();
@@ -101,6 +114,7 @@ fn test_create_font_file_from_bytes() {
     let _new_font = new_font.unwrap();
 }
 
+#[cfg(windows)]
 #[test]
 fn test_glyph_image() {
     let system_fc = FontCollection::system();
@@ -168,3 +182,3567 @@ fn test_glyph_image() {
     let bytes = rt.get_opaque_values_as_mask();
     println!("bytes length: {}", bytes.len());
 }
+
+#[derive(Clone, Debug, PartialEq)]
+enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+#[derive(Clone, Default)]
+struct RecordingOutlineBuilder(Rc<RefCell<Vec<PathCommand>>>);
+
+impl PathCommand {
+    fn points(&self) -> Vec<f32> {
+        match *self {
+            PathCommand::MoveTo(x, y) | PathCommand::LineTo(x, y) => vec![x, y],
+            PathCommand::CurveTo(x0, y0, x1, y1, x, y) => vec![x0, y0, x1, y1, x, y],
+            PathCommand::Close => vec![],
+        }
+    }
+}
+
+fn assert_path_eq(actual: &[PathCommand], expected: &[PathCommand]) {
+    let same = actual.len() == expected.len()
+        && actual.iter().zip(expected).all(|(a, e)| {
+            std::mem::discriminant(a) == std::mem::discriminant(e)
+                && a.points()
+                    .iter()
+                    .zip(e.points())
+                    .all(|(a, e)| (a - e).abs() < 1e-4)
+        });
+    assert!(same, "{:?} != {:?}", actual, expected);
+}
+
+impl OutlineBuilder for RecordingOutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.0.borrow_mut().push(PathCommand::MoveTo(x, y));
+    }
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.0.borrow_mut().push(PathCommand::LineTo(x, y));
+    }
+    fn curve_to(&mut self, cp0x: f32, cp0y: f32, cp1x: f32, cp1y: f32, x: f32, y: f32) {
+        self.0
+            .borrow_mut()
+            .push(PathCommand::CurveTo(cp0x, cp0y, cp1x, cp1y, x, y));
+    }
+    fn close(&mut self) {
+        self.0.borrow_mut().push(PathCommand::Close);
+    }
+}
+
+fn test_font_files() -> Vec<MemoryFontFile> {
+    let regular = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40).build();
+    let bold_italic = test_font::simple_font("Test Sans", "Bold Italic", 700, 5, 0x21).build();
+    let condensed = test_font::simple_font("Test Sans", "Condensed", 400, 3, 0x40).build();
+    let serif = test_font::simple_font("Test Serif", "Regular", 400, 5, 0x40).build();
+    [regular, bold_italic, condensed, serif]
+        .iter()
+        .map(|bytes| MemoryFontFile::new_from_buffer(Arc::new(bytes.clone())).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_memory_family_lookup() {
+    let collection = MemoryFontCollection::from_font_files(&test_font_files());
+    assert_eq!(collection.families().len(), 2);
+    assert_eq!(collection.families()[0].fonts().len(), 3);
+
+    let family = collection
+        .font_family_by_name("test sans")
+        .unwrap()
+        .unwrap();
+    assert_eq!(family.family_name().unwrap(), "Test Sans");
+    assert!(collection.font_family_by_name("Missing").unwrap().is_none());
+
+    let font = family
+        .first_matching_font(FontWeight::Bold, FontStretch::Normal, FontStyle::Italic)
+        .unwrap();
+    assert_eq!(font.face_name(), "Bold Italic");
+    assert_eq!(font.weight(), FontWeight::Bold);
+    assert_eq!(font.style(), FontStyle::Italic);
+
+    let font = family
+        .first_matching_font(
+            FontWeight::Regular,
+            FontStretch::Condensed,
+            FontStyle::Normal,
+        )
+        .unwrap();
+    assert_eq!(font.stretch(), FontStretch::Condensed);
+    assert_eq!(font.is_monospace(), Some(false));
+
+    // Families pick fonts the way CSS does, so a regular request prefers a
+    // medium face to a light one.
+    let files: Vec<_> = [("Light", 300), ("Medium", 500)]
+        .iter()
+        .map(|&(style, weight)| {
+            let bytes = test_font::simple_font("Test Sans", style, weight, 5, 0x40).build();
+            MemoryFontFile::new_from_buffer(Arc::new(bytes)).unwrap()
+        })
+        .collect();
+    let collection = MemoryFontCollection::from_font_files(&files);
+    let family = collection
+        .font_family_by_name("Test Sans")
+        .unwrap()
+        .unwrap();
+    let font = family
+        .first_matching_font(FontWeight::Regular, FontStretch::Normal, FontStyle::Normal)
+        .unwrap();
+    assert_eq!(font.face_name(), "Medium");
+}
+
+#[test]
+fn test_memory_collection_file() {
+    let collection = test_font::collection(&[
+        test_font::simple_font("First", "Regular", 400, 5, 0x40),
+        test_font::simple_font("Second", "Regular", 400, 5, 0x40),
+    ]);
+    let file = MemoryFontFile::new_from_buffer(Arc::new(collection)).unwrap();
+    assert_eq!(file.face_count(), 2);
+    assert_eq!(file.face_type(), FontFaceType::TrueTypeCollection);
+
+    let collection = MemoryFontCollection::from_font_files(&[file]);
+    assert!(collection.font_family_by_name("Second").unwrap().is_some());
+
+    assert!(MemoryFontFile::new_from_buffer(Arc::new(vec![0u8; 64])).is_none());
+    assert_eq!(
+        MemoryFontFile::analyze_buffer(Arc::new(b"OTTO".to_vec())),
+        0
+    );
+}
+
+#[test]
+fn test_font_file_analysis() {
+    let font = test_font::simple_font("Test", "Regular", 400, 5, 0x40).build();
+    let analysis = FontFileAnalysis::analyze(&font);
+    assert_eq!(analysis.format, FontFileFormat::TrueType);
+    assert_eq!(analysis.face_type, FontFaceType::TrueType);
+    assert_eq!(analysis.face_count, 1);
+    assert!(analysis.is_supported());
+
+    let cff = test_font::FontBuilder::new()
+        .table(b"CFF ", vec![1, 0, 4, 1])
+        .build();
+    assert_eq!(FontFileAnalysis::analyze(&cff).face_type, FontFaceType::Cff);
+
+    // Faces with embedded bitmaps but no outlines are bitmap faces, in
+    // TrueType files.
+    for &tag in &[b"EBDT", b"CBDT", b"sbix"] {
+        let bitmap_only = test_font::simple_font("Test", "Regular", 400, 5, 0x40)
+            .without(b"glyf")
+            .without(b"loca")
+            .table(tag, vec![0, 2, 0, 0])
+            .build();
+        let analysis = FontFileAnalysis::analyze(&bitmap_only);
+        assert_eq!(analysis.format, FontFileFormat::TrueType);
+        assert_eq!(analysis.face_type, FontFaceType::Bitmap);
+        assert!(analysis.is_supported());
+    }
+    let with_outlines = test_font::simple_font("Test", "Regular", 400, 5, 0x40)
+        .table(b"EBDT", vec![0, 2, 0, 0])
+        .build();
+    assert_eq!(
+        FontFileAnalysis::analyze(&with_outlines).face_type,
+        FontFaceType::TrueType
+    );
+
+    let collection = test_font::collection(&[
+        test_font::simple_font("First", "Regular", 400, 5, 0x40),
+        test_font::simple_font("Second", "Regular", 400, 5, 0x40),
+        test_font::simple_font("Third", "Regular", 400, 5, 0x40),
+    ]);
+    let analysis = FontFileAnalysis::analyze(&collection);
+    assert_eq!(analysis.face_type, FontFaceType::TrueTypeCollection);
+    assert_eq!(analysis.face_count, 3);
+
+    // A table record pointing past the end of the file.
+    let mut truncated = font.clone();
+    truncated.truncate(font.len() - 8);
+    assert_eq!(
+        FontFileAnalysis::analyze(&truncated).unsupported,
+        Some(UnsupportedFontReason::TableOutOfBounds {
+            face_index: 0,
+            tag: Tag::POST
+        })
+    );
+    assert_eq!(
+        FontFileAnalysis::analyze(b"OTTO").unsupported,
+        Some(UnsupportedFontReason::Truncated)
+    );
+    assert_eq!(
+        FontFileAnalysis::analyze(&[0u8; 64]).unsupported,
+        Some(UnsupportedFontReason::UnknownFormat)
+    );
+
+    let mut woff = Vec::new();
+    woff.extend_from_slice(b"wOFF");
+    woff.extend_from_slice(b"OTTO");
+    test_font::push_u32(&mut woff, 44);
+    woff.resize(44, 0);
+    let analysis = FontFileAnalysis::analyze(&woff);
+    assert_eq!(analysis.format, FontFileFormat::Woff);
+    assert_eq!(analysis.face_type, FontFaceType::Cff);
+    assert_eq!(
+        analysis.unsupported,
+        Some(UnsupportedFontReason::Compressed)
+    );
+
+    // A WOFF2 collection with a single untransformed `head` table.
+    let mut woff2 = Vec::new();
+    woff2.extend_from_slice(b"wOF2");
+    woff2.extend_from_slice(b"ttcf");
+    test_font::push_u32(&mut woff2, 0);
+    test_font::push_u16(&mut woff2, 1);
+    woff2.resize(48, 0);
+    woff2.extend_from_slice(&[1, 0x81, 0x00]);
+    test_font::push_u32(&mut woff2, 0x0002_0000);
+    woff2.push(2);
+    let len = woff2.len() as u32;
+    woff2[8..12].copy_from_slice(&len.to_be_bytes());
+    let analysis = FontFileAnalysis::analyze(&woff2);
+    assert_eq!(analysis.format, FontFileFormat::Woff2);
+    assert_eq!(analysis.face_type, FontFaceType::TrueTypeCollection);
+    assert_eq!(analysis.face_count, 2);
+
+    let analysis = FontFileAnalysis::analyze(b"\x80\x01\x10\x00\x00\x00%!PS-AdobeFont-1.0");
+    assert_eq!(analysis.face_type, FontFaceType::Type1);
+    assert_eq!(analysis.unsupported, Some(UnsupportedFontReason::Type1));
+
+    // A 16-bit executable with two raster `RT_FONT` resources.
+    let mut fon = vec![0u8; 0x80];
+    fon[0..2].copy_from_slice(b"MZ");
+    fon[0x3c] = 0x40;
+    fon[0x40..0x42].copy_from_slice(b"NE");
+    fon[0x64] = 0x10;
+    // Resource table at 0x50: alignment shift, then one `RT_FONT` type
+    // entry with two resources at 0x100.
+    fon[0x50] = 4;
+    fon[0x52..0x54].copy_from_slice(&0x8008u16.to_le_bytes());
+    fon[0x54] = 2;
+    fon[0x5a..0x5c].copy_from_slice(&0x10u16.to_le_bytes());
+    fon[0x66..0x68].copy_from_slice(&0x10u16.to_le_bytes());
+    fon.resize(0x180, 0);
+    let analysis = FontFileAnalysis::analyze(&fon);
+    assert_eq!(analysis.format, FontFileFormat::Fon);
+    assert_eq!(analysis.face_type, FontFaceType::Bitmap);
+    assert_eq!(analysis.face_count, 2);
+    assert_eq!(analysis.unsupported, Some(UnsupportedFontReason::Fon));
+
+    assert_eq!(
+        MemoryFontFile::analyze_buffer(Arc::new(woff)),
+        0,
+        "the memory backend only reads what DirectWrite would"
+    );
+}
+
+#[test]
+fn test_memory_glyph_indices_and_tables() {
+    let file = &test_font_files()[0];
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+    assert_eq!(face.glyph_count(), 3);
+    assert_eq!(
+        face.glyph_indices(&['A' as u32, 'B' as u32, 'C' as u32, 0x1F600])
+            .unwrap(),
+        vec![1, 2, 0, 0]
+    );
+
+    // Tags are byte-reversed, as with DirectWrite.
+    let maxp = face
+        .font_table(u32::from_be_bytes(*b"maxp").swap_bytes())
+        .unwrap()
+        .unwrap();
+    assert_eq!(&maxp[4..6], &[0, 3]);
+    assert!(face
+        .font_table(u32::from_be_bytes(*b"GSUB").swap_bytes())
+        .unwrap()
+        .is_none());
+    assert_eq!(face.files().unwrap()[0].font_file_bytes().unwrap().len(), {
+        let bytes: &[u8] = &file.font_file_bytes().unwrap();
+        bytes.len()
+    });
+}
+
+#[test]
+fn test_memory_glyph_run_outline() {
+    use crate::sys::shared::winerror::E_NOTIMPL;
+
+    let face = test_font_files()[0]
+        .create_face(0, DWRITE_FONT_SIMULATIONS_NONE)
+        .unwrap();
+    let builder = RecordingOutlineBuilder::default();
+    face.glyph_run_outline(
+        10.,
+        &[1, 2],
+        None,
+        None,
+        false,
+        false,
+        Box::new(builder.clone()),
+    )
+    .unwrap();
+    let commands = builder.0.borrow();
+    assert_path_eq(
+        &commands,
+        &[
+            PathCommand::MoveTo(1., 0.),
+            PathCommand::LineTo(1., -5.),
+            PathCommand::LineTo(5., -5.),
+            PathCommand::LineTo(5., 0.),
+            PathCommand::Close,
+            PathCommand::MoveTo(6., 0.),
+            PathCommand::CurveTo(7.666667, -3.333333, 9.333333, -3.333333, 11., 0.),
+            PathCommand::Close,
+        ],
+    );
+
+    let result = face.glyph_run_outline(
+        10.,
+        &[1, 2],
+        Some(&[1.]),
+        None,
+        false,
+        false,
+        Box::new(builder.clone()),
+    );
+    assert!(matches!(result, Err(GlyphRunOutlineError::InvalidInput)));
+
+    // Simulations aren't applied, so faces with them can't be outlined.
+    let file = &test_font_files()[0];
+    for &simulations in &[
+        DWRITE_FONT_SIMULATIONS_BOLD,
+        DWRITE_FONT_SIMULATIONS_OBLIQUE,
+    ] {
+        let face = file.create_face(0, simulations).unwrap();
+        let builder = Box::new(RecordingOutlineBuilder::default());
+        let result = face.glyph_run_outline(10., &[1], None, None, false, false, builder);
+        assert!(matches!(
+            result,
+            Err(GlyphRunOutlineError::Win32Error(E_NOTIMPL))
+        ));
+    }
+
+    // Nor are `gvar` variations, so variable `glyf` faces only outline at
+    // their default instance.
+    let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+        .table(
+            b"fvar",
+            test_font::fvar(&[(b"wght", 100., 400., 900., 256)], &[]),
+        )
+        .table(b"gvar", vec![0; 20])
+        .build();
+    let file = MemoryFontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+    assert_path_eq(&memory_outline(&face, 1), &commands[..5]);
+    let bold = face
+        .create_font_face_with_variations(
+            DWRITE_FONT_SIMULATIONS_NONE,
+            &[Tag::WGHT.axis_value(900.)],
+        )
+        .unwrap();
+    let builder = Box::new(RecordingOutlineBuilder::default());
+    let result = bold.glyph_run_outline(10., &[1], None, None, false, false, builder);
+    assert!(matches!(
+        result,
+        Err(GlyphRunOutlineError::Win32Error(E_NOTIMPL))
+    ));
+}
+
+fn composite_glyf_font() -> test_font::FontBuilder {
+    use test_font::composite_glyph;
+
+    let square = test_font::simple_glyph(&[&[
+        (100, 0, true),
+        (100, 500, true),
+        (500, 500, true),
+        (500, 0, true),
+    ]]);
+    let bump = test_font::simple_glyph(&[&[(0, 0, true), (250, 500, false), (500, 0, true)]]);
+    // Offsets with a uniform scale, a rotation, and an offset scaled along
+    // with the component.
+    let transformed = composite_glyph(&[
+        (1, 0x0002, -100, 0, &[0.5]),
+        (2, 0x0002, 600, 0, &[0., 1., -1., 0.]),
+        (1, 0x0802, 100, 100, &[0.5, 0.25]),
+    ]);
+    // The second square's first point sits on the first square's third.
+    let matched = composite_glyph(&[(1, 0x0002, 0, 0, &[]), (1, 0, 2, 0, &[])]);
+    let cycle = composite_glyph(&[(5, 0x0002, 0, 0, &[])]);
+    let (glyf, loca) =
+        test_font::glyf_and_loca(&[vec![], square, bump, transformed, matched, cycle]);
+    test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+        .table(b"maxp", test_font::maxp(6))
+        .table(b"glyf", glyf)
+        .table(b"loca", loca)
+}
+
+#[test]
+fn test_glyf_outlines() {
+    use crate::tables::glyf::{GlyfTable, Point};
+
+    let bytes = composite_glyf_font().build();
+    let file = MemoryFontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+    let table = |tag: &[u8; 4]| face.font_table(Tag::new(tag).to_dwrite()).unwrap().unwrap();
+    let (head, loca, glyf) = (table(b"head"), table(b"loca"), table(b"glyf"));
+    let glyf = GlyfTable::parse(&head, &loca, &glyf).unwrap();
+    assert_eq!(glyf.units_per_em(), 1000);
+    assert_eq!(glyf.contours(0), Some(vec![]));
+    assert_eq!(
+        glyf.contours(2),
+        Some(vec![vec![
+            Point {
+                x: 0.,
+                y: 0.,
+                on_curve: true
+            },
+            Point {
+                x: 250.,
+                y: 500.,
+                on_curve: false
+            },
+            Point {
+                x: 500.,
+                y: 0.,
+                on_curve: true
+            },
+        ]])
+    );
+
+    let outline = |glyph_id| {
+        let builder = RecordingOutlineBuilder::default();
+        glyf.outline(glyph_id, 10., &mut builder.clone())
+            .map(|()| builder.0.borrow().clone())
+    };
+    let transformed = [
+        PathCommand::MoveTo(-0.5, 0.),
+        PathCommand::LineTo(-0.5, -2.5),
+        PathCommand::LineTo(1.5, -2.5),
+        PathCommand::LineTo(1.5, 0.),
+        PathCommand::Close,
+        PathCommand::MoveTo(6., 0.),
+        PathCommand::CurveTo(2.666667, -1.666667, 2.666667, -3.333333, 6., -5.),
+        PathCommand::Close,
+        PathCommand::MoveTo(1., -0.25),
+        PathCommand::LineTo(1., -1.5),
+        PathCommand::LineTo(3., -1.5),
+        PathCommand::LineTo(3., -0.25),
+        PathCommand::Close,
+    ];
+    assert_path_eq(&outline(3).unwrap(), &transformed);
+    assert_path_eq(
+        &outline(4).unwrap()[5..],
+        &[
+            PathCommand::MoveTo(5., -5.),
+            PathCommand::LineTo(5., -10.),
+            PathCommand::LineTo(9., -10.),
+            PathCommand::LineTo(9., -5.),
+            PathCommand::Close,
+        ],
+    );
+    // Components that refer to themselves, or to missing glyphs, are
+    // malformed.
+    assert_eq!(glyf.contours(5), None);
+    assert_eq!(outline(6), None);
+
+    // The in-memory backend draws composites through the same decoder.
+    let builder = RecordingOutlineBuilder::default();
+    face.glyph_run_outline(
+        10.,
+        &[3],
+        None,
+        None,
+        false,
+        false,
+        Box::new(builder.clone()),
+    )
+    .unwrap();
+    assert_path_eq(&builder.0.borrow(), &transformed);
+    let result = face.glyph_run_outline(
+        10.,
+        &[5],
+        None,
+        None,
+        false,
+        false,
+        Box::new(RecordingOutlineBuilder::default()),
+    );
+    assert!(matches!(result, Err(GlyphRunOutlineError::Win32Error(_))));
+}
+
+fn memory_outline(face: &dyn FontFaceBackend, glyph_id: u16) -> Vec<PathCommand> {
+    let builder = RecordingOutlineBuilder::default();
+    let builder_box = Box::new(builder.clone());
+    face.glyph_run_outline(
+        10.,
+        &[glyph_id],
+        Some(&[0.]),
+        None,
+        false,
+        false,
+        builder_box,
+    )
+    .unwrap();
+    let commands = builder.0.borrow();
+    commands.clone()
+}
+
+#[test]
+fn test_cff_outlines() {
+    use crate::tables::cff::CffTable;
+    use test_font::charstring;
+
+    let square = [
+        PathCommand::MoveTo(1., 0.),
+        PathCommand::LineTo(1., -5.),
+        PathCommand::LineTo(5., -5.),
+        PathCommand::LineTo(5., 0.),
+        PathCommand::Close,
+    ];
+    let curves = [
+        PathCommand::MoveTo(0., 0.),
+        PathCommand::CurveTo(1., -2., 3., -2., 4., 0.),
+        PathCommand::CurveTo(3., 0., 2., 1., 2., 0.),
+        PathCommand::Close,
+    ];
+
+    let glyphs = [
+        charstring(&[(&[], &[14])]),
+        // A width, a stem and its hint mask, then the square.
+        charstring(&[
+            (&[600, 0, 50], &[1]),
+            (&[], &[19, 0x80]),
+            (&[100, 0], &[21]),
+            (&[500, 400, -500], &[7]),
+            (&[], &[14]),
+        ]),
+        // Curves from a local and a global subroutine.
+        charstring(&[
+            (&[0, 0], &[21]),
+            (&[-107], &[10]),
+            (&[-107], &[29]),
+            (&[], &[14]),
+        ]),
+        // The square with the curves as an accent, through `seac`.
+        charstring(&[(&[100, 300, 'A' as i32, 'B' as i32], &[14])]),
+        charstring(&[
+            (&[0, 0], &[21]),
+            (&[100, 100, 50, 100, 100, 100, 100], &[12, 34]),
+            (&[], &[14]),
+        ]),
+        // A line before any move.
+        charstring(&[(&[100], &[6]), (&[], &[14])]),
+    ];
+    let local_subrs = [charstring(&[
+        (&[100, 200, 200, 0, 100, -200], &[8]),
+        (&[], &[11]),
+    ])];
+    let global_subrs = [charstring(&[
+        (&[-100, -100, -100, 100], &[31]),
+        (&[], &[11]),
+    ])];
+    // `A` and `B` are standard strings 34 and 35.
+    let cff = test_font::cff(
+        &glyphs,
+        &global_subrs,
+        &local_subrs,
+        &[34, 35, 400, 401, 402],
+        &[],
+    );
+    let table = CffTable::parse(&test_font::head(1000), &cff).unwrap();
+    assert!(!table.is_cff2());
+    assert_eq!(table.glyph_count(), 6);
+    let builder = RecordingOutlineBuilder::default();
+    table.outline(2, 10., &[], &mut builder.clone()).unwrap();
+    assert_path_eq(&builder.0.borrow(), &curves);
+    assert!(table.outline(5, 10., &[], &mut builder.clone()).is_none());
+    assert!(table.outline(6, 10., &[], &mut builder.clone()).is_none());
+    assert_eq!(builder.0.borrow().len(), curves.len());
+
+    let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+        .without(b"glyf")
+        .without(b"loca")
+        .table(b"maxp", test_font::maxp(6))
+        .table(b"CFF ", cff)
+        .build();
+    let file = MemoryFontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+    assert_eq!(file.face_type(), FontFaceType::Cff);
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+    assert_eq!(memory_outline(&face, 0), vec![]);
+    assert_path_eq(&memory_outline(&face, 1), &square);
+    let accent = curves.iter().map(|command| match *command {
+        PathCommand::MoveTo(x, y) => PathCommand::MoveTo(x + 1., y - 3.),
+        PathCommand::CurveTo(x1, y1, x2, y2, x, y) => {
+            PathCommand::CurveTo(x1 + 1., y1 - 3., x2 + 1., y2 - 3., x + 1., y - 3.)
+        }
+        ref command => command.clone(),
+    });
+    let accented: Vec<_> = square.iter().cloned().chain(accent).collect();
+    assert_path_eq(&memory_outline(&face, 3), &accented);
+    assert_path_eq(
+        &memory_outline(&face, 4),
+        &[
+            PathCommand::MoveTo(0., 0.),
+            PathCommand::CurveTo(1., 0., 2., -0.5, 3., -0.5),
+            PathCommand::CurveTo(4., -0.5, 5., 0., 6., 0.),
+            PathCommand::Close,
+        ],
+    );
+    let result = face.glyph_run_outline(
+        10.,
+        &[5],
+        None,
+        None,
+        false,
+        false,
+        Box::new(RecordingOutlineBuilder::default()),
+    );
+    assert!(matches!(result, Err(GlyphRunOutlineError::Win32Error(_))));
+
+    // A variable `CFF2` square that moves right with the weight.
+    let glyphs = [
+        vec![],
+        charstring(&[
+            (&[0], &[15]),
+            (&[100, 50, 1], &[16]),
+            (&[0], &[21]),
+            (&[-107], &[10]),
+        ]),
+    ];
+    let local_subrs = [charstring(&[(&[500, 400, -500], &[7])])];
+    let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+        .without(b"glyf")
+        .without(b"loca")
+        .table(b"CFF2", test_font::cff2(&glyphs, &[], &local_subrs))
+        .table(
+            b"fvar",
+            test_font::fvar(&[(b"wght", 100., 400., 900., 256)], &[]),
+        )
+        .build();
+    let file = MemoryFontFile::new_from_buffer(Arc::new(bytes.clone())).unwrap();
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+    assert_eq!(memory_outline(&face, 0), vec![]);
+    assert_path_eq(&memory_outline(&face, 1), &square);
+    let variations = face.variations().unwrap();
+    assert_eq!(
+        (variations[0].axisTag, variations[0].value),
+        (Tag::WGHT.to_dwrite(), 400.)
+    );
+
+    let shifted = |dx: f32| {
+        let mut commands = square.to_vec();
+        for command in &mut commands {
+            if let PathCommand::MoveTo(x, _) | PathCommand::LineTo(x, _) = command {
+                *x += dx;
+            }
+        }
+        commands
+    };
+    for &(weight, dx) in &[(650., 0.25), (900., 0.5), (2000., 0.5), (100., 0.)] {
+        let bold = face
+            .create_font_face_with_variations(
+                DWRITE_FONT_SIMULATIONS_NONE,
+                &[
+                    Tag::new(b"wdth").axis_value(50.),
+                    Tag::WGHT.axis_value(weight),
+                ],
+            )
+            .unwrap();
+        assert_eq!(bold.variations().unwrap()[0].value, weight.min(900.));
+        assert_path_eq(&memory_outline(&*bold, 1), &shifted(dx));
+    }
+
+    // Off Windows, the public font faces vary the same way.
+    #[cfg(not(windows))]
+    {
+        let file = FontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+        let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+        assert!(face.has_variations());
+        let bold = face
+            .create_font_face_with_variations_by_tag(
+                DWRITE_FONT_SIMULATIONS_NONE,
+                &[(Tag::WGHT, 900.)],
+            )
+            .unwrap();
+        assert_eq!(bold.variation_by_tag(Tag::WGHT), Ok(Some(900.)));
+        assert_eq!(bold.variation_by_tag(Tag::WDTH), Ok(None));
+        let builder = RecordingOutlineBuilder::default();
+        let builder_box = Box::new(builder.clone());
+        bold.glyph_run_outline(10., &[1], Some(&[0.]), None, false, false, builder_box)
+            .unwrap();
+        assert_path_eq(&builder.0.borrow(), &shifted(0.5));
+    }
+}
+
+// Checks the pure-Rust decoder against DirectWrite's own outlines, for
+// simple glyphs and for accented letters built from components.
+#[cfg(windows)]
+#[test]
+fn test_glyf_outlines_match_directwrite() {
+    use crate::tables::glyf::GlyfTable;
+
+    let system_fc = FontCollection::system();
+    let arial_family = system_fc.font_family_by_name("Arial").unwrap().unwrap();
+    let face = arial_family
+        .first_matching_font(FontWeight::Regular, FontStretch::Normal, FontStyle::Normal)
+        .unwrap()
+        .create_font_face();
+    let head = face.font_table_by_tag(Tag::HEAD).unwrap().unwrap();
+    let loca = face.font_table_by_tag(Tag::LOCA).unwrap().unwrap();
+    let glyf = face.font_table_by_tag(Tag::GLYF).unwrap().unwrap();
+    let glyf = GlyfTable::parse(&head, &loca, &glyf).unwrap();
+
+    let code_points: Vec<u32> = "AOg&\u{c5}\u{e9}\u{1ec7}"
+        .chars()
+        .map(|c| c as u32)
+        .collect();
+    for glyph_id in face.glyph_indices(&code_points).unwrap() {
+        let expected = RecordingOutlineBuilder::default();
+        face.glyph_run_outline(
+            1.,
+            &[glyph_id],
+            Some(&[0.]),
+            None,
+            false,
+            false,
+            Box::new(expected.clone()),
+        )
+        .unwrap();
+        let actual = RecordingOutlineBuilder::default();
+        glyf.outline(glyph_id, 1., &mut actual.clone()).unwrap();
+        assert_path_eq(&actual.0.borrow(), &expected.0.borrow());
+    }
+}
+
+// The tables and outlines of a few DejaVu Sans glyphs, which
+// `test_glyf_outlines_match_recording` checks the decoder against.
+#[cfg(feature = "serde_serialization")]
+const GLYF_FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/dejavu_sans_glyf.json"
+);
+
+// Records DirectWrite's outlines of a few simple and composite glyphs of the
+// DejaVu Sans file at `$DEJAVU_SANS`, keeping only their records (and those
+// of their components) in `glyf`. Run it on Windows with
+// `DEJAVU_SANS=... cargo test record_glyf_fixture -- --ignored`.
+#[cfg(all(windows, feature = "serde_serialization"))]
+#[test]
+#[ignore]
+fn record_glyf_fixture() {
+    use crate::replay::RecordingFontCollection;
+    use crate::tables::glyf::GlyfTable;
+
+    let path = std::env::var("DEJAVU_SANS").expect("DEJAVU_SANS isn't set");
+    let file = FontFile::new_from_buffer(Arc::new(std::fs::read(path).unwrap())).unwrap();
+    let collection = FontCollection::from_loader(CustomFontCollectionLoaderImpl::new(&[file]));
+    let recording = RecordingFontCollection::new(Box::new(collection));
+    let face = recording
+        .font_family_by_name("DejaVu Sans")
+        .unwrap()
+        .unwrap()
+        .first_matching_font(FontWeight::Regular, FontStretch::Normal, FontStyle::Normal)
+        .unwrap()
+        .create_font_face();
+    let code_points: Vec<u32> = "AOg&\u{c5}\u{e9}\u{1ec7}"
+        .chars()
+        .map(|c| c as u32)
+        .collect();
+    let glyph_ids = face.glyph_indices(&code_points).unwrap();
+    let tables: Vec<Vec<u8>> = [Tag::HEAD, Tag::LOCA, Tag::GLYF]
+        .iter()
+        .map(|tag| face.font_table(tag.to_dwrite()).unwrap().unwrap())
+        .collect();
+    for &glyph_id in &glyph_ids {
+        let builder = Box::new(RecordingOutlineBuilder::default());
+        face.glyph_run_outline(1., &[glyph_id], Some(&[0.]), None, false, false, builder)
+            .unwrap();
+    }
+
+    // Keep the fixture small.
+    let glyf = GlyfTable::parse(&tables[0], &tables[1], &tables[2]).unwrap();
+    let mut kept = glyph_ids.clone();
+    let mut i = 0;
+    while i < kept.len() {
+        let data = glyf.glyph_data(kept[i]).unwrap();
+        for component in glyf_components(data) {
+            if !kept.contains(&component) {
+                kept.push(component);
+            }
+        }
+        i += 1;
+    }
+    let mut head = tables[0].clone();
+    head[50..52].copy_from_slice(&1u16.to_be_bytes());
+    let (mut loca, mut subset) = (vec![], vec![]);
+    for glyph_id in 0..face.glyph_count() {
+        loca.extend_from_slice(&(subset.len() as u32).to_be_bytes());
+        if kept.contains(&glyph_id) {
+            subset.extend_from_slice(glyf.glyph_data(glyph_id).unwrap());
+            subset.resize((subset.len() + 3) & !3, 0);
+        }
+    }
+    loca.extend_from_slice(&(subset.len() as u32).to_be_bytes());
+
+    let mut fixture = recording.fixture();
+    let face_record = &mut fixture.families[0].fonts[0].face;
+    for (tag, table) in [(Tag::HEAD, head), (Tag::LOCA, loca), (Tag::GLYF, subset)] {
+        face_record.tables.insert(tag.to_dwrite(), Ok(Some(table)));
+    }
+    std::fs::create_dir_all(std::path::Path::new(GLYF_FIXTURE).parent().unwrap()).unwrap();
+    std::fs::write(GLYF_FIXTURE, serde_json::to_string(&fixture).unwrap()).unwrap();
+}
+
+// The glyphs a composite glyph record is built from.
+#[cfg(all(windows, feature = "serde_serialization"))]
+fn glyf_components(data: &[u8]) -> Vec<u16> {
+    let read_u16 = |offset: usize| u16::from_be_bytes([data[offset], data[offset + 1]]);
+    let mut components = vec![];
+    if data.is_empty() || (read_u16(0) as i16) >= 0 {
+        return components;
+    }
+    let mut offset = 10;
+    loop {
+        let flags = read_u16(offset);
+        components.push(read_u16(offset + 2));
+        // Skip the arguments, then any scale or 2x2 transform.
+        offset += if flags & 0x0001 != 0 { 8 } else { 6 };
+        if flags & 0x0008 != 0 {
+            offset += 2;
+        } else if flags & 0x0040 != 0 {
+            offset += 4;
+        } else if flags & 0x0080 != 0 {
+            offset += 8;
+        }
+        if flags & 0x0020 == 0 {
+            return components;
+        }
+    }
+}
+
+// Checks the pure-Rust decoder against recorded outlines on every platform.
+// The committed outlines were taken from FreeType, unhinted at one pixel per
+// font unit, and converted to what a DirectWrite geometry sink reports;
+// `record_glyf_fixture` replaces them with DirectWrite's own.
+#[cfg(feature = "serde_serialization")]
+#[test]
+fn test_glyf_outlines_match_recording() {
+    use crate::replay::{Fixture, OutlineCommand};
+    use crate::tables::glyf::GlyfTable;
+
+    let json = std::fs::read_to_string(GLYF_FIXTURE).unwrap();
+    let fixture: Fixture = serde_json::from_str(&json).unwrap();
+    let face = &fixture.families[0].fonts[0].face;
+    let table = |tag: Tag| face.tables[&tag.to_dwrite()].clone().unwrap().unwrap();
+    let (head, loca, glyf) = (table(Tag::HEAD), table(Tag::LOCA), table(Tag::GLYF));
+    let glyf = GlyfTable::parse(&head, &loca, &glyf).unwrap();
+
+    assert!(!face.outlines.is_empty());
+    for outline in &face.outlines {
+        let expected: Vec<PathCommand> = outline
+            .result
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|command| match *command {
+                OutlineCommand::MoveTo(x, y) => PathCommand::MoveTo(x, y),
+                OutlineCommand::LineTo(x, y) => PathCommand::LineTo(x, y),
+                OutlineCommand::CurveTo(x0, y0, x1, y1, x, y) => {
+                    PathCommand::CurveTo(x0, y0, x1, y1, x, y)
+                }
+                OutlineCommand::Close => PathCommand::Close,
+            })
+            .collect();
+        let actual = RecordingOutlineBuilder::default();
+        glyf.outline(
+            outline.glyph_indices[0],
+            outline.em_size,
+            &mut actual.clone(),
+        )
+        .unwrap();
+        assert_path_eq(&actual.0.borrow(), &expected);
+    }
+}
+
+#[test]
+fn test_tag() {
+    let tag = Tag::new(b"wght");
+    assert_eq!(tag, Tag::WGHT);
+    assert_eq!("wght".parse::<Tag>().unwrap(), tag);
+    assert_eq!(Tag::from(*b"wght"), tag);
+    assert_eq!(tag.to_string(), "wght");
+    assert_eq!(format!("{:?}", tag), "Tag(\"wght\")");
+    assert_eq!("CFF".parse::<Tag>().unwrap(), Tag::CFF);
+    assert_eq!("".parse::<Tag>(), Err(ParseTagError));
+    assert_eq!("weight".parse::<Tag>(), Err(ParseTagError));
+    assert_eq!("w\u{e9}".parse::<Tag>(), Err(ParseTagError));
+    assert_eq!(Tag([0, 1, 0, 0]).to_string(), "\\x00\\x01\\x00\\x00");
+    assert_eq!(Tag(*b"\\x41").to_string(), "\\\\x41");
+
+    // Tags serialize as strings, escapes and all, and round-trip.
+    #[cfg(feature = "serde_serialization")]
+    {
+        for tag in [
+            Tag::CFF,
+            Tag([0, 0, 0, 1]),
+            Tag(*b"a\tb\xff"),
+            Tag(*b"\\x41"),
+        ] {
+            let json = serde_json::to_string(&tag).unwrap();
+            assert_eq!(serde_json::from_str::<Tag>(&json).unwrap(), tag);
+        }
+        assert_eq!(serde_json::to_string(&Tag::WGHT).unwrap(), r#""wght""#);
+        assert_eq!(serde_json::from_str::<Tag>(r#""CFF""#).unwrap(), Tag::CFF);
+        for json in [r#""\\x4""#, r#""\\y""#, r#""wght ""#, "1413568116"] {
+            assert!(serde_json::from_str::<Tag>(json).is_err(), "{}", json);
+        }
+    }
+
+    // DirectWrite's tags have the first character in the lowest byte.
+    #[cfg(windows)]
+    assert_eq!(
+        Tag::WGHT.to_dwrite(),
+        winapi::um::dwrite_3::DWRITE_FONT_AXIS_TAG_WEIGHT
+    );
+    assert_eq!(Tag::WGHT.to_dwrite(), u32::from_le_bytes(*b"wght"));
+    assert_eq!(Tag::from_dwrite(Tag::HEAD.to_dwrite()), Tag::HEAD);
+    assert_eq!(Tag::HEAD.to_u32(), 0x68656164);
+    assert_eq!(Tag::from_u32(0x68656164), Tag::HEAD);
+    let axis_value = Tag::SLNT.axis_value(-12.);
+    assert_eq!(Tag::from_dwrite(axis_value.axisTag), Tag::SLNT);
+
+    let file = &test_font_files()[0];
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+    assert_eq!(
+        face.font_table(Tag::HEAD.to_dwrite()).unwrap(),
+        Some(test_font::head(1000))
+    );
+}
+
+#[test]
+fn test_name_table() {
+    use crate::tables::name::*;
+    use test_font::push_u16;
+
+    let utf16 = |s: &str| -> Vec<u8> {
+        s.encode_utf16()
+            .flat_map(|unit| unit.to_be_bytes().to_vec())
+            .collect()
+    };
+    // (platform, encoding, language, name ID, bytes)
+    let records: Vec<(u16, u16, u16, u16, Vec<u8>)> = vec![
+        (0, 3, 0, NAME_ID_FAMILY, utf16("Unicode Sans")),
+        (1, 0, 0, NAME_ID_FAMILY, b"Caf\x8e \xa5 Sans".to_vec()),
+        (1, 1, 11, NAME_ID_FAMILY, vec![0x82, 0xa0]),
+        (2, 2, 0, NAME_ID_FAMILY, b"Fa\xe7ade".to_vec()),
+        (3, 1, 0x040C, NAME_ID_FAMILY, utf16("Sans Fran\u{e7}aise")),
+        (3, 1, 0x0409, NAME_ID_FAMILY, utf16("English Sans")),
+        (3, 1, 0x8000, NAME_ID_SUBFAMILY, utf16("\u{1f600} Regular")),
+    ];
+    let language_tag = utf16("en-Latn");
+    let mut storage = vec![];
+    let mut data = vec![];
+    push_u16(&mut data, 1);
+    push_u16(&mut data, records.len() as u16 + 1);
+    push_u16(&mut data, 6 + 12 * (records.len() as u16 + 1) + 2 + 4);
+    for (platform, encoding, language, name_id, bytes) in &records {
+        for &value in &[
+            *platform,
+            *encoding,
+            *language,
+            *name_id,
+            bytes.len() as u16,
+        ] {
+            push_u16(&mut data, value);
+        }
+        push_u16(&mut data, storage.len() as u16);
+        storage.extend_from_slice(bytes);
+    }
+    // A record whose string runs off the end of the table.
+    for &value in &[3, 1, 0x0409, NAME_ID_VERSION, 0xffff, 0] {
+        push_u16(&mut data, value);
+    }
+    push_u16(&mut data, 1);
+    push_u16(&mut data, language_tag.len() as u16);
+    push_u16(&mut data, storage.len() as u16);
+    storage.extend_from_slice(&language_tag);
+    data.extend_from_slice(&storage);
+
+    let table = NameTable::parse(&data).unwrap();
+    assert_eq!(table.records.len(), records.len());
+    assert_eq!(table.language_tags, ["en-Latn"]);
+    let strings: Vec<_> = table.records.iter().map(|r| r.string.as_deref()).collect();
+    assert_eq!(
+        strings,
+        [
+            Some("Unicode Sans"),
+            Some("Caf\u{e9} \u{2022} Sans"),
+            None,
+            Some("Fa\u{e7}ade"),
+            Some("Sans Fran\u{e7}aise"),
+            Some("English Sans"),
+            Some("\u{1f600} Regular"),
+        ]
+    );
+    assert_eq!(table.records[2].bytes, [0x82, 0xa0]);
+    let languages: Vec<_> = table
+        .records
+        .iter()
+        .map(|r| table.language_tag(r))
+        .collect();
+    assert_eq!(
+        languages,
+        [
+            None,
+            Some("en"),
+            Some("ja"),
+            None,
+            Some("fr-FR"),
+            Some("en-US"),
+            Some("en-Latn")
+        ]
+    );
+    assert_eq!(table.records_for(NAME_ID_FAMILY).count(), 6);
+    assert_eq!(table.find(NAME_ID_FAMILY), Some("English Sans"));
+    assert_eq!(table.find(NAME_ID_SUBFAMILY), Some("\u{1f600} Regular"));
+    assert_eq!(table.find(NAME_ID_VERSION), None);
+
+    assert!(NameTable::parse(&data[..20]).is_none());
+    assert_eq!(
+        NameTable::parse(&test_font::name(&[(1, "Test Sans")]))
+            .unwrap()
+            .find(1),
+        Some("Test Sans")
+    );
+}
+
+#[test]
+fn test_os2_table() {
+    use crate::tables::os2::*;
+    use test_font::{push_i16, push_u16, push_u32};
+
+    let mut data = vec![];
+    push_u16(&mut data, 5);
+    push_i16(&mut data, 512);
+    push_u16(&mut data, 700);
+    push_u16(&mut data, 3);
+    push_u16(&mut data, 0x0104);
+    for &value in &[650, 600, 0, 75, 650, 600, 0, 350, 50, 300, 0x0801] {
+        push_i16(&mut data, value);
+    }
+    data.extend_from_slice(&[2, 11, 8, 9, 2, 2, 4, 2, 2, 4]);
+    for &range in &[0x0000_0003, 0, 0x0001_0000, 0] {
+        push_u32(&mut data, range);
+    }
+    data.extend_from_slice(b"TEST");
+    push_u16(&mut data, 0x00a1);
+    push_u16(&mut data, 0x20);
+    push_u16(&mut data, 0xfffd);
+    for &value in &[800, -200, 90] {
+        push_i16(&mut data, value);
+    }
+    push_u16(&mut data, 1000);
+    push_u16(&mut data, 250);
+    let v0_len = data.len();
+    push_u32(&mut data, 0x8002_0001);
+    push_u32(&mut data, 0x8000_0000);
+    for &value in &[480, 700, 0, 0x20, 2] {
+        push_u16(&mut data, value);
+    }
+    push_u16(&mut data, 180);
+    push_u16(&mut data, 1440);
+
+    let table = Os2Table::parse(&data).unwrap();
+    assert_eq!(table.version, 5);
+    assert_eq!((table.weight_class, table.width_class), (700, 3));
+    assert_eq!(table.strikeout_position, 300);
+    assert!(table.panose.is_monospaced());
+    assert_eq!(table.panose.to_bytes(), [2, 11, 8, 9, 2, 2, 4, 2, 2, 4]);
+    assert_eq!(table.unicode_ranges.iter().collect::<Vec<_>>(), [0, 1, 80]);
+    assert_eq!(table.vendor_id, Tag(*b"TEST"));
+    assert!(table.fs_selection.italic() && table.fs_selection.bold());
+    assert!(table.fs_selection.use_typo_metrics() && !table.fs_selection.oblique());
+    assert_eq!(
+        (
+            table.typo_ascender,
+            table.typo_descender,
+            table.typo_line_gap
+        ),
+        (Some(800), Some(-200), Some(90))
+    );
+    assert_eq!(
+        (table.win_ascent, table.win_descent),
+        (Some(1000), Some(250))
+    );
+    let code_pages = table.code_page_ranges.unwrap();
+    assert_eq!(code_pages.code_pages(), [1252, 932, 437]);
+    assert!(code_pages.contains(CodePageRanges::SYMBOL_BIT));
+    assert_eq!(
+        (table.x_height, table.cap_height, table.max_context),
+        (Some(480), Some(700), Some(2))
+    );
+    assert_eq!(table.lower_optical_point_size, Some(9.));
+    assert_eq!(table.upper_optical_point_size, Some(72.));
+    assert_eq!(
+        table.embedding_permissions(),
+        EmbeddingPermissions {
+            usage: EmbeddingUsage::PreviewAndPrint,
+            no_subsetting: true,
+            bitmap_only: false,
+        }
+    );
+
+    // Earlier versions stop where their fields do.
+    data[1] = 0;
+    let table = Os2Table::parse(&data[..v0_len]).unwrap();
+    assert_eq!((table.code_page_ranges, table.x_height), (None, None));
+    assert_eq!(table.lower_optical_point_size, None);
+    // Apple's version 0 tables stop before the typographic metrics.
+    let table = Os2Table::parse(&data[..68]).unwrap();
+    assert_eq!((table.last_char_index, table.typo_ascender), (0xfffd, None));
+    assert_eq!(table.win_descent, None);
+    assert_eq!(Os2Table::parse(&data[..67]), None);
+    data[1] = 2;
+    assert_eq!(Os2Table::parse(&data[..v0_len + 8]), None);
+    assert_eq!(
+        Os2Table::parse(&data[..v0_len + 18]).unwrap().cap_height,
+        Some(700)
+    );
+
+    let permissions = |fs_type| {
+        let mut table = Os2Table::parse(&test_font::os2(400, 5, 0)).unwrap();
+        table.fs_type = fs_type;
+        table.embedding_permissions()
+    };
+    assert_eq!(permissions(0).usage, EmbeddingUsage::Installable);
+    assert_eq!(permissions(0x0002).usage, EmbeddingUsage::Restricted);
+    assert_eq!(permissions(0x000c).usage, EmbeddingUsage::Editable);
+    assert!(permissions(0x0208).bitmap_only);
+}
+
+#[test]
+fn test_cmap_table() {
+    use crate::tables::cmap::*;
+    use test_font::{push_u16, push_u32};
+
+    let push_u24 = |out: &mut Vec<u8>, value: u32| out.extend_from_slice(&value.to_be_bytes()[1..]);
+
+    let mut format0 = vec![];
+    push_u16(&mut format0, 0);
+    push_u16(&mut format0, 262);
+    push_u16(&mut format0, 0);
+    let mut glyphs = [0; 256];
+    glyphs[0x41] = 10;
+    glyphs[0x8e] = 13;
+    format0.extend_from_slice(&glyphs);
+
+    // A delta segment, a glyph array segment, a delta segment that wraps
+    // around to glyph 0, and the terminator.
+    let mut format4 = vec![];
+    for &value in &[4, 56, 0, 8, 8, 2, 0] {
+        push_u16(&mut format4, value);
+    }
+    for &values in &[
+        &[0x43, 0x62, 0xf002, 0xffff][..],
+        &[0],
+        &[0x41, 0x61, 0xf000, 0xffff],
+        &[10u16.wrapping_sub(0x41), 0, 0x0fff, 1],
+        &[0, 6, 0, 0],
+        &[20, 0],
+    ] {
+        for &value in values {
+            push_u16(&mut format4, value);
+        }
+    }
+
+    let mut format6 = vec![];
+    for &value in &[6, 16, 0, 0x8140, 3, 7, 0, 8] {
+        push_u16(&mut format6, value);
+    }
+
+    let format12 = |format: u16, groups: &[(u32, u32, u32)]| {
+        let mut out = vec![];
+        push_u16(&mut out, format);
+        push_u16(&mut out, 0);
+        push_u32(&mut out, 16 + 12 * groups.len() as u32);
+        push_u32(&mut out, 0);
+        push_u32(&mut out, groups.len() as u32);
+        for &(start, end, glyph) in groups {
+            push_u32(&mut out, start);
+            push_u32(&mut out, end);
+            push_u32(&mut out, glyph);
+        }
+        out
+    };
+
+    // U+FE0F: A and B as usual, and U+2764 as glyph 41. U+FE0E: U+2764 as
+    // usual.
+    let mut format14 = vec![];
+    push_u16(&mut format14, 14);
+    push_u32(&mut format14, 57);
+    push_u32(&mut format14, 2);
+    push_u24(&mut format14, 0xfe0e);
+    push_u32(&mut format14, 32);
+    push_u32(&mut format14, 0);
+    push_u24(&mut format14, 0xfe0f);
+    push_u32(&mut format14, 40);
+    push_u32(&mut format14, 48);
+    push_u32(&mut format14, 1);
+    push_u24(&mut format14, 0x2764);
+    format14.push(0);
+    push_u32(&mut format14, 1);
+    push_u24(&mut format14, 0x41);
+    format14.push(1);
+    push_u32(&mut format14, 1);
+    push_u24(&mut format14, 0x2764);
+    push_u16(&mut format14, 41);
+    assert_eq!(format14.len(), 57);
+
+    let subtables = vec![
+        (0, 5, format14),
+        (0, 6, format12(13, &[(0x20, 0x7e, 5)])),
+        (1, 0, format0),
+        (1, 1, format6),
+        (3, 1, format4),
+        (
+            3,
+            10,
+            format12(
+                12,
+                &[
+                    (0x41, 0x43, 10),
+                    (0x2764, 0x2764, 40),
+                    (0x1f600, 0x1f601, 30),
+                ],
+            ),
+        ),
+    ];
+    let mut data = vec![];
+    push_u16(&mut data, 0);
+    push_u16(&mut data, subtables.len() as u16);
+    let mut offset = 4 + 8 * subtables.len() as u32;
+    for (platform, encoding, subtable) in &subtables {
+        push_u16(&mut data, *platform);
+        push_u16(&mut data, *encoding);
+        push_u32(&mut data, offset);
+        offset += subtable.len() as u32;
+    }
+    for (_, _, subtable) in &subtables {
+        data.extend_from_slice(subtable);
+    }
+
+    let cmap = CmapTable::parse(&data).unwrap();
+    assert_eq!(cmap.subtables.len(), 5);
+    assert_eq!(cmap.unicode_subtable().unwrap().format, 12);
+    assert_eq!(cmap.glyph_index(0x42), Some(11));
+    assert_eq!(cmap.glyph_index(0x1f601), Some(31));
+    assert_eq!(cmap.glyph_index(0x61), None);
+    assert_eq!(
+        cmap.mappings().collect::<Vec<_>>(),
+        [
+            (0x41, 10),
+            (0x42, 11),
+            (0x43, 12),
+            (0x2764, 40),
+            (0x1f600, 30),
+            (0x1f601, 31)
+        ]
+    );
+    assert_eq!(cmap.code_points_for_glyph(31), [0x1f601]);
+    assert!(cmap.code_points_for_glyph(0).is_empty());
+    assert_eq!(cmap.reverse_mappings()[&40], [0x2764]);
+
+    assert_eq!(cmap.variation_glyph_index(0x2764, 0xfe0f), Some(41));
+    assert_eq!(cmap.variation_glyph_index(0x2764, 0xfe0e), Some(40));
+    assert_eq!(cmap.variation_glyph_index(0x41, 0xfe0f), Some(10));
+    assert_eq!(cmap.variation_glyph_index(0x43, 0xfe0f), None);
+    assert_eq!(cmap.variation_glyph_index(0x41, 0xfe00), None);
+    assert_eq!(
+        cmap.variation_selectors[1].lookup(0x2764),
+        Some(VariationGlyph::Glyph(41))
+    );
+
+    let format4 = cmap.subtable(3, 1).unwrap();
+    assert_eq!(
+        format4.mappings().collect::<Vec<_>>(),
+        [
+            (0x41, 10),
+            (0x42, 11),
+            (0x43, 12),
+            (0x61, 20),
+            (0xf000, 0xffff),
+            (0xf002, 1)
+        ]
+    );
+    assert_eq!(format4.glyph_index(0xffff), None);
+    let format13 = cmap.subtable(0, 6).unwrap();
+    assert_eq!(format13.glyph_index(0x30), Some(5));
+    assert_eq!(format13.codes_for_glyph(5).len(), 95);
+    assert_eq!(cmap.subtable(1, 0).unwrap().glyph_index(0x8e), Some(13));
+    assert_eq!(
+        cmap.subtable(1, 1).unwrap().mappings().collect::<Vec<_>>(),
+        [(0x8140, 7), (0x8142, 8)]
+    );
+
+    assert!(CmapTable::parse(&data[..6]).is_none());
+    let simple = CmapTable::parse(&test_font::cmap(&[(0x41, 1)])).unwrap();
+    assert_eq!(simple.mappings().collect::<Vec<_>>(), [(0x41, 1)]);
+}
+
+#[test]
+fn test_kerning_pairs() {
+    use crate::tables::kern::*;
+    use test_font::{push_i16, push_u16, push_u32};
+
+    let pair = |first_glyph, second_glyph, x_advance| KerningPair {
+        first_glyph,
+        second_glyph,
+        x_advance,
+    };
+
+    // (coverage, pairs) for each Microsoft subtable.
+    let mut kern = vec![];
+    push_u16(&mut kern, 0);
+    push_u16(&mut kern, 3);
+    for &(coverage, pairs) in &[
+        (0x0001, &[(1, 2, -50), (2, 3, -20)][..]),
+        (0x0005, &[(1, 2, -999)]),
+        (0x0009, &[(1, 2, -15)]),
+    ] {
+        for &value in &[
+            0,
+            14 + 6 * pairs.len() as u16,
+            coverage,
+            pairs.len() as u16,
+            6,
+            0,
+            0,
+        ] {
+            push_u16(&mut kern, value);
+        }
+        for &(first, second, value) in pairs {
+            push_u16(&mut kern, first);
+            push_u16(&mut kern, second);
+            push_i16(&mut kern, value);
+        }
+    }
+    assert_eq!(
+        parse_kern(&kern).unwrap(),
+        [pair(1, 2, -15), pair(2, 3, -20)]
+    );
+    let mut apple_kern = vec![];
+    push_u32(&mut apple_kern, 0x0001_0000);
+    push_u32(&mut apple_kern, 1);
+    push_u32(&mut apple_kern, 22);
+    for &value in &[0, 0, 1, 6, 0, 0, 5, 6] {
+        push_u16(&mut apple_kern, value);
+    }
+    push_i16(&mut apple_kern, -12);
+    assert_eq!(parse_kern(&apple_kern).unwrap(), [pair(5, 6, -12)]);
+
+    // Pair adjustment format 1 with an x placement before each x advance.
+    let pair_pos1 = |pairs: &[(u16, &[(u16, i16)])]| {
+        let mut out = vec![];
+        let header_len = 10 + 2 * pairs.len() as u16;
+        for &value in &[1, header_len, 0x0005, 0, pairs.len() as u16] {
+            push_u16(&mut out, value);
+        }
+        let mut offset = header_len + 4 + 2 * pairs.len() as u16;
+        for &(_, second) in pairs {
+            push_u16(&mut out, offset);
+            offset += 2 + 6 * second.len() as u16;
+        }
+        push_u16(&mut out, 1);
+        push_u16(&mut out, pairs.len() as u16);
+        for &(first, _) in pairs {
+            push_u16(&mut out, first);
+        }
+        for &(_, second) in pairs {
+            push_u16(&mut out, second.len() as u16);
+            for &(glyph, x_advance) in second {
+                push_u16(&mut out, glyph);
+                push_i16(&mut out, 3);
+                push_i16(&mut out, x_advance);
+            }
+        }
+        out
+    };
+    // Pair adjustment format 2 covering glyphs 3 and 4, with glyph 4 in
+    // class 1 and glyphs 7 and 8 in second class 1.
+    let mut pair_pos2 = vec![];
+    for &value in &[2, 24, 0x0004, 0, 34, 42, 2, 2] {
+        push_u16(&mut pair_pos2, value);
+    }
+    for &value in &[0, -10, -7, -40] {
+        push_i16(&mut pair_pos2, value);
+    }
+    for &value in &[2, 1, 3, 4, 0, 1, 4, 1, 1, 2, 1, 7, 8, 1] {
+        push_u16(&mut pair_pos2, value);
+    }
+
+    let lookup = |lookup_type: u16, subtables: &[Vec<u8>]| {
+        let mut out = vec![];
+        push_u16(&mut out, lookup_type);
+        push_u16(&mut out, 0);
+        push_u16(&mut out, subtables.len() as u16);
+        let mut offset = 6 + 2 * subtables.len() as u16;
+        for subtable in subtables {
+            push_u16(&mut out, offset);
+            offset += subtable.len() as u16;
+        }
+        for subtable in subtables {
+            out.extend_from_slice(subtable);
+        }
+        out
+    };
+    let mut extension = vec![];
+    push_u16(&mut extension, 1);
+    push_u16(&mut extension, 2);
+    push_u32(&mut extension, 8);
+    extension.extend(pair_pos1(&[(1, &[(2, -5)])]));
+    let lookups = [
+        // The class-based subtable decides every pair starting with glyph 3
+        // before the last subtable is reached.
+        lookup(
+            2,
+            &[
+                pair_pos1(&[(1, &[(2, -30)]), (4, &[(7, -1)])]),
+                pair_pos2,
+                pair_pos1(&[(3, &[(9, -60)])]),
+            ],
+        ),
+        lookup(9, &[extension]),
+        lookup(2, &[pair_pos1(&[(5, &[(6, -99)])])]),
+    ];
+
+    let mut gpos = vec![];
+    // The header, an empty script list, and features `kern` and `mark`.
+    for &value in &[1, 0, 10, 12, 40, 0, 2] {
+        push_u16(&mut gpos, value);
+    }
+    gpos.extend_from_slice(b"kern");
+    push_u16(&mut gpos, 14);
+    gpos.extend_from_slice(b"mark");
+    push_u16(&mut gpos, 22);
+    for &value in &[0, 2, 0, 1, 0, 1, 2] {
+        push_u16(&mut gpos, value);
+    }
+    assert_eq!(gpos.len(), 40);
+    push_u16(&mut gpos, lookups.len() as u16);
+    let mut offset = 2 + 2 * lookups.len() as u16;
+    for lookup in &lookups {
+        push_u16(&mut gpos, offset);
+        offset += lookup.len() as u16;
+    }
+    for lookup in &lookups {
+        gpos.extend_from_slice(lookup);
+    }
+
+    let pairs = gpos_kerning_pairs(&gpos, 10).unwrap();
+    let mut expected = vec![pair(1, 2, -35), pair(3, 7, -10), pair(3, 8, -10)];
+    expected.extend((0..7).map(|glyph| pair(4, glyph, -7)));
+    expected.extend_from_slice(&[pair(4, 7, -1), pair(4, 8, -40), pair(4, 9, -7)]);
+    assert_eq!(pairs, expected);
+    assert_eq!(kerning_pairs(Some(&gpos), Some(&kern), 10), expected);
+    assert_eq!(kerning_pairs(None, Some(&kern), 10).len(), 2);
+    // A `GPOS` table without a `kern` feature leaves kerning to `kern`.
+    gpos[14..18].copy_from_slice(b"liga");
+    assert_eq!(gpos_kerning_pairs(&gpos, 10), None);
+    assert_eq!(kerning_pairs(Some(&gpos), Some(&kern), 10).len(), 2);
+}
+
+#[test]
+fn test_variation_tables() {
+    use crate::tables::avar::AvarTable;
+    use crate::tables::fvar::FvarTable;
+    use crate::tables::stat::StatTable;
+    use test_font::{push_i16, push_u16, push_u32};
+
+    let fvar = test_font::fvar(
+        &[
+            (b"wght", 100., 400., 900., 256),
+            (b"wdth", 75., 100., 100., 257),
+        ],
+        &[(258, &[700., 75.], 259), (2, &[400., 100.], 0xffff)],
+    );
+    let fvar_table = FvarTable::parse(&fvar).unwrap();
+    assert_eq!(fvar_table.axes.len(), 2);
+    assert_eq!(fvar_table.axes[1].tag, Tag::WDTH);
+    assert_eq!(fvar_table.axes[1].min_value, 75.);
+    assert_eq!(fvar_table.instances[0].coordinates, [700., 75.]);
+    assert_eq!(fvar_table.instances[0].postscript_name_id, Some(259));
+    assert_eq!(fvar_table.instances[1].postscript_name_id, None);
+    assert!(FvarTable::parse(&fvar[..40]).is_none());
+
+    let mut avar = vec![];
+    for &value in &[1, 0, 0, 2, 4] {
+        push_u16(&mut avar, value);
+    }
+    for &value in &[-1., -1., 0., 0., 0.5, 0.75, 1., 1.] {
+        push_i16(&mut avar, (value * 16384.) as i16);
+    }
+    push_u16(&mut avar, 3);
+    for &value in &[-1., -1., 0., 0., 1., 1.] {
+        push_i16(&mut avar, (value * 16384.) as i16);
+    }
+    let avar = AvarTable::parse(&avar).unwrap();
+    assert_eq!(fvar_table.normalize(&[(Tag::WGHT, 650.)], None), [0.5, 0.]);
+    assert_eq!(
+        fvar_table.normalize(&[(Tag::WGHT, 650.)], Some(&avar)),
+        [0.75, 0.]
+    );
+    assert_eq!(
+        fvar_table.normalize(&[(Tag::WGHT, 775.)], Some(&avar)),
+        [0.875, 0.]
+    );
+    assert_eq!(
+        fvar_table.normalize(&[(Tag::WGHT, 250.), (Tag::WDTH, 50.)], Some(&avar)),
+        [-0.5, -1.]
+    );
+
+    let fixed = |out: &mut Vec<u8>, value: f32| push_u32(out, (value * 65536.) as u32);
+    let mut stat = vec![];
+    for &value in &[1, 1, 8, 2] {
+        push_u16(&mut stat, value);
+    }
+    push_u32(&mut stat, 20);
+    push_u16(&mut stat, 4);
+    push_u32(&mut stat, 36);
+    push_u16(&mut stat, 2);
+    // The axes in another order than in `fvar`.
+    stat.extend_from_slice(b"wdth");
+    push_u16(&mut stat, 257);
+    push_u16(&mut stat, 1);
+    stat.extend_from_slice(b"wght");
+    push_u16(&mut stat, 256);
+    push_u16(&mut stat, 0);
+    for &offset in &[8, 24, 36, 56] {
+        push_u16(&mut stat, offset);
+    }
+    for &value in &[3, 1, 0x0002, 260] {
+        push_u16(&mut stat, value);
+    }
+    fixed(&mut stat, 400.);
+    fixed(&mut stat, 700.);
+    for &value in &[1, 1, 0, 261] {
+        push_u16(&mut stat, value);
+    }
+    fixed(&mut stat, 700.);
+    for &value in &[2, 0, 0, 262] {
+        push_u16(&mut stat, value);
+    }
+    for &value in &[75., 75., 87.5] {
+        fixed(&mut stat, value);
+    }
+    for &value in &[4, 2, 0, 258, 0] {
+        push_u16(&mut stat, value);
+    }
+    fixed(&mut stat, 75.);
+    push_u16(&mut stat, 1);
+    fixed(&mut stat, 700.);
+    let stat_table = StatTable::parse(&stat).unwrap();
+    assert_eq!(stat_table.elided_fallback_name_id, Some(2));
+    assert_eq!(stat_table.values.len(), 4);
+    assert_eq!(stat_table.values[3].values, [(0, 75.), (1, 700.)]);
+    assert_eq!(stat_table.values_for_axis(1).count(), 2);
+
+    let names = test_font::name(&[
+        (1, "Test Sans"),
+        (2, "Regular"),
+        (256, "Weight"),
+        (257, "Width"),
+        (258, "Bold Condensed"),
+        (259, "TestSans-BoldCondensed"),
+        (260, "Regular"),
+        (261, "Bold"),
+        (262, "Condensed"),
+    ]);
+    let names = tables::name::NameTable::parse(&names).unwrap();
+    let axes = font_variations::variation_axes(&fvar_table, Some(&stat_table), Some(&names));
+    assert_eq!(axes[0].name.as_deref(), Some("Weight"));
+    assert_eq!(axes[0].ordering, Some(0));
+    assert_eq!(
+        axes[0].value_names,
+        [
+            AxisValueName {
+                name: Some("Regular".to_owned()),
+                value: 400.,
+                range: None,
+                linked_value: Some(700.),
+                elidable: true,
+            },
+            AxisValueName {
+                name: Some("Bold".to_owned()),
+                value: 700.,
+                range: None,
+                linked_value: None,
+                elidable: false,
+            },
+        ]
+    );
+    assert_eq!((axes[1].tag, axes[1].ordering), (Tag::WDTH, Some(1)));
+    assert_eq!(axes[1].value_names[0].range, Some((75., 87.5)));
+    assert_eq!(axes[1].axis_value(50.).value, 75.);
+    assert!(font_variations::variation_axes(&fvar_table, None, None)[0]
+        .value_names
+        .is_empty());
+
+    let instances = font_variations::named_instances(&fvar_table, Some(&names));
+    assert_eq!(instances[0].name.as_deref(), Some("Bold Condensed"));
+    assert_eq!(
+        instances[0].postscript_name.as_deref(),
+        Some("TestSans-BoldCondensed")
+    );
+    assert_eq!(instances[0].value(Tag::WGHT), Some(700.));
+    assert_eq!(instances[0].axis_values()[1].axisTag, Tag::WDTH.to_dwrite());
+    assert_eq!(instances[1].name.as_deref(), Some("Regular"));
+    assert_eq!(instances[1].postscript_name, None);
+    let axis_values: &[DWRITE_FONT_AXIS_VALUE] = instances[1].as_ref();
+    assert_eq!(axis_values.len(), 2);
+}
+
+#[test]
+fn test_layout_table() {
+    use crate::tables::layout::*;
+    use test_font::{push_u16, push_u32};
+
+    let mut gsub = vec![];
+    // Version 1.1, with an empty lookup list.
+    for &value in &[1, 1, 14, 70, 124] {
+        push_u16(&mut gsub, value);
+    }
+    push_u32(&mut gsub, 126);
+    // `DFLT` uses `liga`. `latn` requires `smcp` and uses `liga` and `ss01`,
+    // except in Turkish, which uses both `liga` features.
+    push_u16(&mut gsub, 2);
+    gsub.extend_from_slice(b"DFLT");
+    push_u16(&mut gsub, 14);
+    gsub.extend_from_slice(b"latn");
+    push_u16(&mut gsub, 26);
+    for &value in &[4, 0, 0, 0xffff, 1, 0] {
+        push_u16(&mut gsub, value);
+    }
+    push_u16(&mut gsub, 10);
+    push_u16(&mut gsub, 1);
+    gsub.extend_from_slice(b"TRK ");
+    push_u16(&mut gsub, 20);
+    for &value in &[0, 2, 2, 0, 1, 0, 0xffff, 2, 0, 3] {
+        push_u16(&mut gsub, value);
+    }
+    assert_eq!(gsub.len(), 70);
+    push_u16(&mut gsub, 4);
+    for &(tag, offset) in &[(b"liga", 26), (b"ss01", 32), (b"smcp", 42), (b"liga", 48)] {
+        gsub.extend_from_slice(tag);
+        push_u16(&mut gsub, offset);
+    }
+    for &value in &[0, 1, 0, 6, 1, 1, 0, 256, 0, 1, 2, 0, 1, 3] {
+        push_u16(&mut gsub, value);
+    }
+    push_u16(&mut gsub, 0);
+    assert_eq!(gsub.len(), 126);
+    // Feature 0 uses lookup 4 instead when axis 0 is from 0.5 to 1.
+    push_u16(&mut gsub, 1);
+    push_u16(&mut gsub, 0);
+    push_u32(&mut gsub, 1);
+    push_u32(&mut gsub, 16);
+    push_u32(&mut gsub, 30);
+    push_u16(&mut gsub, 1);
+    push_u32(&mut gsub, 6);
+    for &value in &[1, 0, 0x2000, 0x4000, 1, 0, 1, 0] {
+        push_u16(&mut gsub, value);
+    }
+    push_u32(&mut gsub, 12);
+    for &value in &[0, 1, 4] {
+        push_u16(&mut gsub, value);
+    }
+
+    let table = LayoutTable::parse(&gsub).unwrap();
+    let script_tags: Vec<_> = table.scripts.iter().map(|script| script.tag).collect();
+    assert_eq!(script_tags, [Tag(*b"DFLT"), Tag(*b"latn")]);
+    let latn = table.script(Tag(*b"latn")).unwrap();
+    assert_eq!(latn.languages[0].tag, Tag(*b"TRK "));
+    assert_eq!(
+        latn.default_language.as_ref().unwrap().required_feature,
+        Some(2)
+    );
+    assert_eq!(
+        table.feature_tags(),
+        [Tag(*b"liga"), Tag(*b"smcp"), Tag(*b"ss01")]
+    );
+    assert_eq!(table.features[1].ui_name_id, Some(256));
+    assert_eq!(table.features[0].ui_name_id, None);
+
+    let tags = |features: Vec<&Feature>| -> Vec<Tag> {
+        features.iter().map(|feature| feature.tag).collect()
+    };
+    assert_eq!(
+        tags(table.language_features(Tag(*b"latn"), Tag(*b"TRK "))),
+        [Tag(*b"liga"), Tag(*b"liga")]
+    );
+    assert_eq!(
+        tags(table.language_features(Tag(*b"latn"), Tag(*b"DEU "))),
+        [Tag(*b"smcp"), Tag(*b"liga"), Tag(*b"ss01")]
+    );
+    assert_eq!(
+        tags(table.language_features(Tag(*b"cyrl"), Tag(*b"dflt"))),
+        [Tag(*b"liga")]
+    );
+
+    assert_eq!(
+        table.feature_variations[0].conditions,
+        [Some(AxisCondition {
+            axis_index: 0,
+            min_value: 0.5,
+            max_value: 1.,
+        })]
+    );
+    assert_eq!(table.feature_lookups_at(0, &[0.75]), Some(&[4][..]));
+    assert_eq!(table.feature_lookups_at(0, &[0.25]), Some(&[0][..]));
+    assert_eq!(table.feature_lookups_at(0, &[]), Some(&[0][..]));
+    assert_eq!(table.feature_lookups_at(1, &[0.75]), Some(&[1][..]));
+    assert_eq!(table.feature_lookups_at(4, &[]), None);
+    assert!(LayoutTable::parse(&gsub[..60]).is_none());
+
+    #[cfg(not(windows))]
+    {
+        let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+            .table(b"GSUB", gsub)
+            .build();
+        let file = FontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+        let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+        assert_eq!(face.feature_tags().unwrap().len(), 3);
+        assert!(face.gpos_table().unwrap().is_none());
+    }
+}
+
+#[test]
+fn test_color_glyphs() {
+    use crate::tables::colr::*;
+    use crate::tables::cpal::{Color, CpalTable};
+    use test_font::{colr_paint, push_i16, push_u16, push_u32};
+
+    let solid = |format: u8, palette_index: u16, alpha: i16| {
+        let mut paint = vec![format];
+        push_u16(&mut paint, palette_index);
+        push_i16(&mut paint, alpha);
+        paint
+    };
+    let glyph = |glyph_id: u16, paint: Vec<u8>| {
+        let mut head = vec![10, 0, 0, 0];
+        push_u16(&mut head, glyph_id);
+        colr_paint(&head, &[(1, paint)])
+    };
+    let colr_glyph = |glyph_id: u16| {
+        let mut paint = vec![11];
+        push_u16(&mut paint, glyph_id);
+        paint
+    };
+    // A reflected color line from opaque entry 0 to half-transparent entry 1.
+    let mut color_line = vec![2];
+    for &value in &[2, 0, 0, 0x4000, 0x4000, 1, 0x2000] {
+        push_u16(&mut color_line, value);
+    }
+    let mut linear = vec![4, 0, 0, 0];
+    for &value in &[0, 0, 500, 0, 0, 500] {
+        push_i16(&mut linear, value);
+    }
+    // A quarter turn around (100, 200).
+    let mut rotate = vec![26, 0, 0, 0];
+    for &value in &[0x2000, 100, 200] {
+        push_i16(&mut rotate, value);
+    }
+    // Entry 1, with an alpha that drops to a half at the heaviest weight.
+    let mut var_solid = solid(3, 1, 0x4000);
+    push_u32(&mut var_solid, 0);
+    let mut translate = vec![14, 0, 0, 0];
+    push_i16(&mut translate, 10);
+    push_i16(&mut translate, -20);
+    let composite = colr_paint(
+        &[32, 0, 0, 0, 23, 0, 0, 0],
+        &[
+            (1, colr_glyph(2)),
+            (
+                5,
+                colr_paint(&translate, &[(1, glyph(1, solid(2, 0, 0x4000)))]),
+            ),
+        ],
+    );
+
+    let colr = test_font::colr(
+        &[(1, &[(2, 0), (1, 0xffff)]), (2, &[(1, 1)])],
+        &[
+            (2, vec![1, 2, 0, 0, 0, 0]),
+            (3, composite),
+            (5, colr_glyph(5)),
+        ],
+        &[
+            glyph(1, colr_paint(&linear, &[(1, color_line)])),
+            colr_paint(&rotate, &[(1, glyph(2, var_solid))]),
+        ],
+        &[(2, 3, [0, -100, 600, 700])],
+        Some(test_font::item_variation_store(&[-0x2000])),
+    );
+    let table = ColrTable::parse(&colr).unwrap();
+    assert_eq!(table.version(), 1);
+
+    // Version 0 layers, which version 1 paints take precedence over.
+    let foreground = PaletteColor {
+        palette_index: None,
+        alpha: 1.,
+    };
+    let entry = |index: u16, alpha: f32| PaletteColor {
+        palette_index: Some(index),
+        alpha,
+    };
+    let layer = |glyph_id: u16, color: PaletteColor| Paint::Glyph {
+        glyph_id,
+        paint: Box::new(Paint::Solid(color)),
+    };
+    assert_eq!(
+        table.layers(1),
+        Some(vec![
+            Layer {
+                glyph_id: 2,
+                palette_index: Some(0)
+            },
+            Layer {
+                glyph_id: 1,
+                palette_index: None
+            },
+        ])
+    );
+    assert_eq!(
+        table.paint(1, &[]),
+        Some(Paint::Layers(vec![
+            layer(2, entry(0, 1.)),
+            layer(1, foreground)
+        ]))
+    );
+    assert_eq!(table.layers(2).unwrap().len(), 1);
+    assert_eq!(table.paint(4, &[]), None);
+    // A glyph that paints itself.
+    assert_eq!(table.paint(5, &[]), None);
+
+    let check_glyph_2 = |paint: &Paint, alpha: f32| {
+        let layers = match paint {
+            Paint::Layers(layers) => layers,
+            paint => panic!("unexpected paint {:?}", paint),
+        };
+        let gradient = Paint::LinearGradient {
+            color_line: ColorLine {
+                extend: Extend::Reflect,
+                stops: vec![
+                    ColorStop {
+                        offset: 0.,
+                        color: entry(0, 1.),
+                    },
+                    ColorStop {
+                        offset: 1.,
+                        color: entry(1, 0.5),
+                    },
+                ],
+            },
+            p0: (0., 0.),
+            p1: (500., 0.),
+            p2: (0., 500.),
+        };
+        assert_eq!(
+            layers[0],
+            Paint::Glyph {
+                glyph_id: 1,
+                paint: Box::new(gradient)
+            }
+        );
+        match &layers[1] {
+            Paint::Transform { transform, paint } => {
+                let expected = [0., 1., -1., 0., 300., 100.];
+                let actual = [
+                    transform.xx,
+                    transform.yx,
+                    transform.xy,
+                    transform.yy,
+                    transform.dx,
+                    transform.dy,
+                ];
+                for (actual, expected) in actual.iter().zip(&expected) {
+                    assert!((actual - expected).abs() < 1e-4, "{:?}", transform);
+                }
+                let (x, y) = transform.apply(100., 200.);
+                assert!((x - 100.).abs() < 1e-4 && (y - 200.).abs() < 1e-4);
+                assert_eq!(**paint, layer(2, entry(1, alpha)));
+            }
+            paint => panic!("unexpected paint {:?}", paint),
+        }
+    };
+    let paint = table.paint(2, &[]).unwrap();
+    check_glyph_2(&paint, 1.);
+    assert_eq!(paint.glyphs(), [1, 2]);
+    check_glyph_2(&table.paint(2, &[1.]).unwrap(), 0.5);
+    check_glyph_2(&table.paint(2, &[0.5]).unwrap(), 0.75);
+
+    match table.paint(3, &[]).unwrap() {
+        Paint::Composite {
+            mode,
+            source,
+            backdrop,
+        } => {
+            assert_eq!(mode, CompositeMode::Multiply);
+            assert_eq!(
+                *source,
+                Paint::ColrGlyph {
+                    glyph_id: 2,
+                    paint: Box::new(paint)
+                }
+            );
+            let translate = Affine {
+                dx: 10.,
+                dy: -20.,
+                ..Affine::IDENTITY
+            };
+            assert_eq!(
+                *backdrop,
+                Paint::Transform {
+                    transform: translate,
+                    paint: Box::new(layer(1, entry(0, 1.)))
+                }
+            );
+        }
+        paint => panic!("unexpected paint {:?}", paint),
+    }
+    assert_eq!(table.paint(3, &[]).unwrap().glyphs(), [1, 1, 2]);
+
+    let clip_box = ClipBox {
+        x_min: 0.,
+        y_min: -100.,
+        x_max: 600.,
+        y_max: 700.,
+    };
+    assert_eq!(table.clip_box(3, &[]), Some(clip_box));
+    assert_eq!(table.clip_box(1, &[]), None);
+    assert_eq!(table.clip_box(4, &[]), None);
+
+    let cpal = test_font::cpal(
+        &[
+            &[[255, 0, 0, 255], [0, 0, 255, 128]],
+            &[[0, 255, 0, 255], [255, 255, 255, 255]],
+        ],
+        &[1, 2],
+    );
+    let palettes = CpalTable::parse(&cpal).unwrap();
+    assert_eq!(palettes.palettes.len(), 2);
+    let blue = Color {
+        red: 0,
+        green: 0,
+        blue: 255,
+        alpha: 128,
+    };
+    assert_eq!(palettes.color(0, 1), Some(blue));
+    assert_eq!(palettes.color(1, 2), None);
+    let dark = &palettes.palettes[1];
+    assert!(dark.usable_with_dark_background && !dark.usable_with_light_background);
+    assert_eq!(dark.label_name_id, None);
+    let black = Color {
+        red: 0,
+        green: 0,
+        blue: 0,
+        alpha: 255,
+    };
+    let palette = &palettes.palettes[0];
+    assert_eq!(
+        entry(1, 0.5).resolve(palette, black),
+        Some(Color { alpha: 64, ..blue })
+    );
+    assert_eq!(foreground.resolve(palette, black), Some(black));
+    assert_eq!(entry(2, 1.).resolve(palette, black), None);
+    assert_eq!(CpalTable::parse(&cpal[..20]), None);
+
+    // Off Windows, the public font faces resolve color glyphs at their own
+    // variation coordinates.
+    #[cfg(not(windows))]
+    {
+        let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+            .table(b"COLR", colr)
+            .table(b"CPAL", cpal)
+            .table(
+                b"fvar",
+                test_font::fvar(&[(b"wght", 100., 400., 900., 256)], &[]),
+            )
+            .build();
+        let file = FontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+        let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+        assert_eq!(face.cpal_table().unwrap(), Some(palettes));
+        let color_glyphs = face.color_glyphs().unwrap().unwrap();
+        check_glyph_2(&color_glyphs.paint(2).unwrap(), 1.);
+        assert_eq!(color_glyphs.paint(0), None);
+        let bold = face
+            .create_font_face_with_variations_by_tag(
+                DWRITE_FONT_SIMULATIONS_NONE,
+                &[(Tag::WGHT, 900.)],
+            )
+            .unwrap();
+        let color_glyphs = bold.color_glyphs().unwrap().unwrap();
+        assert_eq!(color_glyphs.coordinates(), &[1.][..]);
+        check_glyph_2(&color_glyphs.paint(2).unwrap(), 0.5);
+
+        // The leaves are ordinary glyphs.
+        let builder = RecordingOutlineBuilder::default();
+        let builder_box = Box::new(builder.clone());
+        face.glyph_run_outline(10., &[1], Some(&[0.]), None, false, false, builder_box)
+            .unwrap();
+        assert!(!builder.0.borrow().is_empty());
+    }
+}
+#[test]
+fn test_bitmap_glyphs() {
+    use crate::tables::bitmap::*;
+    use test_font::{png_header, push_u32};
+
+    let png = |width: u32, height: u32| {
+        let mut png = png_header(width, height);
+        png.extend_from_slice(b"pixels");
+        png
+    };
+    let sbix = test_font::sbix(
+        3,
+        &[
+            (
+                20,
+                72,
+                &[
+                    (1, 1, -2, b"png ", png(10, 12)),
+                    (2, 0, 0, b"dupe", vec![0, 1]),
+                ],
+            ),
+            (40, 144, &[(1, 2, -4, b"png ", png(20, 24))]),
+        ],
+    );
+    let table = SbixTable::parse(&sbix, 3).unwrap();
+    let strike = |ppem: u16, ppi: u16| BitmapStrike {
+        table: Tag::SBIX,
+        ppem,
+        ppi,
+        bit_depth: 32,
+    };
+    assert_eq!(table.strikes(), [strike(20, 72), strike(40, 144)]);
+    let small = GlyphBitmap {
+        strike: strike(20, 72),
+        data: BitmapData::Png(png(10, 12)),
+        width: 10,
+        height: 12,
+        left: 1,
+        top: 10,
+        advance: None,
+    };
+    assert_eq!(table.glyph_bitmap(1, 0), Some(small.clone()));
+    assert_eq!(table.glyph_bitmap(2, 0), Some(small.clone()));
+    assert_eq!(table.glyph_bitmap(2, 1), None);
+    assert_eq!(table.glyph_bitmap(3, 0), None);
+
+    // Small color bitmaps, then black and white and grayscale ones.
+    let mut color_glyph = vec![12, 10, 1, 10, 11];
+    push_u32(&mut color_glyph, png(10, 12).len() as u32);
+    color_glyph.extend(png(10, 12));
+    let (cblc, cbdt) = test_font::eblc_and_ebdt(true, &[(109, 32, &[(1, 17, color_glyph)])]);
+    let (eblc, ebdt) = test_font::eblc_and_ebdt(
+        false,
+        &[
+            (
+                12,
+                1,
+                &[
+                    (1, 1, vec![2, 3, 0, 2, 4, 0b1010_0000, 0b0100_0000]),
+                    (2, 8, vec![2, 6, 0, 2, 7, 0, 0, 2, 0, 1, 0, 0, 0, 1, 3, 0]),
+                ],
+            ),
+            (24, 2, &[(1, 2, vec![1, 3, 0, 1, 3, 0b1101_1000])]),
+        ],
+    );
+    let tables = BitmapTables::parse(
+        Some(&sbix),
+        Some(&cblc),
+        Some(&cbdt),
+        Some(&eblc),
+        Some(&ebdt),
+        3,
+    );
+    let ppems: Vec<_> = tables
+        .strikes()
+        .iter()
+        .map(|strike| (strike.table, strike.ppem))
+        .collect();
+    assert_eq!(
+        ppems,
+        [
+            (Tag::SBIX, 20),
+            (Tag::SBIX, 40),
+            (Tag::CBLC, 109),
+            (Tag::EBLC, 12),
+            (Tag::EBLC, 24)
+        ]
+    );
+    assert_eq!(tables.glyph_bitmap(1, 30.).unwrap().strike.ppem, 40);
+    assert_eq!(tables.glyph_bitmap(1, 50.).unwrap().strike.ppem, 40);
+    assert_eq!(tables.glyph_bitmap(1, 10.), Some(small.clone()));
+    // The 40 pixel strike lacks the duplicate.
+    assert_eq!(tables.glyph_bitmap(2, 30.), Some(small));
+
+    let tables = BitmapTables::parse(None, Some(&cblc), Some(&cbdt), Some(&eblc), Some(&ebdt), 3);
+    let color = tables.glyph_bitmap(1, 12.).unwrap();
+    assert_eq!(
+        color.strike,
+        BitmapStrike {
+            table: Tag::CBLC,
+            ppem: 109,
+            ppi: 72,
+            bit_depth: 32
+        }
+    );
+    assert_eq!(color.data, BitmapData::Png(png(10, 12)));
+    assert_eq!(
+        (color.width, color.height, color.left, color.top),
+        (10, 12, 1, 10)
+    );
+    assert_eq!(color.advance, Some(11));
+
+    let tables = BitmapTables::parse(None, None, None, Some(&eblc), Some(&ebdt), 3);
+    let mono = tables.glyph_bitmap(1, 12.).unwrap();
+    assert_eq!(mono.data, BitmapData::Mask(vec![255, 0, 255, 0, 255, 0]));
+    assert_eq!(
+        (mono.width, mono.height, mono.top, mono.advance),
+        (3, 2, 2, Some(4))
+    );
+    // Two copies of glyph 1, side by side.
+    let composite = tables.glyph_bitmap(2, 12.).unwrap();
+    assert_eq!(
+        composite.data,
+        BitmapData::Mask(vec![255, 0, 255, 255, 0, 255, 0, 255, 0, 0, 255, 0])
+    );
+    let gray = tables.glyph_bitmap(1, 20.).unwrap();
+    assert_eq!((gray.strike.ppem, gray.strike.bit_depth), (24, 2));
+    assert_eq!(gray.data, BitmapData::Mask(vec![255, 85, 170]));
+    assert_eq!(tables.glyph_bitmap(0, 12.), None);
+
+    // Off Windows, the public font faces read the same tables.
+    #[cfg(not(windows))]
+    {
+        let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+            .table(b"sbix", sbix)
+            .table(b"EBLC", eblc)
+            .table(b"EBDT", ebdt)
+            .build();
+        let file = FontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+        let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+        let tables = face.bitmap_tables().unwrap();
+        assert_eq!(tables.strikes().len(), 4);
+        assert_eq!(tables.glyph_bitmap(1, 40.).unwrap().strike, strike(40, 144));
+        assert_eq!(tables.glyph_bitmap(0, 40.), None);
+    }
+}
+#[test]
+fn test_svg_glyphs() {
+    use crate::tables::svg::SvgTable;
+
+    let shared = b"<svg><g id=\"glyph1\"/><g id=\"glyph2\"/><g id=\"glyph5\"/></svg>";
+    let compressed = test_font::gzip(shared);
+    let svg = test_font::svg(&[
+        (1, 2, shared),
+        (3, 3, b"<svg id=\"glyph3\"/>"),
+        (5, 5, shared),
+        (6, 7, &compressed),
+        (8, 8, &compressed[..20]),
+    ]);
+    let table = SvgTable::parse(&svg).unwrap();
+    assert_eq!(
+        table.glyph_ranges(),
+        [(1, 2), (3, 3), (5, 5), (6, 7), (8, 8)]
+    );
+
+    let first = table.glyph_document(2).unwrap();
+    assert_eq!((first.start_glyph, first.end_glyph), (1, 2));
+    assert_eq!(first.data, shared);
+    let single = table.glyph_document(3).unwrap();
+    assert_eq!(single.data, b"<svg id=\"glyph3\"/>");
+    // Ranges that share a document share its offset.
+    let fifth = table.glyph_document(5).unwrap();
+    assert_eq!(fifth.offset, first.offset);
+    assert_eq!(fifth.data, shared);
+    let inflated = table.glyph_document(7).unwrap();
+    assert_eq!(
+        (inflated.start_glyph, inflated.data.as_slice()),
+        (6, &shared[..])
+    );
+    assert_ne!(inflated.offset, first.offset);
+    assert_eq!(table.glyph_document(0), None);
+    assert_eq!(table.glyph_document(4), None);
+    assert_eq!(table.glyph_document(8), None);
+    assert_eq!(table.glyph_document(9), None);
+    assert!(SvgTable::parse(&svg[..20]).is_none());
+
+    // Off Windows, the public font faces read the same table.
+    #[cfg(not(windows))]
+    {
+        let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+            .table(b"SVG ", svg)
+            .build();
+        let file = FontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+        let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+        let table = face.svg_table().unwrap().unwrap();
+        assert_eq!(table.glyph_document(1).unwrap().data, shared);
+        assert_eq!(table.glyph_document(0), None);
+    }
+}
+#[test]
+fn test_glyph_names() {
+    use crate::tables::cff::CffTable;
+    use crate::tables::post::PostTable;
+
+    assert_eq!(code_points_for_glyph_name("A"), [0x41]);
+    assert_eq!(code_points_for_glyph_name("Lcommaaccent.sc"), [0x13b]);
+    assert_eq!(
+        code_points_for_glyph_name("dalethatafpatah"),
+        [0x5d3, 0x5b2]
+    );
+    assert_eq!(
+        code_points_for_glyph_name("a_unknown_uni0042"),
+        [0x61, 0x42]
+    );
+    assert_eq!(code_points_for_glyph_name("uni00410308.alt"), [0x41, 0x308]);
+    assert_eq!(code_points_for_glyph_name("u1F600"), [0x1f600]);
+    for name in &[
+        "",
+        ".notdef",
+        "unknown",
+        "uni0041030",
+        "uni00e9",
+        "uniD800",
+        "u110000",
+        "u12",
+    ] {
+        assert!(code_points_for_glyph_name(name).is_empty(), "{}", name);
+    }
+
+    let post = test_font::post_with_names(&[".notdef", "A", "B.alt", "uni0416", "A"]);
+    let table = PostTable::parse(&post).unwrap();
+    assert_eq!(table.version, 0x0002_0000);
+    assert_eq!(table.glyph_names, [".notdef", "A", "B.alt", "uni0416", "A"]);
+    assert_eq!(table.glyph_name(3), Some("uni0416"));
+    assert_eq!(table.glyph_name(5), None);
+    assert_eq!(table.glyph_by_name("A"), Some(1));
+    assert_eq!(table.glyph_by_name("B"), None);
+    assert!(PostTable::parse(&post[..post.len() - 1]).is_none());
+    let mut post_1 = test_font::post(false);
+    post_1[1] = 1;
+    let table = PostTable::parse(&post_1).unwrap();
+    assert_eq!(
+        (table.glyph_names.len(), table.glyph_name(257)),
+        (258, Some("dcroat"))
+    );
+    // Format 2.5 offsets glyphs into the standard names.
+    let mut post_2_5 = test_font::post(false);
+    post_2_5[1..3].copy_from_slice(&[2, 0x50]);
+    post_2_5.extend_from_slice(&[0, 3, 0, 35, 35]);
+    let table = PostTable::parse(&post_2_5).unwrap();
+    assert_eq!(table.glyph_names, [".notdef", "A", "B"]);
+    let table = PostTable::parse(&test_font::post(true)).unwrap();
+    assert!(table.is_fixed_pitch && table.glyph_names.is_empty());
+    assert_eq!(
+        (table.underline_position, table.underline_thickness),
+        (-100, 50)
+    );
+
+    // A custom charset with a string of the font's own, then the ISOAdobe
+    // charset.
+    let glyphs = vec![test_font::charstring(&[(&[], &[14])]); 4];
+    let head = test_font::head(1000);
+    let cff = test_font::cff(&glyphs, &[], &[], &[34, 391, 999], &["A.swash"]);
+    let table = CffTable::parse(&head, &cff).unwrap();
+    let names: Vec<_> = (0..5).map(|glyph_id| table.glyph_name(glyph_id)).collect();
+    let expected = [Some(".notdef"), Some("A"), Some("A.swash"), None, None];
+    assert_eq!(names, expected.map(|name| name.map(String::from)));
+    assert_eq!(table.glyph_by_name("A.swash"), Some(2));
+    assert_eq!(table.glyph_by_name("B"), None);
+    let cff = test_font::cff(&glyphs, &[], &[], &[], &[]);
+    let table = CffTable::parse(&head, &cff).unwrap();
+    assert_eq!(table.glyph_name(3).as_deref(), Some("quotedbl"));
+
+    // Off Windows, the public font faces read names from `post` or else
+    // `CFF `, and fall back on `cmap` for names they don't list.
+    #[cfg(not(windows))]
+    {
+        let face = |font: test_font::FontBuilder| {
+            let file = FontFile::new_from_buffer(Arc::new(font.build())).unwrap();
+            file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap()
+        };
+        let named = face(
+            test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40).table(
+                b"post",
+                test_font::post_with_names(&[".notdef", "A", "B.alt"]),
+            ),
+        );
+        let names = named.glyph_names().unwrap();
+        assert_eq!(names.glyph_name(2), Some("B.alt"));
+        assert_eq!(names.glyph_by_name("B.alt"), Some(2));
+        assert_eq!(names.glyph_by_name("uni0042"), Some(2));
+        assert_eq!(names.glyph_by_name("B"), Some(2));
+        assert_eq!(names.glyph_by_name("C"), None);
+        assert_eq!(names.glyph_by_name("A_B"), None);
+
+        let unnamed = face(
+            test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+                .without(b"glyf")
+                .without(b"loca")
+                .table(b"CFF ", cff),
+        );
+        let names = unnamed.glyph_names().unwrap();
+        assert_eq!(names.glyph_name(1), Some("space"));
+        assert_eq!(names.glyph_by_name("exclam"), Some(2));
+        assert_eq!(names.glyph_name(4), None);
+    }
+}
+#[test]
+fn test_recommended_rendering_mode() {
+    use crate::tables::gasp::GaspTable;
+
+    // Version 0 tables can't ask for the ClearType flags.
+    let gasp = GaspTable::parse(&test_font::gasp(0, &[(8, 0x2), (0xffff, 0xf)])).unwrap();
+    assert!(gasp.ranges.iter().all(|range| !range.symmetric_smoothing));
+    assert!(GaspTable::parse(&test_font::gasp(2, &[])).is_none());
+    assert!(GaspTable::parse(&test_font::gasp(1, &[(8, 0x2)])[..7]).is_none());
+    let gasp = GaspTable::parse(&test_font::gasp(1, &[(8, 0x2), (16, 0xf), (24, 0x3)])).unwrap();
+    assert_eq!(gasp.range(8.).map(|range| range.grayscale), Some(true));
+    assert!(gasp
+        .range(8.5)
+        .is_some_and(|range| range.symmetric_grid_fit && range.grid_fit));
+    assert_eq!(gasp.range(24.5), None);
+
+    let mode = |em_size, measuring_mode, outline_threshold| {
+        recommended_rendering_mode(
+            &gasp.ranges,
+            em_size,
+            1.5,
+            measuring_mode,
+            outline_threshold,
+        )
+    };
+    assert_eq!(
+        mode(4., DWRITE_MEASURING_MODE_NATURAL, None),
+        RenderingMode::Natural
+    );
+    assert_eq!(
+        mode(10., DWRITE_MEASURING_MODE_NATURAL, None),
+        RenderingMode::NaturalSymmetric
+    );
+    assert_eq!(
+        mode(13., DWRITE_MEASURING_MODE_NATURAL, None),
+        RenderingMode::Natural
+    );
+    assert_eq!(
+        mode(14., DWRITE_MEASURING_MODE_NATURAL, None),
+        RenderingMode::NaturalSymmetric
+    );
+    assert_eq!(
+        mode(10., DWRITE_MEASURING_MODE_GDI_CLASSIC, None),
+        RenderingMode::GdiClassic
+    );
+    assert_eq!(
+        mode(10., DWRITE_MEASURING_MODE_GDI_NATURAL, None),
+        RenderingMode::GdiNatural
+    );
+    assert_eq!(
+        mode(66., DWRITE_MEASURING_MODE_GDI_CLASSIC, None),
+        RenderingMode::GdiClassic
+    );
+    assert_eq!(
+        mode(67., DWRITE_MEASURING_MODE_GDI_CLASSIC, None),
+        RenderingMode::Outline
+    );
+    let large = mode(67., DWRITE_MEASURING_MODE_NATURAL, Some(350.));
+    assert_eq!(large, RenderingMode::NaturalSymmetric);
+    assert_eq!(
+        mode(4., DWRITE_MEASURING_MODE_NATURAL, Some(6.)),
+        RenderingMode::Outline
+    );
+    assert_eq!(
+        recommended_rendering_mode(&[], 13., 1.5, DWRITE_MEASURING_MODE_NATURAL, None),
+        RenderingMode::Natural
+    );
+
+    for &mode in &[
+        RenderingMode::Aliased,
+        RenderingMode::GdiNatural,
+        RenderingMode::Outline,
+    ] {
+        assert_eq!(RenderingMode::from_dwrite(mode.to_dwrite()), Some(mode));
+    }
+    assert_eq!(
+        RenderingMode::from_dwrite(DWRITE_RENDERING_MODE_DEFAULT),
+        None
+    );
+
+    // Off Windows, faces read their own `gasp` table, if they have one.
+    #[cfg(not(windows))]
+    {
+        let face = |font: test_font::FontBuilder| {
+            let file = FontFile::new_from_buffer(Arc::new(font.build())).unwrap();
+            file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap()
+        };
+        let font = || test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40);
+        let plain = face(font());
+        let smoothed = face(font().table(b"gasp", test_font::gasp(1, &[(0xffff, 0xa)])));
+        let natural = DWRITE_MEASURING_MODE_NATURAL;
+        let mode = plain.recommended_rendering_mode(12., 1., natural, None);
+        assert_eq!(mode, Ok(RenderingMode::Natural));
+        assert_eq!(
+            smoothed.recommended_rendering_mode(12., 1., natural, None),
+            Ok(RenderingMode::NaturalSymmetric)
+        );
+        let mode = smoothed.recommended_rendering_mode(50., 2., natural, None);
+        assert_eq!(mode, Ok(RenderingMode::Outline));
+    }
+}
+
+#[test]
+fn test_font_stretch_and_style_conversions() {
+    use std::convert::TryFrom;
+
+    assert_eq!(FontStretch::try_from(0), Ok(FontStretch::Undefined));
+    assert_eq!(FontStretch::try_from(9), Ok(FontStretch::UltraExpanded));
+    assert_eq!(
+        FontStretch::try_from(10),
+        Err(FontPropertyError::OutOfRange(10))
+    );
+    assert_eq!(FontStretch::from_u32(0xffff_ffff), FontStretch::Undefined);
+    assert_eq!(FontStyle::try_from(2), Ok(FontStyle::Italic));
+    assert_eq!(
+        FontStyle::try_from(3),
+        Err(FontPropertyError::OutOfRange(3))
+    );
+    assert_eq!(FontStyle::from_u32(3), FontStyle::Normal);
+    for v in 0..10 {
+        assert_eq!(FontStretch::from_u32(v).to_u32(), v);
+    }
+
+    assert_eq!(FontStretch::Condensed.to_percentage(), Some(75.));
+    assert_eq!(FontStretch::Undefined.to_percentage(), None);
+    assert_eq!(FontStretch::from_percentage(100.), Ok(FontStretch::Normal));
+    assert_eq!(
+        FontStretch::from_percentage(80.),
+        Ok(FontStretch::Condensed)
+    );
+    assert_eq!(
+        FontStretch::from_percentage(175.),
+        Ok(FontStretch::ExtraExpanded)
+    );
+    assert_eq!(
+        FontStretch::from_percentage(210.),
+        Err(FontPropertyError::InvalidPercentage(210.))
+    );
+    assert!(FontStretch::from_percentage(f32::NAN).is_err());
+
+    assert_eq!("Semi-Expanded".parse(), Ok(FontStretch::SemiExpanded));
+    assert_eq!(" 62.5% ".parse(), Ok(FontStretch::ExtraCondensed));
+    assert!("wide".parse::<FontStretch>().is_err());
+    assert!("300%".parse::<FontStretch>().is_err());
+    for v in 0..10 {
+        let stretch = FontStretch::from_u32(v);
+        assert_eq!(stretch.to_string().parse(), Ok(stretch));
+    }
+    assert_eq!(FontStyle::Oblique.to_string(), "oblique");
+    assert_eq!("ITALIC".parse(), Ok(FontStyle::Italic));
+    assert_eq!(
+        "slanted".parse::<FontStyle>(),
+        Err(FontPropertyError::InvalidKeyword("slanted".to_owned()))
+    );
+}
+
+#[test]
+fn test_font_weight() {
+    use std::collections::HashSet;
+
+    assert_eq!(FontWeight::Unknown(700), FontWeight::Bold);
+    assert!(FontWeight::Regular < FontWeight::Unknown(450));
+    assert!(FontWeight::Unknown(450) < FontWeight::Medium);
+    assert_eq!(
+        FontWeight::Unknown(1200).clamp(FontWeight::Thin, FontWeight::Black),
+        FontWeight::Black
+    );
+    let weights: HashSet<_> = vec![FontWeight::Bold, FontWeight::from_u32(700)]
+        .into_iter()
+        .collect();
+    assert_eq!(weights.len(), 1);
+
+    assert_eq!(
+        FontWeight::Unknown(340).nearest_keyword(),
+        FontWeight::SemiLight
+    );
+    assert_eq!(
+        FontWeight::Unknown(450).nearest_keyword(),
+        FontWeight::Medium
+    );
+    assert_eq!(FontWeight::Unknown(1).nearest_keyword(), FontWeight::Thin);
+    assert_eq!(
+        FontWeight::Unknown(1000).nearest_keyword(),
+        FontWeight::ExtraBlack
+    );
+
+    assert_eq!("bold".parse(), Ok(FontWeight::Bold));
+    assert_eq!(" Normal ".parse(), Ok(FontWeight::Regular));
+    assert_eq!("450".parse(), Ok(FontWeight::Unknown(450)));
+    assert_eq!("649.6".parse(), Ok(FontWeight::Unknown(650)));
+    assert!("0".parse::<FontWeight>().is_err());
+    assert!("1001".parse::<FontWeight>().is_err());
+    assert!("bolder".parse::<FontWeight>().is_err());
+    assert_eq!(
+        FontWeight::from_css("bolder", FontWeight::Regular),
+        Ok(FontWeight::Bold)
+    );
+    assert_eq!(
+        FontWeight::from_css("bolder", FontWeight::Bold),
+        Ok(FontWeight::Black)
+    );
+    assert_eq!(
+        FontWeight::from_css("bolder", FontWeight::Unknown(950)),
+        Ok(FontWeight::ExtraBlack)
+    );
+    assert_eq!(
+        FontWeight::from_css("lighter", FontWeight::Bold),
+        Ok(FontWeight::Regular)
+    );
+    assert_eq!(
+        FontWeight::from_css("LIGHTER", FontWeight::Black),
+        Ok(FontWeight::Bold)
+    );
+    assert_eq!(
+        FontWeight::from_css("300", FontWeight::Black),
+        Ok(FontWeight::Light)
+    );
+    assert_eq!(
+        FontWeight::Unknown(450).to_string().parse(),
+        Ok(FontWeight::Unknown(450))
+    );
+
+    let axis_value = FontWeight::Unknown(450).axis_value();
+    assert_eq!(Tag::from_dwrite(axis_value.axisTag), Tag::WGHT);
+    assert_eq!(axis_value.value, 450.);
+    assert_eq!(FontWeight::from_axis_value(449.7), FontWeight::Unknown(450));
+    assert_eq!(FontWeight::from_axis_value(0.), FontWeight::Unknown(1));
+    assert_eq!(FontWeight::from_axis_value(f32::NAN), FontWeight::Regular);
+}
+
+#[test]
+fn test_font_simulations() {
+    use std::convert::TryFrom;
+
+    let both = FontSimulations::Bold | FontSimulations::Oblique;
+    assert_eq!(both, FontSimulations::BoldOblique);
+    assert!(both.contains(FontSimulations::Bold));
+    assert!(both.contains(FontSimulations::None));
+    assert!(!FontSimulations::Bold.contains(both));
+    assert_eq!(both & FontSimulations::Oblique, FontSimulations::Oblique);
+    assert_eq!(both - FontSimulations::Oblique, FontSimulations::Bold);
+    assert!(FontSimulations::default().is_empty());
+    let mut simulations = FontSimulations::None;
+    simulations |= FontSimulations::Oblique;
+    assert_eq!(simulations.bits(), DWRITE_FONT_SIMULATIONS_OBLIQUE);
+    assert_eq!(format!("{:?}", simulations), "Oblique");
+    match simulations {
+        FontSimulations::Oblique => {}
+        _ => panic!("simulations constants should work as patterns"),
+    }
+
+    assert_eq!(
+        FontSimulations::try_from(3),
+        Ok(FontSimulations::BoldOblique)
+    );
+    assert_eq!(
+        FontSimulations::try_from(4),
+        Err(FontPropertyError::OutOfRange(4))
+    );
+    assert_eq!(FontSimulations::from_bits(0xff), None);
+    assert_eq!(
+        FontSimulations::from_bits_truncate(0xfd),
+        FontSimulations::Bold
+    );
+}
+
+#[test]
+fn test_font_descriptor_oblique_angle() {
+    let mut descriptor = FontDescriptor {
+        family_name: "Fake Sans".to_owned(),
+        weight: FontWeight::Regular,
+        stretch: FontStretch::Normal,
+        style: FontStyle::Oblique,
+        oblique_angle: Some(FontDescriptor::DEFAULT_OBLIQUE_ANGLE),
+        simulations: None,
+    };
+    let slant = descriptor.slant_axis_value().unwrap();
+    assert_eq!(Tag::from_dwrite(slant.axisTag), Tag::SLNT);
+    assert_eq!(slant.value, -14.);
+    assert_eq!(
+        FontDescriptor::oblique_angle_from_axis_values(&[slant]),
+        Some(14.)
+    );
+    assert_eq!(
+        FontDescriptor::oblique_angle_from_axis_values(&[Tag::WGHT.axis_value(400.)]),
+        None
+    );
+
+    let mut axis_values = vec![Tag::WGHT.axis_value(400.), Tag::SLNT.axis_value(0.)];
+    assert!(descriptor.apply_to_axis_values(&mut axis_values));
+    assert_eq!(axis_values[0].value, 400.);
+    assert_eq!(axis_values[1].value, -14.);
+    assert!(!descriptor.apply_to_axis_values(&mut axis_values));
+    let mut upright_axes = vec![Tag::WGHT.axis_value(400.)];
+    assert!(!descriptor.apply_to_axis_values(&mut upright_axes));
+
+    descriptor.oblique_angle = Some(120.);
+    assert_eq!(descriptor.slant_axis_value().unwrap().value, -90.);
+    descriptor.oblique_angle = Some(f32::NAN);
+    assert!(descriptor.slant_axis_value().is_none());
+    descriptor.oblique_angle = None;
+    assert!(descriptor.slant_axis_value().is_none());
+    descriptor.oblique_angle = Some(10.);
+    descriptor.style = FontStyle::Italic;
+    assert!(descriptor.slant_axis_value().is_none());
+
+    // Descriptors serialized before the angle and simulations existed still
+    // read from self-describing formats.
+    #[cfg(feature = "serde_serialization")]
+    {
+        let json = r#"{"family_name": "Fake Sans", "weight": "Bold", "stretch": "Normal",
+            "style": "Oblique"}"#;
+        let descriptor: FontDescriptor = serde_json::from_str(json).unwrap();
+        assert_eq!(descriptor.weight, FontWeight::Bold);
+        assert_eq!(
+            (descriptor.oblique_angle, descriptor.simulations),
+            (None, None)
+        );
+    }
+}
+
+#[test]
+fn test_match_fonts() {
+    let face = |weight: u32, stretch, style, oblique_angle| FontDescriptor {
+        family_name: "Fake Sans".to_owned(),
+        weight: FontWeight::from_u32(weight),
+        stretch,
+        style,
+        oblique_angle,
+        simulations: None,
+    };
+    let best = |desired: &FontDescriptor, candidates: &[FontDescriptor]| {
+        match_fonts(desired, candidates)
+            .iter()
+            .map(|m| m.index)
+            .collect::<Vec<_>>()
+    };
+    let weights = |desired: u32, candidates: &[u32]| {
+        let desired = face(desired, FontStretch::Normal, FontStyle::Normal, None);
+        let candidates: Vec<_> = candidates
+            .iter()
+            .map(|&weight| face(weight, FontStretch::Normal, FontStyle::Normal, None))
+            .collect();
+        best(&desired, &candidates)
+            .iter()
+            .map(|&index| candidates[index].weight.to_u32())
+            .collect::<Vec<_>>()
+    };
+
+    // 400 and 500 look up to 500 first, then lighter, then heavier.
+    assert_eq!(weights(400, &[300, 600, 500]), [500, 300, 600]);
+    assert_eq!(weights(450, &[600, 400]), [400, 600]);
+    assert_eq!(weights(500, &[600, 400]), [400, 600]);
+    assert_eq!(weights(300, &[400, 200, 100]), [200, 100, 400]);
+    assert_eq!(weights(700, &[600, 900, 800]), [800, 900, 600]);
+    assert_eq!(weights(450, &[450]), [450]);
+
+    // Stretch wins over style, and style over weight.
+    let desired = face(600, FontStretch::Condensed, FontStyle::Italic, None);
+    let candidates = [
+        face(400, FontStretch::Normal, FontStyle::Italic, None),
+        face(700, FontStretch::Condensed, FontStyle::Normal, None),
+        face(700, FontStretch::Condensed, FontStyle::Italic, None),
+        face(400, FontStretch::UltraCondensed, FontStyle::Italic, None),
+    ];
+    assert_eq!(best(&desired, &candidates), [2, 1, 3, 0]);
+    let matches = match_fonts(&desired, &candidates);
+    assert_eq!(matches[0].distance.stretch, 0.);
+    assert_eq!(matches[0].distance.style, 0.);
+    assert_eq!(matches[0].distance.weight, 100.);
+    assert!(matches[0].distance < matches[1].distance);
+
+    // Narrow requests try narrower faces first, wide ones wider faces.
+    let desired = face(400, FontStretch::SemiCondensed, FontStyle::Normal, None);
+    let candidates = [
+        face(400, FontStretch::Normal, FontStyle::Normal, None),
+        face(400, FontStretch::UltraCondensed, FontStyle::Normal, None),
+    ];
+    assert_eq!(best(&desired, &candidates), [1, 0]);
+    let desired = face(400, FontStretch::SemiExpanded, FontStyle::Normal, None);
+    assert_eq!(best(&desired, &candidates), [0, 1]);
+
+    // Italic falls back to oblique then normal, and normal to oblique then
+    // italic.
+    let candidates = [
+        face(400, FontStretch::Normal, FontStyle::Normal, None),
+        face(400, FontStretch::Normal, FontStyle::Italic, None),
+        face(400, FontStretch::Normal, FontStyle::Oblique, None),
+    ];
+    let desired = face(400, FontStretch::Normal, FontStyle::Italic, None);
+    assert_eq!(best(&desired, &candidates), [1, 2, 0]);
+    assert_eq!(best(&desired, &candidates[..1]), [0]);
+    let desired = face(400, FontStretch::Normal, FontStyle::Normal, None);
+    assert_eq!(best(&desired, &candidates[1..]), [1, 0]);
+    let desired = face(400, FontStretch::Normal, FontStyle::Oblique, None);
+    assert_eq!(best(&desired, &candidates), [2, 1, 0]);
+
+    // Oblique angles search steeper slants first, except below 11°.
+    let obliques = [
+        face(400, FontStretch::Normal, FontStyle::Oblique, Some(8.)),
+        face(400, FontStretch::Normal, FontStyle::Oblique, Some(12.)),
+        face(400, FontStretch::Normal, FontStyle::Oblique, Some(25.)),
+        face(400, FontStretch::Normal, FontStyle::Oblique, Some(-20.)),
+    ];
+    let desired = face(400, FontStretch::Normal, FontStyle::Oblique, Some(20.));
+    assert_eq!(best(&desired, &obliques), [2, 1, 0, 3]);
+    let desired = face(400, FontStretch::Normal, FontStyle::Oblique, Some(5.));
+    assert_eq!(best(&desired, &obliques), [0, 3, 1, 2]);
+    let desired = face(400, FontStretch::Normal, FontStyle::Oblique, Some(-10.));
+    assert_eq!(best(&desired, &obliques), [0, 1, 2, 3]);
+}
+
+#[test]
+fn test_font_spec_css() {
+    let spec = FontSpec::from_css(r#"italic bold condensed 12px "Segoe UI", sans-serif"#).unwrap();
+    assert_eq!(spec.descriptor.family_name, "Segoe UI");
+    assert_eq!(spec.descriptor.style, FontStyle::Italic);
+    assert_eq!(spec.descriptor.weight, FontWeight::Bold);
+    assert_eq!(spec.descriptor.stretch, FontStretch::Condensed);
+    assert_eq!(spec.fallback_families, ["sans-serif"]);
+    assert_eq!(spec.size, Some(FontSize::Pixels(12.)));
+    assert_eq!(
+        spec.to_css(),
+        r#"italic bold condensed 12px "Segoe UI", sans-serif"#
+    );
+
+    let spec = FontSpec::from_css("normal 450 10.5pt/1.2 DejaVu   Sans Mono").unwrap();
+    assert_eq!(spec.descriptor.family_name, "DejaVu Sans Mono");
+    assert_eq!(spec.descriptor.weight, FontWeight::Unknown(450));
+    assert_eq!(spec.descriptor.style, FontStyle::Normal);
+    assert_eq!(spec.size, Some(FontSize::Points(10.5)));
+    assert_eq!(spec.to_css(), r#"450 10.5pt "DejaVu Sans Mono""#);
+
+    let spec = FontSpec::from_css(r#"oblique -8deg medium 'Fake \'Q\' Sans'"#).unwrap();
+    assert_eq!(spec.descriptor.family_name, "Fake 'Q' Sans");
+    assert_eq!(spec.descriptor.style, FontStyle::Oblique);
+    assert_eq!(spec.descriptor.oblique_angle, Some(-8.));
+    assert_eq!(spec.size, None);
+
+    for css in &[
+        "12px serif",
+        "oblique 12px serif",
+        r#"oblique 14deg 300 ultra-expanded 16px "A \"quoted\" name", "B", monospace"#,
+        r#"bold medium "Back\\slash""#,
+    ] {
+        let spec = FontSpec::from_css(css).unwrap();
+        assert_eq!(&spec.to_css(), css);
+        assert_eq!(FontSpec::from_css(&spec.to_css()).unwrap(), spec);
+    }
+
+    let error = |css| FontSpec::from_css(css).unwrap_err();
+    assert_eq!(error("bold"), ParseFontSpecError::MissingSize);
+    assert_eq!(
+        error("bold Arial"),
+        ParseFontSpecError::Unexpected("Arial".to_owned())
+    );
+    assert_eq!(error("12px"), ParseFontSpecError::MissingFamily);
+    assert_eq!(error("12px Arial,"), ParseFontSpecError::MissingFamily);
+    assert_eq!(
+        error("12px \"Arial"),
+        ParseFontSpecError::UnterminatedString
+    );
+    assert_eq!(
+        error("bold 700 12px Arial"),
+        ParseFontSpecError::Duplicate("weight")
+    );
+    assert_eq!(
+        error("normal normal normal normal normal 12px Arial"),
+        ParseFontSpecError::Duplicate("normal")
+    );
+    assert_eq!(
+        error("small-caps 12px Arial"),
+        ParseFontSpecError::Unsupported("small-caps".to_owned())
+    );
+    assert_eq!(
+        error("-2px Arial"),
+        ParseFontSpecError::Unexpected("-2px".to_owned())
+    );
+    assert_eq!(
+        error("2em Arial"),
+        ParseFontSpecError::Unsupported("2em".to_owned())
+    );
+    assert_eq!(
+        error("wide 12px Arial"),
+        ParseFontSpecError::Unexpected("wide".to_owned())
+    );
+    assert_eq!(
+        error("12px \"A\" B"),
+        ParseFontSpecError::Unexpected("B".to_owned())
+    );
+    assert!(matches!(
+        error("1200 12px Arial"),
+        ParseFontSpecError::InvalidProperty(_)
+    ));
+}
+
+#[test]
+fn test_font_spec_fontconfig() {
+    let spec = FontSpec::from_fontconfig("DejaVu Sans:weight=200:slant=italic").unwrap();
+    assert_eq!(spec.descriptor.family_name, "DejaVu Sans");
+    assert_eq!(spec.descriptor.weight, FontWeight::Bold);
+    assert_eq!(spec.descriptor.style, FontStyle::Italic);
+    assert_eq!(spec.size, None);
+    assert_eq!(spec.to_fontconfig(), "DejaVu Sans:weight=200:slant=100");
+
+    let spec = FontSpec::from_fontconfig(
+        "Noto Sans,sans\\-serif-11:light:condensed:antialias=true:fontvariations=wght=350,wdth=80",
+    )
+    .unwrap();
+    assert_eq!(spec.descriptor.family_name, "Noto Sans");
+    assert_eq!(spec.fallback_families, ["sans-serif"]);
+    assert_eq!(spec.descriptor.weight, FontWeight::Light);
+    assert_eq!(spec.descriptor.stretch, FontStretch::Condensed);
+    assert_eq!(spec.size, Some(FontSize::Points(11.)));
+    assert_eq!(spec.variations, [(Tag::WGHT, 350.), (Tag::WDTH, 80.)]);
+
+    let spec = FontSpec::from_fontconfig("Mono:pixelsize=13:slant=110:width=63:weight=90").unwrap();
+    assert_eq!(spec.descriptor.style, FontStyle::Oblique);
+    assert_eq!(spec.descriptor.stretch, FontStretch::ExtraCondensed);
+    assert_eq!(spec.descriptor.weight, FontWeight::Unknown(450));
+    assert_eq!(spec.size, Some(FontSize::Pixels(13.)));
+
+    for pattern in &[
+        "Sans",
+        "A\\-B\\:C\\,D-10.5",
+        "Serif,Sans:weight=90:slant=110:width=112.5:pixelsize=16",
+        "Mono:weight=0:fontvariations=wght=100,slnt=-12",
+    ] {
+        let spec = FontSpec::from_fontconfig(pattern).unwrap();
+        assert_eq!(&spec.to_fontconfig(), pattern);
+        assert_eq!(
+            FontSpec::from_fontconfig(&spec.to_fontconfig()).unwrap(),
+            spec
+        );
+    }
+
+    // Every CSS weight survives the trip through fontconfig's scale.
+    for weight in 1..=1000 {
+        let mut spec = FontSpec::from_fontconfig("Sans").unwrap();
+        spec.descriptor.weight = FontWeight::from_u32(weight);
+        let round_trip = FontSpec::from_fontconfig(&spec.to_fontconfig()).unwrap();
+        assert_eq!(round_trip.descriptor.weight.to_u32(), weight.max(100));
+    }
+
+    let error = |pattern| FontSpec::from_fontconfig(pattern).unwrap_err();
+    assert_eq!(error(":bold"), ParseFontSpecError::MissingFamily);
+    assert_eq!(
+        error("Sans-big"),
+        ParseFontSpecError::Unexpected("big".to_owned())
+    );
+    assert_eq!(
+        error("Sans:weight=italic"),
+        ParseFontSpecError::Unexpected("italic".to_owned())
+    );
+    assert!(matches!(
+        error("Sans:width=20"),
+        ParseFontSpecError::InvalidProperty(_)
+    ));
+}
+
+// Makes the same queries of any collection, returning the outline drawn.
+fn query_collection(collection: &dyn FontCollectionBackend) -> Vec<PathCommand> {
+    let family = collection
+        .font_family_by_name("test sans")
+        .unwrap()
+        .unwrap();
+    assert_eq!(family.family_name().unwrap(), "Test Sans");
+    assert!(collection.font_family_by_name("Missing").unwrap().is_none());
+
+    let font = family
+        .first_matching_font(FontWeight::Bold, FontStretch::Normal, FontStyle::Italic)
+        .unwrap();
+    assert_eq!(font.face_name(), "Bold Italic");
+    assert_eq!(font.weight(), FontWeight::Bold);
+    assert_eq!(font.is_monospace(), Some(false));
+
+    let face = font.create_font_face();
+    assert_eq!(face.face_type(), FontFaceType::TrueType);
+    assert_eq!(face.glyph_count(), 3);
+    assert_eq!(face.metrics().designUnitsPerEm, 1000);
+    assert_eq!(face.metrics().underlinePosition, -100);
+    assert_eq!(
+        face.glyph_indices(&['A' as u32, 'Z' as u32]).unwrap(),
+        vec![1, 0]
+    );
+    let head = face
+        .font_table(u32::from_le_bytes(*b"head"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(head, test_font::head(1000));
+    assert_eq!(face.font_table(u32::from_le_bytes(*b"GSUB")).unwrap(), None);
+    let files = face.files().unwrap();
+    assert_eq!(files.len(), 1);
+    assert!(files[0]
+        .font_file_bytes()
+        .unwrap()
+        .starts_with(&[0, 1, 0, 0]));
+
+    let builder = RecordingOutlineBuilder::default();
+    face.glyph_run_outline(
+        10.,
+        &[1, 2],
+        None,
+        None,
+        false,
+        false,
+        Box::new(builder.clone()),
+    )
+    .unwrap();
+    let commands = builder.0.borrow();
+    commands.clone()
+}
+
+#[test]
+fn test_record_and_replay() {
+    use crate::replay::{RecordingFontCollection, ReplayFontCollection, NOT_RECORDED};
+
+    let recording = RecordingFontCollection::new(Box::new(MemoryFontCollection::from_font_files(
+        &test_font_files(),
+    )));
+    let recorded_outline = query_collection(&recording);
+
+    let fixture = recording.fixture();
+    #[cfg(feature = "serde_serialization")]
+    let fixture = {
+        use crate::replay::Fixture;
+
+        let json = serde_json::to_string(&fixture).unwrap();
+        let fixture: Fixture = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&fixture).unwrap(), json);
+
+        // Other format versions, and answers referring to missing
+        // families, are rejected.
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], 2);
+        value["version"] = 1.into();
+        assert!(serde_json::from_value::<Fixture>(value.clone()).is_err());
+        value["version"] = 2.into();
+        value["families"] = serde_json::Value::Array(vec![]);
+        assert!(serde_json::from_value::<Fixture>(value).is_err());
+        fixture
+    };
+
+    let replay = ReplayFontCollection::new(fixture);
+    assert_eq!(replay.font_family_count(), 2);
+    assert_eq!(query_collection(&replay), recorded_outline);
+
+    // Anything not asked for while recording is missing.
+    assert_eq!(
+        replay.font_family_by_name("Test Serif").err(),
+        Some(NOT_RECORDED)
+    );
+    let family = replay.font_family_by_name("Test Sans").unwrap().unwrap();
+    let face = family
+        .first_matching_font(FontWeight::Bold, FontStretch::Normal, FontStyle::Italic)
+        .unwrap()
+        .create_font_face();
+    assert_eq!(face.glyph_indices(&['B' as u32]), Err(NOT_RECORDED));
+    let result = face.glyph_run_outline(
+        12.,
+        &[1],
+        None,
+        None,
+        false,
+        false,
+        Box::new(RecordingOutlineBuilder::default()),
+    );
+    assert_eq!(result, Err(GlyphRunOutlineError::Win32Error(NOT_RECORDED)));
+
+    // Off Windows, the replayed answers come through the public API too.
+    #[cfg(not(windows))]
+    {
+        let collection = FontCollection::take(Box::new(replay));
+        let family = collection
+            .font_family_by_name("TEST SANS")
+            .unwrap()
+            .unwrap();
+        assert_eq!(family.family_name().unwrap(), "Test Sans");
+        // Neither family was asked for by index, so iterating skips both.
+        assert_eq!(collection.families_iter().count(), 0);
+    }
+}
+
+#[test]
+fn test_record_and_replay_variations() {
+    use crate::replay::{RecordingFontCollection, ReplayFontCollection};
+
+    let variable = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+        .table(
+            b"fvar",
+            test_font::fvar(&[(b"wght", 100., 400., 900., 256)], &[]),
+        )
+        .build();
+    let broken = test_font::simple_font("Test Serif", "Regular", 400, 5, 0x40)
+        .table(b"cmap", vec![0; 2])
+        .build();
+    let files: Vec<_> = [variable, broken]
+        .iter()
+        .map(|bytes| MemoryFontFile::new_from_buffer(Arc::new(bytes.clone())).unwrap())
+        .collect();
+    let recording =
+        RecordingFontCollection::new(Box::new(MemoryFontCollection::from_font_files(&files)));
+    let face = |collection: &dyn FontCollectionBackend, family_name| {
+        collection
+            .font_family_by_name(family_name)
+            .unwrap()
+            .unwrap()
+            .first_matching_font(FontWeight::Regular, FontStretch::Normal, FontStyle::Normal)
+            .unwrap()
+            .create_font_face()
+    };
+
+    // Variations and failed lookups pass through the recording unchanged.
+    let recorded = face(&recording, "Test Sans");
+    let variations = recorded.variations().unwrap();
+    assert_eq!(
+        (variations[0].axisTag, variations[0].value),
+        (Tag::WGHT.to_dwrite(), 400.)
+    );
+    let bold = recorded
+        .create_font_face_with_variations(
+            DWRITE_FONT_SIMULATIONS_NONE,
+            &[Tag::WGHT.axis_value(700.)],
+        )
+        .unwrap();
+    assert_eq!(bold.variations().unwrap()[0].value, 700.);
+    recorded.files().unwrap();
+    let error = face(&recording, "Test Serif").glyph_indices(&['A' as u32]);
+    assert!(error.is_err());
+
+    let replay = ReplayFontCollection::new(recording.fixture());
+    let replayed = face(&replay, "Test Sans");
+    let variations = replayed.variations().unwrap();
+    assert_eq!(
+        (variations[0].axisTag, variations[0].value),
+        (Tag::WGHT.to_dwrite(), 400.)
+    );
+    let bold = replayed
+        .create_font_face_with_variations(
+            DWRITE_FONT_SIMULATIONS_NONE,
+            &[Tag::WGHT.axis_value(700.)],
+        )
+        .unwrap();
+    assert_eq!(bold.variations().unwrap()[0].value, 700.);
+    assert_eq!(
+        face(&replay, "Test Serif").glyph_indices(&['A' as u32]),
+        error
+    );
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_portable_font_types() {
+    let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40).build();
+    let file = FontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+    let collection = FontCollection::from_font_files(&[file]).unwrap();
+    assert_eq!(collection.families_iter().count(), 1);
+
+    let family = collection
+        .font_family_by_name("Test Sans")
+        .unwrap()
+        .unwrap();
+    let font = family
+        .first_matching_font(FontWeight::Regular, FontStretch::Normal, FontStyle::Normal)
+        .unwrap();
+    let descriptor = font.to_descriptor();
+    let font_2 = collection
+        .font_from_descriptor(&descriptor)
+        .unwrap()
+        .unwrap();
+    assert_eq!(descriptor, font_2.to_descriptor());
+
+    let face = font.create_font_face();
+    assert_eq!(face.get_type(), FontFaceType::TrueType);
+    assert_eq!(face.glyph_indices(&['B' as u32]).unwrap(), vec![2]);
+    assert_eq!(
+        face.font_table_by_tag(Tag::HEAD).unwrap(),
+        Some(test_font::head(1000))
+    );
+    let names = face.name_table().unwrap().unwrap();
+    assert_eq!(names.find(tables::name::NAME_ID_FAMILY), Some("Test Sans"));
+    assert_eq!(names.records.len(), 2);
+    let os2 = face.os2_table().unwrap().unwrap();
+    assert_eq!((os2.version, os2.weight_class), (0, 400));
+    let cmap = face.cmap_table().unwrap().unwrap();
+    assert_eq!(cmap.code_points_for_glyph(2), ['B' as u32]);
+    assert_eq!(face.kerning_pairs().unwrap(), []);
+    assert!(face.variation_axes().unwrap().is_empty());
+
+    let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40)
+        .table(
+            b"fvar",
+            test_font::fvar(&[(b"wght", 100., 400., 900., 2)], &[(2, &[400.], 0xffff)]),
+        )
+        .build();
+    let file = FontFile::new_from_buffer(Arc::new(bytes)).unwrap();
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+    let axes = face.variation_axes().unwrap();
+    assert_eq!((axes[0].tag, axes[0].max_value), (Tag::WGHT, 900.));
+    assert_eq!(axes[0].name.as_deref(), Some("Regular"));
+    let instances = face.named_instances().unwrap();
+    assert_eq!(instances[0].value(Tag::WGHT), Some(400.));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_isolated_factory() {
+    let factory = Factory::new(FactoryType::Isolated).unwrap();
+    assert_eq!(factory.factory_type(), FactoryType::Isolated);
+    assert!(GdiInterop::create_with_factory(&factory).is_ok());
+    assert!(FontFallback::get_system_fallback_with_factory(&factory).is_some());
+
+    let system_fc = FontCollection::system();
+    let arial_family = system_fc.font_family_by_name("Arial").unwrap().unwrap();
+    let arial_font = arial_family
+        .first_matching_font(FontWeight::Regular, FontStretch::Normal, FontStyle::Normal)
+        .unwrap();
+    let bytes = arial_font.create_font_face().files().unwrap()[0]
+        .font_file_bytes()
+        .unwrap();
+    let file = FontFile::new_from_buffer_with_factory(&factory, Arc::new(bytes)).unwrap();
+    assert!(file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).is_ok());
+}
+
+// Stands in for a factory, counting the buffers it is asked to wrap and
+// refusing all of them once `fail` is set.
+#[cfg(not(windows))]
+#[derive(Clone, Default)]
+struct CountingFactory {
+    created: Rc<RefCell<u32>>,
+    fail: bool,
+}
+
+#[cfg(not(windows))]
+impl FactoryBackend for CountingFactory {
+    fn create_font_file_from_buffer(
+        &self,
+        data: Arc<dyn AsRef<[u8]> + Sync + Send>,
+    ) -> Result<Box<dyn FontFileBackend>, HRESULT> {
+        *self.created.borrow_mut() += 1;
+        if self.fail {
+            return Err(0x80004005_u32 as HRESULT);
+        }
+        MemoryFactory.create_font_file_from_buffer(data)
+    }
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_injected_factory() {
+    let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40).build();
+
+    let stand_in = CountingFactory::default();
+    let factory = Factory::from_backend(Box::new(stand_in.clone()), FactoryType::Isolated);
+    assert_eq!(factory.factory_type(), FactoryType::Isolated);
+    let file = FontFile::new_from_buffer_with_factory(&factory, Arc::new(bytes.clone())).unwrap();
+    assert_eq!(*stand_in.created.borrow(), 1);
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_NONE).unwrap();
+    assert_eq!(face.glyph_indices(&['A' as u32]).unwrap(), vec![1]);
+
+    let failing = Factory::from_backend(
+        Box::new(CountingFactory {
+            fail: true,
+            ..CountingFactory::default()
+        }),
+        FactoryType::Shared,
+    );
+    assert!(FontFile::new_from_buffer_with_factory(&failing, Arc::new(bytes.clone())).is_none());
+
+    assert_eq!(
+        Factory::shared().unwrap().factory_type(),
+        FactoryType::Shared
+    );
+    assert!(FontFile::new_from_buffer(Arc::new(bytes)).is_some());
+}
+
+#[test]
+fn test_fake_factory() {
+    use crate::factory::Factory;
+    use crate::font_fallback::FontFallback;
+    use crate::font_file::FontFile;
+    use crate::gdi_interop::GdiInterop;
+    use crate::sys::um::dwrite::DWRITE_FONT_FACE_TYPE_TRUETYPE;
+
+    let bytes = test_font::simple_font("Test Sans", "Regular", 400, 5, 0x40).build();
+    let fixture = test_com::FactoryFixture {
+        file: test_com::FileFixture {
+            face_type: DWRITE_FONT_FACE_TYPE_TRUETYPE,
+            face_count: 1,
+        },
+        face: fake_face_fixture(vec![]),
+        has_fallback: true,
+        register_error: None,
+    };
+
+    let calls = test_com::CallLog::default();
+    let factory = Factory::take(
+        test_com::fake_factory(fixture.clone(), calls.clone()),
+        FactoryType::Isolated,
+    );
+    assert_eq!(factory.factory_type(), FactoryType::Isolated);
+    let file = FontFile::new_from_buffer_with_factory(&factory, Arc::new(bytes.clone())).unwrap();
+    let face = file.create_face(0, DWRITE_FONT_SIMULATIONS_BOLD).unwrap();
+    assert_eq!(face.get_glyph_count(), 3);
+    assert_eq!(face.simulations(), FontSimulations::Bold);
+    assert!(file.create_face(1, DWRITE_FONT_SIMULATIONS_NONE).is_err());
+    // The loader is only registered once per factory.
+    assert!(FontFile::new_from_buffer_with_factory(&factory, Arc::new(bytes.clone())).is_some());
+    assert!(GdiInterop::create_with_factory(&factory).is_ok());
+    assert!(FontFallback::get_system_fallback_with_factory(&factory).is_some());
+    assert_eq!(
+        *calls.borrow(),
+        [
+            "RegisterFontFileLoader",
+            "CreateCustomFontFileReference",
+            "CreateFontFace",
+            "CreateFontFace",
+            "CreateCustomFontFileReference",
+            "GetGdiInterop",
+            "GetSystemFontFallback",
+        ]
+    );
+
+    // Factories older than `IDWriteFactory2` have no system fallback.
+    let old = Factory::take(
+        test_com::fake_factory(
+            test_com::FactoryFixture {
+                has_fallback: false,
+                ..fixture.clone()
+            },
+            calls.clone(),
+        ),
+        FactoryType::Shared,
+    );
+    assert!(FontFallback::get_system_fallback_with_factory(&old).is_none());
+
+    let failing = Factory::take(
+        test_com::fake_factory(
+            test_com::FactoryFixture {
+                register_error: Some(0x80004005_u32 as HRESULT),
+                ..fixture
+            },
+            calls,
+        ),
+        FactoryType::Isolated,
+    );
+    assert!(FontFile::new_from_buffer_with_factory(&failing, Arc::new(bytes)).is_none());
+}
+
+fn fake_face_fixture(axes: Vec<test_com::AxisFixture>) -> test_com::FaceFixture {
+    use crate::sys::um::dwrite::DWRITE_FONT_FACE_TYPE_TRUETYPE;
+
+    test_com::FaceFixture {
+        face_type: DWRITE_FONT_FACE_TYPE_TRUETYPE,
+        index: 0,
+        simulations: DWRITE_FONT_SIMULATIONS_NONE,
+        glyph_count: 3,
+        axes,
+        files: vec![test_com::FileFixture {
+            face_type: DWRITE_FONT_FACE_TYPE_TRUETYPE,
+            face_count: 1,
+        }],
+    }
+}
+
+fn fake_family_fixture() -> test_com::FamilyFixture {
+    let font = |face_name, weight: FontWeight, stretch: FontStretch, style: FontStyle| {
+        test_com::FontFixture {
+            face_names: vec![("en-us", face_name)],
+            weight: weight.to_u32(),
+            stretch: stretch.to_u32(),
+            style: style.to_u32(),
+            simulations: DWRITE_FONT_SIMULATIONS_NONE,
+            face: fake_face_fixture(vec![]),
+        }
+    };
+    test_com::FamilyFixture {
+        family_names: vec![("en-us", "Fake Sans"), ("x-test", "Faux Sans")],
+        fonts: vec![
+            font(
+                "Regular",
+                FontWeight::Regular,
+                FontStretch::Normal,
+                FontStyle::Normal,
+            ),
+            font(
+                "Bold Italic",
+                FontWeight::Bold,
+                FontStretch::Normal,
+                FontStyle::Italic,
+            ),
+        ],
+    }
+}
+
+#[test]
+fn test_fake_face_variations() {
+    use crate::font_face::FontFace;
+    use crate::sys::um::dwrite_3::{DWRITE_FONT_AXIS_TAG_WEIGHT, DWRITE_FONT_AXIS_TAG_WIDTH};
+
+    let axis = |tag, value, variable| test_com::AxisFixture {
+        value: DWRITE_FONT_AXIS_VALUE {
+            axisTag: tag,
+            value,
+        },
+        variable,
+    };
+    let face = FontFace::take(test_com::fake_font_face(fake_face_fixture(vec![
+        axis(DWRITE_FONT_AXIS_TAG_WEIGHT, 650., true),
+        axis(DWRITE_FONT_AXIS_TAG_WIDTH, 100., false),
+    ])));
+    assert!(face.has_variations());
+    let variations = face.variations().unwrap();
+    assert_eq!(variations.len(), 1);
+    assert_eq!(variations[0].axisTag, DWRITE_FONT_AXIS_TAG_WEIGHT);
+    assert_eq!(variations[0].value, 650.);
+    assert_eq!(face.variation_by_tag(Tag::WGHT).unwrap(), Some(650.));
+    assert_eq!(face.variation_by_tag(Tag::WDTH).unwrap(), None);
+
+    assert_eq!(face.get_type(), FontFaceType::TrueType);
+    assert_eq!(face.get_glyph_count(), 3);
+    assert_eq!(face.files().unwrap().len(), 1);
+
+    // Axes that only record the default instance aren't variations.
+    let face = FontFace::take(test_com::fake_font_face(fake_face_fixture(vec![axis(
+        DWRITE_FONT_AXIS_TAG_WIDTH,
+        100.,
+        false,
+    )])));
+    assert!(!face.has_variations());
+    assert!(face.variations().unwrap().is_empty());
+}
+
+#[test]
+fn test_fake_family_first_matching_font() {
+    use crate::font_collection::FontCollection;
+    use crate::font_family::FontFamily;
+
+    let family = FontFamily::take(test_com::fake_font_family(Rc::new(fake_family_fixture())));
+    assert_eq!(family.get_font_count(), 2);
+    assert_eq!(family.family_name().unwrap(), "Fake Sans");
+
+    let font = family
+        .first_matching_font(FontWeight::Bold, FontStretch::Normal, FontStyle::Italic)
+        .unwrap();
+    assert_eq!(font.face_name(), "Bold Italic");
+    assert_eq!(font.weight(), FontWeight::Bold);
+    assert_eq!(font.style(), FontStyle::Italic);
+    assert_eq!(font.family_name(), "Fake Sans");
+    assert_eq!(font.create_font_face().get_glyph_count(), 3);
+
+    assert!(family
+        .first_matching_font(FontWeight::Thin, FontStretch::Normal, FontStyle::Normal)
+        .is_err());
+
+    let collection =
+        FontCollection::take(test_com::fake_font_collection(vec![fake_family_fixture()]));
+    let descriptor = font.to_descriptor();
+    let font_2 = collection
+        .font_from_descriptor(&descriptor)
+        .unwrap()
+        .unwrap();
+    assert_eq!(font_2.to_descriptor(), descriptor);
+    assert!(collection
+        .font_family_by_name("Faux Sans")
+        .unwrap()
+        .is_some());
+    assert!(collection.font_family_by_name("Missing").unwrap().is_none());
+}
+
+#[test]
+fn test_fake_font_face_from_descriptor() {
+    use crate::font_collection::FontCollection;
+    use crate::sys::um::dwrite_3::DWRITE_FONT_AXIS_TAG_SLANT;
+
+    let mut family = fake_family_fixture();
+    let mut oblique = family.fonts[0].clone();
+    oblique.face_names = vec![("en-us", "Oblique")];
+    oblique.style = FontStyle::Oblique.to_u32();
+    oblique.face.axes = vec![test_com::AxisFixture {
+        value: Tag::SLNT.axis_value(0.),
+        variable: true,
+    }];
+    family.fonts.push(oblique);
+    let collection = FontCollection::take(test_com::fake_font_collection(vec![family]));
+
+    let mut descriptor = FontDescriptor {
+        family_name: "Fake Sans".to_owned(),
+        weight: FontWeight::Regular,
+        stretch: FontStretch::Normal,
+        style: FontStyle::Oblique,
+        oblique_angle: Some(12.),
+        simulations: None,
+    };
+    let face = collection
+        .font_face_from_descriptor(&descriptor)
+        .unwrap()
+        .unwrap();
+    assert_eq!(face.variation_by_tag(Tag::SLNT).unwrap(), Some(-12.));
+    assert_eq!(
+        face.variations().unwrap()[0].axisTag,
+        DWRITE_FONT_AXIS_TAG_SLANT
+    );
+
+    assert_eq!(face.simulations(), FontSimulations::None);
+
+    descriptor.oblique_angle = None;
+    let face = collection
+        .font_face_from_descriptor(&descriptor)
+        .unwrap()
+        .unwrap();
+    assert_eq!(face.variation_by_tag(Tag::SLNT).unwrap(), Some(0.));
+
+    descriptor.simulations = Some(FontSimulations::Bold);
+    let face = collection
+        .font_face_from_descriptor(&descriptor)
+        .unwrap()
+        .unwrap();
+    assert_eq!(face.simulations(), FontSimulations::Bold);
+
+    descriptor.family_name = "Missing".to_owned();
+    assert!(collection
+        .font_face_from_descriptor(&descriptor)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_get_locale_string() {
+    use crate::helpers::get_locale_string;
+
+    let mut strings =
+        test_com::fake_localized_strings(vec![("x-test", "Test"), ("en-US", "English")]);
+    assert_eq!(get_locale_string(&mut strings), "English");
+
+    // Without a match for the user's locale or en-US, the first string wins.
+    let mut strings =
+        test_com::fake_localized_strings(vec![("x-test", "Test"), ("x-other", "Other")]);
+    assert_eq!(get_locale_string(&mut strings), "Test");
+}
+
+// The declarations in `crate::sys` that stand in for winapi off Windows
+// must match it, or the wrappers tested with them aren't the ones shipped.
+#[cfg(windows)]
+#[test]
+fn test_sys_mirror_matches_winapi() {
+    use crate::sys::mirror_shared as shared;
+    use crate::sys::mirror_um as um;
+    use std::mem::{align_of, size_of};
+    use winapi::shared::guiddef::GUID;
+    use winapi::Interface;
+
+    fn guid(guid: GUID) -> (u32, u16, u16, [u8; 8]) {
+        (guid.Data1, guid.Data2, guid.Data3, guid.Data4)
+    }
+
+    macro_rules! assert_same_layout {
+        ($($top:ident :: $module:ident :: $name:ident),* $(,)?) => {$(
+            assert_eq!(
+                (size_of::<$top::$module::$name>(), align_of::<$top::$module::$name>()),
+                (
+                    size_of::<winapi::$top::$module::$name>(),
+                    align_of::<winapi::$top::$module::$name>(),
+                ),
+                stringify!($name),
+            );
+        )*};
+    }
+    assert_same_layout!(
+        shared::d3d9types::D3DCOLORVALUE,
+        shared::guiddef::GUID,
+        shared::minwindef::FILETIME,
+        shared::windef::POINT,
+        shared::windef::RECT,
+        shared::windef::SIZE,
+        um::d2d1::D2D1_BEZIER_SEGMENT,
+        um::dcommon::D2D_POINT_2F,
+        um::dcommon::D2D_SIZE_U,
+        um::dcommon::DWRITE_GLYPH_IMAGE_DATA,
+        um::dwrite::DWRITE_CARET_METRICS,
+        um::dwrite::DWRITE_FONT_METRICS,
+        um::dwrite::DWRITE_GLYPH_METRICS,
+        um::dwrite::DWRITE_GLYPH_OFFSET,
+        um::dwrite::DWRITE_GLYPH_RUN,
+        um::dwrite::DWRITE_GLYPH_RUN_DESCRIPTION,
+        um::dwrite::DWRITE_MATRIX,
+        um::dwrite::DWRITE_UNICODE_RANGE,
+        um::dwrite_1::DWRITE_FONT_METRICS1,
+        um::dwrite_1::DWRITE_PANOSE,
+        um::dwrite_3::DWRITE_FONT_AXIS_RANGE,
+        um::dwrite_3::DWRITE_FONT_AXIS_VALUE,
+        um::wingdi::BITMAP,
+        um::wingdi::LOGFONTW,
+    );
+
+    // Every interface is mirrored with its whole vtable, so the vtables
+    // must be the same size as well as the IIDs the same.
+    macro_rules! assert_same_interface {
+        ($($module:ident :: $name:ident ($vtbl:ident)),* $(,)?) => {$(
+            assert_eq!(
+                guid(<um::$module::$name as Interface>::uuidof()),
+                guid(<winapi::um::$module::$name as Interface>::uuidof()),
+                stringify!($name),
+            );
+            assert_eq!(
+                size_of::<um::$module::$vtbl>(),
+                size_of::<winapi::um::$module::$vtbl>(),
+                stringify!($vtbl),
+            );
+        )*};
+    }
+    assert_same_interface!(
+        unknwnbase::IUnknown(IUnknownVtbl),
+        d2d1::ID2D1SimplifiedGeometrySink(ID2D1SimplifiedGeometrySinkVtbl),
+        dwrite::IDWriteBitmapRenderTarget(IDWriteBitmapRenderTargetVtbl),
+        dwrite::IDWriteFactory(IDWriteFactoryVtbl),
+        dwrite::IDWriteFont(IDWriteFontVtbl),
+        dwrite::IDWriteFontCollection(IDWriteFontCollectionVtbl),
+        dwrite::IDWriteFontCollectionLoader(IDWriteFontCollectionLoaderVtbl),
+        dwrite::IDWriteFontFace(IDWriteFontFaceVtbl),
+        dwrite::IDWriteFontFamily(IDWriteFontFamilyVtbl),
+        dwrite::IDWriteFontFile(IDWriteFontFileVtbl),
+        dwrite::IDWriteFontFileEnumerator(IDWriteFontFileEnumeratorVtbl),
+        dwrite::IDWriteFontFileLoader(IDWriteFontFileLoaderVtbl),
+        dwrite::IDWriteFontFileStream(IDWriteFontFileStreamVtbl),
+        dwrite::IDWriteFontList(IDWriteFontListVtbl),
+        dwrite::IDWriteGdiInterop(IDWriteGdiInteropVtbl),
+        dwrite::IDWriteGlyphRunAnalysis(IDWriteGlyphRunAnalysisVtbl),
+        dwrite::IDWriteLocalFontFileLoader(IDWriteLocalFontFileLoaderVtbl),
+        dwrite::IDWriteLocalizedStrings(IDWriteLocalizedStringsVtbl),
+        dwrite::IDWriteNumberSubstitution(IDWriteNumberSubstitutionVtbl),
+        dwrite::IDWriteRenderingParams(IDWriteRenderingParamsVtbl),
+        dwrite::IDWriteTextAnalysisSource(IDWriteTextAnalysisSourceVtbl),
+        dwrite_1::IDWriteFactory1(IDWriteFactory1Vtbl),
+        dwrite_1::IDWriteFont1(IDWriteFont1Vtbl),
+        dwrite_1::IDWriteFontFace1(IDWriteFontFace1Vtbl),
+        dwrite_2::IDWriteFactory2(IDWriteFactory2Vtbl),
+        dwrite_2::IDWriteFontFace2(IDWriteFontFace2Vtbl),
+        dwrite_2::IDWriteFontFallback(IDWriteFontFallbackVtbl),
+        dwrite_3::IDWriteFontFace3(IDWriteFontFace3Vtbl),
+        dwrite_3::IDWriteFontFace4(IDWriteFontFace4Vtbl),
+        dwrite_3::IDWriteFontFace5(IDWriteFontFace5Vtbl),
+        dwrite_3::IDWriteFontResource(IDWriteFontResourceVtbl),
+    );
+}
@@ -0,0 +1,1284 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// Fake DirectWrite objects, implemented in Rust with the `com_helpers`
+// machinery and driven by fixture data, so that the wrappers can be tested
+// without depending on the fonts installed on the machine, or on Windows.
+//
+// Only the methods the wrappers call are modelled. Every other vtable slot
+// gets a stub of the slot's own signature: `not_implemented` for the ones
+// that return an HRESULT, and `returns_zero` for the rest, which have no
+// way to fail.
+
+#![allow(non_snake_case, non_upper_case_globals)]
+
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::c_void;
+use crate::sys::shared::guiddef::{IsEqualGUID, REFIID};
+use crate::sys::shared::minwindef::{BOOL, FALSE, TRUE, ULONG};
+use crate::sys::shared::winerror::{
+    DWRITE_E_ALREADYREGISTERED, E_INVALIDARG, E_NOINTERFACE, E_NOTIMPL, S_OK,
+};
+use crate::sys::um::dwrite::{
+    IDWriteFactory, IDWriteFactoryVtbl, IDWriteFont, IDWriteFontCollection,
+    IDWriteFontCollectionVtbl, IDWriteFontFace, IDWriteFontFaceVtbl, IDWriteFontFamily,
+    IDWriteFontFamilyVtbl, IDWriteFontFile, IDWriteFontFileLoader, IDWriteFontFileStream,
+    IDWriteFontFileVtbl, IDWriteFontList, IDWriteFontListVtbl, IDWriteFontVtbl, IDWriteGdiInterop,
+    IDWriteGdiInteropVtbl, IDWriteLocalizedStrings, IDWriteLocalizedStringsVtbl,
+    DWRITE_FONT_FACE_TYPE, DWRITE_FONT_FILE_TYPE, DWRITE_FONT_FILE_TYPE_UNKNOWN,
+    DWRITE_FONT_SIMULATIONS, DWRITE_FONT_STRETCH, DWRITE_FONT_STYLE, DWRITE_FONT_WEIGHT,
+};
+use crate::sys::um::dwrite_1::{
+    IDWriteFactory1, IDWriteFactory1Vtbl, IDWriteFontFace1, IDWriteFontFace1Vtbl,
+};
+use crate::sys::um::dwrite_2::{
+    IDWriteFactory2, IDWriteFactory2Vtbl, IDWriteFontFace2, IDWriteFontFace2Vtbl,
+    IDWriteFontFallback, IDWriteFontFallbackVtbl,
+};
+use crate::sys::um::dwrite_3::{
+    IDWriteFontFace3, IDWriteFontFace3Vtbl, IDWriteFontFace4, IDWriteFontFace4Vtbl,
+    IDWriteFontFace5, IDWriteFontFace5Vtbl, IDWriteFontResource, IDWriteFontResourceVtbl,
+    DWRITE_FONT_AXIS_ATTRIBUTES, DWRITE_FONT_AXIS_ATTRIBUTES_NONE,
+    DWRITE_FONT_AXIS_ATTRIBUTES_VARIABLE, DWRITE_FONT_AXIS_VALUE,
+};
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::winnt::{HRESULT, WCHAR};
+use crate::sys::Interface;
+use std::cell::RefCell;
+use std::ptr;
+use std::rc::Rc;
+use std::sync::atomic::{self, AtomicUsize};
+
+use crate::com_helpers::*;
+
+const DWRITE_E_NOFONT: HRESULT = 0x88985002_u32 as HRESULT;
+
+/// The value a stub returns, for stubs returning `R`.
+trait StubValue<R> {
+    const VALUE: R;
+}
+
+struct NotImplemented;
+
+impl StubValue<HRESULT> for NotImplemented {
+    const VALUE: HRESULT = E_NOTIMPL;
+}
+
+struct Zero;
+
+macro_rules! zero_values {
+    ($($ty:ty = $zero:expr),*) => {
+        $(impl StubValue<$ty> for Zero {
+            const VALUE: $ty = $zero;
+        })*
+    };
+}
+
+zero_values!(() = (), i32 = 0, u16 = 0, u32 = 0, f32 = 0.);
+
+/// A vtable slot type, which can be filled with a function of the same
+/// signature that just returns `V`'s value.
+trait Stub<V> {
+    const STUB: Self;
+}
+
+macro_rules! stubs {
+    ($($arg:ident),*) => {
+        impl<V: StubValue<R>, R, $($arg),*> Stub<V> for unsafe extern "system" fn($($arg),*) -> R {
+            const STUB: Self = {
+                unsafe extern "system" fn stub<V: StubValue<R>, R, $($arg),*>($(_: $arg),*) -> R {
+                    V::VALUE
+                }
+                stub::<V, R, $($arg),*>
+            };
+        }
+    };
+}
+
+stubs!(A);
+stubs!(A, B);
+stubs!(A, B, C);
+stubs!(A, B, C, D);
+stubs!(A, B, C, D, E);
+stubs!(A, B, C, D, E, F);
+stubs!(A, B, C, D, E, F, G);
+stubs!(A, B, C, D, E, F, G, H);
+stubs!(A, B, C, D, E, F, G, H, I);
+stubs!(A, B, C, D, E, F, G, H, I, J);
+stubs!(A, B, C, D, E, F, G, H, I, J, K);
+stubs!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+const fn not_implemented<F: Stub<NotImplemented>>() -> F {
+    F::STUB
+}
+
+const fn returns_zero<F: Stub<Zero>>() -> F {
+    F::STUB
+}
+
+/// Localized strings, as `(locale, string)` pairs.
+pub type LocalizedStrings = Vec<(&'static str, &'static str)>;
+
+#[derive(Clone)]
+pub struct AxisFixture {
+    pub value: DWRITE_FONT_AXIS_VALUE,
+    pub variable: bool,
+}
+
+#[derive(Clone)]
+pub struct FileFixture {
+    pub face_type: DWRITE_FONT_FACE_TYPE,
+    pub face_count: u32,
+}
+
+#[derive(Clone)]
+pub struct FaceFixture {
+    pub face_type: DWRITE_FONT_FACE_TYPE,
+    pub index: u32,
+    pub simulations: DWRITE_FONT_SIMULATIONS,
+    pub glyph_count: u16,
+    pub axes: Vec<AxisFixture>,
+    pub files: Vec<FileFixture>,
+}
+
+#[derive(Clone)]
+pub struct FontFixture {
+    pub face_names: LocalizedStrings,
+    pub weight: DWRITE_FONT_WEIGHT,
+    pub stretch: DWRITE_FONT_STRETCH,
+    pub style: DWRITE_FONT_STYLE,
+    pub simulations: DWRITE_FONT_SIMULATIONS,
+    pub face: FaceFixture,
+}
+
+#[derive(Clone)]
+pub struct FamilyFixture {
+    pub family_names: LocalizedStrings,
+    pub fonts: Vec<FontFixture>,
+}
+
+unsafe fn wide_str<'a>(string: *const WCHAR) -> &'a [u16] {
+    let mut len = 0;
+    while *string.add(len) != 0 {
+        len += 1;
+    }
+    std::slice::from_raw_parts(string, len)
+}
+
+// Copies `string` and a terminating NUL into a caller buffer of `size`
+// code units.
+unsafe fn copy_wide(string: &str, buffer: *mut WCHAR, size: u32) -> HRESULT {
+    let wide: Vec<u16> = string.encode_utf16().chain(Some(0)).collect();
+    if wide.len() > size as usize {
+        return E_INVALIDARG;
+    }
+    ptr::copy_nonoverlapping(wide.as_ptr(), buffer, wide.len());
+    S_OK
+}
+
+struct FakeLocalizedStrings {
+    refcount: AtomicUsize,
+    strings: LocalizedStrings,
+}
+
+const FakeLocalizedStringsVtbl: &IDWriteLocalizedStringsVtbl = &IDWriteLocalizedStringsVtbl {
+    parent: implement_iunknown!(IDWriteLocalizedStrings, FakeLocalizedStrings),
+    GetCount: {
+        unsafe extern "system" fn GetCount(This: *mut IDWriteLocalizedStrings) -> u32 {
+            let this = FakeLocalizedStrings::from_interface(This);
+            this.strings.len() as u32
+        }
+        GetCount
+    },
+    FindLocaleName: {
+        unsafe extern "system" fn FindLocaleName(
+            This: *mut IDWriteLocalizedStrings,
+            localeName: *const WCHAR,
+            index: *mut u32,
+            exists: *mut BOOL,
+        ) -> HRESULT {
+            let this = FakeLocalizedStrings::from_interface(This);
+            let locale = String::from_utf16_lossy(wide_str(localeName)).to_lowercase();
+            match this
+                .strings
+                .iter()
+                .position(|(candidate, _)| candidate.to_lowercase() == locale)
+            {
+                Some(position) => {
+                    *index = position as u32;
+                    *exists = TRUE;
+                }
+                None => {
+                    *index = u32::MAX;
+                    *exists = FALSE;
+                }
+            }
+            S_OK
+        }
+        FindLocaleName
+    },
+    GetLocaleNameLength: {
+        unsafe extern "system" fn GetLocaleNameLength(
+            This: *mut IDWriteLocalizedStrings,
+            index: u32,
+            length: *mut u32,
+        ) -> HRESULT {
+            let this = FakeLocalizedStrings::from_interface(This);
+            match this.strings.get(index as usize) {
+                Some((locale, _)) => {
+                    *length = locale.encode_utf16().count() as u32;
+                    S_OK
+                }
+                None => E_INVALIDARG,
+            }
+        }
+        GetLocaleNameLength
+    },
+    GetLocaleName: {
+        unsafe extern "system" fn GetLocaleName(
+            This: *mut IDWriteLocalizedStrings,
+            index: u32,
+            localeName: *mut WCHAR,
+            size: u32,
+        ) -> HRESULT {
+            let this = FakeLocalizedStrings::from_interface(This);
+            match this.strings.get(index as usize) {
+                Some((locale, _)) => copy_wide(locale, localeName, size),
+                None => E_INVALIDARG,
+            }
+        }
+        GetLocaleName
+    },
+    GetStringLength: {
+        unsafe extern "system" fn GetStringLength(
+            This: *mut IDWriteLocalizedStrings,
+            index: u32,
+            length: *mut u32,
+        ) -> HRESULT {
+            let this = FakeLocalizedStrings::from_interface(This);
+            match this.strings.get(index as usize) {
+                Some((_, string)) => {
+                    *length = string.encode_utf16().count() as u32;
+                    S_OK
+                }
+                None => E_INVALIDARG,
+            }
+        }
+        GetStringLength
+    },
+    GetString: {
+        unsafe extern "system" fn GetString(
+            This: *mut IDWriteLocalizedStrings,
+            index: u32,
+            stringBuffer: *mut WCHAR,
+            size: u32,
+        ) -> HRESULT {
+            let this = FakeLocalizedStrings::from_interface(This);
+            match this.strings.get(index as usize) {
+                Some((_, string)) => copy_wide(string, stringBuffer, size),
+                None => E_INVALIDARG,
+            }
+        }
+        GetString
+    },
+};
+
+impl Com<IDWriteLocalizedStrings> for FakeLocalizedStrings {
+    type Vtbl = IDWriteLocalizedStringsVtbl;
+    fn vtbl() -> &'static IDWriteLocalizedStringsVtbl {
+        FakeLocalizedStringsVtbl
+    }
+}
+
+impl Com<IUnknown> for FakeLocalizedStrings {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeLocalizedStringsVtbl.parent
+    }
+}
+
+pub fn fake_localized_strings(strings: LocalizedStrings) -> ComPtr<IDWriteLocalizedStrings> {
+    let fake = FakeLocalizedStrings {
+        refcount: AtomicUsize::new(1),
+        strings,
+    };
+    unsafe { ComPtr::from_raw(fake.into_interface()) }
+}
+
+struct FakeFontFile {
+    refcount: AtomicUsize,
+    fixture: FileFixture,
+}
+
+const FakeFontFileVtbl: &IDWriteFontFileVtbl = &IDWriteFontFileVtbl {
+    parent: implement_iunknown!(IDWriteFontFile, FakeFontFile),
+    GetReferenceKey: not_implemented(),
+    GetLoader: not_implemented(),
+    Analyze: {
+        unsafe extern "system" fn Analyze(
+            This: *mut IDWriteFontFile,
+            isSupportedFontType: *mut BOOL,
+            fontFileType: *mut DWRITE_FONT_FILE_TYPE,
+            fontFaceType: *mut DWRITE_FONT_FACE_TYPE,
+            numberOfFaces: *mut u32,
+        ) -> HRESULT {
+            let this = FakeFontFile::from_interface(This);
+            *isSupportedFontType = if this.fixture.face_count > 0 {
+                TRUE
+            } else {
+                FALSE
+            };
+            *fontFileType = DWRITE_FONT_FILE_TYPE_UNKNOWN;
+            *fontFaceType = this.fixture.face_type;
+            *numberOfFaces = this.fixture.face_count;
+            S_OK
+        }
+        Analyze
+    },
+};
+
+impl Com<IDWriteFontFile> for FakeFontFile {
+    type Vtbl = IDWriteFontFileVtbl;
+    fn vtbl() -> &'static IDWriteFontFileVtbl {
+        FakeFontFileVtbl
+    }
+}
+
+impl Com<IUnknown> for FakeFontFile {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeFontFileVtbl.parent
+    }
+}
+
+pub fn fake_font_file(fixture: FileFixture) -> ComPtr<IDWriteFontFile> {
+    let fake = FakeFontFile {
+        refcount: AtomicUsize::new(1),
+        fixture,
+    };
+    unsafe { ComPtr::from_raw(fake.into_interface()) }
+}
+
+// The resource behind a fake face, which creates copies of that face with
+// other axis values.
+struct FakeFontResource {
+    refcount: AtomicUsize,
+    face: FaceFixture,
+}
+
+const FakeFontResourceVtbl: &IDWriteFontResourceVtbl = &IDWriteFontResourceVtbl {
+    parent: implement_iunknown!(IDWriteFontResource, FakeFontResource),
+    GetFontFile: not_implemented(),
+    GetFontFaceIndex: returns_zero(),
+    GetFontAxisCount: {
+        unsafe extern "system" fn GetFontAxisCount(This: *mut IDWriteFontResource) -> u32 {
+            let this = FakeFontResource::from_interface(This);
+            this.face.axes.len() as u32
+        }
+        GetFontAxisCount
+    },
+    GetDefaultFontAxisValues: not_implemented(),
+    GetFontAxisRanges: not_implemented(),
+    GetFontAxisAttributes: {
+        unsafe extern "system" fn GetFontAxisAttributes(
+            This: *mut IDWriteFontResource,
+            axis: u32,
+        ) -> DWRITE_FONT_AXIS_ATTRIBUTES {
+            let this = FakeFontResource::from_interface(This);
+            match this.face.axes.get(axis as usize) {
+                Some(axis) if axis.variable => DWRITE_FONT_AXIS_ATTRIBUTES_VARIABLE,
+                _ => DWRITE_FONT_AXIS_ATTRIBUTES_NONE,
+            }
+        }
+        GetFontAxisAttributes
+    },
+    GetAxisNames: not_implemented(),
+    GetAxisValueNameCount: returns_zero(),
+    GetAxisValueNames: not_implemented(),
+    HasVariations: {
+        unsafe extern "system" fn HasVariations(This: *mut IDWriteFontResource) -> BOOL {
+            let this = FakeFontResource::from_interface(This);
+            if this.face.axes.iter().any(|axis| axis.variable) {
+                TRUE
+            } else {
+                FALSE
+            }
+        }
+        HasVariations
+    },
+    CreateFontFace: {
+        unsafe extern "system" fn CreateFontFace(
+            This: *mut IDWriteFontResource,
+            simulations: DWRITE_FONT_SIMULATIONS,
+            values: *const DWRITE_FONT_AXIS_VALUE,
+            valueCount: u32,
+            fontFace: *mut *mut IDWriteFontFace5,
+        ) -> HRESULT {
+            let this = FakeFontResource::from_interface(This);
+            let mut face = this.face.clone();
+            face.simulations = simulations;
+            // Like DirectWrite, ignore values for axes the font doesn't have.
+            for value in std::slice::from_raw_parts(values, valueCount as usize) {
+                if let Some(axis) = face
+                    .axes
+                    .iter_mut()
+                    .find(|axis| axis.variable && axis.value.axisTag == value.axisTag)
+                {
+                    axis.value.value = value.value;
+                }
+            }
+            let fake = FakeFontFace {
+                refcount: AtomicUsize::new(1),
+                fixture: face,
+            };
+            *fontFace = fake.into_interface();
+            S_OK
+        }
+        CreateFontFace
+    },
+    CreateFontFaceReference: not_implemented(),
+};
+
+impl Com<IDWriteFontResource> for FakeFontResource {
+    type Vtbl = IDWriteFontResourceVtbl;
+    fn vtbl() -> &'static IDWriteFontResourceVtbl {
+        FakeFontResourceVtbl
+    }
+}
+
+impl Com<IUnknown> for FakeFontResource {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeFontResourceVtbl.parent
+    }
+}
+
+// A font face answering to every `IDWriteFontFace` version up to 5, since
+// the wrapper reaches the newer methods through `QueryInterface`.
+struct FakeFontFace {
+    refcount: AtomicUsize,
+    fixture: FaceFixture,
+}
+
+const FakeFontFaceVtbl: &IDWriteFontFace5Vtbl = &IDWriteFontFace5Vtbl {
+    parent: IDWriteFontFace4Vtbl {
+        parent: IDWriteFontFace3Vtbl {
+            parent: IDWriteFontFace2Vtbl {
+                parent: IDWriteFontFace1Vtbl {
+                    parent: IDWriteFontFaceVtbl {
+                        parent: IUnknownVtbl {
+                            QueryInterface: {
+                                unsafe extern "system" fn QueryInterface(
+                                    unknown_this: *mut IUnknown,
+                                    riid: REFIID,
+                                    ppv_object: *mut *mut c_void,
+                                ) -> HRESULT {
+                                    let supported = [
+                                        IUnknown::uuidof(),
+                                        IDWriteFontFace::uuidof(),
+                                        IDWriteFontFace1::uuidof(),
+                                        IDWriteFontFace2::uuidof(),
+                                        IDWriteFontFace3::uuidof(),
+                                        IDWriteFontFace4::uuidof(),
+                                        IDWriteFontFace5::uuidof(),
+                                    ];
+                                    if !supported.iter().any(|iid| IsEqualGUID(&*riid, iid)) {
+                                        return E_NOINTERFACE;
+                                    }
+                                    (*unknown_this).AddRef();
+                                    *ppv_object = unknown_this as *mut c_void;
+                                    S_OK
+                                }
+                                QueryInterface
+                            },
+                            AddRef: {
+                                unsafe extern "system" fn AddRef(
+                                    unknown_this: *mut IUnknown,
+                                ) -> ULONG {
+                                    let this = FakeFontFace::from_interface(unknown_this);
+                                    let count =
+                                        this.refcount.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+                                    count as ULONG
+                                }
+                                AddRef
+                            },
+                            Release: {
+                                unsafe extern "system" fn Release(
+                                    unknown_this: *mut IUnknown,
+                                ) -> ULONG {
+                                    let this = FakeFontFace::from_interface(unknown_this);
+                                    let count =
+                                        this.refcount.fetch_sub(1, atomic::Ordering::Release) - 1;
+                                    if count == 0 {
+                                        <FakeFontFace as Com<IDWriteFontFace5>>::destroy(
+                                            unknown_this as *mut IDWriteFontFace5,
+                                        );
+                                    }
+                                    count as ULONG
+                                }
+                                Release
+                            },
+                        },
+                        GetType: {
+                            unsafe extern "system" fn GetType(
+                                This: *mut IDWriteFontFace,
+                            ) -> DWRITE_FONT_FACE_TYPE {
+                                let this = FakeFontFace::from_interface(This);
+                                this.fixture.face_type
+                            }
+                            GetType
+                        },
+                        GetFiles: {
+                            unsafe extern "system" fn GetFiles(
+                                This: *mut IDWriteFontFace,
+                                numberOfFiles: *mut u32,
+                                fontFiles: *mut *mut IDWriteFontFile,
+                            ) -> HRESULT {
+                                let this = FakeFontFace::from_interface(This);
+                                let files = &this.fixture.files;
+                                if !fontFiles.is_null() {
+                                    if (*numberOfFiles as usize) < files.len() {
+                                        return E_INVALIDARG;
+                                    }
+                                    for (index, file) in files.iter().enumerate() {
+                                        *fontFiles.add(index) =
+                                            fake_font_file(file.clone()).into_raw();
+                                    }
+                                }
+                                *numberOfFiles = files.len() as u32;
+                                S_OK
+                            }
+                            GetFiles
+                        },
+                        GetIndex: {
+                            unsafe extern "system" fn GetIndex(This: *mut IDWriteFontFace) -> u32 {
+                                let this = FakeFontFace::from_interface(This);
+                                this.fixture.index
+                            }
+                            GetIndex
+                        },
+                        GetSimulations: {
+                            unsafe extern "system" fn GetSimulations(
+                                This: *mut IDWriteFontFace,
+                            ) -> DWRITE_FONT_SIMULATIONS {
+                                let this = FakeFontFace::from_interface(This);
+                                this.fixture.simulations
+                            }
+                            GetSimulations
+                        },
+                        IsSymbolFont: returns_zero(),
+                        GetMetrics: returns_zero(),
+                        GetGlyphCount: {
+                            unsafe extern "system" fn GetGlyphCount(
+                                This: *mut IDWriteFontFace,
+                            ) -> u16 {
+                                let this = FakeFontFace::from_interface(This);
+                                this.fixture.glyph_count
+                            }
+                            GetGlyphCount
+                        },
+                        GetDesignGlyphMetrics: not_implemented(),
+                        GetGlyphIndices: not_implemented(),
+                        TryGetFontTable: not_implemented(),
+                        ReleaseFontTable: not_implemented(),
+                        GetGlyphRunOutline: not_implemented(),
+                        GetRecommendedRenderingMode: not_implemented(),
+                        GetGdiCompatibleMetrics: not_implemented(),
+                        GetGdiCompatibleGlyphMetrics: not_implemented(),
+                    },
+                    GetMetrics: returns_zero(),
+                    GetGdiCompatibleMetrics: not_implemented(),
+                    GetCaretMetrics: returns_zero(),
+                    GetUnicodeRanges: not_implemented(),
+                    IsMonospacedFont: returns_zero(),
+                    GetDesignGlyphAdvances: not_implemented(),
+                    GetGdiCompatibleGlyphAdvances: not_implemented(),
+                    GetKerningPairAdjustments: not_implemented(),
+                    HasKerningPairs: returns_zero(),
+                    GetRecommendedRenderingMode: not_implemented(),
+                    GetVerticalGlyphVariants: not_implemented(),
+                    HasVerticalGlyphVariants: returns_zero(),
+                },
+                IsColorFont: returns_zero(),
+                GetColorPaletteCount: returns_zero(),
+                GetPaletteEntryCount: returns_zero(),
+                GetPaletteEntries: not_implemented(),
+                GetRecommendedRenderingMode: not_implemented(),
+            },
+            GetFontFaceReference: not_implemented(),
+            GetPanose: returns_zero(),
+            GetWeight: returns_zero(),
+            GetStretch: returns_zero(),
+            GetStyle: returns_zero(),
+            GetFamilyNames: not_implemented(),
+            GetFaceNames: not_implemented(),
+            GetInformationalStrings: not_implemented(),
+            HasCharacter: returns_zero(),
+            GetRecommendedRenderingMode: not_implemented(),
+            IsCharacterLocal: returns_zero(),
+            IsGlyphLocal: returns_zero(),
+            AreCharactersLocal: not_implemented(),
+            AreGlyphsLocal: not_implemented(),
+        },
+        GetGlyphImageFormats_2: not_implemented(),
+        GetGlyphImageFormats_1: returns_zero(),
+        GetGlyphImageData: not_implemented(),
+        ReleaseGlyphImageData: returns_zero(),
+    },
+    GetFontAxisValueCount: {
+        unsafe extern "system" fn GetFontAxisValueCount(This: *mut IDWriteFontFace5) -> u32 {
+            let this = FakeFontFace::from_interface(This);
+            this.fixture.axes.len() as u32
+        }
+        GetFontAxisValueCount
+    },
+    GetFontAxisValues: {
+        unsafe extern "system" fn GetFontAxisValues(
+            This: *mut IDWriteFontFace5,
+            values: *mut DWRITE_FONT_AXIS_VALUE,
+            valueCount: u32,
+        ) -> HRESULT {
+            let this = FakeFontFace::from_interface(This);
+            let axes = &this.fixture.axes;
+            if (valueCount as usize) < axes.len() {
+                return E_INVALIDARG;
+            }
+            for (index, axis) in axes.iter().enumerate() {
+                *values.add(index) = axis.value;
+            }
+            S_OK
+        }
+        GetFontAxisValues
+    },
+    HasVariations: {
+        unsafe extern "system" fn HasVariations(This: *mut IDWriteFontFace5) -> BOOL {
+            let this = FakeFontFace::from_interface(This);
+            if this.fixture.axes.iter().any(|axis| axis.variable) {
+                TRUE
+            } else {
+                FALSE
+            }
+        }
+        HasVariations
+    },
+    GetFontResource: {
+        unsafe extern "system" fn GetFontResource(
+            This: *mut IDWriteFontFace5,
+            resource: *mut *mut IDWriteFontResource,
+        ) -> HRESULT {
+            let this = FakeFontFace::from_interface(This);
+            let fake = FakeFontResource {
+                refcount: AtomicUsize::new(1),
+                face: this.fixture.clone(),
+            };
+            *resource = fake.into_interface();
+            S_OK
+        }
+        GetFontResource
+    },
+    Equals: returns_zero(),
+};
+
+impl Com<IDWriteFontFace5> for FakeFontFace {
+    type Vtbl = IDWriteFontFace5Vtbl;
+    fn vtbl() -> &'static IDWriteFontFace5Vtbl {
+        FakeFontFaceVtbl
+    }
+}
+
+impl Com<IDWriteFontFace> for FakeFontFace {
+    type Vtbl = IDWriteFontFaceVtbl;
+    fn vtbl() -> &'static IDWriteFontFaceVtbl {
+        &FakeFontFaceVtbl.parent.parent.parent.parent.parent
+    }
+}
+
+impl Com<IUnknown> for FakeFontFace {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeFontFaceVtbl.parent.parent.parent.parent.parent.parent
+    }
+}
+
+pub fn fake_font_face(fixture: FaceFixture) -> ComPtr<IDWriteFontFace> {
+    let fake = FakeFontFace {
+        refcount: AtomicUsize::new(1),
+        fixture,
+    };
+    let face5: *mut IDWriteFontFace5 = fake.into_interface();
+    unsafe { ComPtr::from_raw(face5 as *mut IDWriteFontFace) }
+}
+
+struct FakeFont {
+    refcount: AtomicUsize,
+    family: Rc<FamilyFixture>,
+    index: usize,
+}
+
+impl FakeFont {
+    fn fixture(&self) -> &FontFixture {
+        &self.family.fonts[self.index]
+    }
+}
+
+const FakeFontVtbl: &IDWriteFontVtbl = &IDWriteFontVtbl {
+    parent: implement_iunknown!(IDWriteFont, FakeFont),
+    GetFontFamily: {
+        unsafe extern "system" fn GetFontFamily(
+            This: *mut IDWriteFont,
+            fontFamily: *mut *mut IDWriteFontFamily,
+        ) -> HRESULT {
+            let this = FakeFont::from_interface(This);
+            *fontFamily = fake_font_family(this.family.clone()).into_raw();
+            S_OK
+        }
+        GetFontFamily
+    },
+    GetWeight: {
+        unsafe extern "system" fn GetWeight(This: *mut IDWriteFont) -> DWRITE_FONT_WEIGHT {
+            FakeFont::from_interface(This).fixture().weight
+        }
+        GetWeight
+    },
+    GetStretch: {
+        unsafe extern "system" fn GetStretch(This: *mut IDWriteFont) -> DWRITE_FONT_STRETCH {
+            FakeFont::from_interface(This).fixture().stretch
+        }
+        GetStretch
+    },
+    GetStyle: {
+        unsafe extern "system" fn GetStyle(This: *mut IDWriteFont) -> DWRITE_FONT_STYLE {
+            FakeFont::from_interface(This).fixture().style
+        }
+        GetStyle
+    },
+    IsSymbolFont: returns_zero(),
+    GetFaceNames: {
+        unsafe extern "system" fn GetFaceNames(
+            This: *mut IDWriteFont,
+            names: *mut *mut IDWriteLocalizedStrings,
+        ) -> HRESULT {
+            let this = FakeFont::from_interface(This);
+            *names = fake_localized_strings(this.fixture().face_names.clone()).into_raw();
+            S_OK
+        }
+        GetFaceNames
+    },
+    GetInformationalStrings: not_implemented(),
+    GetSimulations: {
+        unsafe extern "system" fn GetSimulations(
+            This: *mut IDWriteFont,
+        ) -> DWRITE_FONT_SIMULATIONS {
+            FakeFont::from_interface(This).fixture().simulations
+        }
+        GetSimulations
+    },
+    GetMetrics: returns_zero(),
+    HasCharacter: not_implemented(),
+    CreateFontFace: {
+        unsafe extern "system" fn CreateFontFace(
+            This: *mut IDWriteFont,
+            fontFace: *mut *mut IDWriteFontFace,
+        ) -> HRESULT {
+            let this = FakeFont::from_interface(This);
+            *fontFace = fake_font_face(this.fixture().face.clone()).into_raw();
+            S_OK
+        }
+        CreateFontFace
+    },
+};
+
+impl Com<IDWriteFont> for FakeFont {
+    type Vtbl = IDWriteFontVtbl;
+    fn vtbl() -> &'static IDWriteFontVtbl {
+        FakeFontVtbl
+    }
+}
+
+impl Com<IUnknown> for FakeFont {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeFontVtbl.parent
+    }
+}
+
+fn fake_font(family: Rc<FamilyFixture>, index: usize) -> ComPtr<IDWriteFont> {
+    let fake = FakeFont {
+        refcount: AtomicUsize::new(1),
+        family,
+        index,
+    };
+    unsafe { ComPtr::from_raw(fake.into_interface()) }
+}
+
+struct FakeFontFamily {
+    refcount: AtomicUsize,
+    fixture: Rc<FamilyFixture>,
+}
+
+const FakeFontFamilyVtbl: &IDWriteFontFamilyVtbl = &IDWriteFontFamilyVtbl {
+    parent: IDWriteFontListVtbl {
+        parent: implement_iunknown!(IDWriteFontFamily, FakeFontFamily),
+        GetFontCollection: not_implemented(),
+        GetFontCount: {
+            unsafe extern "system" fn GetFontCount(This: *mut IDWriteFontList) -> u32 {
+                let this = FakeFontFamily::from_interface(This as *mut IDWriteFontFamily);
+                this.fixture.fonts.len() as u32
+            }
+            GetFontCount
+        },
+        GetFont: {
+            unsafe extern "system" fn GetFont(
+                This: *mut IDWriteFontList,
+                index: u32,
+                font: *mut *mut IDWriteFont,
+            ) -> HRESULT {
+                let this = FakeFontFamily::from_interface(This as *mut IDWriteFontFamily);
+                if index as usize >= this.fixture.fonts.len() {
+                    return E_INVALIDARG;
+                }
+                *font = fake_font(this.fixture.clone(), index as usize).into_raw();
+                S_OK
+            }
+            GetFont
+        },
+    },
+    GetFamilyNames: {
+        unsafe extern "system" fn GetFamilyNames(
+            This: *mut IDWriteFontFamily,
+            names: *mut *mut IDWriteLocalizedStrings,
+        ) -> HRESULT {
+            let this = FakeFontFamily::from_interface(This);
+            *names = fake_localized_strings(this.fixture.family_names.clone()).into_raw();
+            S_OK
+        }
+        GetFamilyNames
+    },
+    // Only exact matches are modelled; anything else fails the way
+    // DirectWrite does for an empty family.
+    GetFirstMatchingFont: {
+        unsafe extern "system" fn GetFirstMatchingFont(
+            This: *mut IDWriteFontFamily,
+            weight: DWRITE_FONT_WEIGHT,
+            stretch: DWRITE_FONT_STRETCH,
+            style: DWRITE_FONT_STYLE,
+            matchingFont: *mut *mut IDWriteFont,
+        ) -> HRESULT {
+            let this = FakeFontFamily::from_interface(This);
+            match this.fixture.fonts.iter().position(|font| {
+                font.weight == weight && font.stretch == stretch && font.style == style
+            }) {
+                Some(index) => {
+                    *matchingFont = fake_font(this.fixture.clone(), index).into_raw();
+                    S_OK
+                }
+                None => {
+                    *matchingFont = ptr::null_mut();
+                    DWRITE_E_NOFONT
+                }
+            }
+        }
+        GetFirstMatchingFont
+    },
+    GetMatchingFonts: not_implemented(),
+};
+
+impl Com<IDWriteFontFamily> for FakeFontFamily {
+    type Vtbl = IDWriteFontFamilyVtbl;
+    fn vtbl() -> &'static IDWriteFontFamilyVtbl {
+        FakeFontFamilyVtbl
+    }
+}
+
+impl Com<IUnknown> for FakeFontFamily {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeFontFamilyVtbl.parent.parent
+    }
+}
+
+pub fn fake_font_family(fixture: Rc<FamilyFixture>) -> ComPtr<IDWriteFontFamily> {
+    let fake = FakeFontFamily {
+        refcount: AtomicUsize::new(1),
+        fixture,
+    };
+    unsafe { ComPtr::from_raw(fake.into_interface()) }
+}
+
+struct FakeFontCollection {
+    refcount: AtomicUsize,
+    families: Vec<Rc<FamilyFixture>>,
+}
+
+const FakeFontCollectionVtbl: &IDWriteFontCollectionVtbl = &IDWriteFontCollectionVtbl {
+    parent: implement_iunknown!(IDWriteFontCollection, FakeFontCollection),
+    GetFontFamilyCount: {
+        unsafe extern "system" fn GetFontFamilyCount(This: *mut IDWriteFontCollection) -> u32 {
+            let this = FakeFontCollection::from_interface(This);
+            this.families.len() as u32
+        }
+        GetFontFamilyCount
+    },
+    GetFontFamily: {
+        unsafe extern "system" fn GetFontFamily(
+            This: *mut IDWriteFontCollection,
+            index: u32,
+            fontFamily: *mut *mut IDWriteFontFamily,
+        ) -> HRESULT {
+            let this = FakeFontCollection::from_interface(This);
+            match this.families.get(index as usize) {
+                Some(family) => {
+                    *fontFamily = fake_font_family(family.clone()).into_raw();
+                    S_OK
+                }
+                None => E_INVALIDARG,
+            }
+        }
+        GetFontFamily
+    },
+    FindFamilyName: {
+        unsafe extern "system" fn FindFamilyName(
+            This: *mut IDWriteFontCollection,
+            familyName: *const WCHAR,
+            index: *mut u32,
+            exists: *mut BOOL,
+        ) -> HRESULT {
+            let this = FakeFontCollection::from_interface(This);
+            let name = String::from_utf16_lossy(wide_str(familyName)).to_lowercase();
+            match this.families.iter().position(|family| {
+                family
+                    .family_names
+                    .iter()
+                    .any(|(_, candidate)| candidate.to_lowercase() == name)
+            }) {
+                Some(position) => {
+                    *index = position as u32;
+                    *exists = TRUE;
+                }
+                None => {
+                    *index = u32::MAX;
+                    *exists = FALSE;
+                }
+            }
+            S_OK
+        }
+        FindFamilyName
+    },
+    GetFontFromFontFace: not_implemented(),
+};
+
+impl Com<IDWriteFontCollection> for FakeFontCollection {
+    type Vtbl = IDWriteFontCollectionVtbl;
+    fn vtbl() -> &'static IDWriteFontCollectionVtbl {
+        FakeFontCollectionVtbl
+    }
+}
+
+impl Com<IUnknown> for FakeFontCollection {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeFontCollectionVtbl.parent
+    }
+}
+
+pub fn fake_font_collection(families: Vec<FamilyFixture>) -> ComPtr<IDWriteFontCollection> {
+    let fake = FakeFontCollection {
+        refcount: AtomicUsize::new(1),
+        families: families.into_iter().map(Rc::new).collect(),
+    };
+    unsafe { ComPtr::from_raw(fake.into_interface()) }
+}
+
+/// The DirectWrite methods a fake factory was called with, in order.
+pub type CallLog = Rc<RefCell<Vec<&'static str>>>;
+
+#[derive(Clone)]
+pub struct FactoryFixture {
+    /// What every buffer registered with the factory turns out to hold.
+    pub file: FileFixture,
+    pub face: FaceFixture,
+    /// Whether the factory answers to `IDWriteFactory2`, which is where the
+    /// system font fallback lives.
+    pub has_fallback: bool,
+    /// The error `RegisterFontFileLoader` fails with, if any.
+    pub register_error: Option<HRESULT>,
+}
+
+// A factory creating fake files, faces, GDI interops and fallbacks, so that
+// the `_with_factory` constructors can be given one in place of DirectWrite's.
+struct FakeFactory {
+    refcount: AtomicUsize,
+    fixture: FactoryFixture,
+    calls: CallLog,
+    loaders: Vec<*mut IDWriteFontFileLoader>,
+}
+
+impl FakeFactory {
+    fn log(&self, method: &'static str) {
+        self.calls.borrow_mut().push(method);
+    }
+}
+
+const FakeFactoryVtbl: &IDWriteFactory2Vtbl = &IDWriteFactory2Vtbl {
+    parent: IDWriteFactory1Vtbl {
+        parent: IDWriteFactoryVtbl {
+            parent: IUnknownVtbl {
+                QueryInterface: {
+                    unsafe extern "system" fn QueryInterface(
+                        unknown_this: *mut IUnknown,
+                        riid: REFIID,
+                        ppv_object: *mut *mut c_void,
+                    ) -> HRESULT {
+                        let this = FakeFactory::from_interface(unknown_this);
+                        let supported = [IUnknown::uuidof(), IDWriteFactory::uuidof()];
+                        let newer = [IDWriteFactory1::uuidof(), IDWriteFactory2::uuidof()];
+                        let newer: &[_] = if this.fixture.has_fallback {
+                            &newer
+                        } else {
+                            &[]
+                        };
+                        if !supported
+                            .iter()
+                            .chain(newer)
+                            .any(|iid| IsEqualGUID(&*riid, iid))
+                        {
+                            return E_NOINTERFACE;
+                        }
+                        (*unknown_this).AddRef();
+                        *ppv_object = unknown_this as *mut c_void;
+                        S_OK
+                    }
+                    QueryInterface
+                },
+                AddRef: {
+                    unsafe extern "system" fn AddRef(unknown_this: *mut IUnknown) -> ULONG {
+                        let this = FakeFactory::from_interface(unknown_this);
+                        let count = this.refcount.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+                        count as ULONG
+                    }
+                    AddRef
+                },
+                Release: {
+                    unsafe extern "system" fn Release(unknown_this: *mut IUnknown) -> ULONG {
+                        let this = FakeFactory::from_interface(unknown_this);
+                        let count = this.refcount.fetch_sub(1, atomic::Ordering::Release) - 1;
+                        if count == 0 {
+                            <FakeFactory as Com<IDWriteFactory2>>::destroy(
+                                unknown_this as *mut IDWriteFactory2,
+                            );
+                        }
+                        count as ULONG
+                    }
+                    Release
+                },
+            },
+            GetSystemFontCollection: not_implemented(),
+            CreateCustomFontCollection: not_implemented(),
+            RegisterFontCollectionLoader: not_implemented(),
+            UnregisterFontCollectionLoader: not_implemented(),
+            CreateFontFileReference: not_implemented(),
+            CreateCustomFontFileReference: {
+                unsafe extern "system" fn CreateCustomFontFileReference(
+                    This: *mut IDWriteFactory,
+                    fontFileReferenceKey: *const c_void,
+                    fontFileReferenceKeySize: u32,
+                    fontFileLoader: *mut IDWriteFontFileLoader,
+                    fontFile: *mut *mut IDWriteFontFile,
+                ) -> HRESULT {
+                    let this = FakeFactory::from_interface(This);
+                    this.log("CreateCustomFontFileReference");
+                    if !this.loaders.contains(&fontFileLoader) {
+                        return E_INVALIDARG;
+                    }
+                    // Like DirectWrite, check that the key opens a stream.
+                    let mut stream: *mut IDWriteFontFileStream = ptr::null_mut();
+                    let hr = (*fontFileLoader).CreateStreamFromKey(
+                        fontFileReferenceKey,
+                        fontFileReferenceKeySize,
+                        &mut stream,
+                    );
+                    if hr != S_OK {
+                        return hr;
+                    }
+                    drop(ComPtr::from_raw(stream));
+                    *fontFile = fake_font_file(this.fixture.file.clone()).into_raw();
+                    S_OK
+                }
+                CreateCustomFontFileReference
+            },
+            CreateFontFace: {
+                unsafe extern "system" fn CreateFontFace(
+                    This: *mut IDWriteFactory,
+                    fontFaceType: DWRITE_FONT_FACE_TYPE,
+                    numberOfFiles: u32,
+                    _fontFiles: *const *mut IDWriteFontFile,
+                    faceIndex: u32,
+                    fontFaceSimulationFlags: DWRITE_FONT_SIMULATIONS,
+                    fontFace: *mut *mut IDWriteFontFace,
+                ) -> HRESULT {
+                    let this = FakeFactory::from_interface(This);
+                    this.log("CreateFontFace");
+                    if numberOfFiles != 1 || faceIndex >= this.fixture.file.face_count {
+                        return E_INVALIDARG;
+                    }
+                    let mut face = this.fixture.face.clone();
+                    face.face_type = fontFaceType;
+                    face.index = faceIndex;
+                    face.simulations = fontFaceSimulationFlags;
+                    *fontFace = fake_font_face(face).into_raw();
+                    S_OK
+                }
+                CreateFontFace
+            },
+            CreateRenderingParams: not_implemented(),
+            CreateMonitorRenderingParams: not_implemented(),
+            CreateCustomRenderingParams: not_implemented(),
+            RegisterFontFileLoader: {
+                unsafe extern "system" fn RegisterFontFileLoader(
+                    This: *mut IDWriteFactory,
+                    fontFileLoader: *mut IDWriteFontFileLoader,
+                ) -> HRESULT {
+                    let this = FakeFactory::from_interface(This);
+                    this.log("RegisterFontFileLoader");
+                    if let Some(hr) = this.fixture.register_error {
+                        return hr;
+                    }
+                    if this.loaders.contains(&fontFileLoader) {
+                        return DWRITE_E_ALREADYREGISTERED;
+                    }
+                    this.loaders.push(fontFileLoader);
+                    S_OK
+                }
+                RegisterFontFileLoader
+            },
+            UnregisterFontFileLoader: not_implemented(),
+            CreateTextFormat: not_implemented(),
+            CreateTypography: not_implemented(),
+            GetGdiInterop: {
+                unsafe extern "system" fn GetGdiInterop(
+                    This: *mut IDWriteFactory,
+                    gdiInterop: *mut *mut IDWriteGdiInterop,
+                ) -> HRESULT {
+                    let this = FakeFactory::from_interface(This);
+                    this.log("GetGdiInterop");
+                    let fake = FakeGdiInterop {
+                        refcount: AtomicUsize::new(1),
+                    };
+                    *gdiInterop = fake.into_interface();
+                    S_OK
+                }
+                GetGdiInterop
+            },
+            CreateTextLayout: not_implemented(),
+            CreateGdiCompatibleTextLayout: not_implemented(),
+            CreateEllipsisTrimmingSign: not_implemented(),
+            CreateTextAnalyzer: not_implemented(),
+            CreateNumberSubstitution: not_implemented(),
+            CreateGlyphRunAnalysis: not_implemented(),
+        },
+        GetEudcFontCollection: not_implemented(),
+        CreateCustomRenderingParams: not_implemented(),
+    },
+    GetSystemFontFallback: {
+        unsafe extern "system" fn GetSystemFontFallback(
+            This: *mut IDWriteFactory2,
+            fontFallback: *mut *mut IDWriteFontFallback,
+        ) -> HRESULT {
+            let this = FakeFactory::from_interface(This);
+            this.log("GetSystemFontFallback");
+            let fake = FakeFontFallback {
+                refcount: AtomicUsize::new(1),
+            };
+            *fontFallback = fake.into_interface();
+            S_OK
+        }
+        GetSystemFontFallback
+    },
+    CreateFontFallbackBuilder: not_implemented(),
+    TranslateColorGlyphRun: not_implemented(),
+    CreateCustomRenderingParams: not_implemented(),
+    CreateGlyphRunAnalysis: not_implemented(),
+};
+
+impl Com<IDWriteFactory2> for FakeFactory {
+    type Vtbl = IDWriteFactory2Vtbl;
+    fn vtbl() -> &'static IDWriteFactory2Vtbl {
+        FakeFactoryVtbl
+    }
+}
+
+impl Com<IDWriteFactory> for FakeFactory {
+    type Vtbl = IDWriteFactoryVtbl;
+    fn vtbl() -> &'static IDWriteFactoryVtbl {
+        &FakeFactoryVtbl.parent.parent
+    }
+}
+
+impl Com<IUnknown> for FakeFactory {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeFactoryVtbl.parent.parent.parent
+    }
+}
+
+pub fn fake_factory(fixture: FactoryFixture, calls: CallLog) -> ComPtr<IDWriteFactory> {
+    let fake = FakeFactory {
+        refcount: AtomicUsize::new(1),
+        fixture,
+        calls,
+        loaders: vec![],
+    };
+    let factory2: *mut IDWriteFactory2 = fake.into_interface();
+    unsafe { ComPtr::from_raw(factory2 as *mut IDWriteFactory) }
+}
+
+struct FakeGdiInterop {
+    refcount: AtomicUsize,
+}
+
+const FakeGdiInteropVtbl: &IDWriteGdiInteropVtbl = &IDWriteGdiInteropVtbl {
+    parent: implement_iunknown!(IDWriteGdiInterop, FakeGdiInterop),
+    CreateFontFromLOGFONT: not_implemented(),
+    ConvertFontToLOGFONT: not_implemented(),
+    ConvertFontFaceToLOGFONT: not_implemented(),
+    CreateFontFaceFromHdc: not_implemented(),
+    CreateBitmapRenderTarget: not_implemented(),
+};
+
+impl Com<IDWriteGdiInterop> for FakeGdiInterop {
+    type Vtbl = IDWriteGdiInteropVtbl;
+    fn vtbl() -> &'static IDWriteGdiInteropVtbl {
+        FakeGdiInteropVtbl
+    }
+}
+
+impl Com<IUnknown> for FakeGdiInterop {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeGdiInteropVtbl.parent
+    }
+}
+
+struct FakeFontFallback {
+    refcount: AtomicUsize,
+}
+
+const FakeFontFallbackVtbl: &IDWriteFontFallbackVtbl = &IDWriteFontFallbackVtbl {
+    parent: implement_iunknown!(IDWriteFontFallback, FakeFontFallback),
+    MapCharacters: not_implemented(),
+};
+
+impl Com<IDWriteFontFallback> for FakeFontFallback {
+    type Vtbl = IDWriteFontFallbackVtbl;
+    fn vtbl() -> &'static IDWriteFontFallbackVtbl {
+        FakeFontFallbackVtbl
+    }
+}
+
+impl Com<IUnknown> for FakeFontFallback {
+    type Vtbl = IUnknownVtbl;
+    fn vtbl() -> &'static IUnknownVtbl {
+        &FakeFontFallbackVtbl.parent
+    }
+}
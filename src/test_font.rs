@@ -0,0 +1,897 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// Builds small but well-formed fonts in memory, so that the pure-Rust code
+// can be tested without shipping font files.
+
+pub(crate) fn push_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+pub(crate) fn push_i16(out: &mut Vec<u8>, value: i16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+pub(crate) fn push_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Assembles an sfnt from raw tables.
+#[derive(Default)]
+pub(crate) struct FontBuilder {
+    tables: Vec<([u8; 4], Vec<u8>)>,
+}
+
+impl FontBuilder {
+    pub fn new() -> FontBuilder {
+        FontBuilder::default()
+    }
+
+    pub fn table(mut self, tag: &[u8; 4], data: Vec<u8>) -> FontBuilder {
+        self.tables.retain(|(existing, _)| existing != tag);
+        self.tables.push((*tag, data));
+        self
+    }
+
+    pub fn without(mut self, tag: &[u8; 4]) -> FontBuilder {
+        self.tables.retain(|(existing, _)| existing != tag);
+        self
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        self.build_at(0)
+    }
+
+    // Lays the font out as if it started at `base` within a larger file, as
+    // faces inside a collection do.
+    fn build_at(&self, base: usize) -> Vec<u8> {
+        let mut tables = self.tables.clone();
+        tables.sort_by_key(|(tag, _)| *tag);
+
+        let mut out = Vec::new();
+        push_u32(&mut out, 0x0001_0000);
+        push_u16(&mut out, tables.len() as u16);
+        push_u16(&mut out, 0);
+        push_u16(&mut out, 0);
+        push_u16(&mut out, 0);
+
+        let mut offset = base + 12 + 16 * tables.len();
+        for (tag, data) in &tables {
+            out.extend_from_slice(tag);
+            push_u32(&mut out, 0);
+            push_u32(&mut out, offset as u32);
+            push_u32(&mut out, data.len() as u32);
+            offset += (data.len() + 3) & !3;
+        }
+        for (_, data) in &tables {
+            out.extend_from_slice(data);
+            while out.len() % 4 != 0 {
+                out.push(0);
+            }
+        }
+        out
+    }
+}
+
+/// Assembles a TrueType collection from per-face builders.
+pub(crate) fn collection(faces: &[FontBuilder]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(b"ttcf");
+    push_u32(&mut out, 0x0001_0000);
+    push_u32(&mut out, faces.len() as u32);
+    let header_len = 12 + 4 * faces.len();
+
+    let mut bodies = Vec::new();
+    let mut offset = header_len;
+    for face in faces {
+        let body = face.build_at(offset);
+        push_u32(&mut out, offset as u32);
+        offset += body.len();
+        bodies.push(body);
+    }
+    for body in bodies {
+        out.extend_from_slice(&body);
+    }
+    out
+}
+
+pub(crate) fn head(units_per_em: u16) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u32(&mut out, 0x0001_0000);
+    push_u32(&mut out, 0x0001_0000);
+    push_u32(&mut out, 0);
+    push_u32(&mut out, 0x5F0F_3CF5);
+    push_u16(&mut out, 0);
+    push_u16(&mut out, units_per_em);
+    out.extend_from_slice(&[0; 16]);
+    out.extend_from_slice(&[0; 8]);
+    push_u16(&mut out, 0);
+    push_u16(&mut out, 8);
+    push_i16(&mut out, 2);
+    // Long `loca` offsets.
+    push_i16(&mut out, 1);
+    push_i16(&mut out, 0);
+    out
+}
+
+pub(crate) fn hhea(number_of_h_metrics: u16) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u32(&mut out, 0x0001_0000);
+    push_i16(&mut out, 800);
+    push_i16(&mut out, -200);
+    push_i16(&mut out, 0);
+    out.extend_from_slice(&[0; 24]);
+    push_u16(&mut out, number_of_h_metrics);
+    out
+}
+
+pub(crate) fn maxp(num_glyphs: u16) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u32(&mut out, 0x0000_5000);
+    push_u16(&mut out, num_glyphs);
+    out
+}
+
+pub(crate) fn hmtx(advances: &[u16]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for &advance in advances {
+        push_u16(&mut out, advance);
+        push_i16(&mut out, 0);
+    }
+    out
+}
+
+/// A `cmap` with a single Windows Unicode BMP subtable mapping each code
+/// point to its glyph.
+pub(crate) fn cmap(mappings: &[(u16, u16)]) -> Vec<u8> {
+    let mut mappings = mappings.to_vec();
+    mappings.sort();
+    mappings.push((0xffff, 0));
+    let seg_count = mappings.len() as u16;
+
+    let mut subtable = Vec::new();
+    push_u16(&mut subtable, 4);
+    push_u16(&mut subtable, 16 + 8 * seg_count);
+    push_u16(&mut subtable, 0);
+    push_u16(&mut subtable, seg_count * 2);
+    let search_range = 2 * (1 << (15 - seg_count.leading_zeros())) as u16;
+    push_u16(&mut subtable, search_range);
+    push_u16(&mut subtable, 15 - seg_count.leading_zeros() as u16);
+    push_u16(&mut subtable, seg_count * 2 - search_range);
+    for &(code_point, _) in &mappings {
+        push_u16(&mut subtable, code_point);
+    }
+    push_u16(&mut subtable, 0);
+    for &(code_point, _) in &mappings {
+        push_u16(&mut subtable, code_point);
+    }
+    for &(code_point, glyph) in &mappings {
+        push_u16(
+            &mut subtable,
+            if code_point == 0xffff {
+                1
+            } else {
+                glyph.wrapping_sub(code_point)
+            },
+        );
+    }
+    for _ in &mappings {
+        push_u16(&mut subtable, 0);
+    }
+
+    let mut out = Vec::new();
+    push_u16(&mut out, 0);
+    push_u16(&mut out, 1);
+    push_u16(&mut out, 3);
+    push_u16(&mut out, 1);
+    push_u32(&mut out, 12);
+    out.extend_from_slice(&subtable);
+    out
+}
+
+/// A `name` table with Windows US English records.
+pub(crate) fn name(records: &[(u16, &str)]) -> Vec<u8> {
+    let mut storage = Vec::new();
+    let mut out = Vec::new();
+    push_u16(&mut out, 0);
+    push_u16(&mut out, records.len() as u16);
+    push_u16(&mut out, 6 + 12 * records.len() as u16);
+    for &(name_id, string) in records {
+        let encoded: Vec<u8> = string
+            .encode_utf16()
+            .flat_map(|unit| unit.to_be_bytes().to_vec())
+            .collect();
+        push_u16(&mut out, 3);
+        push_u16(&mut out, 1);
+        push_u16(&mut out, 0x0409);
+        push_u16(&mut out, name_id);
+        push_u16(&mut out, encoded.len() as u16);
+        push_u16(&mut out, storage.len() as u16);
+        storage.extend_from_slice(&encoded);
+    }
+    out.extend_from_slice(&storage);
+    out
+}
+
+/// A version 0 `OS/2` table.
+pub(crate) fn os2(weight_class: u16, width_class: u16, fs_selection: u16) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u16(&mut out, 0);
+    push_i16(&mut out, 500);
+    push_u16(&mut out, weight_class);
+    push_u16(&mut out, width_class);
+    out.resize(62, 0);
+    push_u16(&mut out, fs_selection);
+    out.resize(78, 0);
+    out
+}
+
+/// An `fvar` table from `(tag, min, default, max, name ID)` axes and
+/// `(subfamily name ID, coordinates, PostScript name ID)` instances.
+pub(crate) fn fvar(
+    axes: &[(&[u8; 4], f32, f32, f32, u16)],
+    instances: &[(u16, &[f32], u16)],
+) -> Vec<u8> {
+    let fixed = |out: &mut Vec<u8>, value: f32| push_u32(out, (value * 65536.) as i32 as u32);
+    let mut out = Vec::new();
+    for &value in &[1, 0, 16, 2, axes.len() as u16, 20, instances.len() as u16] {
+        push_u16(&mut out, value);
+    }
+    push_u16(&mut out, 6 + 4 * axes.len() as u16);
+    for &(tag, min, default, max, name_id) in axes {
+        out.extend_from_slice(tag);
+        fixed(&mut out, min);
+        fixed(&mut out, default);
+        fixed(&mut out, max);
+        push_u16(&mut out, 0);
+        push_u16(&mut out, name_id);
+    }
+    for &(name_id, coordinates, postscript_name_id) in instances {
+        push_u16(&mut out, name_id);
+        push_u16(&mut out, 0);
+        for &value in coordinates {
+            fixed(&mut out, value);
+        }
+        push_u16(&mut out, postscript_name_id);
+    }
+    out
+}
+
+pub(crate) fn post(is_fixed_pitch: bool) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u32(&mut out, 0x0003_0000);
+    push_u32(&mut out, 0);
+    push_i16(&mut out, -100);
+    push_i16(&mut out, 50);
+    push_u32(&mut out, is_fixed_pitch as u32);
+    out.extend_from_slice(&[0; 16]);
+    out
+}
+
+/// A format 2 `post` table naming each glyph, with the standard Macintosh
+/// names stored by index.
+pub(crate) fn post_with_names(glyph_names: &[&str]) -> Vec<u8> {
+    let mut out = post(false);
+    out[1] = 2;
+    push_u16(&mut out, glyph_names.len() as u16);
+    let mut strings = Vec::new();
+    let mut count = 0;
+    for &name in glyph_names {
+        let index = match name {
+            ".notdef" => 0,
+            "space" => 3,
+            "A" => 36,
+            "B" => 37,
+            _ => {
+                strings.push(name.len() as u8);
+                strings.extend_from_slice(name.as_bytes());
+                count += 1;
+                257 + count
+            }
+        };
+        push_u16(&mut out, index);
+    }
+    out.extend(strings);
+    out
+}
+
+/// Encodes a simple glyph from contours of `(x, y, on_curve)` points.
+pub(crate) fn simple_glyph(contours: &[&[(i16, i16, bool)]]) -> Vec<u8> {
+    let points: Vec<(i16, i16, bool)> = contours.iter().flat_map(|c| c.iter().cloned()).collect();
+    let mut out = Vec::new();
+    push_i16(&mut out, contours.len() as i16);
+    push_i16(&mut out, points.iter().map(|p| p.0).min().unwrap_or(0));
+    push_i16(&mut out, points.iter().map(|p| p.1).min().unwrap_or(0));
+    push_i16(&mut out, points.iter().map(|p| p.0).max().unwrap_or(0));
+    push_i16(&mut out, points.iter().map(|p| p.1).max().unwrap_or(0));
+    let mut end = 0;
+    for contour in contours {
+        end += contour.len();
+        push_u16(&mut out, end as u16 - 1);
+    }
+    push_u16(&mut out, 0);
+    for point in &points {
+        out.push(point.2 as u8);
+    }
+    let (mut last_x, mut last_y) = (0, 0);
+    for point in &points {
+        push_i16(&mut out, point.0 - last_x);
+        last_x = point.0;
+    }
+    for point in &points {
+        push_i16(&mut out, point.1 - last_y);
+        last_y = point.1;
+    }
+    out
+}
+
+/// Encodes a composite glyph from `(glyph_id, flags, arg1, arg2, transform)`
+/// components. Arguments are always stored as words, every component but
+/// the last gets `MORE_COMPONENTS`, and a transform of one, two or four
+/// values sets the matching scale flag.
+pub(crate) fn composite_glyph(components: &[(u16, u16, i16, i16, &[f32])]) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_i16(&mut out, -1);
+    out.extend_from_slice(&[0; 8]);
+    for (i, &(glyph_id, flags, arg1, arg2, transform)) in components.iter().enumerate() {
+        let mut flags = flags | 0x0001;
+        if i + 1 < components.len() {
+            flags |= 0x0020;
+        }
+        flags |= match transform.len() {
+            0 => 0,
+            1 => 0x0008,
+            2 => 0x0040,
+            4 => 0x0080,
+            _ => panic!("unsupported component transform"),
+        };
+        push_u16(&mut out, flags);
+        push_u16(&mut out, glyph_id);
+        push_i16(&mut out, arg1);
+        push_i16(&mut out, arg2);
+        for &value in transform {
+            push_i16(&mut out, (value * 16384.) as i16);
+        }
+    }
+    out
+}
+
+/// Builds `glyf` and a long-format `loca` from encoded glyphs.
+pub(crate) fn glyf_and_loca(glyphs: &[Vec<u8>]) -> (Vec<u8>, Vec<u8>) {
+    let mut glyf = Vec::new();
+    let mut loca = Vec::new();
+    for glyph in glyphs {
+        push_u32(&mut loca, glyf.len() as u32);
+        glyf.extend_from_slice(glyph);
+        while glyf.len() % 4 != 0 {
+            glyf.push(0);
+        }
+    }
+    push_u32(&mut loca, glyf.len() as u32);
+    (glyf, loca)
+}
+
+/// Encodes a Type 2 charstring from `(operands, operator)` steps. The
+/// operator bytes may carry extra data, such as a hint mask.
+pub(crate) fn charstring(steps: &[(&[i32], &[u8])]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for &(operands, operator) in steps {
+        for &value in operands {
+            match value {
+                -107..=107 => out.push((value + 139) as u8),
+                108..=1131 => {
+                    out.push(((value - 108) / 256 + 247) as u8);
+                    out.push(((value - 108) % 256) as u8);
+                }
+                -1131..=-108 => {
+                    out.push(((-value - 108) / 256 + 251) as u8);
+                    out.push(((-value - 108) % 256) as u8);
+                }
+                _ => {
+                    out.push(28);
+                    push_i16(&mut out, value as i16);
+                }
+            }
+        }
+        out.extend_from_slice(operator);
+    }
+    out
+}
+
+/// Encodes a CFF INDEX, with the 32-bit count of `CFF2` if `cff2` is set.
+fn cff_index(items: &[Vec<u8>], cff2: bool) -> Vec<u8> {
+    let mut out = Vec::new();
+    if cff2 {
+        push_u32(&mut out, items.len() as u32);
+    } else {
+        push_u16(&mut out, items.len() as u16);
+    }
+    if items.is_empty() {
+        return out;
+    }
+    out.push(4);
+    let mut offset = 1;
+    push_u32(&mut out, offset);
+    for item in items {
+        offset += item.len() as u32;
+        push_u32(&mut out, offset);
+    }
+    for item in items {
+        out.extend_from_slice(item);
+    }
+    out
+}
+
+/// Appends a DICT operand in its five-byte form, so that DICT sizes don't
+/// depend on the offsets they hold.
+fn push_dict_int(out: &mut Vec<u8>, value: usize) {
+    out.push(29);
+    push_u32(out, value as u32);
+}
+
+/// A Private DICT holding just a `Subrs` offset, followed by the local
+/// subroutines.
+fn private_dict(local_subrs: &[Vec<u8>], cff2: bool) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_dict_int(&mut out, 6);
+    out.push(19);
+    out.extend(cff_index(local_subrs, cff2));
+    out
+}
+
+/// A `CFF ` table with the given charstrings and subroutines. `charset`
+/// lists the string IDs of glyphs 1 and up, and an empty one selects the
+/// ISOAdobe charset. `strings` are string IDs 391 and up.
+pub(crate) fn cff(
+    char_strings: &[Vec<u8>],
+    global_subrs: &[Vec<u8>],
+    local_subrs: &[Vec<u8>],
+    charset: &[u16],
+    strings: &[&str],
+) -> Vec<u8> {
+    let name = cff_index(&[b"Test".to_vec()], false);
+    let strings: Vec<_> = strings
+        .iter()
+        .map(|string| string.as_bytes().to_vec())
+        .collect();
+    let strings = cff_index(&strings, false);
+    let global_subrs = cff_index(global_subrs, false);
+    let char_strings = cff_index(char_strings, false);
+    let mut charset_data = vec![];
+    if !charset.is_empty() {
+        charset_data.push(0);
+        for &sid in charset {
+            push_u16(&mut charset_data, sid);
+        }
+    }
+    let private = private_dict(local_subrs, false);
+
+    // Header, then a Top DICT INDEX holding one 23-byte DICT.
+    let top_dict_offset = 4 + name.len();
+    let char_strings_offset = top_dict_offset + 34 + strings.len() + global_subrs.len();
+    let charset_offset = char_strings_offset + char_strings.len();
+    let private_offset = charset_offset + charset_data.len();
+    let mut top_dict = Vec::new();
+    push_dict_int(&mut top_dict, char_strings_offset);
+    top_dict.push(17);
+    push_dict_int(
+        &mut top_dict,
+        if charset.is_empty() {
+            0
+        } else {
+            charset_offset
+        },
+    );
+    top_dict.push(15);
+    push_dict_int(&mut top_dict, 6);
+    push_dict_int(&mut top_dict, private_offset);
+    top_dict.push(18);
+
+    let mut out = vec![1, 0, 4, 4];
+    out.extend(name);
+    out.extend(cff_index(&[top_dict], false));
+    out.extend(strings);
+    out.extend(global_subrs);
+    out.extend(char_strings);
+    out.extend(charset_data);
+    out.extend(private);
+    out
+}
+
+/// An item variation store with one region, which peaks at the top of the
+/// first axis, and one item variation data subtable holding a word delta
+/// for each item.
+pub(crate) fn item_variation_store(deltas: &[i16]) -> Vec<u8> {
+    // The header, the region list at 12 and the item variation data at 22.
+    let mut out = Vec::new();
+    push_u16(&mut out, 1);
+    push_u32(&mut out, 12);
+    push_u16(&mut out, 1);
+    push_u32(&mut out, 22);
+    for &value in &[1, 1, 0, 16384, 16384, deltas.len() as u16, 1, 1, 0] {
+        push_u16(&mut out, value);
+    }
+    for &delta in deltas {
+        push_i16(&mut out, delta);
+    }
+    out
+}
+
+/// A `CFF2` table with the given charstrings and subroutines, and a
+/// variation store with one region that peaks at the top of the first
+/// axis.
+pub(crate) fn cff2(
+    char_strings: &[Vec<u8>],
+    global_subrs: &[Vec<u8>],
+    local_subrs: &[Vec<u8>],
+) -> Vec<u8> {
+    let global_subrs = cff_index(global_subrs, true);
+    let char_strings = cff_index(char_strings, true);
+    // `CFF2` prefixes the store with its length.
+    let store = item_variation_store(&[]);
+    let mut var_store = Vec::new();
+    push_u16(&mut var_store, store.len() as u16);
+    var_store.extend(store);
+    let private = private_dict(local_subrs, true);
+
+    // Header, then a 19-byte Top DICT.
+    let char_strings_offset = 5 + 19 + global_subrs.len();
+    let var_store_offset = char_strings_offset + char_strings.len();
+    let fd_array_offset = var_store_offset + var_store.len();
+    // A Font DICT INDEX holding one 11-byte DICT.
+    let private_offset = fd_array_offset + 4 + 1 + 8 + 11;
+    let mut top_dict = Vec::new();
+    push_dict_int(&mut top_dict, char_strings_offset);
+    top_dict.push(17);
+    push_dict_int(&mut top_dict, fd_array_offset);
+    top_dict.extend_from_slice(&[12, 36]);
+    push_dict_int(&mut top_dict, var_store_offset);
+    top_dict.push(24);
+    let mut font_dict = Vec::new();
+    push_dict_int(&mut font_dict, 6);
+    push_dict_int(&mut font_dict, private_offset);
+    font_dict.push(18);
+
+    let mut out = vec![2, 0, 5];
+    push_u16(&mut out, top_dict.len() as u16);
+    out.extend(top_dict);
+    out.extend(global_subrs);
+    out.extend(char_strings);
+    out.extend(var_store);
+    out.extend(cff_index(&[font_dict], true));
+    out.extend(private);
+    out
+}
+
+/// A `CPAL` table from palettes of `[red, green, blue, alpha]` colors, with
+/// version 1 palette types if `types` isn't empty.
+pub(crate) fn cpal(palettes: &[&[[u8; 4]]], types: &[u32]) -> Vec<u8> {
+    let entry_count = palettes.first().map_or(0, |palette| palette.len());
+    let version = if types.is_empty() { 0 } else { 1 };
+    let header_size = 12 + 2 * palettes.len() + if version == 1 { 12 } else { 0 };
+    let colors_offset = header_size + 4 * types.len();
+    let mut out = Vec::new();
+    push_u16(&mut out, version);
+    push_u16(&mut out, entry_count as u16);
+    push_u16(&mut out, palettes.len() as u16);
+    push_u16(&mut out, (entry_count * palettes.len()) as u16);
+    push_u32(&mut out, colors_offset as u32);
+    for index in 0..palettes.len() {
+        push_u16(&mut out, (index * entry_count) as u16);
+    }
+    if version == 1 {
+        push_u32(&mut out, header_size as u32);
+        push_u32(&mut out, 0);
+        push_u32(&mut out, 0);
+        for &palette_type in types {
+            push_u32(&mut out, palette_type);
+        }
+    }
+    for palette in palettes {
+        for &[red, green, blue, alpha] in palette.iter() {
+            out.extend_from_slice(&[blue, green, red, alpha]);
+        }
+    }
+    out
+}
+
+/// Encodes a `COLR` paint, or a color line or transform, from its fixed
+/// fields followed by its children. Each child is given with the position
+/// of the Offset24 in `head` that should point at it.
+pub(crate) fn colr_paint(head: &[u8], children: &[(usize, Vec<u8>)]) -> Vec<u8> {
+    let mut out = head.to_vec();
+    for (position, child) in children {
+        let offset = (out.len() as u32).to_be_bytes();
+        out[*position..*position + 3].copy_from_slice(&offset[1..]);
+        out.extend_from_slice(child);
+    }
+    out
+}
+
+/// A `COLR` table with version 0 `(glyph, [(layer glyph, palette index)])`
+/// base glyphs. It is version 1 if there are any `(glyph, paint)` base
+/// paints, layer paints, `(first glyph, last glyph, [x_min, y_min, x_max,
+/// y_max])` clips or a variation store.
+pub(crate) fn colr(
+    base_glyphs: &[(u16, &[(u16, u16)])],
+    base_paints: &[(u16, Vec<u8>)],
+    layers: &[Vec<u8>],
+    clips: &[(u16, u16, [i16; 4])],
+    var_store: Option<Vec<u8>>,
+) -> Vec<u8> {
+    let version =
+        !(base_paints.is_empty() && layers.is_empty() && clips.is_empty() && var_store.is_none())
+            as u16;
+    let mut records = Vec::new();
+    let mut layer_records = Vec::new();
+    for &(glyph_id, glyph_layers) in base_glyphs {
+        push_u16(&mut records, glyph_id);
+        push_u16(&mut records, (layer_records.len() / 4) as u16);
+        push_u16(&mut records, glyph_layers.len() as u16);
+        for &(layer_glyph_id, palette_index) in glyph_layers {
+            push_u16(&mut layer_records, layer_glyph_id);
+            push_u16(&mut layer_records, palette_index);
+        }
+    }
+
+    // Each list's offsets count from the start of the list.
+    let mut base_glyph_list = Vec::new();
+    push_u32(&mut base_glyph_list, base_paints.len() as u32);
+    let mut offset = 4 + 6 * base_paints.len();
+    for (glyph_id, paint) in base_paints {
+        push_u16(&mut base_glyph_list, *glyph_id);
+        push_u32(&mut base_glyph_list, offset as u32);
+        offset += paint.len();
+    }
+    for (_, paint) in base_paints {
+        base_glyph_list.extend_from_slice(paint);
+    }
+    let mut layer_list = Vec::new();
+    push_u32(&mut layer_list, layers.len() as u32);
+    let mut offset = 4 + 4 * layers.len();
+    for paint in layers {
+        push_u32(&mut layer_list, offset as u32);
+        offset += paint.len();
+    }
+    for paint in layers {
+        layer_list.extend_from_slice(paint);
+    }
+    let mut clip_list = vec![1];
+    push_u32(&mut clip_list, clips.len() as u32);
+    for (index, &(first, last, _)) in clips.iter().enumerate() {
+        push_u16(&mut clip_list, first);
+        push_u16(&mut clip_list, last);
+        let offset = (5 + 7 * clips.len() + 9 * index) as u32;
+        clip_list.extend_from_slice(&offset.to_be_bytes()[1..]);
+    }
+    for &(_, _, bounds) in clips {
+        clip_list.push(1);
+        for &value in &bounds {
+            push_i16(&mut clip_list, value);
+        }
+    }
+
+    let header_size = if version == 0 { 14 } else { 34 };
+    let mut subtables = vec![records, layer_records];
+    if version == 1 {
+        subtables.extend(vec![base_glyph_list, layer_list, clip_list]);
+        subtables.push(var_store.unwrap_or_default());
+    }
+    let mut offsets = Vec::new();
+    let mut offset = header_size;
+    for subtable in &subtables {
+        // Empty optional subtables get a null offset.
+        offsets.push(if subtable.is_empty() && offsets.len() >= 2 {
+            0
+        } else {
+            offset
+        });
+        offset += subtable.len();
+    }
+
+    let mut out = Vec::new();
+    push_u16(&mut out, version);
+    push_u16(&mut out, base_glyphs.len() as u16);
+    push_u32(&mut out, offsets[0] as u32);
+    push_u32(&mut out, offsets[1] as u32);
+    push_u16(&mut out, (subtables[1].len() / 4) as u16);
+    if version == 1 {
+        for &offset in &offsets[2..5] {
+            push_u32(&mut out, offset as u32);
+        }
+        // No delta set index map.
+        push_u32(&mut out, 0);
+        push_u32(&mut out, offsets[5] as u32);
+    }
+    for subtable in subtables {
+        out.extend(subtable);
+    }
+    out
+}
+
+/// The start of a PNG file: its signature and an `IHDR` chunk with the
+/// given size.
+pub(crate) fn png_header(width: u32, height: u32) -> Vec<u8> {
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    push_u32(&mut out, 13);
+    out.extend_from_slice(b"IHDR");
+    push_u32(&mut out, width);
+    push_u32(&mut out, height);
+    out.extend_from_slice(&[8, 6, 0, 0, 0]);
+    out
+}
+
+/// An `sbix` table from `(ppem, ppi, glyphs)` strikes, where each glyph is
+/// `(glyph_id, origin_x, origin_y, graphic_type, data)`.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sbix(
+    num_glyphs: u16,
+    strikes: &[(u16, u16, &[(u16, i16, i16, &[u8; 4], Vec<u8>)])],
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u16(&mut out, 1);
+    push_u16(&mut out, 1);
+    push_u32(&mut out, strikes.len() as u32);
+    let mut strike_data = Vec::new();
+    for &(ppem, ppi, glyphs) in strikes {
+        push_u32(&mut out, (8 + 4 * strikes.len() + strike_data.len()) as u32);
+        let mut strike = Vec::new();
+        push_u16(&mut strike, ppem);
+        push_u16(&mut strike, ppi);
+        let mut glyph_data = Vec::new();
+        let offsets_size = 4 + 4 * (num_glyphs as usize + 1);
+        for glyph_id in 0..=num_glyphs {
+            push_u32(&mut strike, (offsets_size + glyph_data.len()) as u32);
+            if let Some((_, x, y, graphic_type, data)) =
+                glyphs.iter().find(|glyph| glyph.0 == glyph_id)
+            {
+                push_i16(&mut glyph_data, *x);
+                push_i16(&mut glyph_data, *y);
+                glyph_data.extend_from_slice(*graphic_type);
+                glyph_data.extend_from_slice(data);
+            }
+        }
+        strike.extend(glyph_data);
+        strike_data.extend(strike);
+    }
+    out.extend(strike_data);
+    out
+}
+
+/// Builds `CBLC` and `CBDT`, or `EBLC` and `EBDT`, from `(ppem, bit_depth,
+/// glyphs)` strikes, where each glyph is `(glyph_id, image_format, data)`.
+/// Every glyph gets its own format 1 index subtable.
+#[allow(clippy::type_complexity)]
+pub(crate) fn eblc_and_ebdt(
+    color: bool,
+    strikes: &[(u8, u8, &[(u16, u16, Vec<u8>)])],
+) -> (Vec<u8>, Vec<u8>) {
+    let version = if color { 3 } else { 2 };
+    let mut location = Vec::new();
+    push_u16(&mut location, version);
+    push_u16(&mut location, 0);
+    push_u32(&mut location, strikes.len() as u32);
+    let mut data = Vec::new();
+    push_u16(&mut data, version);
+    push_u16(&mut data, 0);
+
+    let mut arrays = Vec::new();
+    for &(ppem, bit_depth, glyphs) in strikes {
+        // The index subtable records, then the 16-byte subtables.
+        let mut array = Vec::new();
+        let mut subtables = Vec::new();
+        for (glyph_id, image_format, image) in glyphs {
+            push_u16(&mut array, *glyph_id);
+            push_u16(&mut array, *glyph_id);
+            push_u32(&mut array, (8 * glyphs.len() + subtables.len()) as u32);
+            push_u16(&mut subtables, 1);
+            push_u16(&mut subtables, *image_format);
+            push_u32(&mut subtables, data.len() as u32);
+            push_u32(&mut subtables, 0);
+            push_u32(&mut subtables, image.len() as u32);
+            data.extend_from_slice(image);
+        }
+        array.extend(subtables);
+
+        push_u32(
+            &mut location,
+            (8 + 48 * strikes.len() + arrays.len()) as u32,
+        );
+        push_u32(&mut location, array.len() as u32);
+        push_u32(&mut location, glyphs.len() as u32);
+        // The color reference, line metrics and glyph range.
+        location.extend_from_slice(&[0; 32]);
+        location.extend_from_slice(&[ppem, ppem, bit_depth, 1]);
+        arrays.extend(array);
+    }
+    location.extend(arrays);
+    (location, data)
+}
+
+/// Compresses `data` into a gzip member with a file name, and a zero CRC
+/// since nothing here checks it.
+pub(crate) fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1f, 0x8b, 8, 0x08, 0, 0, 0, 0, 0, 255];
+    out.extend_from_slice(b"glyphs.svg\0");
+    out.extend(miniz_oxide::deflate::compress_to_vec(data, 6));
+    push_u32(&mut out, 0);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+/// An `SVG ` table from `(start_glyph, end_glyph, document)` records.
+/// Records with the same document share one copy of it.
+pub(crate) fn svg(records: &[(u16, u16, &[u8])]) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u16(&mut out, 0);
+    push_u32(&mut out, 10);
+    push_u32(&mut out, 0);
+    push_u16(&mut out, records.len() as u16);
+    let mut documents: Vec<&[u8]> = Vec::new();
+    let mut offsets = Vec::new();
+    let mut size = 2 + 12 * records.len();
+    for &(start_glyph, end_glyph, document) in records {
+        let index = match documents.iter().position(|&other| other == document) {
+            Some(index) => index,
+            None => {
+                documents.push(document);
+                offsets.push(size);
+                size += document.len();
+                documents.len() - 1
+            }
+        };
+        push_u16(&mut out, start_glyph);
+        push_u16(&mut out, end_glyph);
+        push_u32(&mut out, offsets[index] as u32);
+        push_u32(&mut out, document.len() as u32);
+    }
+    for document in documents {
+        out.extend_from_slice(document);
+    }
+    out
+}
+
+/// A `gasp` table of `(max_ppem, behavior)` ranges.
+pub(crate) fn gasp(version: u16, ranges: &[(u16, u16)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u16(&mut out, version);
+    push_u16(&mut out, ranges.len() as u16);
+    for &(max_ppem, behavior) in ranges {
+        push_u16(&mut out, max_ppem);
+        push_u16(&mut out, behavior);
+    }
+    out
+}
+
+/// A complete TrueType font with 1000 units per em and three glyphs:
+/// `.notdef` (empty), `A` (a square) and `B` (a quadratic bump), both with
+/// an advance of 600.
+pub(crate) fn simple_font(
+    family: &str,
+    subfamily: &str,
+    weight_class: u16,
+    width_class: u16,
+    fs_selection: u16,
+) -> FontBuilder {
+    let square = simple_glyph(&[&[
+        (100, 0, true),
+        (100, 500, true),
+        (500, 500, true),
+        (500, 0, true),
+    ]]);
+    let bump = simple_glyph(&[&[(0, 0, true), (250, 500, false), (500, 0, true)]]);
+    let (glyf, loca) = glyf_and_loca(&[vec![], square, bump]);
+
+    FontBuilder::new()
+        .table(b"head", head(1000))
+        .table(b"hhea", hhea(3))
+        .table(b"maxp", maxp(3))
+        .table(b"hmtx", hmtx(&[500, 600, 600]))
+        .table(b"cmap", cmap(&[('A' as u16, 1), ('B' as u16, 2)]))
+        .table(b"name", name(&[(1, family), (2, subfamily)]))
+        .table(b"OS/2", os2(weight_class, width_class, fs_selection))
+        .table(b"post", post(false))
+        .table(b"glyf", glyf)
+        .table(b"loca", loca)
+}
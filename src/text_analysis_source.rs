@@ -2,14 +2,16 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::wchar_t;
+use crate::sys::um::dwrite::IDWriteTextAnalysisSource;
 use std::borrow::Cow;
 use std::marker::PhantomData;
-use winapi::ctypes::wchar_t;
-use winapi::um::dwrite::IDWriteTextAnalysisSource;
-use wio::com::ComPtr;
 
-use super::*;
 use crate::com_helpers::Com;
+use crate::text_analysis_source_impl::{
+    CustomTextAnalysisSourceImpl, NumberSubstitution, TextAnalysisSourceMethods,
+};
 
 pub struct TextAnalysisSource<'a> {
     native: ComPtr<IDWriteTextAnalysisSource>,
@@ -7,26 +7,25 @@
 
 #![allow(non_snake_case)]
 
+use crate::sys::com::ComPtr;
+use crate::sys::ctypes::wchar_t;
+use crate::sys::ffi::OsStrExt;
+use crate::sys::shared::basetsd::UINT32;
+use crate::sys::shared::guiddef::REFIID;
+use crate::sys::shared::minwindef::{FALSE, TRUE, ULONG};
+use crate::sys::shared::ntdef::LOCALE_NAME_MAX_LENGTH;
+use crate::sys::shared::winerror::{E_INVALIDARG, S_OK};
+use crate::sys::um::dwrite::IDWriteNumberSubstitution;
+use crate::sys::um::dwrite::IDWriteTextAnalysisSource;
+use crate::sys::um::dwrite::IDWriteTextAnalysisSourceVtbl;
+use crate::sys::um::dwrite::DWRITE_NUMBER_SUBSTITUTION_METHOD;
+use crate::sys::um::dwrite::DWRITE_READING_DIRECTION;
+use crate::sys::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::sys::um::winnt::HRESULT;
 use std::borrow::Cow;
 use std::ffi::OsStr;
-use std::mem;
-use std::os::windows::ffi::OsStrExt;
 use std::ptr::{self, null};
 use std::sync::atomic::AtomicUsize;
-use winapi::ctypes::wchar_t;
-use winapi::shared::basetsd::UINT32;
-use winapi::shared::guiddef::REFIID;
-use winapi::shared::minwindef::{FALSE, TRUE, ULONG};
-use winapi::shared::ntdef::LOCALE_NAME_MAX_LENGTH;
-use winapi::shared::winerror::{E_INVALIDARG, S_OK};
-use winapi::um::dwrite::IDWriteNumberSubstitution;
-use winapi::um::dwrite::IDWriteTextAnalysisSource;
-use winapi::um::dwrite::IDWriteTextAnalysisSourceVtbl;
-use winapi::um::dwrite::DWRITE_NUMBER_SUBSTITUTION_METHOD;
-use winapi::um::dwrite::DWRITE_READING_DIRECTION;
-use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
-use winapi::um::winnt::HRESULT;
-use wio::com::ComPtr;
 
 use super::DWriteFactory;
 use crate::com_helpers::Com;
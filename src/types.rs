@@ -3,12 +3,20 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 /* this is include!()'d in lib.rs */
-use std::mem;
-use winapi::um::dwrite::{DWRITE_FONT_STYLE, DWRITE_FONT_WEIGHT, DWRITE_FONT_STRETCH};
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::{error, fmt};
+#[cfg(any(windows, test))]
+use crate::sys::um::dwrite::{DWRITE_FONT_STYLE, DWRITE_FONT_WEIGHT, DWRITE_FONT_STRETCH};
+#[cfg(any(windows, test))]
+use crate::sys::um::dwrite::DWRITE_FACTORY_TYPE;
 
 // mirrors DWRITE_FONT_WEIGHT
+//
+// Weights compare, order and hash by their numeric value, so
+// `Unknown(700) == Bold`.
 #[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub enum FontWeight {
     Thin,
     ExtraLight,
@@ -25,8 +33,9 @@ pub enum FontWeight {
 }
 
 impl FontWeight {
+    #[cfg(any(windows, test))]
     fn t(&self) -> DWRITE_FONT_WEIGHT {
-        unsafe { mem::transmute::<u32, DWRITE_FONT_WEIGHT>(self.to_u32()) }
+        self.to_u32() as DWRITE_FONT_WEIGHT
     }
     pub fn to_u32(&self) -> u32 {
         match self {
@@ -60,6 +69,136 @@ impl FontWeight {
                 _ => FontWeight::Unknown(v)
             }
     }
+
+    // The named weights, lightest first.
+    const KEYWORDS: [FontWeight; 11] = [
+        FontWeight::Thin,
+        FontWeight::ExtraLight,
+        FontWeight::Light,
+        FontWeight::SemiLight,
+        FontWeight::Regular,
+        FontWeight::Medium,
+        FontWeight::SemiBold,
+        FontWeight::Bold,
+        FontWeight::ExtraBold,
+        FontWeight::Black,
+        FontWeight::ExtraBlack,
+    ];
+
+    /// Returns the named weight nearest to this one, so that `Unknown(450)`
+    /// becomes `Medium` and `Unknown(340)` becomes `SemiLight`. Ties go to
+    /// the heavier weight.
+    pub fn nearest_keyword(&self) -> FontWeight {
+        let v = self.to_u32();
+        let mut nearest = FontWeight::KEYWORDS[0];
+        for &keyword in &FontWeight::KEYWORDS[1..] {
+            if keyword.to_u32().abs_diff(v) <= nearest.to_u32().abs_diff(v) {
+                nearest = keyword;
+            }
+        }
+        nearest
+    }
+
+    /// The weight CSS `bolder` computes to when the inherited weight is
+    /// `self`.
+    pub fn bolder(&self) -> FontWeight {
+        match self.to_u32() {
+            0..=349 => FontWeight::Regular,
+            350..=549 => FontWeight::Bold,
+            550..=899 => FontWeight::Black,
+            _ => *self,
+        }
+    }
+
+    /// The weight CSS `lighter` computes to when the inherited weight is
+    /// `self`.
+    pub fn lighter(&self) -> FontWeight {
+        match self.to_u32() {
+            0..=99 => *self,
+            100..=549 => FontWeight::Thin,
+            550..=749 => FontWeight::Regular,
+            _ => FontWeight::Bold,
+        }
+    }
+
+    /// Parses a CSS `font-weight` value, resolving `bolder` and `lighter`
+    /// against `inherited`. Fractional weights are rounded.
+    pub fn from_css(value: &str, inherited: FontWeight) -> Result<FontWeight, FontPropertyError> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("bolder") {
+            Ok(inherited.bolder())
+        } else if value.eq_ignore_ascii_case("lighter") {
+            Ok(inherited.lighter())
+        } else {
+            value.parse()
+        }
+    }
+
+    /// This weight as a value on the `wght` variation axis.
+    pub fn axis_value(&self) -> DWRITE_FONT_AXIS_VALUE {
+        Tag::WGHT.axis_value(self.to_u32() as f32)
+    }
+
+    /// Converts a `wght` axis value, which OpenType restricts to 1–1000, to
+    /// the nearest whole weight.
+    pub fn from_axis_value(value: f32) -> FontWeight {
+        let value = if value.is_nan() { 400. } else { value.clamp(1., 1000.) };
+        FontWeight::from_u32(value.round() as u32)
+    }
+}
+
+impl PartialEq for FontWeight {
+    fn eq(&self, other: &FontWeight) -> bool {
+        self.to_u32() == other.to_u32()
+    }
+}
+
+impl Eq for FontWeight {}
+
+impl PartialOrd for FontWeight {
+    fn partial_cmp(&self, other: &FontWeight) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FontWeight {
+    fn cmp(&self, other: &FontWeight) -> std::cmp::Ordering {
+        self.to_u32().cmp(&other.to_u32())
+    }
+}
+
+impl std::hash::Hash for FontWeight {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.to_u32().hash(state)
+    }
+}
+
+impl fmt::Display for FontWeight {
+    /// Formats the weight as a CSS number, such as `400`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_u32())
+    }
+}
+
+impl FromStr for FontWeight {
+    type Err = FontPropertyError;
+
+    /// Parses an absolute CSS `font-weight`: `normal`, `bold` or a number
+    /// from 1 to 1000. Use `FontWeight::from_css` for `bolder` and
+    /// `lighter`, which depend on the inherited weight.
+    fn from_str(s: &str) -> Result<FontWeight, FontPropertyError> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("normal") {
+            return Ok(FontWeight::Regular);
+        }
+        if s.eq_ignore_ascii_case("bold") {
+            return Ok(FontWeight::Bold);
+        }
+        match s.parse::<f32>() {
+            Ok(v) if (1. ..=1000.).contains(&v) => Ok(FontWeight::from_u32(v.round() as u32)),
+            _ => Err(FontPropertyError::InvalidKeyword(s.to_owned())),
+        }
+    }
 }
 
 // mirrors DWRITE_FONT_STRETCH
@@ -80,11 +219,102 @@ pub enum FontStretch {
 }
 
 impl FontStretch {
+    // Every stretch but `Undefined`, with its CSS keyword and percentage.
+    const KEYWORDS: [(FontStretch, &'static str, f32); 9] = [
+        (FontStretch::UltraCondensed, "ultra-condensed", 50.),
+        (FontStretch::ExtraCondensed, "extra-condensed", 62.5),
+        (FontStretch::Condensed, "condensed", 75.),
+        (FontStretch::SemiCondensed, "semi-condensed", 87.5),
+        (FontStretch::Normal, "normal", 100.),
+        (FontStretch::SemiExpanded, "semi-expanded", 112.5),
+        (FontStretch::Expanded, "expanded", 125.),
+        (FontStretch::ExtraExpanded, "extra-expanded", 150.),
+        (FontStretch::UltraExpanded, "ultra-expanded", 200.),
+    ];
+
+    #[cfg(any(windows, test))]
     fn t(&self) -> DWRITE_FONT_STRETCH {
-        unsafe { mem::transmute::<FontStretch, DWRITE_FONT_STRETCH>(*self) }
+        self.to_u32() as DWRITE_FONT_STRETCH
+    }
+    pub fn to_u32(&self) -> u32 { *self as u32 }
+    /// Out-of-range values become `Undefined`; use `FontStretch::try_from` to
+    /// detect them.
+    pub fn from_u32(v: u32) -> FontStretch {
+        FontStretch::try_from(v).unwrap_or(FontStretch::Undefined)
+    }
+
+    /// The CSS `font-stretch` keyword, or `None` for `Undefined`.
+    pub fn css_keyword(&self) -> Option<&'static str> {
+        FontStretch::KEYWORDS
+            .iter()
+            .find(|&&(stretch, _, _)| stretch == *self)
+            .map(|&(_, keyword, _)| keyword)
+    }
+
+    /// The width as a percentage of normal, as CSS `font-stretch` and the
+    /// `wdth` axis express it, or `None` for `Undefined`.
+    pub fn to_percentage(&self) -> Option<f32> {
+        FontStretch::KEYWORDS
+            .iter()
+            .find(|&&(stretch, _, _)| stretch == *self)
+            .map(|&(_, _, percentage)| percentage)
+    }
+
+    /// Returns the stretch nearest to `percentage`, which must be between
+    /// 50% and 200%. Ties go to the narrower stretch.
+    pub fn from_percentage(percentage: f32) -> Result<FontStretch, FontPropertyError> {
+        if !(50. ..=200.).contains(&percentage) {
+            return Err(FontPropertyError::InvalidPercentage(percentage));
+        }
+        let mut nearest = FontStretch::KEYWORDS[0];
+        for &keyword in &FontStretch::KEYWORDS[1..] {
+            if (keyword.2 - percentage).abs() < (nearest.2 - percentage).abs() {
+                nearest = keyword;
+            }
+        }
+        Ok(nearest.0)
+    }
+}
+
+impl TryFrom<u32> for FontStretch {
+    type Error = FontPropertyError;
+
+    fn try_from(v: u32) -> Result<FontStretch, FontPropertyError> {
+        match v {
+            0 => Ok(FontStretch::Undefined),
+            1..=9 => Ok(FontStretch::KEYWORDS[v as usize - 1].0),
+            _ => Err(FontPropertyError::OutOfRange(v)),
+        }
+    }
+}
+
+impl fmt::Display for FontStretch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.css_keyword().unwrap_or("undefined"))
+    }
+}
+
+impl FromStr for FontStretch {
+    type Err = FontPropertyError;
+
+    /// Parses a CSS `font-stretch` keyword or percentage, or `undefined`.
+    fn from_str(s: &str) -> Result<FontStretch, FontPropertyError> {
+        let s = s.trim();
+        if let Some(number) = s.strip_suffix('%') {
+            let percentage = number
+                .parse()
+                .map_err(|_| FontPropertyError::InvalidKeyword(s.to_owned()))?;
+            return FontStretch::from_percentage(percentage);
+        }
+        if s.eq_ignore_ascii_case("undefined") {
+            return Ok(FontStretch::Undefined);
+        }
+        FontStretch::KEYWORDS
+            .iter()
+            .find(|&&(_, keyword, _)| s.eq_ignore_ascii_case(keyword))
+            .map(|&(stretch, _, _)| stretch)
+            .ok_or_else(|| FontPropertyError::InvalidKeyword(s.to_owned()))
     }
-    pub fn to_u32(&self) -> u32 { unsafe { mem::transmute::<FontStretch, u32>(*self) } }
-    pub fn from_u32(v: u32) -> FontStretch { unsafe { mem::transmute::<u32, FontStretch>(v) } }
 }
 
 // mirrors DWRITE_FONT_STYLE
@@ -98,24 +328,193 @@ pub enum FontStyle {
 }
 
 impl FontStyle {
+    #[cfg(any(windows, test))]
     fn t(&self) -> DWRITE_FONT_STYLE {
-        unsafe { mem::transmute::<FontStyle, DWRITE_FONT_STYLE>(*self) }
+        self.to_u32() as DWRITE_FONT_STYLE
+    }
+    pub fn to_u32(&self) -> u32 { *self as u32 }
+    /// Out-of-range values become `Normal`; use `FontStyle::try_from` to
+    /// detect them.
+    pub fn from_u32(v: u32) -> FontStyle {
+        FontStyle::try_from(v).unwrap_or(FontStyle::Normal)
+    }
+
+    /// The CSS `font-style` keyword.
+    pub fn css_keyword(&self) -> &'static str {
+        match self {
+            FontStyle::Normal => "normal",
+            FontStyle::Oblique => "oblique",
+            FontStyle::Italic => "italic",
+        }
+    }
+}
+
+impl TryFrom<u32> for FontStyle {
+    type Error = FontPropertyError;
+
+    fn try_from(v: u32) -> Result<FontStyle, FontPropertyError> {
+        match v {
+            0 => Ok(FontStyle::Normal),
+            1 => Ok(FontStyle::Oblique),
+            2 => Ok(FontStyle::Italic),
+            _ => Err(FontPropertyError::OutOfRange(v)),
+        }
     }
-    pub fn to_u32(&self) -> u32 { unsafe { mem::transmute::<FontStyle, u32>(*self) } }
-    pub fn from_u32(v: u32) -> FontStyle { unsafe { mem::transmute::<u32, FontStyle>(v) } }
 }
 
+impl fmt::Display for FontStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.css_keyword())
+    }
+}
+
+impl FromStr for FontStyle {
+    type Err = FontPropertyError;
+
+    /// Parses a CSS `font-style` keyword.
+    fn from_str(s: &str) -> Result<FontStyle, FontPropertyError> {
+        let s = s.trim();
+        [FontStyle::Normal, FontStyle::Oblique, FontStyle::Italic]
+            .iter()
+            .find(|style| s.eq_ignore_ascii_case(style.css_keyword()))
+            .cloned()
+            .ok_or_else(|| FontPropertyError::InvalidKeyword(s.to_owned()))
+    }
+}
+
+/// The error returned when converting a raw value or a string into a font
+/// property fails.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontPropertyError {
+    /// The raw value doesn't correspond to any variant.
+    OutOfRange(u32),
+    /// The percentage is outside the range CSS allows.
+    InvalidPercentage(f32),
+    /// The string isn't a keyword or value the property accepts.
+    InvalidKeyword(String),
+}
+
+impl fmt::Display for FontPropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange(v) => write!(f, "Value {} is out of range", v),
+            Self::InvalidPercentage(p) => write!(f, "Percentage {}% is out of range", p),
+            Self::InvalidKeyword(s) => write!(f, "Invalid keyword {:?}", s),
+        }
+    }
+}
+
+impl error::Error for FontPropertyError {}
+
 // mirrors DWRITE_FONT_SIMULATIONS
-#[repr(u32)]
-#[derive(PartialEq, Debug, Clone, Copy)]
-pub enum FontSimulations {
-    None = winapi::um::dwrite::DWRITE_FONT_SIMULATIONS_NONE,
-    Bold = winapi::um::dwrite::DWRITE_FONT_SIMULATIONS_BOLD,
-    Oblique = winapi::um::dwrite::DWRITE_FONT_SIMULATIONS_OBLIQUE,
-    BoldOblique = winapi::um::dwrite::DWRITE_FONT_SIMULATIONS_BOLD |
-        winapi::um::dwrite::DWRITE_FONT_SIMULATIONS_OBLIQUE,
+//
+// A set of flags. The constants keep the names of the variants this had as
+// an enum, so they still work in patterns.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct FontSimulations(u32);
+
+#[allow(non_upper_case_globals)]
+impl FontSimulations {
+    pub const None: FontSimulations = FontSimulations(DWRITE_FONT_SIMULATIONS_NONE);
+    pub const Bold: FontSimulations = FontSimulations(DWRITE_FONT_SIMULATIONS_BOLD);
+    pub const Oblique: FontSimulations = FontSimulations(DWRITE_FONT_SIMULATIONS_OBLIQUE);
+    pub const BoldOblique: FontSimulations =
+        FontSimulations(DWRITE_FONT_SIMULATIONS_BOLD | DWRITE_FONT_SIMULATIONS_OBLIQUE);
+
+    /// The raw `DWRITE_FONT_SIMULATIONS` value.
+    pub const fn bits(self) -> u32 { self.0 }
+    /// Returns `None` if `bits` has flags DirectWrite doesn't define.
+    pub fn from_bits(bits: u32) -> Option<FontSimulations> {
+        if bits & !FontSimulations::BoldOblique.0 == 0 {
+            Some(FontSimulations(bits))
+        } else {
+            None
+        }
+    }
+    /// Drops any flags DirectWrite doesn't define.
+    pub const fn from_bits_truncate(bits: u32) -> FontSimulations {
+        FontSimulations(bits & FontSimulations::BoldOblique.0)
+    }
+    pub const fn is_empty(self) -> bool { self.0 == 0 }
+    pub const fn contains(self, other: FontSimulations) -> bool { self.0 & other.0 == other.0 }
+    pub const fn union(self, other: FontSimulations) -> FontSimulations {
+        FontSimulations(self.0 | other.0)
+    }
+    pub const fn intersection(self, other: FontSimulations) -> FontSimulations {
+        FontSimulations(self.0 & other.0)
+    }
+    pub const fn difference(self, other: FontSimulations) -> FontSimulations {
+        FontSimulations(self.0 & !other.0)
+    }
+}
+
+impl TryFrom<u32> for FontSimulations {
+    type Error = FontPropertyError;
+
+    fn try_from(bits: u32) -> Result<FontSimulations, FontPropertyError> {
+        FontSimulations::from_bits(bits).ok_or(FontPropertyError::OutOfRange(bits))
+    }
+}
+
+impl From<FontSimulations> for u32 {
+    fn from(simulations: FontSimulations) -> u32 {
+        simulations.bits()
+    }
+}
+
+impl std::ops::BitOr for FontSimulations {
+    type Output = FontSimulations;
+    fn bitor(self, other: FontSimulations) -> FontSimulations { self.union(other) }
+}
+
+impl std::ops::BitOrAssign for FontSimulations {
+    fn bitor_assign(&mut self, other: FontSimulations) { *self = self.union(other) }
+}
+
+impl std::ops::BitAnd for FontSimulations {
+    type Output = FontSimulations;
+    fn bitand(self, other: FontSimulations) -> FontSimulations { self.intersection(other) }
+}
+
+impl std::ops::Sub for FontSimulations {
+    type Output = FontSimulations;
+    fn sub(self, other: FontSimulations) -> FontSimulations { self.difference(other) }
 }
 
+impl fmt::Debug for FontSimulations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            FontSimulations::None => "None",
+            FontSimulations::Bold => "Bold",
+            FontSimulations::Oblique => "Oblique",
+            _ => "BoldOblique",
+        })
+    }
+}
+
+// Serialized as the raw flags, which are checked when deserializing.
+#[cfg(feature = "serde_serialization")]
+impl serde::Serialize for FontSimulations {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+#[cfg(feature = "serde_serialization")]
+impl<'de> serde::Deserialize<'de> for FontSimulations {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = u32::deserialize(deserializer)?;
+        FontSimulations::try_from(bits).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The family and properties of a font to look for.
+///
+/// Descriptors serialized before 0.12, which had no `oblique_angle` or
+/// `simulations`, still deserialize with both set to `None`, but only from
+/// self-describing formats such as JSON. Formats that rely on the number and
+/// order of fields, such as bincode, can't tell that the fields are missing
+/// and fail to read them.
 #[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
 #[derive(PartialEq, Debug, Clone)]
 pub struct FontDescriptor {
@@ -123,4 +522,102 @@ pub struct FontDescriptor {
     pub weight: FontWeight,
     pub stretch: FontStretch,
     pub style: FontStyle,
+    /// The slant of an `Oblique` style in degrees, positive leaning right as
+    /// in CSS `oblique 14deg`. `None`, or any other style, leaves the face's
+    /// default slant alone.
+    #[cfg_attr(feature = "serde_serialization", serde(default))]
+    pub oblique_angle: Option<f32>,
+    /// The simulations to apply when creating a face for the font, or `None`
+    /// for whatever the font comes with.
+    #[cfg_attr(feature = "serde_serialization", serde(default))]
+    pub simulations: Option<FontSimulations>,
 }
+
+impl FontDescriptor {
+    /// The angle CSS uses for `oblique` without an explicit angle.
+    pub const DEFAULT_OBLIQUE_ANGLE: f32 = 14.;
+
+    /// The `slnt` axis value that gives this descriptor's oblique angle.
+    /// OpenType measures slant counter-clockwise, the opposite of CSS, and
+    /// restricts it to ±90°.
+    pub fn slant_axis_value(&self) -> Option<DWRITE_FONT_AXIS_VALUE> {
+        match (self.style, self.oblique_angle) {
+            (FontStyle::Oblique, Some(angle)) if !angle.is_nan() => {
+                Some(Tag::SLNT.axis_value(-angle.clamp(-90., 90.)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the oblique angle given by the `slnt` value in `axis_values`,
+    /// if there is one.
+    pub fn oblique_angle_from_axis_values(axis_values: &[DWRITE_FONT_AXIS_VALUE]) -> Option<f32> {
+        axis_values
+            .iter()
+            .find(|axis_value| axis_value.axisTag == Tag::SLNT.to_dwrite())
+            .map(|axis_value| -axis_value.value)
+    }
+
+    /// Replaces the `slnt` value in a face's `axis_values` with the one this
+    /// descriptor asks for, returning whether anything changed. Faces
+    /// without a `slnt` axis are left as they are.
+    pub fn apply_to_axis_values(&self, axis_values: &mut [DWRITE_FONT_AXIS_VALUE]) -> bool {
+        let slant = match self.slant_axis_value() {
+            Some(slant) => slant,
+            None => return false,
+        };
+        match axis_values.iter_mut().find(|axis_value| axis_value.axisTag == slant.axisTag) {
+            Some(axis_value) if axis_value.value != slant.value => {
+                axis_value.value = slant.value;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// mirrors DWRITE_FACTORY_TYPE
+#[repr(u32)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum FactoryType {
+    Shared = 0,
+    Isolated = 1,
+}
+
+impl FactoryType {
+    #[cfg(any(windows, test))]
+    fn t(&self) -> DWRITE_FACTORY_TYPE {
+        *self as DWRITE_FACTORY_TYPE
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+pub enum FontFaceType {
+    Unknown,
+    Cff,
+    RawCff,
+    TrueType,
+    TrueTypeCollection,
+    Type1,
+    Vector,
+    Bitmap,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde_serialization", derive(Deserialize, Serialize))]
+pub enum GlyphRunOutlineError {
+    InvalidInput,
+    Win32Error(HRESULT),
+}
+
+impl fmt::Display for GlyphRunOutlineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidInput => write!(f, "Invalid input"),
+            Self::Win32Error(code) => write!(f, "{:#x}", code),
+        }
+    }
+}
+
+impl error::Error for GlyphRunOutlineError {}